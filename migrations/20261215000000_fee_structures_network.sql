@@ -0,0 +1,16 @@
+-- migrate:up
+-- Scope fee structures to a Stellar network
+--
+-- NULL keeps the existing behaviour (row applies on every network), so
+-- current data needs no backfill.
+ALTER TABLE fee_structures
+    ADD COLUMN IF NOT EXISTS network TEXT;
+
+ALTER TABLE fee_structures
+    ADD CONSTRAINT chk_fee_structures_network
+        CHECK (network IS NULL OR network IN ('testnet', 'mainnet'));
+
+CREATE INDEX IF NOT EXISTS idx_fee_structures_type_network
+ON fee_structures(fee_type, network)
+WHERE is_active = TRUE;
+
@@ -0,0 +1,19 @@
+-- migrate:up
+-- Memo ids that attribute incoming exchange deposits to an internal wallet
+--
+-- Deposits built with a `deposit_account_id` carry it as the transaction's
+-- MEMO_ID; the reconciler looks the memo up here to find whose deposit it is.
+CREATE TABLE IF NOT EXISTS deposit_memo_ids (
+    memo_id         BIGINT PRIMARY KEY CHECK (memo_id > 0),
+    -- Not a foreign key: deposits may be built before the wallet is connected
+    wallet_address  VARCHAR(255) NOT NULL,
+    created_at      TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+COMMENT ON TABLE deposit_memo_ids IS 'Maps deposit MEMO_ID values to the wallet they credit.';
+
+CREATE INDEX IF NOT EXISTS idx_deposit_memo_ids_wallet
+ON deposit_memo_ids(wallet_address);
+
+-- migrate:down
+DROP TABLE IF EXISTS deposit_memo_ids;
@@ -0,0 +1,34 @@
+-- migrate:up
+-- One conversion audit per (transaction, currency pair)
+--
+-- Reconciliation retries used to insert a second audit for the same
+-- transaction. The index is partial: quotes recorded before a transaction
+-- exists have no transaction_id and are never treated as duplicates.
+
+-- Audit rows are never thrown away: later duplicates are moved here, with
+-- the earliest row of each group kept in place, so the index can be built
+CREATE TABLE IF NOT EXISTS conversion_audits_duplicates_archive (
+    LIKE conversion_audits INCLUDING DEFAULTS,
+    archived_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+WITH moved AS (
+    DELETE FROM conversion_audits a
+    USING conversion_audits b
+    WHERE a.transaction_id IS NOT NULL
+      AND a.transaction_id = b.transaction_id
+      AND a.from_currency = b.from_currency
+      AND a.to_currency = b.to_currency
+      AND (a.created_at, a.id) > (b.created_at, b.id)
+    RETURNING a.*
+)
+INSERT INTO conversion_audits_duplicates_archive
+SELECT * FROM moved;
+
+CREATE UNIQUE INDEX IF NOT EXISTS uq_conversion_audits_transaction_pair
+ON conversion_audits(transaction_id, from_currency, to_currency)
+WHERE transaction_id IS NOT NULL;
+
+-- migrate:down
+-- The archive is left in place; its rows are audit history, not schema
+DROP INDEX IF EXISTS uq_conversion_audits_transaction_pair;
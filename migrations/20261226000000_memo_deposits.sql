@@ -0,0 +1,44 @@
+-- migrate:up
+-- Inbound payments to the shared deposit address, as attributed by the memo
+-- deposit reconciler
+--
+-- One row per Horizon payment operation, so a payment seen twice (a restart,
+-- an overlapping page) is only credited once. Payments whose memo doesn't
+-- identify a deposit account, or whose amount can't be parsed, stay
+-- `quarantined` for manual review.
+CREATE TABLE IF NOT EXISTS memo_deposits (
+    payment_id           TEXT PRIMARY KEY,
+    transaction_hash     VARCHAR(64) NOT NULL,
+    deposit_address      VARCHAR(255) NOT NULL,
+    source_address       VARCHAR(255),
+    asset_code           TEXT NOT NULL,
+    asset_issuer         TEXT,
+    amount               NUMERIC(36, 18) NOT NULL CHECK (amount >= 0),
+    memo_type            TEXT,
+    memo                 TEXT,
+    -- Wallet the memo id maps to; NULL while quarantined
+    wallet_address       VARCHAR(255),
+    conversion_audit_id  UUID REFERENCES conversion_audits(id) ON DELETE SET NULL,
+    status               TEXT NOT NULL CHECK (status IN ('credited', 'quarantined')),
+    quarantine_reason    TEXT CHECK (quarantine_reason IN ('missing_memo', 'non_id_memo', 'unknown_memo', 'invalid_amount')),
+    created_at           TIMESTAMPTZ NOT NULL DEFAULT now(),
+    CHECK ((status = 'quarantined') = (quarantine_reason IS NOT NULL))
+);
+
+COMMENT ON TABLE memo_deposits IS 'Inbound deposit-address payments, credited by memo id or quarantined.';
+
+CREATE INDEX IF NOT EXISTS idx_memo_deposits_quarantined
+ON memo_deposits(created_at) WHERE status = 'quarantined';
+
+-- Horizon paging token of the last payment the reconciler looked at, per
+-- deposit address. Kept apart from memo_deposits because most payments to the
+-- address (other assets, outgoing payments) are skipped without a row.
+CREATE TABLE IF NOT EXISTS memo_deposit_cursors (
+    deposit_address  VARCHAR(255) PRIMARY KEY,
+    paging_token     TEXT NOT NULL,
+    updated_at       TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+-- migrate:down
+DROP TABLE IF EXISTS memo_deposit_cursors;
+DROP TABLE IF EXISTS memo_deposits;
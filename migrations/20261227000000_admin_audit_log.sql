@@ -0,0 +1,28 @@
+-- migrate:up
+-- Compliance trail of actions taken through admin-guarded endpoints
+--
+-- Handlers that change a row record its state before and after, in the same
+-- transaction as the change; every other mutating admin request is recorded by
+-- the admin guard with only the route it hit.
+CREATE TABLE IF NOT EXISTS admin_audit_log (
+    id          UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    -- `jwt:<subject>` or `api_key:<key id>`
+    actor       TEXT NOT NULL,
+    -- e.g. `fee_structure.deactivate`, or `POST /api/admin/afri/mint`
+    action      TEXT NOT NULL,
+    -- What was acted on, e.g. `fee_structure:<id>` or the request path
+    target      TEXT NOT NULL,
+    before      JSONB,
+    after       JSONB,
+    request_id  TEXT,
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+COMMENT ON TABLE admin_audit_log IS 'Who did what to which resource through the admin API, with before/after state.';
+
+CREATE INDEX IF NOT EXISTS idx_admin_audit_log_created_at ON admin_audit_log(created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_admin_audit_log_actor ON admin_audit_log(actor, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_admin_audit_log_target ON admin_audit_log(target, created_at DESC);
+
+-- migrate:down
+DROP TABLE IF EXISTS admin_audit_log;
@@ -0,0 +1,76 @@
+//! Admin action audit trail.
+//!
+//! GET /api/admin/audit-log — recorded admin actions, newest first, filterable by
+//! `actor`, `action` and `target`
+
+use axum::{
+    extract::{Query, State},
+    http::HeaderMap,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Deserialize;
+use sqlx::PgPool;
+use std::sync::Arc;
+
+use crate::database::admin_audit_repository::{AdminAuditFilter, AdminAuditRepository};
+use crate::database::repository::{ListQuery, Page};
+use crate::error::AppError;
+use crate::middleware::error::get_request_id_from_headers;
+
+// ─── State ────────────────────────────────────────────────────────────────────
+
+#[derive(Clone)]
+pub struct AuditLogState {
+    pub db: Arc<PgPool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AuditLogPageQuery {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+// ─── Handlers ─────────────────────────────────────────────────────────────────
+
+/// GET /api/admin/audit-log
+pub async fn list_audit_log(
+    State(state): State<AuditLogState>,
+    headers: HeaderMap,
+    Query(page): Query<AuditLogPageQuery>,
+    Query(filter): Query<AdminAuditFilter>,
+) -> Response {
+    let request_id = get_request_id_from_headers(&headers);
+    let limit = page
+        .limit
+        .unwrap_or(ListQuery::DEFAULT_LIMIT)
+        .clamp(1, ListQuery::MAX_LIMIT);
+    let offset = page.offset.unwrap_or(0).max(0);
+
+    let repo = AdminAuditRepository::new(state.db.as_ref().clone());
+    let result = async {
+        let items = repo.find_filtered(&filter, limit, offset).await?;
+        let total = repo.count_filtered(&filter).await?;
+        Ok::<_, crate::database::error::DatabaseError>((items, total))
+    }
+    .await;
+
+    match result {
+        Ok((items, total)) => Json(Page {
+            has_more: offset + (items.len() as i64) < total,
+            items,
+            total,
+            limit,
+            offset,
+        })
+        .into_response(),
+        Err(e) => {
+            let err: AppError = e.into();
+            match request_id {
+                Some(id) => err.with_request_id(id),
+                None => err,
+            }
+            .into_response()
+        }
+    }
+}
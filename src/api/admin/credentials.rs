@@ -0,0 +1,98 @@
+//! Admin payment provider credential rotation.
+//!
+//! POST /api/admin/payments/providers/{provider}/credentials — validate and swap in a new secret
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::payments::factory::PaymentProviderFactory;
+use crate::payments::types::ProviderName;
+
+// ─── State ────────────────────────────────────────────────────────────────────
+
+#[derive(Clone)]
+pub struct CredentialRotationState {
+    pub factory: Arc<PaymentProviderFactory>,
+}
+
+// ─── Request / Response ───────────────────────────────────────────────────────
+
+#[derive(Deserialize)]
+pub struct RotateCredentialRequest {
+    pub secret_key: String,
+}
+
+// Hand-written so the secret can never end up in logs via `{:?}`.
+impl std::fmt::Debug for RotateCredentialRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RotateCredentialRequest")
+            .field("secret_key", &"[REDACTED]")
+            .finish()
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct RotateCredentialResponse {
+    pub provider: String,
+    pub rotated: bool,
+    pub rotated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+struct ErrorBody {
+    code: String,
+    message: String,
+}
+
+fn err(status: StatusCode, code: &str, msg: impl Into<String>) -> Response {
+    (
+        status,
+        Json(ErrorBody {
+            code: code.to_string(),
+            message: msg.into(),
+        }),
+    )
+        .into_response()
+}
+
+// ─── Handlers ─────────────────────────────────────────────────────────────────
+
+/// POST /api/admin/payments/providers/{provider}/credentials
+pub async fn rotate_provider_credentials(
+    State(state): State<CredentialRotationState>,
+    Path(provider): Path<String>,
+    Json(req): Json<RotateCredentialRequest>,
+) -> Response {
+    let provider = match ProviderName::from_str(&provider) {
+        Ok(p) => p,
+        Err(e) => return err(StatusCode::BAD_REQUEST, "INVALID_PROVIDER", e.user_message()),
+    };
+
+    match state.factory.rotate_credential(provider.clone(), &req.secret_key).await {
+        Ok(()) => (
+            StatusCode::OK,
+            Json(RotateCredentialResponse {
+                provider: provider.to_string(),
+                rotated: true,
+                rotated_at: Utc::now(),
+            }),
+        )
+            .into_response(),
+        Err(crate::payments::PaymentError::ValidationError { message, .. }) => {
+            err(StatusCode::BAD_REQUEST, "INVALID_ROTATION_REQUEST", message)
+        }
+        Err(_) => err(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "CREDENTIAL_VALIDATION_FAILED",
+            "the new credentials were rejected by the provider; the existing key remains active",
+        ),
+    }
+}
@@ -0,0 +1,180 @@
+//! Admin fee structure management.
+//!
+//! POST /api/fees/structures/batch                 — create structures, all or none
+//! POST /api/admin/fees/structures/{id}/deactivate — take a fee structure out of force
+//!
+//! Both sit behind the admin guard, the batch route included even though it
+//! is mounted outside `/api/admin`. Each change is recorded in
+//! `admin_audit_log` with the structure's state before and after, in the same
+//! transaction as the change itself.
+
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+use chrono::Utc;
+use serde::Deserialize;
+use sqlx::PgPool;
+use std::sync::Arc;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::api::validation::ArrayBounds;
+use crate::database::admin_audit_repository::{AdminAuditRepository, NewAdminAuditEntry};
+use crate::database::error::DatabaseError;
+use crate::database::fee_structure_repository::{FeeStructureRepository, NewFeeStructure};
+use crate::error::{AppError, ErrorCode};
+use crate::services::fee_structure::{
+    normalize_currencies, validate_batch, FeeCurrencies, FeeStructureIssue,
+};
+use crate::middleware::admin::{AdminActor, AdminAudited};
+use crate::middleware::error::{get_request_id_from_headers, ErrorResponse};
+
+// ─── State ────────────────────────────────────────────────────────────────────
+
+#[derive(Clone)]
+pub struct AdminFeesState {
+    pub db: Arc<PgPool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FeeStructureBatchRequest {
+    pub structures: Vec<NewFeeStructure>,
+}
+
+fn not_found(id: Uuid, request_id: Option<String>) -> Response {
+    (
+        StatusCode::NOT_FOUND,
+        Json(ErrorResponse {
+            error: ErrorCode::NotFound,
+            message: format!("Fee structure {} not found", id),
+            request_id,
+            timestamp: Utc::now().to_rfc3339(),
+            details: None,
+            retryable: Some(false),
+        }),
+    )
+        .into_response()
+}
+
+fn database_error(e: DatabaseError, request_id: Option<String>) -> Response {
+    let err: AppError = e.into();
+    match request_id {
+        Some(id) => err.with_request_id(id),
+        None => err,
+    }
+    .into_response()
+}
+
+fn invalid_batch(issues: &[FeeStructureIssue], request_id: Option<String>) -> Response {
+    let first = &issues[0];
+    let field = format!("structures[{}].{}", first.index, first.field);
+    let response = ErrorResponse::validation_error(request_id, &field, &first.message)
+        .with_details(serde_json::json!({
+            "field": field,
+            "error": first.message,
+            "issues": issues,
+        }));
+    (StatusCode::BAD_REQUEST, Json(response)).into_response()
+}
+
+// ─── Handlers ─────────────────────────────────────────────────────────────────
+
+/// POST /api/fees/structures/batch
+pub async fn create_fee_structure_batch(
+    State(state): State<AdminFeesState>,
+    Extension(actor): Extension<AdminActor>,
+    headers: HeaderMap,
+    Json(mut payload): Json<FeeStructureBatchRequest>,
+) -> Response {
+    let request_id = get_request_id_from_headers(&headers);
+
+    let bounds = ArrayBounds::from_env("FEE_STRUCTURE_BATCH_MAX_ITEMS", 100);
+    if let Err(e) = bounds.check("structures", &payload.structures) {
+        return (StatusCode::BAD_REQUEST, Json(e.to_error_response(request_id))).into_response();
+    }
+    normalize_currencies(&mut payload.structures);
+    if let Err(issues) = validate_batch(&payload.structures, &FeeCurrencies::from_env()) {
+        return invalid_batch(&issues, request_id);
+    }
+
+    let result: Result<_, DatabaseError> = async {
+        let mut tx = state.db.begin().await.map_err(DatabaseError::from_sqlx)?;
+        let created = FeeStructureRepository::create_batch_in(&mut tx, &payload.structures).await?;
+
+        AdminAuditRepository::record_in(
+            &mut tx,
+            &NewAdminAuditEntry {
+                actor: actor.0.clone(),
+                action: "fee_structure.create_batch".to_string(),
+                target: "fee_structures".to_string(),
+                before: None,
+                after: Some(serde_json::to_value(&created).unwrap_or_default()),
+                request_id: request_id.clone(),
+            },
+        )
+        .await?;
+
+        tx.commit().await.map_err(DatabaseError::from_sqlx)?;
+        Ok(created)
+    }
+    .await;
+
+    match result {
+        Ok(created) => {
+            info!(actor = %actor, count = created.len(), "Admin: fee structures created");
+            let mut response = (StatusCode::CREATED, Json(created)).into_response();
+            response.extensions_mut().insert(AdminAudited);
+            response
+        }
+        Err(e) => database_error(e, request_id),
+    }
+}
+
+/// POST /api/admin/fees/structures/{id}/deactivate
+pub async fn deactivate_fee_structure(
+    State(state): State<AdminFeesState>,
+    Extension(actor): Extension<AdminActor>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+) -> Response {
+    let request_id = get_request_id_from_headers(&headers);
+
+    let result: Result<_, DatabaseError> = async {
+        let mut tx = state.db.begin().await.map_err(DatabaseError::from_sqlx)?;
+        let Some(before) = FeeStructureRepository::find_for_update(&mut tx, id).await? else {
+            return Ok(None);
+        };
+        let after = FeeStructureRepository::deactivate_in(&mut tx, id).await?;
+
+        AdminAuditRepository::record_in(
+            &mut tx,
+            &NewAdminAuditEntry {
+                actor: actor.0.clone(),
+                action: "fee_structure.deactivate".to_string(),
+                target: format!("fee_structure:{}", id),
+                before: Some(serde_json::to_value(&before).unwrap_or_default()),
+                after: Some(serde_json::to_value(&after).unwrap_or_default()),
+                request_id: request_id.clone(),
+            },
+        )
+        .await?;
+
+        tx.commit().await.map_err(DatabaseError::from_sqlx)?;
+        Ok(Some(after))
+    }
+    .await;
+
+    match result {
+        Ok(Some(after)) => {
+            info!(actor = %actor, fee_structure_id = %id, "Admin: fee structure deactivated");
+            let mut response = (StatusCode::OK, Json(after)).into_response();
+            response.extensions_mut().insert(AdminAudited);
+            response
+        }
+        Ok(None) => not_found(id, request_id),
+        Err(e) => database_error(e, request_id),
+    }
+}
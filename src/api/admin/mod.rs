@@ -1,4 +1,9 @@
+pub mod audit_log;
+pub mod credentials;
+pub mod fees;
 pub mod ip_reputation;
 pub mod keys;
 pub mod scopes;
 pub mod revocation;
+pub mod config;
+pub mod token;
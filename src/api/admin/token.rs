@@ -0,0 +1,166 @@
+//! Issuer-side token supply management.
+//!
+//! POST /api/admin/afri/mint — mint tokens to an address
+//! POST /api/admin/afri/burn — burn tokens from an address
+//! POST /api/admin/afri/ttl/bump — extend the storage TTL of an address's balance
+//!
+//! All of them go to the configured Soroban token contract, signed by the admin key.
+
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use bigdecimal::BigDecimal;
+use serde::{Deserialize, Serialize};
+
+use crate::chains::stellar::soroban::{validate_admin_amount, ContractAdmin, SorobanRpcClient};
+use crate::error::AppError;
+
+// ─── State ────────────────────────────────────────────────────────────────────
+
+#[derive(Clone)]
+pub struct TokenAdminState {
+    pub soroban: SorobanRpcClient,
+    pub admin: ContractAdmin,
+}
+
+// ─── Request / Response ───────────────────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+pub struct SupplyChangeRequest {
+    pub address: String,
+    /// Decimal token amount, e.g. "150.25"
+    pub amount: BigDecimal,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SupplyChangeResponse {
+    pub operation: &'static str,
+    pub address: String,
+    pub amount: String,
+    pub transaction_hash: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TtlBumpRequest {
+    pub address: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TtlBumpResponse {
+    pub address: String,
+    /// Ledgers from now the instance and balance entries will live for
+    pub extend_to_ledgers: u32,
+    pub transaction_hash: String,
+}
+
+#[derive(Debug, Serialize)]
+struct ErrorBody {
+    code: String,
+    message: String,
+}
+
+fn err(status: StatusCode, code: &str, msg: impl Into<String>) -> Response {
+    (
+        status,
+        Json(ErrorBody {
+            code: code.to_string(),
+            message: msg.into(),
+        }),
+    )
+        .into_response()
+}
+
+// ─── Handlers ─────────────────────────────────────────────────────────────────
+
+/// POST /api/admin/afri/mint
+pub async fn mint(
+    State(state): State<TokenAdminState>,
+    Json(req): Json<SupplyChangeRequest>,
+) -> Response {
+    change_supply(state, "mint", req).await
+}
+
+/// POST /api/admin/afri/burn
+pub async fn burn(
+    State(state): State<TokenAdminState>,
+    Json(req): Json<SupplyChangeRequest>,
+) -> Response {
+    change_supply(state, "burn", req).await
+}
+
+async fn change_supply(
+    state: TokenAdminState,
+    operation: &'static str,
+    req: SupplyChangeRequest,
+) -> Response {
+    let units = match validate_admin_amount(&req.amount, state.admin.decimals) {
+        Ok(units) => units,
+        Err(e) => return err(StatusCode::BAD_REQUEST, "INVALID_AMOUNT", e.to_string()),
+    };
+
+    let result = match operation {
+        "mint" => state.soroban.invoke_mint(&state.admin, &req.address, units).await,
+        _ => state.soroban.invoke_burn(&state.admin, &req.address, units).await,
+    };
+
+    match result {
+        Ok(transaction_hash) => {
+            tracing::info!(
+                operation,
+                address = %req.address,
+                amount = %req.amount,
+                hash = %transaction_hash,
+                "token supply change submitted"
+            );
+            (
+                StatusCode::OK,
+                Json(SupplyChangeResponse {
+                    operation,
+                    address: req.address,
+                    amount: req.amount.to_string(),
+                    transaction_hash,
+                }),
+            )
+                .into_response()
+        }
+        Err(e) => AppError::from(e).into_response(),
+    }
+}
+
+/// POST /api/admin/afri/ttl/bump
+///
+/// Persistent entries are archived once their TTL runs out, after which
+/// balance reads fail until restored. This extends both the contract instance
+/// and the address's balance entry.
+pub async fn bump_balance_ttl(
+    State(state): State<TokenAdminState>,
+    Json(req): Json<TtlBumpRequest>,
+) -> Response {
+    match state
+        .soroban
+        .extend_balance_ttl(&state.admin, &req.address)
+        .await
+    {
+        Ok(transaction_hash) => {
+            tracing::info!(
+                address = %req.address,
+                extend_to = state.admin.ttl_extend_to,
+                hash = %transaction_hash,
+                "balance entry TTL extension submitted"
+            );
+            (
+                StatusCode::OK,
+                Json(TtlBumpResponse {
+                    address: req.address,
+                    extend_to_ledgers: state.admin.ttl_extend_to,
+                    transaction_hash,
+                }),
+            )
+                .into_response()
+        }
+        Err(e) => AppError::from(e).into_response(),
+    }
+}
@@ -20,6 +20,8 @@ use std::sync::Arc;
 use tracing::{error, info, warn};
 use uuid::Uuid;
 
+use crate::api::validation::ArrayBounds;
+
 // ─── State ───────────────────────────────────────────────────────────────────
 
 #[derive(Clone)]
@@ -32,11 +34,13 @@ pub struct BatchState {
 }
 
 impl BatchState {
+    /// Limits default to 100 / 500 items and can be overridden with
+    /// `BATCH_MAX_CNGN_TRANSFERS` / `BATCH_MAX_FIAT_PAYOUTS`.
     pub fn new(db: Arc<PgPool>) -> Self {
         Self {
             db,
-            max_cngn_batch_size: 100,
-            max_fiat_batch_size: 500,
+            max_cngn_batch_size: ArrayBounds::from_env("BATCH_MAX_CNGN_TRANSFERS", 100).max_len,
+            max_fiat_batch_size: ArrayBounds::from_env("BATCH_MAX_FIAT_PAYOUTS", 500).max_len,
         }
     }
 }
@@ -161,23 +165,9 @@ pub async fn create_cngn_transfer_batch(
         "Creating cNGN transfer batch"
     );
 
-    if body.transfers.is_empty() {
-        return error_response(
-            StatusCode::BAD_REQUEST,
-            "EMPTY_BATCH",
-            "Batch must contain at least one transfer",
-        );
-    }
-
-    if body.transfers.len() > state.max_cngn_batch_size {
-        return error_response(
-            StatusCode::BAD_REQUEST,
-            "BATCH_TOO_LARGE",
-            &format!(
-                "Batch exceeds maximum size of {} items",
-                state.max_cngn_batch_size
-            ),
-        );
+    let bounds = ArrayBounds::new(state.max_cngn_batch_size);
+    if let Err(e) = bounds.check("transfers", &body.transfers) {
+        return e.into_response();
     }
 
     if !is_valid_stellar_address(&body.source_wallet) {
@@ -302,23 +292,9 @@ pub async fn create_fiat_payout_batch(
 ) -> Response {
     info!(count = body.payouts.len(), "Creating fiat payout batch");
 
-    if body.payouts.is_empty() {
-        return error_response(
-            StatusCode::BAD_REQUEST,
-            "EMPTY_BATCH",
-            "Batch must contain at least one payout",
-        );
-    }
-
-    if body.payouts.len() > state.max_fiat_batch_size {
-        return error_response(
-            StatusCode::BAD_REQUEST,
-            "BATCH_TOO_LARGE",
-            &format!(
-                "Batch exceeds maximum size of {} items",
-                state.max_fiat_batch_size
-            ),
-        );
+    let bounds = ArrayBounds::new(state.max_fiat_batch_size);
+    if let Err(e) = bounds.check("payouts", &body.payouts) {
+        return e.into_response();
     }
 
     // Validate all items
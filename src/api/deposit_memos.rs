@@ -0,0 +1,58 @@
+//! Deposit memo ids for exchange-style deposits to a shared address.
+//!
+//! POST /api/deposits/memo — the memo id that credits deposits to the caller's wallet
+//!
+//! The memo id is issued to the wallet in the caller's access token and is the
+//! only way a mapping is created, so a deposit memo always points at the wallet
+//! that asked for it.
+
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+use sqlx::PgPool;
+use std::sync::Arc;
+use tracing::info;
+
+use crate::auth::jwt::TokenClaims;
+use crate::database::deposit_memo_repository::DepositMemoRepository;
+use crate::error::AppError;
+use crate::middleware::error::get_request_id_from_headers;
+
+// ─── State ────────────────────────────────────────────────────────────────────
+
+#[derive(Clone)]
+pub struct DepositMemoState {
+    pub db: Arc<PgPool>,
+}
+
+// ─── Handlers ─────────────────────────────────────────────────────────────────
+
+/// POST /api/deposits/memo
+pub async fn issue_deposit_memo(
+    State(state): State<DepositMemoState>,
+    Extension(claims): Extension<TokenClaims>,
+    headers: HeaderMap,
+) -> Response {
+    let request_id = get_request_id_from_headers(&headers);
+
+    match DepositMemoRepository::new(state.db.as_ref().clone())
+        .issue(&claims.sub)
+        .await
+    {
+        Ok(memo) => {
+            info!(wallet = %memo.wallet_address, memo_id = memo.memo_id, "Deposit memo issued");
+            (StatusCode::OK, Json(memo)).into_response()
+        }
+        Err(e) => {
+            let err: AppError = e.into();
+            match request_id {
+                Some(id) => err.with_request_id(id),
+                None => err,
+            }
+            .into_response()
+        }
+    }
+}
@@ -14,3 +14,5 @@ pub mod admin;
 pub mod batch;
 pub mod key_rotation;
 pub mod developer;
+pub mod deposit_memos;
+pub mod validation;
@@ -5,7 +5,9 @@ use crate::chains::stellar::client::StellarClient;
 use crate::chains::stellar::trustline::CngnTrustlineManager;
 use crate::chains::stellar::types::is_valid_stellar_address;
 use crate::error::{AppError, AppErrorKind, ValidationError};
-use crate::services::exchange_rate::{ConversionDirection, ConversionRequest, ExchangeRateService};
+use crate::services::exchange_rate::{
+    ConversionDirection, ConversionRequest, ExchangeRateError, ExchangeRateService,
+};
 use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
 use chrono::Utc;
 use serde_json::json;
@@ -67,12 +69,15 @@ pub async fn create_quote(
         .exchange_rate_service
         .calculate_conversion(conversion_request)
         .await
-        .map_err(|e| {
-            error!("Failed to fetch exchange rate: {}", e);
-            AppError::new(AppErrorKind::External(crate::error::ExternalError::Timeout {
-                service: "rate_service".to_string(),
-                timeout_secs: 30,
-            }))
+        .map_err(|e| match e {
+            ExchangeRateError::Fee(e) => AppError::from(e),
+            e => {
+                error!("Failed to fetch exchange rate: {}", e);
+                AppError::new(AppErrorKind::External(crate::error::ExternalError::Timeout {
+                    service: "rate_service".to_string(),
+                    timeout_secs: 30,
+                }))
+            }
         })?;
 
     let rate = BigDecimal::from_str(&conversion_result.base_rate)
@@ -0,0 +1,393 @@
+//! Shared shape checks for request input.
+//!
+//! Every batch-style handler bounds its input the same way: empty arrays and
+//! arrays over the endpoint's limit are rejected with a `VALIDATION_ERROR`
+//! naming the field and the limit. Routes with an `{address}` path segment
+//! take `ValidStellarAddress`, which rejects malformed addresses before the
+//! handler (and Horizon) ever sees them.
+
+use std::collections::HashMap;
+
+use axum::{
+    extract::{FromRequestParts, Path},
+    http::{request::Parts, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+use stellar_strkey::ed25519::{MuxedAccount as StrkeyMuxedAccount, PublicKey as StrkeyPublicKey};
+use thiserror::Error;
+
+use crate::error::{AppError, AppErrorKind, ValidationError};
+use crate::middleware::error::{get_request_id_from_headers, ErrorResponse};
+
+/// Limit used when an endpoint has no more specific configuration
+pub const DEFAULT_MAX_ARRAY_LEN: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArrayBounds {
+    pub max_len: usize,
+}
+
+impl Default for ArrayBounds {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_ARRAY_LEN)
+    }
+}
+
+impl ArrayBounds {
+    pub const fn new(max_len: usize) -> Self {
+        Self { max_len }
+    }
+
+    /// Read the limit from `env_key`, falling back to `default` when unset or not a positive integer.
+    pub fn from_env(env_key: &str, default: usize) -> Self {
+        let max_len = std::env::var(env_key)
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok())
+            .filter(|n| *n > 0)
+            .unwrap_or(default);
+        Self::new(max_len)
+    }
+
+    pub fn check<T>(&self, field: &str, items: &[T]) -> Result<(), ArrayBoundsError> {
+        if items.is_empty() {
+            return Err(ArrayBoundsError::Empty {
+                field: field.to_string(),
+            });
+        }
+        if items.len() > self.max_len {
+            return Err(ArrayBoundsError::TooLarge {
+                field: field.to_string(),
+                max_len: self.max_len,
+                actual: items.len(),
+            });
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ArrayBoundsError {
+    #[error("{field} must contain at least one item")]
+    Empty { field: String },
+    #[error("{field} must contain at most {max_len} items (got {actual})")]
+    TooLarge {
+        field: String,
+        max_len: usize,
+        actual: usize,
+    },
+}
+
+impl ArrayBoundsError {
+    pub fn field(&self) -> &str {
+        match self {
+            Self::Empty { field } | Self::TooLarge { field, .. } => field,
+        }
+    }
+
+    pub fn to_error_response(&self, request_id: Option<String>) -> ErrorResponse {
+        let mut details = serde_json::json!({
+            "field": self.field(),
+            "error": self.to_string(),
+        });
+        if let Self::TooLarge {
+            max_len, actual, ..
+        } = self
+        {
+            details["max_items"] = (*max_len).into();
+            details["actual_items"] = (*actual).into();
+        }
+        ErrorResponse::validation_error(request_id, self.field(), &self.to_string())
+            .with_details(details)
+    }
+}
+
+impl IntoResponse for ArrayBoundsError {
+    fn into_response(self) -> Response {
+        (StatusCode::BAD_REQUEST, Json(self.to_error_response(None))).into_response()
+    }
+}
+
+/// `{address}` path parameter whose strkey checksum has been verified
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidStellarAddress {
+    /// G... account id
+    Account(String),
+    /// M... muxed account
+    Muxed(String),
+}
+
+impl ValidStellarAddress {
+    pub fn parse(raw: &str) -> Result<Self, AppError> {
+        let raw = raw.trim();
+        let invalid = |reason: &str| {
+            AppError::new(AppErrorKind::Validation(ValidationError::InvalidWalletAddress {
+                address: raw.to_string(),
+                reason: reason.to_string(),
+            }))
+        };
+
+        match raw.chars().next() {
+            Some('G') => StrkeyPublicKey::from_string(raw)
+                .map(|_| Self::Account(raw.to_string()))
+                .map_err(|_| invalid("not a valid G... account id")),
+            Some('M') => StrkeyMuxedAccount::from_string(raw)
+                .map(|_| Self::Muxed(raw.to_string()))
+                .map_err(|_| invalid("not a valid M... muxed address")),
+            _ => Err(invalid("Stellar addresses start with G or M")),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Account(address) | Self::Muxed(address) => address,
+        }
+    }
+
+    /// G... account behind the address; Horizon's `/accounts` routes only
+    /// accept these
+    pub fn account_id(&self) -> String {
+        match self {
+            Self::Account(address) => address.clone(),
+            Self::Muxed(address) => StrkeyMuxedAccount::from_string(address)
+                .map(|muxed| StrkeyPublicKey(muxed.ed25519).to_string())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl<S> FromRequestParts<S> for ValidStellarAddress
+where
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Path(params) = Path::<HashMap<String, String>>::from_request_parts(parts, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+        let raw = params.get("address").map(String::as_str).unwrap_or_default();
+
+        Self::parse(raw).map_err(|err| {
+            match get_request_id_from_headers(&parts.headers) {
+                Some(request_id) => err.with_request_id(request_id),
+                None => err,
+            }
+            .into_response()
+        })
+    }
+}
+
+/// Length of a G... account strkey
+const ACCOUNT_STRKEY_LEN: usize = 56;
+/// Length of an M... muxed account strkey
+const MUXED_STRKEY_LEN: usize = 69;
+
+/// Why an address failed the offline check, most fundamental problem first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AddressIssue {
+    /// Strkeys are upper-case base32; a lower-cased copy never decodes
+    Lowercase,
+    NotAccountAddress,
+    WrongLength,
+    BadChecksum,
+}
+
+impl AddressIssue {
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::Lowercase => "lowercase",
+            Self::NotAccountAddress => "not a G/M address",
+            Self::WrongLength => "wrong length",
+            Self::BadChecksum => "bad checksum",
+        }
+    }
+}
+
+/// One entry of a batch address check
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AddressCheck {
+    pub address: String,
+    pub valid: bool,
+    /// Set when `valid` is false
+    pub reason: Option<String>,
+}
+
+impl AddressCheck {
+    pub fn of(address: &str) -> Self {
+        let issue = address_issue(address);
+        Self {
+            address: address.to_string(),
+            valid: issue.is_none(),
+            reason: issue.map(|i| i.description().to_string()),
+        }
+    }
+}
+
+/// Offline strkey check of a G... or M... address: no network calls, just
+/// the prefix, length and CRC16 checksum.
+pub fn address_issue(address: &str) -> Option<AddressIssue> {
+    if address.chars().any(|c| c.is_ascii_lowercase()) {
+        return Some(AddressIssue::Lowercase);
+    }
+
+    let (expected_len, decodes) = match address.chars().next() {
+        Some('G') => (ACCOUNT_STRKEY_LEN, StrkeyPublicKey::from_string(address).is_ok()),
+        Some('M') => (MUXED_STRKEY_LEN, StrkeyMuxedAccount::from_string(address).is_ok()),
+        _ => return Some(AddressIssue::NotAccountAddress),
+    };
+    if address.len() != expected_len {
+        return Some(AddressIssue::WrongLength);
+    }
+    if !decodes {
+        return Some(AddressIssue::BadChecksum);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_empty_and_oversized_arrays() {
+        let bounds = ArrayBounds::new(2);
+
+        assert_eq!(
+            bounds.check::<u8>("items", &[]),
+            Err(ArrayBoundsError::Empty {
+                field: "items".to_string()
+            })
+        );
+        assert_eq!(
+            bounds.check("items", &[1, 2, 3]),
+            Err(ArrayBoundsError::TooLarge {
+                field: "items".to_string(),
+                max_len: 2,
+                actual: 3,
+            })
+        );
+        assert!(bounds.check("items", &[1, 2]).is_ok());
+    }
+
+    #[test]
+    fn error_response_names_field_and_limit() {
+        let err = ArrayBoundsError::TooLarge {
+            field: "payouts".to_string(),
+            max_len: 500,
+            actual: 501,
+        };
+        let body = serde_json::to_value(err.to_error_response(None)).unwrap();
+
+        assert_eq!(body["error"], "VALIDATION_ERROR");
+        assert_eq!(body["details"]["field"], "payouts");
+        assert_eq!(body["details"]["max_items"], 500);
+    }
+
+    const ACCOUNT: &str = "GCJRI5CIWK5IU67Q6DGA7QW52JDKRO7JEAHQKFNDUJUPEZGURDBX3LDX";
+
+    async fn get_address(path: &str) -> (StatusCode, serde_json::Value, usize) {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+        use tower::ServiceExt;
+
+        // Stands in for the Horizon call a real handler would make
+        let handler_calls = Arc::new(AtomicUsize::new(0));
+        let calls = handler_calls.clone();
+        let app = axum::Router::new().route(
+            "/accounts/{address}",
+            axum::routing::get(move |address: ValidStellarAddress| async move {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Json(serde_json::json!({
+                    "address": address.as_str(),
+                    "account_id": address.account_id(),
+                }))
+            }),
+        );
+
+        let response = app
+            .oneshot(
+                axum::http::Request::get(path)
+                    .header("x-request-id", "req_addr")
+                    .body(axum::body::Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (
+            status,
+            serde_json::from_slice(&bytes).unwrap(),
+            handler_calls.load(Ordering::SeqCst),
+        )
+    }
+
+    #[tokio::test]
+    async fn invalid_address_is_rejected_before_the_handler() {
+        // Right shape, broken checksum
+        let corrupted = format!("{}A", &ACCOUNT[..55]);
+        for bad in ["not-an-address", corrupted.as_str()] {
+            let (status, body, handler_calls) = get_address(&format!("/accounts/{}", bad)).await;
+
+            assert_eq!(status, StatusCode::BAD_REQUEST);
+            assert_eq!(body["error"], "INVALID_WALLET");
+            assert_eq!(body["request_id"], "req_addr");
+            assert_eq!(handler_calls, 0);
+        }
+    }
+
+    #[tokio::test]
+    async fn valid_account_and_muxed_addresses_pass_through() {
+        let (status, body, handler_calls) = get_address(&format!("/accounts/{}", ACCOUNT)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["address"], ACCOUNT);
+        assert_eq!(handler_calls, 1);
+
+        let muxed = StrkeyMuxedAccount {
+            ed25519: StrkeyPublicKey::from_string(ACCOUNT).unwrap().0,
+            id: 42,
+        }
+        .to_string();
+        let (status, body, _) = get_address(&format!("/accounts/{}", muxed)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["address"], muxed.as_str());
+        assert_eq!(body["account_id"], ACCOUNT);
+    }
+
+    #[test]
+    fn address_check_names_each_failure() {
+        let muxed = StrkeyMuxedAccount {
+            ed25519: StrkeyPublicKey::from_string(ACCOUNT).unwrap().0,
+            id: 7,
+        }
+        .to_string();
+        let corrupted = format!("{}A", &ACCOUNT[..55]);
+        let lowercase = ACCOUNT.to_lowercase();
+        let secret = format!("S{}", &ACCOUNT[1..]);
+        let truncated = &ACCOUNT[..50];
+        let cases = [
+            (ACCOUNT, None),
+            (muxed.as_str(), None),
+            (truncated, Some(AddressIssue::WrongLength)),
+            (&muxed[..60], Some(AddressIssue::WrongLength)),
+            (corrupted.as_str(), Some(AddressIssue::BadChecksum)),
+            (lowercase.as_str(), Some(AddressIssue::Lowercase)),
+            (secret.as_str(), Some(AddressIssue::NotAccountAddress)),
+            ("", Some(AddressIssue::NotAccountAddress)),
+        ];
+
+        for (address, expected) in cases {
+            assert_eq!(address_issue(address), expected, "{}", address);
+        }
+
+        let check = AddressCheck::of(&corrupted);
+        assert!(!check.valid);
+        assert_eq!(check.reason.as_deref(), Some("bad checksum"));
+        assert_eq!(AddressCheck::of(&muxed).reason, None);
+    }
+}
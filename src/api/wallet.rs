@@ -1,4 +1,4 @@
-use crate::services::balance::{BalanceService, WalletBalance};
+use crate::services::balance::{BalanceService, WalletBalance, DATA_FRESHNESS_HEADER};
 use axum::{
     extract::{Query, State},
     http::StatusCode,
@@ -49,7 +49,15 @@ pub async fn get_balance(
         .get_balance(&params.address, params.refresh)
         .await
     {
-        Ok(balance) => (StatusCode::OK, Json(balance)).into_response(),
+        Ok(balance) => {
+            let freshness = balance.freshness.as_str();
+            (
+                StatusCode::OK,
+                [(DATA_FRESHNESS_HEADER, freshness)],
+                Json(balance),
+            )
+                .into_response()
+        }
         Err(e) => handle_error(e, &params.address),
     }
 }
@@ -101,6 +109,27 @@ fn handle_error(error: crate::chains::stellar::errors::StellarError, address: &s
         )
             .into_response(),
 
+        StellarError::ServiceUnavailable { retry_after } => {
+            let mut response = (
+                StatusCode::SERVICE_UNAVAILABLE,
+                Json(ErrorResponse {
+                    error: ErrorDetail {
+                        code: "UPSTREAM_UNAVAILABLE".to_string(),
+                        message: "Stellar Horizon is temporarily unavailable".to_string(),
+                        details: Some("Horizon is under maintenance, please retry later".to_string()),
+                        wallet_address: None,
+                    },
+                }),
+            )
+                .into_response();
+            if let Some(secs) = retry_after {
+                response
+                    .headers_mut()
+                    .insert(axum::http::header::RETRY_AFTER, secs.into());
+            }
+            response
+        }
+
         StellarError::TimeoutError { .. } | StellarError::NetworkError { .. } => {
             error!("Stellar network error: {}", error);
             (
@@ -1,10 +1,10 @@
 use axum::{
+    body::Bytes,
     extract::{Path, State},
-    http::StatusCode,
+    http::{header, StatusCode},
     response::IntoResponse,
     Json,
 };
-use serde_json::Value as JsonValue;
 use std::sync::Arc;
 use tracing::{error, info, warn};
 
@@ -15,11 +15,14 @@ pub struct WebhookState {
 }
 
 /// POST /webhooks/:provider
+///
+/// Takes the body as raw `Bytes`: signature verification needs the exact bytes
+/// the provider signed, so decoding is left to the processor.
 pub async fn handle_webhook(
     State(state): State<Arc<WebhookState>>,
     Path(provider): Path<String>,
     headers: axum::http::HeaderMap,
-    body: String,
+    body: Bytes,
 ) -> impl IntoResponse {
     info!(provider = %provider, "Received webhook");
 
@@ -41,19 +44,14 @@ pub async fn handle_webhook(
         return (StatusCode::UNAUTHORIZED, "Missing signature").into_response();
     }
 
-    // Parse payload
-    let payload: JsonValue = match serde_json::from_str(&body) {
-        Ok(p) => p,
-        Err(e) => {
-            error!(provider = %provider, error = %e, "Invalid JSON payload");
-            return (StatusCode::BAD_REQUEST, "Invalid JSON").into_response();
-        }
-    };
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok());
 
     // Process webhook
     match state
         .processor
-        .process_webhook(&provider, signature.as_deref(), &payload)
+        .process_webhook(&provider, signature.as_deref(), content_type, &body)
         .await
     {
         Ok(_) => {
@@ -64,6 +62,18 @@ pub async fn handle_webhook(
             warn!(provider = %provider, "Invalid webhook signature");
             (StatusCode::UNAUTHORIZED, "Invalid signature").into_response()
         }
+        Err(WebhookProcessorError::UnsupportedContentType(content_type)) => {
+            warn!(
+                provider = %provider,
+                content_type = %content_type,
+                "Unsupported webhook content type"
+            );
+            (StatusCode::UNSUPPORTED_MEDIA_TYPE, "Unsupported content type").into_response()
+        }
+        Err(WebhookProcessorError::InvalidPayload(e)) => {
+            error!(provider = %provider, error = %e, "Invalid webhook payload");
+            (StatusCode::BAD_REQUEST, "Invalid payload").into_response()
+        }
         Err(WebhookProcessorError::AlreadyProcessed) => {
             info!(provider = %provider, "Webhook already processed");
             (StatusCode::OK, Json(serde_json::json!({"status": "ok"}))).into_response()
@@ -189,8 +189,10 @@ pub fn validate_token(token: &str, jwt_secret: &str) -> Result<TokenClaims, JwtE
 
     let claims = token_data.claims;
 
-    // Manual expiry check (we disabled library-level check for typed errors)
-    if claims.exp < Utc::now().timestamp() {
+    // Manual expiry check (we disabled library-level check for typed errors),
+    // allowing for clock skew with the issuer
+    let tolerance = crate::clock_skew::tolerance_secs();
+    if crate::clock_skew::is_expired(claims.exp, Utc::now().timestamp(), tolerance) {
         return Err(JwtError::TokenExpired);
     }
 
@@ -405,6 +407,23 @@ mod tests {
         assert!(matches!(result, Err(JwtError::TokenExpired)));
     }
 
+    #[test]
+    fn test_just_expired_token_accepted_within_clock_skew() {
+        use jsonwebtoken::{encode, Header};
+        let now = Utc::now().timestamp();
+        let claims = TokenClaims {
+            sub: "GTEST".to_string(),
+            iat: now - 900,
+            exp: now - 5, // expired by less than the default skew tolerance
+            token_type: TokenType::Access,
+            scope: Scope::User,
+            session_id: "s".to_string(),
+            jti: None,
+        };
+        let token = encode(&Header::default(), &claims, &encoding_key(SECRET)).unwrap();
+        assert!(validate_token(&token, SECRET).is_ok());
+    }
+
     #[test]
     fn test_admin_scope() {
         let (token, _) =
@@ -129,9 +129,9 @@ impl OAuthTokenValidator {
             return Err(TokenValidationError::TokenAudienceMismatch);
         }
 
-        // Validate expiry
+        // Validate expiry, allowing for clock skew with the issuer
         let now = Utc::now().timestamp();
-        if claims.exp < now {
+        if crate::clock_skew::is_expired(claims.exp, now, crate::clock_skew::tolerance_secs()) {
             return Err(TokenValidationError::TokenExpired);
         }
 
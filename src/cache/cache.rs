@@ -81,38 +81,43 @@ impl RedisCache {
 #[async_trait]
 impl<T: Serialize + DeserializeOwned + Send + Sync + 'static> Cache<T> for RedisCache {
     async fn get(&self, key: &str) -> CacheResult<Option<T>> {
+        use crate::metrics::cache::{record_error, record_hit, record_miss, CacheName};
+
         let _timer = crate::metrics::cache::operation_duration_seconds()
             .with_label_values(&["get"])
             .start_timer();
+        let cache = CacheName::from_key(key);
         let mut conn = match self.get_connection().await {
             Ok(conn) => conn,
-            Err(_) => return Ok(None), // Graceful degradation
+            Err(_) => {
+                record_error(cache);
+                return Ok(None); // Graceful degradation
+            }
         };
 
         let result: Option<String> = conn.get(key).await.map_err(|e| {
             warn!("Redis GET failed for key '{}': {}", key, e);
+            record_error(cache);
             e
         })?;
 
         match result {
-            Some(json_str) => {
-                let value: T = serde_json::from_str(&json_str)
-                    .map_err(|e| {
-                        warn!("Failed to deserialize cache value for key '{}': {}", key, e);
-                        <serde_json::Error as Into<CacheError>>::into(e)
-                    })
-                    .unwrap();
-                debug!("Cache hit for key: {}", key);
-                crate::metrics::cache::hits_total()
-                    .with_label_values(&[crate::metrics::key_prefix(key)])
-                    .inc();
-                Ok(Some(value))
-            }
+            Some(json_str) => match serde_json::from_str::<T>(&json_str) {
+                Ok(value) => {
+                    debug!("Cache hit for key: {}", key);
+                    record_hit(cache);
+                    Ok(Some(value))
+                }
+                Err(e) => {
+                    // Treat an undecodable entry (e.g. an older schema) as absent
+                    warn!("Failed to deserialize cache value for key '{}': {}", key, e);
+                    record_error(cache);
+                    Ok(None)
+                }
+            },
             None => {
                 debug!("Cache miss for key: {}", key);
-                crate::metrics::cache::misses_total()
-                    .with_label_values(&[crate::metrics::key_prefix(key)])
-                    .inc();
+                record_miss(cache);
                 Ok(None)
             }
         }
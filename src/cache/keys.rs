@@ -28,6 +28,27 @@ pub mod wallet {
         }
     }
 
+    /// Longer-lived copy of the last balance fetched from Horizon, served
+    /// when Horizon is unavailable
+    #[derive(Debug, Clone)]
+    pub struct LastKnownBalanceKey {
+        pub address: String,
+    }
+
+    impl LastKnownBalanceKey {
+        pub fn new(address: impl Into<String>) -> Self {
+            Self {
+                address: address.into(),
+            }
+        }
+    }
+
+    impl fmt::Display for LastKnownBalanceKey {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}:{}:balance:last_known:{}", VERSION, NAMESPACE, self.address)
+        }
+    }
+
     #[derive(Debug, Clone)]
     pub struct TrustlineKey {
         pub address: String,
@@ -65,6 +86,46 @@ pub mod wallet {
             write!(f, "{}:{}:tx_count:{}", VERSION, NAMESPACE, self.address)
         }
     }
+
+    /// Aggregated balance + recent activity served by the wallet overview endpoint
+    #[derive(Debug, Clone)]
+    pub struct OverviewKey {
+        pub address: String,
+    }
+
+    impl OverviewKey {
+        pub fn new(address: impl Into<String>) -> Self {
+            Self {
+                address: address.into(),
+            }
+        }
+    }
+
+    impl fmt::Display for OverviewKey {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}:{}:overview:{}", VERSION, NAMESPACE, self.address)
+        }
+    }
+
+    /// Horizon account record; shared by concurrent lookups of a hot account
+    #[derive(Debug, Clone)]
+    pub struct AccountKey {
+        pub address: String,
+    }
+
+    impl AccountKey {
+        pub fn new(address: impl Into<String>) -> Self {
+            Self {
+                address: address.into(),
+            }
+        }
+    }
+
+    impl fmt::Display for AccountKey {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}:{}:account:{}", VERSION, NAMESPACE, self.address)
+        }
+    }
 }
 
 pub mod exchange_rate {
@@ -175,6 +236,90 @@ pub mod transaction {
             write!(f, "{}:{}:recent:{}", VERSION, NAMESPACE, self.address)
         }
     }
+
+    /// Result of submitting the transaction with this source and sequence,
+    /// kept to answer client retries
+    #[derive(Debug, Clone)]
+    pub struct SubmissionKey {
+        pub source: String,
+        pub sequence: i64,
+    }
+
+    impl SubmissionKey {
+        pub fn new(source: impl Into<String>, sequence: i64) -> Self {
+            Self {
+                source: source.into(),
+                sequence,
+            }
+        }
+    }
+
+    impl fmt::Display for SubmissionKey {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(
+                f,
+                "{}:{}:submission:{}:{}",
+                VERSION, NAMESPACE, self.source, self.sequence
+            )
+        }
+    }
+
+    /// Held while the transaction with this source and sequence is being
+    /// submitted, so concurrent retries don't both reach Horizon
+    #[derive(Debug, Clone)]
+    pub struct SubmissionInFlightKey {
+        pub source: String,
+        pub sequence: i64,
+    }
+
+    impl SubmissionInFlightKey {
+        pub fn new(source: impl Into<String>, sequence: i64) -> Self {
+            Self {
+                source: source.into(),
+                sequence,
+            }
+        }
+    }
+
+    impl fmt::Display for SubmissionInFlightKey {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(
+                f,
+                "{}:{}:submission_in_flight:{}:{}",
+                VERSION, NAMESPACE, self.source, self.sequence
+            )
+        }
+    }
+
+    /// Platform-wide submitted volume for one asset in one UTC hour; the daily
+    /// limit sums the last 24 of these
+    #[derive(Debug, Clone)]
+    pub struct VolumeBucketKey {
+        pub asset: String,
+        pub hour: chrono::DateTime<chrono::Utc>,
+    }
+
+    impl VolumeBucketKey {
+        pub fn new(asset: impl Into<String>, hour: chrono::DateTime<chrono::Utc>) -> Self {
+            Self {
+                asset: asset.into(),
+                hour,
+            }
+        }
+    }
+
+    impl fmt::Display for VolumeBucketKey {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(
+                f,
+                "{}:{}:daily_volume:{}:{}",
+                VERSION,
+                NAMESPACE,
+                self.asset,
+                self.hour.format("%Y%m%d%H")
+            )
+        }
+    }
 }
 
 pub mod auth {
@@ -409,6 +554,12 @@ mod tests {
         assert_eq!(key.to_string(), "v1:wallet:balance:GA123456789");
     }
 
+    #[test]
+    fn test_wallet_last_known_balance_key() {
+        let key = wallet::LastKnownBalanceKey::new("GA123456789");
+        assert_eq!(key.to_string(), "v1:wallet:balance:last_known:GA123456789");
+    }
+
     #[test]
     fn test_exchange_rate_key() {
         let key = exchange_rate::CurrencyPairKey::cngn_rate("USD");
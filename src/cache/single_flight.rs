@@ -6,15 +6,18 @@
 
 use std::collections::HashMap;
 use std::future::Future;
-use std::sync::Arc;
-use tokio::sync::{Mutex, broadcast};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use tokio::sync::broadcast;
 use tracing::{debug, info};
 
 type SharedResult<T> = Arc<Result<T, String>>;
+type InFlight<T> = HashMap<String, broadcast::Sender<SharedResult<T>>>;
 
 /// A map of in-flight rebuild operations keyed by cache key.
 pub struct SingleFlight<T: Clone + Send + 'static> {
-    in_flight: Mutex<HashMap<String, broadcast::Sender<SharedResult<T>>>>,
+    // Never held across an await, so a std mutex is enough and lets the
+    // leader guard clean up from `Drop`
+    in_flight: Mutex<InFlight<T>>,
 }
 
 impl<T: Clone + Send + 'static> SingleFlight<T> {
@@ -24,6 +27,10 @@ impl<T: Clone + Send + 'static> SingleFlight<T> {
         })
     }
 
+    fn lock(&self) -> MutexGuard<'_, InFlight<T>> {
+        self.in_flight.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
     /// Execute `rebuild` for `key`, or wait for an in-flight rebuild to finish.
     ///
     /// Returns `Ok(value)` on success, `Err(msg)` if the rebuild failed.
@@ -32,43 +39,66 @@ impl<T: Clone + Send + 'static> SingleFlight<T> {
         F: FnOnce() -> Fut,
         Fut: Future<Output = Result<T, String>>,
     {
-        {
-            let map = self.in_flight.lock().await;
-
-            if let Some(tx) = map.get(key) {
-                // Another request is already rebuilding — subscribe and wait.
-                let mut rx = tx.subscribe();
-                drop(map); // release lock before awaiting
+        // Checking for a leader and becoming one happen under one lock, so
+        // two callers can never both lead the same key.
+        let role = {
+            let mut map = self.lock();
+            match map.get(key) {
+                Some(tx) => Err(tx.subscribe()),
+                None => {
+                    let (tx, _rx) = broadcast::channel::<SharedResult<T>>(1);
+                    map.insert(key.to_string(), tx.clone());
+                    Ok(tx)
+                }
+            }
+        };
 
+        let tx = match role {
+            Ok(tx) => tx,
+            Err(mut rx) => {
                 debug!(key, "single-flight: waiting for in-flight rebuild");
-                match rx.recv().await {
-                    Ok(result) => {
-                        return (*result).clone().map_err(|e| e.clone());
-                    }
-                    Err(_) => {
-                        // Sender dropped without sending — treat as miss and rebuild.
-                    }
-                }
+                return match rx.recv().await {
+                    Ok(result) => (*result).clone(),
+                    // The leader was cancelled before finishing
+                    Err(_) => rebuild().await,
+                };
             }
-        }
+        };
 
-        // We are the leader for this key.
-        let (tx, _rx) = broadcast::channel::<SharedResult<T>>(1);
-        let mut map = self.in_flight.lock().await;
-        map.insert(key.to_string(), tx.clone());
-        drop(map); // release lock before doing the expensive rebuild
+        let mut guard = LeaderGuard {
+            flight: self,
+            key,
+            armed: true,
+        };
 
         info!(key, "single-flight: leader rebuilding cache entry");
         let result = rebuild().await;
-        let shared: SharedResult<T> = Arc::new(result.clone().map_err(|e| e.to_string()));
 
-        // Broadcast result to all waiters (ignore send errors — no subscribers is fine).
-        let _ = tx.send(shared);
-
-        // Remove from in-flight map.
-        let mut map = self.in_flight.lock().await;
+        // Retire the entry and publish under the lock: every waiter that found
+        // the entry subscribed before this point and so receives the result.
+        guard.armed = false;
+        let mut map = self.lock();
         map.remove(key);
+        // No subscribers is fine
+        let _ = tx.send(Arc::new(result.clone()));
+        drop(map);
 
         result
     }
 }
+
+/// Removes the leader's entry if its rebuild is dropped part-way, so waiters
+/// see the channel close instead of hanging and later callers can lead again.
+struct LeaderGuard<'a, T: Clone + Send + 'static> {
+    flight: &'a SingleFlight<T>,
+    key: &'a str,
+    armed: bool,
+}
+
+impl<T: Clone + Send + 'static> Drop for LeaderGuard<'_, T> {
+    fn drop(&mut self) {
+        if self.armed {
+            self.flight.lock().remove(self.key);
+        }
+    }
+}
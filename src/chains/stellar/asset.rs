@@ -0,0 +1,305 @@
+//! Validated Stellar asset identifiers
+//!
+//! Every code path that names an asset (trustlines, payments, balance lookups)
+//! goes through `StellarAsset`, so a code/issuer pair is checked once, at
+//! construction, instead of ad hoc wherever the strings are used.
+
+use crate::chains::stellar::errors::{StellarError, StellarResult};
+use crate::chains::stellar::types::AssetBalance;
+use std::fmt;
+use std::str::FromStr;
+use stellar_strkey::ed25519::PublicKey as StrkeyPublicKey;
+use stellar_xdr::next::{
+    AccountId, AlphaNum12, AlphaNum4, Asset, AssetCode12, AssetCode4, ChangeTrustAsset,
+    PublicKey, Uint256,
+};
+
+const MAX_ALPHANUM4_LEN: usize = 4;
+const MAX_ALPHANUM12_LEN: usize = 12;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StellarAsset(AssetKind);
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum AssetKind {
+    Native,
+    Credit {
+        code: String,
+        issuer: String,
+        issuer_key: [u8; 32],
+    },
+}
+
+impl StellarAsset {
+    /// XLM, the network's native asset
+    pub fn native() -> Self {
+        Self(AssetKind::Native)
+    }
+
+    /// An issued asset. `code` must be 1–12 ASCII letters or digits and
+    /// `issuer` a valid `G...` account strkey.
+    pub fn credit(code: &str, issuer: &str) -> StellarResult<Self> {
+        let code = code.trim();
+        if code.is_empty() || code.len() > MAX_ALPHANUM12_LEN {
+            return Err(StellarError::invalid_asset(
+                code,
+                "asset code must be 1 to 12 characters",
+            ));
+        }
+        if !code.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(StellarError::invalid_asset(
+                code,
+                "asset code may only contain ASCII letters and digits",
+            ));
+        }
+
+        let issuer = issuer.trim();
+        let issuer_key = StrkeyPublicKey::from_string(issuer)
+            .map_err(|_| StellarError::invalid_address(issuer))?;
+
+        Ok(Self(AssetKind::Credit {
+            code: code.to_string(),
+            issuer: issuer.to_string(),
+            issuer_key: issuer_key.0,
+        }))
+    }
+
+    pub fn is_native(&self) -> bool {
+        matches!(self.0, AssetKind::Native)
+    }
+
+    /// Asset code as configured; `XLM` for the native asset.
+    pub fn code(&self) -> &str {
+        match &self.0 {
+            AssetKind::Native => "XLM",
+            AssetKind::Credit { code, .. } => code,
+        }
+    }
+
+    pub fn issuer(&self) -> Option<&str> {
+        match &self.0 {
+            AssetKind::Native => None,
+            AssetKind::Credit { issuer, .. } => Some(issuer),
+        }
+    }
+
+    /// Horizon `asset_type` for this asset.
+    pub fn to_alphanum_type(&self) -> &'static str {
+        match &self.0 {
+            AssetKind::Native => "native",
+            AssetKind::Credit { code, .. } if code.len() <= MAX_ALPHANUM4_LEN => {
+                "credit_alphanum4"
+            }
+            AssetKind::Credit { .. } => "credit_alphanum12",
+        }
+    }
+
+    /// Canonical Horizon form: `native` or `CODE:ISSUER`.
+    pub fn to_horizon_string(&self) -> String {
+        self.to_string()
+    }
+
+    /// Whether a Horizon balance line refers to this asset. Codes compare
+    /// case-insensitively, as Horizon echoes the on-ledger (upper-case) code.
+    pub fn matches_balance(&self, balance: &AssetBalance) -> bool {
+        match &self.0 {
+            AssetKind::Native => balance.asset_type == "native",
+            AssetKind::Credit { code, issuer, .. } => {
+                matches!(
+                    balance.asset_type.as_str(),
+                    "credit_alphanum4" | "credit_alphanum12"
+                ) && balance
+                    .asset_code
+                    .as_deref()
+                    .is_some_and(|c| c.eq_ignore_ascii_case(code))
+                    && balance.asset_issuer.as_deref() == Some(issuer.as_str())
+            }
+        }
+    }
+
+    /// The account's balance line for this asset, if it holds one.
+    pub fn find_balance<'a>(&self, balances: &'a [AssetBalance]) -> Option<&'a AssetBalance> {
+        balances.iter().find(|b| self.matches_balance(b))
+    }
+
+    pub fn to_xdr_asset(&self) -> Asset {
+        match &self.0 {
+            AssetKind::Native => Asset::Native,
+            AssetKind::Credit {
+                code, issuer_key, ..
+            } => {
+                let issuer = account_id(issuer_key);
+                let bytes = xdr_code_bytes(code);
+                if code.len() <= MAX_ALPHANUM4_LEN {
+                    let mut code4 = [0u8; 4];
+                    code4[..bytes.len()].copy_from_slice(&bytes);
+                    Asset::CreditAlphanum4(AlphaNum4 {
+                        asset_code: AssetCode4(code4),
+                        issuer,
+                    })
+                } else {
+                    let mut code12 = [0u8; 12];
+                    code12[..bytes.len()].copy_from_slice(&bytes);
+                    Asset::CreditAlphanum12(AlphaNum12 {
+                        asset_code: AssetCode12(code12),
+                        issuer,
+                    })
+                }
+            }
+        }
+    }
+
+    /// Inverse of `to_xdr_asset`, for decoding envelopes built elsewhere.
+    pub fn from_xdr_asset(asset: &Asset) -> StellarResult<Self> {
+        let (code_bytes, issuer): (&[u8], &AccountId) = match asset {
+            Asset::Native => return Ok(Self::native()),
+            Asset::CreditAlphanum4(a) => (&a.asset_code.0, &a.issuer),
+            Asset::CreditAlphanum12(a) => (&a.asset_code.0, &a.issuer),
+        };
+        let code_len = code_bytes
+            .iter()
+            .position(|b| *b == 0)
+            .unwrap_or(code_bytes.len());
+        let code = std::str::from_utf8(&code_bytes[..code_len])
+            .map_err(|_| StellarError::invalid_asset("?", "asset code is not valid UTF-8"))?;
+        let AccountId(PublicKey::PublicKeyTypeEd25519(Uint256(key))) = issuer;
+        Self::credit(code, &StrkeyPublicKey(*key).to_string())
+    }
+
+    pub fn to_change_trust_asset(&self) -> StellarResult<ChangeTrustAsset> {
+        match self.to_xdr_asset() {
+            Asset::Native => Err(StellarError::invalid_asset(
+                "XLM",
+                "cannot create a trustline to the native asset",
+            )),
+            Asset::CreditAlphanum4(a) => Ok(ChangeTrustAsset::CreditAlphanum4(a)),
+            Asset::CreditAlphanum12(a) => Ok(ChangeTrustAsset::CreditAlphanum12(a)),
+        }
+    }
+}
+
+impl fmt::Display for StellarAsset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.0 {
+            AssetKind::Native => f.write_str("native"),
+            AssetKind::Credit { code, issuer, .. } => write!(f, "{}:{}", code, issuer),
+        }
+    }
+}
+
+impl FromStr for StellarAsset {
+    type Err = StellarError;
+
+    /// Parses the Horizon form produced by `to_horizon_string`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("native") {
+            return Ok(Self::native());
+        }
+        let (code, issuer) = s
+            .split_once(':')
+            .ok_or_else(|| StellarError::invalid_asset(s, "expected `native` or `CODE:ISSUER`"))?;
+        Self::credit(code, issuer)
+    }
+}
+
+// Asset codes have always been submitted upper-cased by this service, so
+// trustlines and payments keep targeting the same on-ledger asset.
+fn xdr_code_bytes(code: &str) -> Vec<u8> {
+    code.to_ascii_uppercase().into_bytes()
+}
+
+fn account_id(key: &[u8; 32]) -> AccountId {
+    AccountId(PublicKey::PublicKeyTypeEd25519(Uint256(*key)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ISSUER: &str = "GCJRI5CIWK5IU67Q6DGA7QW52JDKRO7JEAHQKFNDUJUPEZGURDBX3LDX";
+
+    #[test]
+    fn native_asset() {
+        let asset = StellarAsset::native();
+
+        assert!(asset.is_native());
+        assert_eq!(asset.issuer(), None);
+        assert_eq!(asset.to_alphanum_type(), "native");
+        assert_eq!(asset.to_horizon_string(), "native");
+        assert_eq!(asset.to_xdr_asset(), Asset::Native);
+        assert!(asset.to_change_trust_asset().is_err());
+    }
+
+    #[test]
+    fn alphanum4_asset() {
+        let asset = StellarAsset::credit("cNGN", ISSUER).unwrap();
+
+        assert_eq!(asset.code(), "cNGN");
+        assert_eq!(asset.issuer(), Some(ISSUER));
+        assert_eq!(asset.to_alphanum_type(), "credit_alphanum4");
+        assert_eq!(asset.to_horizon_string(), format!("cNGN:{}", ISSUER));
+        match asset.to_xdr_asset() {
+            Asset::CreditAlphanum4(a) => assert_eq!(&a.asset_code.0, b"CNGN"),
+            other => panic!("unexpected asset {:?}", other),
+        }
+    }
+
+    #[test]
+    fn alphanum12_asset() {
+        let asset = StellarAsset::credit("NAIRABOND01", ISSUER).unwrap();
+
+        assert_eq!(asset.to_alphanum_type(), "credit_alphanum12");
+        match asset.to_change_trust_asset().unwrap() {
+            ChangeTrustAsset::CreditAlphanum12(a) => {
+                assert_eq!(&a.asset_code.0[..11], b"NAIRABOND01");
+                assert_eq!(a.asset_code.0[11], 0);
+            }
+            other => panic!("unexpected asset {:?}", other),
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_codes() {
+        for code in ["", "   ", "THIRTEENCHARS", "cN-GN", "NGN€"] {
+            assert!(
+                matches!(
+                    StellarAsset::credit(code, ISSUER),
+                    Err(StellarError::InvalidAsset { .. })
+                ),
+                "{code:?} accepted"
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_issuer() {
+        assert!(matches!(
+            StellarAsset::credit("cNGN", "GCNGN_TESTNET_ISSUER_PLACEHOLDER"),
+            Err(StellarError::InvalidAddress { .. })
+        ));
+        assert!(matches!(
+            StellarAsset::credit("cNGN", "SBAD"),
+            Err(StellarError::InvalidAddress { .. })
+        ));
+    }
+
+    #[test]
+    fn xdr_asset_round_trips() {
+        let asset = StellarAsset::credit("NAIRABOND01", ISSUER).unwrap();
+
+        let decoded = StellarAsset::from_xdr_asset(&asset.to_xdr_asset()).unwrap();
+        assert_eq!(decoded.to_horizon_string(), format!("NAIRABOND01:{}", ISSUER));
+        assert!(StellarAsset::from_xdr_asset(&Asset::Native)
+            .unwrap()
+            .is_native());
+    }
+
+    #[test]
+    fn horizon_string_round_trips() {
+        let asset = StellarAsset::credit("USDC", ISSUER).unwrap();
+
+        assert_eq!(asset.to_horizon_string().parse::<StellarAsset>().unwrap(), asset);
+        assert!("native".parse::<StellarAsset>().unwrap().is_native());
+        assert!("USDC".parse::<StellarAsset>().is_err());
+    }
+}
@@ -1,23 +1,34 @@
 use crate::chains::stellar::{
     config::StellarConfig,
     errors::{StellarError, StellarResult},
+    eta::{FeeStats, LedgerRecord},
+    problem::HorizonProblem,
     types::{
         extract_afri_balance, extract_asset_balance, extract_cngn_balance,
-        is_valid_stellar_address, HealthStatus, HorizonAccount, StellarAccountInfo,
+        is_valid_stellar_address, AfriBalanceStatus, HealthStatus, HorizonAccount,
+        IssuerFlags, StellarAccountInfo,
     },
 };
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, PoisonError};
 use std::time::{Duration, Instant};
 use tokio::time::timeout;
 use tracing::{debug, error, info, warn};
 
+/// Issuer flags change rarely (and `auth_immutable` issuers never), so a
+/// few minutes of staleness is fine
+const ISSUER_FLAGS_TTL: Duration = Duration::from_secs(300);
+
 #[allow(dead_code)]
 #[derive(Debug, Clone)]
 pub struct StellarClient {
     http_client: Client,
     config: StellarConfig,
+    /// Shared by clones, keyed by issuer address
+    issuer_flags: Arc<Mutex<HashMap<String, (Instant, IssuerFlags)>>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -42,6 +53,47 @@ pub struct HorizonTransactionsPage {
     pub records: Vec<HorizonTransactionRecord>,
 }
 
+/// A payment-like operation from `/accounts/{id}/payments`, with the memo
+/// of its parent transaction attached.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaymentRecord {
+    pub id: String,
+    pub paging_token: Option<String>,
+    #[serde(rename = "type")]
+    pub payment_type: String,
+    pub created_at: Option<String>,
+    pub transaction_hash: String,
+    #[serde(default)]
+    pub from: Option<String>,
+    #[serde(default)]
+    pub to: Option<String>,
+    #[serde(default)]
+    pub amount: Option<String>,
+    #[serde(default)]
+    pub asset_type: Option<String>,
+    #[serde(default)]
+    pub asset_code: Option<String>,
+    #[serde(default)]
+    pub asset_issuer: Option<String>,
+    /// `None` when the transaction carries no memo
+    #[serde(default)]
+    pub memo: Option<String>,
+    #[serde(default)]
+    pub memo_type: Option<String>,
+    /// The transaction's memo couldn't be fetched, so `memo` and `memo_type`
+    /// are unknown rather than empty
+    #[serde(default)]
+    pub memo_unavailable: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HorizonPaymentsPage {
+    pub records: Vec<PaymentRecord>,
+    /// Records Horizon returned that couldn't be parsed and were left out
+    #[serde(default)]
+    pub skipped: usize,
+}
+
 #[allow(dead_code)]
 impl StellarClient {
     pub fn new(config: StellarConfig) -> StellarResult<Self> {
@@ -67,6 +119,7 @@ impl StellarClient {
         Ok(Self {
             http_client,
             config,
+            issuer_flags: Arc::new(Mutex::new(HashMap::new())),
         })
     }
 
@@ -77,34 +130,13 @@ impl StellarClient {
 
         debug!("Fetching account details for address: {}", address);
 
-        let url = format!("{}/accounts/{}", self.config.horizon_url(), address);
-
-        let response = timeout(
-            self.config.request_timeout,
-            self.http_client.get(&url).send(),
+        let response = self.horizon_get(&format!("/accounts/{}", address)).await?;
+        let response = ensure_success(
+            response,
+            || StellarError::account_not_found(address),
+            StellarError::network_error,
         )
-        .await
-        .map_err(|_| StellarError::timeout_error(self.config.request_timeout.as_secs()))?;
-
-        let response = response.map_err(|e| {
-            if e.status() == Some(reqwest::StatusCode::NOT_FOUND) {
-                StellarError::account_not_found(address)
-            } else if e.status() == Some(reqwest::StatusCode::TOO_MANY_REQUESTS) {
-                StellarError::RateLimitError
-            } else {
-                StellarError::network_error(format!("Horizon API error: {}", e))
-            }
-        })?;
-
-        let response = response.error_for_status().map_err(|e: reqwest::Error| {
-            if e.status() == Some(reqwest::StatusCode::NOT_FOUND) {
-                StellarError::account_not_found(address)
-            } else if e.status() == Some(reqwest::StatusCode::TOO_MANY_REQUESTS) {
-                StellarError::RateLimitError
-            } else {
-                StellarError::network_error(format!("Horizon API error: {}", e))
-            }
-        })?;
+        .await?;
 
         let account_result: HorizonAccount = response
             .json()
@@ -117,6 +149,36 @@ impl StellarClient {
         Ok(account_info)
     }
 
+    /// Flags of the configured AFRI issuer on this client's network.
+    pub async fn get_issuer_flags(&self) -> StellarResult<IssuerFlags> {
+        let config = crate::chains::stellar::trustline::CngnAssetConfig::from_env();
+        self.get_issuer_flags_for(config.issuer_for_network(self.network()))
+            .await
+    }
+
+    /// Flags of `issuer`, served from a short-lived in-process cache.
+    pub async fn get_issuer_flags_for(&self, issuer: &str) -> StellarResult<IssuerFlags> {
+        let cached = self
+            .issuer_flags
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(issuer)
+            .filter(|(fetched_at, _)| fetched_at.elapsed() < ISSUER_FLAGS_TTL)
+            .map(|(_, flags)| flags.clone());
+        if let Some(flags) = cached {
+            debug!("Issuer flags cache hit for {}", issuer);
+            return Ok(flags);
+        }
+
+        let account = self.get_account(issuer).await?;
+        let flags = IssuerFlags::new(issuer, account.flags);
+        self.issuer_flags
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(issuer.to_string(), (Instant::now(), flags.clone()));
+        Ok(flags)
+    }
+
     pub async fn account_exists(&self, address: &str) -> StellarResult<bool> {
         if !is_valid_stellar_address(address) {
             return Err(StellarError::invalid_address(address));
@@ -167,14 +229,15 @@ impl StellarClient {
         Ok(balances)
     }
 
-    pub async fn get_afri_balance(&self, address: &str) -> StellarResult<Option<String>> {
+    pub async fn get_afri_balance(&self, address: &str) -> StellarResult<AfriBalanceStatus> {
         let account = self.get_account(address).await?;
         let afri_balance = extract_afri_balance(&account.balances);
 
         debug!(
-            "AFRI balance for address {}: {}",
+            "AFRI balance for address {}: {} (trustline: {})",
             address,
-            afri_balance.as_deref().unwrap_or("None")
+            afri_balance.balance.as_deref().unwrap_or("None"),
+            afri_balance.has_trustline
         );
 
         Ok(afri_balance)
@@ -211,34 +274,13 @@ impl StellarClient {
     pub async fn get_transaction_details(&self, tx_hash: &str) -> StellarResult<HorizonTransactionRecord> {
         debug!("Fetching transaction details for hash: {}", tx_hash);
 
-        let url = format!("{}/transactions/{}", self.config.horizon_url(), tx_hash);
-
-        let response = timeout(
-            self.config.request_timeout,
-            self.http_client.get(&url).send(),
+        let response = self.horizon_get(&format!("/transactions/{}", tx_hash)).await?;
+        let response = ensure_success(
+            response,
+            || StellarError::transaction_not_found(tx_hash),
+            StellarError::network_error,
         )
-        .await
-        .map_err(|_| StellarError::timeout_error(self.config.request_timeout.as_secs()))?;
-
-        let response = response.map_err(|e| {
-            if e.status() == Some(reqwest::StatusCode::NOT_FOUND) {
-                StellarError::transaction_not_found(tx_hash)
-            } else if e.status() == Some(reqwest::StatusCode::TOO_MANY_REQUESTS) {
-                StellarError::RateLimitError
-            } else {
-                StellarError::network_error(format!("Horizon API error: {}", e))
-            }
-        })?;
-
-        let response = response.error_for_status().map_err(|e: reqwest::Error| {
-            if e.status() == Some(reqwest::StatusCode::NOT_FOUND) {
-                StellarError::transaction_not_found(tx_hash)
-            } else if e.status() == Some(reqwest::StatusCode::TOO_MANY_REQUESTS) {
-                StellarError::RateLimitError
-            } else {
-                StellarError::network_error(format!("Horizon API error: {}", e))
-            }
-        })?;
+        .await?;
 
         let transaction: HorizonTransactionRecord = response
             .json()
@@ -284,6 +326,10 @@ impl StellarClient {
                     error_message: None,
                 })
             }
+            Ok(Ok(response)) if response.status() == reqwest::StatusCode::SERVICE_UNAVAILABLE => {
+                warn!("Stellar Horizon health check: Horizon reports 503, treating as degraded");
+                Err(StellarError::service_unavailable(retry_after_secs(&response)))
+            }
             Ok(Ok(response)) => {
                 let error_msg = format!("HTTP status: {}", response.status());
                 error!("Stellar Horizon health check failed: {}", error_msg);
@@ -334,43 +380,33 @@ impl StellarClient {
         &self.config.network
     }
 
+    /// Fails over like every other Horizon call. Resubmitting after a timeout
+    /// is safe: the envelope's sequence number lets it apply at most once, and
+    /// Horizon answers for a transaction already in its history.
     pub async fn submit_transaction_xdr(&self, xdr_base64: &str) -> StellarResult<JsonValue> {
-        let url = format!("{}/transactions", self.config.horizon_url());
-
-        let response = timeout(
-            self.config.request_timeout,
-            self.http_client
-                .post(&url)
-                .header(
-                    reqwest::header::CONTENT_TYPE,
-                    "application/x-www-form-urlencoded",
-                )
-                .body(format!("tx={}", encode_form_component(xdr_base64)))
-                .send(),
-        )
-        .await
-        .map_err(|_| StellarError::timeout_error(self.config.request_timeout.as_secs()))?;
-
-        let response = response.map_err(|e| {
-            if e.status() == Some(reqwest::StatusCode::TOO_MANY_REQUESTS) {
-                StellarError::RateLimitError
-            } else {
-                StellarError::network_error(format!("Horizon submit error: {}", e))
-            }
-        })?;
+        let form = format!("tx={}", encode_form_component(xdr_base64));
+        let response = self
+            .horizon_request("/transactions", |url| {
+                self.http_client
+                    .post(url)
+                    .header(
+                        reqwest::header::CONTENT_TYPE,
+                        "application/x-www-form-urlencoded",
+                    )
+                    .body(form.clone())
+            })
+            .await?;
 
-        let status = response.status();
+        let response = ensure_success(
+            response,
+            || StellarError::transaction_failed("Horizon submit endpoint not found"),
+            StellarError::transaction_failed,
+        )
+        .await?;
         let body = response.text().await.map_err(|e| {
             StellarError::network_error(format!("Horizon submit read error: {}", e))
         })?;
 
-        if !status.is_success() {
-            return Err(StellarError::transaction_failed(format!(
-                "Horizon submit failed (status {}): {}",
-                status, body
-            )));
-        }
-
         let json = serde_json::from_str::<JsonValue>(&body).map_err(|e| {
             StellarError::serialization_error(format!("Horizon submit JSON parse error: {}", e))
         })?;
@@ -382,33 +418,13 @@ impl StellarClient {
         &self,
         tx_hash: &str,
     ) -> StellarResult<HorizonTransactionRecord> {
-        let url = format!("{}/transactions/{}", self.config.horizon_url(), tx_hash);
-        let response = timeout(
-            self.config.request_timeout,
-            self.http_client.get(&url).send(),
+        let response = self.horizon_get(&format!("/transactions/{}", tx_hash)).await?;
+        let response = ensure_success(
+            response,
+            || StellarError::transaction_failed(format!("transaction not found: {}", tx_hash)),
+            StellarError::network_error,
         )
-        .await
-        .map_err(|_| StellarError::timeout_error(self.config.request_timeout.as_secs()))?;
-
-        let response = response.map_err(|e| {
-            if e.status() == Some(reqwest::StatusCode::NOT_FOUND) {
-                StellarError::transaction_failed(format!("transaction not found: {}", tx_hash))
-            } else if e.status() == Some(reqwest::StatusCode::TOO_MANY_REQUESTS) {
-                StellarError::RateLimitError
-            } else {
-                StellarError::network_error(format!("Horizon transaction fetch error: {}", e))
-            }
-        })?;
-
-        let response = response.error_for_status().map_err(|e| {
-            if e.status() == Some(reqwest::StatusCode::NOT_FOUND) {
-                StellarError::transaction_failed(format!("transaction not found: {}", tx_hash))
-            } else if e.status() == Some(reqwest::StatusCode::TOO_MANY_REQUESTS) {
-                StellarError::RateLimitError
-            } else {
-                StellarError::network_error(format!("Horizon transaction fetch error: {}", e))
-            }
-        })?;
+        .await?;
 
         response
             .json::<HorizonTransactionRecord>()
@@ -421,43 +437,79 @@ impl StellarClient {
         account: &str,
         limit: usize,
         cursor: Option<&str>,
+    ) -> StellarResult<HorizonTransactionsPage> {
+        self.fetch_account_transactions(account, limit, cursor, "asc").await
+    }
+
+    /// Most recent transactions for an account, newest first.
+    pub async fn list_recent_account_transactions(
+        &self,
+        account: &str,
+        limit: usize,
+    ) -> StellarResult<HorizonTransactionsPage> {
+        self.fetch_account_transactions(account, limit, None, "desc").await
+    }
+
+    /// When `account` was created, as the `created_at` of its first
+    /// operation, which is always the one that funded it. Horizon's account
+    /// resource carries no creation time of its own. `None` when no operation
+    /// is reported.
+    pub async fn account_created_at(&self, account: &str) -> StellarResult<Option<String>> {
+        if !is_valid_stellar_address(account) {
+            return Err(StellarError::invalid_address(account));
+        }
+
+        let path = format!("/accounts/{}/operations?order=asc&limit=1", account);
+        let response = ensure_success(
+            self.horizon_get(&path).await?,
+            || StellarError::account_not_found(account),
+            StellarError::network_error,
+        )
+        .await?;
+
+        let body = response
+            .json::<JsonValue>()
+            .await
+            .map_err(|e| StellarError::serialization_error(format!("JSON parsing error: {}", e)))?;
+
+        Ok(body
+            .get("_embedded")
+            .and_then(|v| v.get("records"))
+            .and_then(|v| v.as_array())
+            .and_then(|records| records.first())
+            .and_then(|op| op.get("created_at"))
+            .and_then(|v| v.as_str())
+            .map(str::to_string))
+    }
+
+    async fn fetch_account_transactions(
+        &self,
+        account: &str,
+        limit: usize,
+        cursor: Option<&str>,
+        order: &str,
     ) -> StellarResult<HorizonTransactionsPage> {
         if !is_valid_stellar_address(account) {
             return Err(StellarError::invalid_address(account));
         }
 
-        let mut url = format!(
-            "{}/accounts/{}/transactions?order=asc&limit={}",
-            self.config.horizon_url(),
+        let mut path = format!(
+            "/accounts/{}/transactions?order={}&limit={}",
             account,
+            order,
             limit.min(200)
         );
         if let Some(c) = cursor {
-            url.push_str("&cursor=");
-            url.push_str(&encode_form_component(c));
+            path.push_str("&cursor=");
+            path.push_str(&encode_form_component(c));
         }
 
-        let response = timeout(
-            self.config.request_timeout,
-            self.http_client.get(&url).send(),
+        let response = ensure_success(
+            self.horizon_get(&path).await?,
+            || StellarError::account_not_found(account),
+            StellarError::network_error,
         )
-        .await
-        .map_err(|_| StellarError::timeout_error(self.config.request_timeout.as_secs()))?
-        .map_err(|e| {
-            if e.status() == Some(reqwest::StatusCode::TOO_MANY_REQUESTS) {
-                StellarError::RateLimitError
-            } else {
-                StellarError::network_error(format!("Horizon account tx listing error: {}", e))
-            }
-        })?
-        .error_for_status()
-        .map_err(|e| {
-            if e.status() == Some(reqwest::StatusCode::TOO_MANY_REQUESTS) {
-                StellarError::RateLimitError
-            } else {
-                StellarError::network_error(format!("Horizon account tx listing error: {}", e))
-            }
-        })?;
+        .await?;
 
         let body = response
             .json::<JsonValue>()
@@ -477,34 +529,217 @@ impl StellarClient {
         Ok(HorizonTransactionsPage { records })
     }
 
-    pub async fn get_transaction_operations(&self, tx_hash: &str) -> StellarResult<Vec<JsonValue>> {
-        let response = timeout(
-            self.config.request_timeout,
-            self.http_client
-                .get(format!(
-                    "{}/transactions/{}/operations?limit=200",
-                    self.config.horizon_url(),
-                    tx_hash
-                ))
-                .send(),
+    /// Payments for an account, newest first, each carrying its transaction's
+    /// memo. Horizon embeds the transaction via `join=transactions`; records
+    /// without it fall back to one lookup per distinct hash, and are marked
+    /// `memo_unavailable` if that lookup fails. Records that don't parse are
+    /// logged and counted in `skipped`.
+    pub async fn list_account_payments(
+        &self,
+        account: &str,
+        limit: usize,
+        cursor: Option<&str>,
+    ) -> StellarResult<HorizonPaymentsPage> {
+        self.fetch_account_payments(account, "desc", limit, cursor).await
+    }
+
+    /// Payments for an account after `cursor`, oldest first, for following an
+    /// account's payments as they arrive. Memos are attached as in
+    /// [`Self::list_account_payments`].
+    pub async fn list_account_payments_since(
+        &self,
+        account: &str,
+        cursor: Option<&str>,
+        limit: usize,
+    ) -> StellarResult<HorizonPaymentsPage> {
+        self.fetch_account_payments(account, "asc", limit, cursor).await
+    }
+
+    async fn fetch_account_payments(
+        &self,
+        account: &str,
+        order: &str,
+        limit: usize,
+        cursor: Option<&str>,
+    ) -> StellarResult<HorizonPaymentsPage> {
+        if !is_valid_stellar_address(account) {
+            return Err(StellarError::invalid_address(account));
+        }
+
+        let mut path = format!(
+            "/accounts/{}/payments?order={}&limit={}&join=transactions",
+            account,
+            order,
+            limit.min(200)
+        );
+        if let Some(c) = cursor {
+            path.push_str("&cursor=");
+            path.push_str(&encode_form_component(c));
+        }
+
+        let response = ensure_success(
+            self.horizon_get(&path).await?,
+            || StellarError::account_not_found(account),
+            StellarError::network_error,
         )
-        .await
-        .map_err(|_| StellarError::timeout_error(self.config.request_timeout.as_secs()))?
-        .map_err(|e| {
-            if e.status() == Some(reqwest::StatusCode::TOO_MANY_REQUESTS) {
-                StellarError::RateLimitError
-            } else {
-                StellarError::network_error(format!("Horizon operations fetch error: {}", e))
+        .await?;
+
+        let body = response
+            .json::<JsonValue>()
+            .await
+            .map_err(|e| StellarError::serialization_error(format!("JSON parsing error: {}", e)))?;
+
+        let raw_records = body
+            .get("_embedded")
+            .and_then(|v| v.get("records"))
+            .and_then(|v| v.as_array())
+            .cloned()
+            .unwrap_or_default();
+
+        let mut memos: HashMap<String, (Option<String>, Option<String>)> = HashMap::new();
+        let mut records = Vec::with_capacity(raw_records.len());
+        let mut skipped = 0;
+        for raw in raw_records {
+            let mut record = match serde_json::from_value::<PaymentRecord>(raw.clone()) {
+                Ok(record) => record,
+                Err(e) => {
+                    warn!(
+                        account,
+                        id = ?raw.get("id").and_then(|v| v.as_str()),
+                        error = %e,
+                        "skipping unparseable Horizon payment record"
+                    );
+                    skipped += 1;
+                    continue;
+                }
+            };
+
+            if let Some(tx) = raw.get("transaction") {
+                let memo = (
+                    tx.get("memo_type").and_then(|v| v.as_str()).map(str::to_string),
+                    tx.get("memo").and_then(|v| v.as_str()).map(str::to_string),
+                );
+                memos.entry(record.transaction_hash.clone()).or_insert(memo);
             }
-        })?
-        .error_for_status()
-        .map_err(|e| {
-            if e.status() == Some(reqwest::StatusCode::TOO_MANY_REQUESTS) {
-                StellarError::RateLimitError
-            } else {
-                StellarError::network_error(format!("Horizon operations fetch error: {}", e))
+            if !memos.contains_key(&record.transaction_hash) {
+                match self.get_transaction_details(&record.transaction_hash).await {
+                    Ok(tx) => {
+                        memos.insert(record.transaction_hash.clone(), (tx.memo_type, tx.memo));
+                    }
+                    Err(e) => {
+                        warn!(
+                            tx_hash = %record.transaction_hash,
+                            error = %e,
+                            "memo lookup failed; returning payment without its memo"
+                        );
+                        record.memo_unavailable = true;
+                        records.push(record);
+                        continue;
+                    }
+                }
             }
-        })?;
+
+            let (memo_type, memo) = memos[&record.transaction_hash].clone();
+            record.memo_type = memo_type.filter(|t| t != "none");
+            record.memo = memo;
+            records.push(record);
+        }
+
+        Ok(HorizonPaymentsPage { records, skipped })
+    }
+
+    /// Horizon `/fee_stats`: recent fee percentiles and ledger capacity usage.
+    pub async fn get_fee_stats(&self) -> StellarResult<FeeStats> {
+        let body = self.horizon_get_json("/fee_stats", "fee stats").await?;
+        serde_json::from_value(body)
+            .map_err(|e| StellarError::serialization_error(format!("Invalid fee stats: {}", e)))
+    }
+
+    /// The most recently closed ledgers, newest first.
+    pub async fn get_recent_ledgers(&self, limit: usize) -> StellarResult<Vec<LedgerRecord>> {
+        let path = format!("/ledgers?order=desc&limit={}", limit.clamp(1, 200));
+        let body = self.horizon_get_json(&path, "ledgers").await?;
+        let records = body
+            .get("_embedded")
+            .and_then(|v| v.get("records"))
+            .cloned()
+            .unwrap_or(JsonValue::Array(Vec::new()));
+        serde_json::from_value(records)
+            .map_err(|e| StellarError::serialization_error(format!("Invalid ledger records: {}", e)))
+    }
+
+    /// GET `path` from the first configured Horizon endpoint that answers
+    async fn horizon_get(&self, path: &str) -> StellarResult<reqwest::Response> {
+        self.horizon_request(path, |url| self.http_client.get(url)).await
+    }
+
+    /// Send the request `build` makes for `path` to each configured Horizon
+    /// endpoint in turn. Timeouts, connection failures and 503s move on to
+    /// the next endpoint; any other response, 4xx included, is returned to
+    /// the caller as-is.
+    async fn horizon_request(
+        &self,
+        path: &str,
+        build: impl Fn(&str) -> reqwest::RequestBuilder,
+    ) -> StellarResult<reqwest::Response> {
+        let mut last_error = None;
+
+        for endpoint in self.config.horizon_urls() {
+            let started = Instant::now();
+            let result = match timeout(
+                self.config.request_timeout,
+                build(&format!("{}{}", endpoint, path)).send(),
+            )
+            .await
+            {
+                Err(_) => Err(StellarError::timeout_error(self.config.request_timeout.as_secs())),
+                Ok(Err(e)) => {
+                    Err(StellarError::network_error(format!("Horizon API error: {}", e)))
+                }
+                Ok(Ok(response)) => ensure_horizon_available(response),
+            };
+            record_horizon_request(
+                &StellarConfig::horizon_endpoint_label(endpoint),
+                result.is_ok(),
+                started.elapsed(),
+            );
+
+            match result {
+                Ok(response) => return Ok(response),
+                Err(e) => {
+                    warn!(endpoint, error = %e, "Horizon endpoint failed");
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| {
+            StellarError::config_error("no Horizon endpoints configured")
+        }))
+    }
+
+    async fn horizon_get_json(&self, path: &str, what: &str) -> StellarResult<JsonValue> {
+        let response = ensure_success(
+            self.horizon_get(path).await?,
+            || StellarError::network_error(format!("Horizon {} not found", what)),
+            StellarError::network_error,
+        )
+        .await?;
+
+        response
+            .json::<JsonValue>()
+            .await
+            .map_err(|e| StellarError::serialization_error(format!("JSON parsing error: {}", e)))
+    }
+
+    pub async fn get_transaction_operations(&self, tx_hash: &str) -> StellarResult<Vec<JsonValue>> {
+        let path = format!("/transactions/{}/operations?limit=200", tx_hash);
+        let response = ensure_success(
+            self.horizon_get(&path).await?,
+            || StellarError::transaction_not_found(tx_hash),
+            StellarError::network_error,
+        )
+        .await?;
 
         let body = response
             .json::<JsonValue>()
@@ -520,6 +755,64 @@ impl StellarClient {
     }
 }
 
+fn record_horizon_request(endpoint: &str, success: bool, elapsed: Duration) {
+    // The client is also used outside the server binary, before anything has
+    // touched the registry
+    let _ = crate::metrics::registry();
+    let result = if success { "success" } else { "error" };
+    crate::metrics::stellar::horizon_requests_total()
+        .with_label_values(&[endpoint, result])
+        .inc();
+    crate::metrics::stellar::horizon_request_duration_seconds()
+        .with_label_values(&[endpoint])
+        .observe(elapsed.as_secs_f64());
+}
+
+/// Horizon returns 503 during maintenance or when overloaded. That is not a
+/// connectivity failure, so surface it as `ServiceUnavailable` with the
+/// `Retry-After` hint (delta-seconds form) when Horizon provides one.
+fn ensure_horizon_available(response: reqwest::Response) -> StellarResult<reqwest::Response> {
+    if response.status() != reqwest::StatusCode::SERVICE_UNAVAILABLE {
+        return Ok(response);
+    }
+
+    let retry_after = retry_after_secs(&response);
+    warn!(
+        url = %response.url(),
+        retry_after = ?retry_after,
+        "Horizon temporarily unavailable"
+    );
+    Err(StellarError::service_unavailable(retry_after))
+}
+
+/// Pass successful responses through and turn anything else into the
+/// `StellarError` its Horizon problem body calls for; see
+/// [`HorizonProblem::into_error`] for `not_found` and `fallback`.
+async fn ensure_success(
+    response: reqwest::Response,
+    not_found: impl FnOnce() -> StellarError,
+    fallback: impl FnOnce(String) -> StellarError,
+) -> StellarResult<reqwest::Response> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    let retry_after = retry_after_secs(&response);
+    let body = response.text().await.unwrap_or_default();
+    let problem = HorizonProblem::parse(status.as_u16(), &body);
+    debug!(problem = %problem.describe(), "Horizon request failed");
+    Err(problem.into_error(retry_after, not_found, fallback))
+}
+
+fn retry_after_secs(response: &reqwest::Response) -> Option<u64> {
+    response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<u64>().ok())
+}
+
 fn encode_form_component(input: &str) -> String {
     let mut output = String::with_capacity(input.len());
     for &b in input.as_bytes() {
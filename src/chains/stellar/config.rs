@@ -23,12 +23,34 @@ impl StellarNetwork {
             StellarNetwork::Mainnet => "Public Global Stellar Network ; September 2015",
         }
     }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StellarNetwork::Testnet => "testnet",
+            StellarNetwork::Mainnet => "mainnet",
+        }
+    }
+
+    /// Network named by `STELLAR_NETWORK`, falling back to testnet like `StellarConfig::from_env`.
+    pub fn from_env() -> Self {
+        match std::env::var("STELLAR_NETWORK")
+            .unwrap_or_default()
+            .to_lowercase()
+            .as_str()
+        {
+            "mainnet" => StellarNetwork::Mainnet,
+            _ => StellarNetwork::Testnet,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StellarConfig {
     pub network: StellarNetwork,
     pub horizon_url_override: Option<String>,
+    /// Tried in order when the primary Horizon URL is unreachable
+    #[serde(default)]
+    pub horizon_fallback_urls: Vec<String>,
     pub request_timeout: Duration,
     pub max_retries: u32,
     pub health_check_interval: Duration,
@@ -39,6 +61,7 @@ impl Default for StellarConfig {
         Self {
             network: StellarNetwork::Testnet,
             horizon_url_override: None,
+            horizon_fallback_urls: Vec::new(),
             request_timeout: Duration::from_secs(10),
             max_retries: 3,
             health_check_interval: Duration::from_secs(30),
@@ -77,6 +100,14 @@ impl StellarConfig {
             });
 
         let horizon_url_override = std::env::var("STELLAR_HORIZON_URL").ok();
+        let horizon_fallback_urls = std::env::var("STELLAR_HORIZON_FALLBACK_URLS")
+            .map(|v| {
+                v.split(',')
+                    .map(|url| url.trim().trim_end_matches('/').to_string())
+                    .filter(|url| !url.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
 
         let max_retries = std::env::var("STELLAR_MAX_RETRIES")
             .ok()
@@ -95,6 +126,7 @@ impl StellarConfig {
         Ok(Self {
             network,
             horizon_url_override,
+            horizon_fallback_urls,
             request_timeout,
             max_retries,
             health_check_interval,
@@ -127,6 +159,15 @@ impl StellarConfig {
             }
         }
 
+        for fallback_url in &self.horizon_fallback_urls {
+            let parsed = reqwest::Url::parse(fallback_url).map_err(|e| {
+                anyhow::anyhow!("Invalid STELLAR_HORIZON_FALLBACK_URLS entry: {}", e)
+            })?;
+            if parsed.scheme() != "http" && parsed.scheme() != "https" {
+                anyhow::bail!("STELLAR_HORIZON_FALLBACK_URLS entries must use http or https");
+            }
+        }
+
         info!(
             "Stellar configuration validated - Network: {:?}, Horizon URL: {}, Timeout: {:?}, Max retries: {}",
             self.network,
@@ -143,4 +184,29 @@ impl StellarConfig {
             .as_deref()
             .unwrap_or_else(|| self.network.horizon_url())
     }
+
+    /// Primary Horizon URL followed by the fallbacks, without duplicates
+    pub fn horizon_urls(&self) -> Vec<&str> {
+        let mut urls = vec![self.horizon_url()];
+        for url in &self.horizon_fallback_urls {
+            if !urls.contains(&url.as_str()) {
+                urls.push(url);
+            }
+        }
+        urls
+    }
+
+    /// `endpoint` metrics label for a configured Horizon URL: its host and
+    /// port, so paths or credentials in the URL never reach a label and the
+    /// label set stays as small as the configuration.
+    pub fn horizon_endpoint_label(url: &str) -> String {
+        match reqwest::Url::parse(url) {
+            Ok(parsed) => match (parsed.host_str(), parsed.port()) {
+                (Some(host), Some(port)) => format!("{}:{}", host, port),
+                (Some(host), None) => host.to_string(),
+                (None, _) => "unknown".to_string(),
+            },
+            Err(_) => "unknown".to_string(),
+        }
+    }
 }
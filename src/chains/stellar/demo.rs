@@ -0,0 +1,62 @@
+//! Startup demo
+//!
+//! Looks up a fixed testnet account and logs its balances, which is handy when
+//! poking at a local setup but only adds Horizon calls and log noise to a
+//! production start. Runs only with `RUN_STARTUP_DEMO=true`.
+
+use crate::chains::stellar::client::StellarClient;
+use tracing::info;
+
+/// Testnet account the demo looks up
+pub const DEMO_ADDRESS: &str = "GCJRI5CIWK5IU67Q6DGA7QW52JDKRO7JEAHQKFNDUJUPEZGURDBX3LDX";
+
+pub fn startup_demo_enabled() -> bool {
+    std::env::var("RUN_STARTUP_DEMO")
+        .unwrap_or_else(|_| "false".to_string())
+        .to_lowercase()
+        == "true"
+}
+
+/// Run the demo when `RUN_STARTUP_DEMO` allows it. Returns whether it ran.
+pub async fn maybe_run_startup_demo(stellar_client: &StellarClient) -> bool {
+    if !startup_demo_enabled() {
+        return false;
+    }
+    run_startup_demo(stellar_client).await;
+    true
+}
+
+/// Log what Horizon knows about [`DEMO_ADDRESS`]. Failures are logged, never
+/// returned: the demo must not stop the server from starting.
+pub async fn run_startup_demo(stellar_client: &StellarClient) {
+    info!("🧪 Demo: Testing Stellar functionality");
+
+    match stellar_client.account_exists(DEMO_ADDRESS).await {
+        Ok(true) => {
+            info!(address = DEMO_ADDRESS, "✅ Test account exists");
+            match stellar_client.get_account(DEMO_ADDRESS).await {
+                Ok(account) => {
+                    info!(
+                        account_id = %account.account_id,
+                        sequence = account.sequence,
+                        balances = account.balances.len(),
+                        "✅ Successfully fetched account details"
+                    );
+                    for balance in &account.balances {
+                        info!(
+                            balance = %balance.balance,
+                            asset_type = %balance.asset_type,
+                            "Account balance"
+                        );
+                    }
+                }
+                Err(e) => info!(error = %e, "⚠️  Account exists but couldn't fetch details"),
+            }
+        }
+        Ok(false) => info!(
+            address = DEMO_ADDRESS,
+            "ℹ️  Test account does not exist (expected)"
+        ),
+        Err(e) => info!(error = %e, "ℹ️  Error checking account existence (expected for test)"),
+    }
+}
@@ -1,9 +1,14 @@
+use crate::retry::RetryClass;
+use std::time::Duration;
 use thiserror::Error;
 
+/// Cap on a `Retry-After` hint so a misbehaving server cannot stall a call
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(30);
+
 pub type StellarResult<T> = Result<T, StellarError>;
 
 #[allow(dead_code)]
-#[derive(Debug, Error)]
+#[derive(Debug, Clone, Error)]
 pub enum StellarError {
     #[error("Account not found: {address}")]
     AccountNotFound { address: String },
@@ -14,12 +19,19 @@ pub enum StellarError {
     #[error("Invalid Stellar address: {address}")]
     InvalidAddress { address: String },
 
+    #[error("Invalid asset {code}: {reason}")]
+    InvalidAsset { code: String, reason: String },
+
     #[error("Network error: {message}")]
     NetworkError { message: String },
 
     #[error("Rate limit exceeded. Please try again later")]
     RateLimitError,
 
+    /// Horizon answered 503: it is reachable but in maintenance or overloaded.
+    #[error("Horizon is temporarily unavailable")]
+    ServiceUnavailable { retry_after: Option<u64> },
+
     #[error("Configuration error: {message}")]
     ConfigError { message: String },
 
@@ -38,6 +50,16 @@ pub enum StellarError {
     #[error("Insufficient XLM. Available: {available}, Required: {required}")]
     InsufficientXlm { available: String, required: String },
 
+    #[error(
+        "Insufficient {asset} balance. Available: {available}, Required: {required}, Shortfall: {shortfall}"
+    )]
+    InsufficientBalance {
+        asset: String,
+        available: String,
+        required: String,
+        shortfall: String,
+    },
+
     #[error("Trustline already exists for account {address} and asset {asset}")]
     TrustlineAlreadyExists { address: String, asset: String },
 
@@ -46,6 +68,53 @@ pub enum StellarError {
 
     #[error("Signing error: {message}")]
     SigningError { message: String },
+
+    #[error("Contract execution failed: {message}")]
+    ContractError { message: String },
+
+    #[error("Sequence {requested} is not above the account's current sequence {current}")]
+    SequenceTooLow { current: i64, requested: i64 },
+
+    #[error("Account {address} cannot be merged while it has subentries: {}", blockers.join(", "))]
+    AccountNotMergeable {
+        address: String,
+        blockers: Vec<String>,
+    },
+
+    #[error("Cannot merge account {address} into itself")]
+    SelfMerge { address: String },
+
+    /// The envelope's upper time bound has passed; the network would reject it
+    #[error("Transaction expired: time bounds ended at {max_time}")]
+    TransactionExpired { max_time: u64 },
+
+    /// The envelope's lower time bound is still ahead, beyond clock skew
+    #[error("Transaction not yet valid: time bounds start at {min_time}")]
+    TransactionNotYetValid { min_time: u64 },
+
+    /// A text memo over the byte limit; the caller has to shorten it
+    #[error("Invalid memo: {reason}")]
+    InvalidMemo { max_bytes: usize, reason: String },
+
+    #[error("A transaction needs at least one operation")]
+    NoOperations,
+
+    #[error("Transaction has {count} operations; at most {max} are allowed")]
+    TooManyOperations { count: usize, max: usize },
+
+    /// Another request is submitting the same source + sequence right now
+    #[error("Transaction {hash} is already being submitted")]
+    SubmissionInProgress { hash: String },
+
+    /// Total fee for `operations` ops is above the configured per-op ceiling
+    #[error(
+        "Fee of {fee_stroops} stroops for {operations} operations exceeds the ceiling of {max_fee_stroops}"
+    )]
+    FeeAboveCeiling {
+        fee_stroops: u64,
+        max_fee_stroops: u64,
+        operations: usize,
+    },
 }
 
 #[allow(dead_code)]
@@ -68,12 +137,23 @@ impl StellarError {
         }
     }
 
+    pub fn invalid_asset(code: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self::InvalidAsset {
+            code: code.into(),
+            reason: reason.into(),
+        }
+    }
+
     pub fn network_error(message: impl Into<String>) -> Self {
         Self::NetworkError {
             message: message.into(),
         }
     }
 
+    pub fn service_unavailable(retry_after: Option<u64>) -> Self {
+        Self::ServiceUnavailable { retry_after }
+    }
+
     pub fn config_error(message: impl Into<String>) -> Self {
         Self::ConfigError {
             message: message.into(),
@@ -109,6 +189,20 @@ impl StellarError {
         }
     }
 
+    pub fn insufficient_balance(
+        asset: impl Into<String>,
+        available: impl Into<String>,
+        required: impl Into<String>,
+        shortfall: impl Into<String>,
+    ) -> Self {
+        Self::InsufficientBalance {
+            asset: asset.into(),
+            available: available.into(),
+            required: required.into(),
+            shortfall: shortfall.into(),
+        }
+    }
+
     pub fn trustline_already_exists(address: impl Into<String>, asset: impl Into<String>) -> Self {
         Self::TrustlineAlreadyExists {
             address: address.into(),
@@ -127,6 +221,83 @@ impl StellarError {
             message: message.into(),
         }
     }
+
+    pub fn contract_error(message: impl Into<String>) -> Self {
+        Self::ContractError {
+            message: message.into(),
+        }
+    }
+
+    pub fn sequence_too_low(current: i64, requested: i64) -> Self {
+        Self::SequenceTooLow { current, requested }
+    }
+
+    pub fn account_not_mergeable(address: impl Into<String>, blockers: Vec<String>) -> Self {
+        Self::AccountNotMergeable {
+            address: address.into(),
+            blockers,
+        }
+    }
+
+    pub fn self_merge(address: impl Into<String>) -> Self {
+        Self::SelfMerge {
+            address: address.into(),
+        }
+    }
+
+    pub fn transaction_expired(max_time: u64) -> Self {
+        Self::TransactionExpired { max_time }
+    }
+
+    pub fn transaction_not_yet_valid(min_time: u64) -> Self {
+        Self::TransactionNotYetValid { min_time }
+    }
+
+    pub fn invalid_memo(max_bytes: usize, reason: impl Into<String>) -> Self {
+        Self::InvalidMemo {
+            max_bytes,
+            reason: reason.into(),
+        }
+    }
+
+    pub fn too_many_operations(count: usize, max: usize) -> Self {
+        Self::TooManyOperations { count, max }
+    }
+
+    pub fn submission_in_progress(hash: impl Into<String>) -> Self {
+        Self::SubmissionInProgress { hash: hash.into() }
+    }
+
+    pub fn fee_above_ceiling(fee_stroops: u64, max_fee_stroops: u64, operations: usize) -> Self {
+        Self::FeeAboveCeiling {
+            fee_stroops,
+            max_fee_stroops,
+            operations,
+        }
+    }
+
+    /// Transient failures worth retrying; anything deterministic (bad input,
+    /// contract traps, missing accounts) is not.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            Self::NetworkError { .. }
+                | Self::RateLimitError
+                | Self::ServiceUnavailable { .. }
+                | Self::TimeoutError { .. }
+        )
+    }
+
+    /// Classifier for `retry_async`; honours Horizon's `Retry-After` on 503s
+    pub fn retry_class(&self) -> RetryClass {
+        match self {
+            Self::ServiceUnavailable {
+                retry_after: Some(secs),
+            } => RetryClass::RetryAfter(Duration::from_secs(*secs).min(MAX_RETRY_AFTER)),
+            e if e.is_retryable() => RetryClass::Retry,
+            _ => RetryClass::Fatal,
+        }
+    }
 }
 
 impl From<Box<dyn std::error::Error + Send + Sync>> for StellarError {
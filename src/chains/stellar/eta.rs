@@ -0,0 +1,261 @@
+//! Best-effort confirmation time estimates
+//!
+//! Combines the average close time of recent ledgers with Horizon's
+//! `/fee_stats` to guess how many ledgers a transaction offering a given fee
+//! will wait before inclusion. Outside surge pricing every fee at or above the
+//! base fee lands in the next ledger; during surge pricing a bid that fewer
+//! competing transactions beat is expected to clear sooner.
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// Used when fewer than two recent ledgers are available
+pub const DEFAULT_LEDGER_CLOSE_SECS: f64 = 5.0;
+/// Ledger capacity usage above which Horizon applies surge pricing
+pub const SURGE_CAPACITY_THRESHOLD: f64 = 0.9;
+/// Estimates are capped here; beyond this the fee is effectively too low
+pub const MAX_ESTIMATED_LEDGERS: u32 = 20;
+
+const PERCENTILES: [u8; 11] = [10, 20, 30, 40, 50, 60, 70, 80, 90, 95, 99];
+
+fn de_u32_str<'de, D: Deserializer<'de>>(d: D) -> Result<u32, D::Error> {
+    let s = String::deserialize(d)?;
+    s.parse().map_err(serde::de::Error::custom)
+}
+
+fn de_f64_str<'de, D: Deserializer<'de>>(d: D) -> Result<f64, D::Error> {
+    let s = String::deserialize(d)?;
+    s.parse().map_err(serde::de::Error::custom)
+}
+
+/// Percentile distribution of per-operation fees (stroops) in recent ledgers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeeDistribution {
+    #[serde(deserialize_with = "de_u32_str")]
+    pub max: u32,
+    #[serde(deserialize_with = "de_u32_str")]
+    pub min: u32,
+    #[serde(deserialize_with = "de_u32_str")]
+    pub mode: u32,
+    #[serde(deserialize_with = "de_u32_str")]
+    pub p10: u32,
+    #[serde(deserialize_with = "de_u32_str")]
+    pub p20: u32,
+    #[serde(deserialize_with = "de_u32_str")]
+    pub p30: u32,
+    #[serde(deserialize_with = "de_u32_str")]
+    pub p40: u32,
+    #[serde(deserialize_with = "de_u32_str")]
+    pub p50: u32,
+    #[serde(deserialize_with = "de_u32_str")]
+    pub p60: u32,
+    #[serde(deserialize_with = "de_u32_str")]
+    pub p70: u32,
+    #[serde(deserialize_with = "de_u32_str")]
+    pub p80: u32,
+    #[serde(deserialize_with = "de_u32_str")]
+    pub p90: u32,
+    #[serde(deserialize_with = "de_u32_str")]
+    pub p95: u32,
+    #[serde(deserialize_with = "de_u32_str")]
+    pub p99: u32,
+}
+
+impl FeeDistribution {
+    /// Fee at one of Horizon's reported percentiles (10–90 by tens, 95, 99)
+    pub fn percentile(&self, p: u8) -> Option<u32> {
+        Some(match p {
+            10 => self.p10,
+            20 => self.p20,
+            30 => self.p30,
+            40 => self.p40,
+            50 => self.p50,
+            60 => self.p60,
+            70 => self.p70,
+            80 => self.p80,
+            90 => self.p90,
+            95 => self.p95,
+            99 => self.p99,
+            _ => return None,
+        })
+    }
+
+    /// Highest reported percentile whose fee `fee_stroops` meets or beats;
+    /// 100 when it matches the maximum bid, 0 when it is below p10.
+    pub fn rank_of(&self, fee_stroops: u32) -> u8 {
+        if fee_stroops >= self.max {
+            return 100;
+        }
+        PERCENTILES
+            .iter()
+            .rev()
+            .find(|p| self.percentile(**p).is_some_and(|fee| fee_stroops >= fee))
+            .copied()
+            .unwrap_or(0)
+    }
+}
+
+/// Horizon `/fee_stats`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeeStats {
+    #[serde(deserialize_with = "de_u32_str")]
+    pub last_ledger: u32,
+    #[serde(deserialize_with = "de_u32_str")]
+    pub last_ledger_base_fee: u32,
+    #[serde(deserialize_with = "de_f64_str")]
+    pub ledger_capacity_usage: f64,
+    pub fee_charged: FeeDistribution,
+    pub max_fee: FeeDistribution,
+}
+
+/// The fields of a Horizon ledger record the estimate needs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LedgerRecord {
+    pub sequence: u32,
+    pub closed_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfirmationEstimate {
+    /// Always `best_effort`: network conditions can change before submission
+    pub estimate_type: &'static str,
+    pub fee_stroops: u32,
+    /// Percentile of recent max-fee bids this fee meets or beats
+    pub fee_percentile: u8,
+    pub base_fee_stroops: u32,
+    pub ledger_capacity_usage: f64,
+    pub surge_pricing: bool,
+    pub average_ledger_close_secs: f64,
+    pub estimated_ledgers: u32,
+    pub estimated_seconds: f64,
+    /// Set when the fee is below the network base fee and will be rejected
+    pub below_base_fee: bool,
+}
+
+/// Mean gap between consecutive close times, in any order
+pub fn average_close_secs(ledgers: &[LedgerRecord]) -> f64 {
+    let (Some(first), Some(last)) = (
+        ledgers.iter().map(|l| l.closed_at).min(),
+        ledgers.iter().map(|l| l.closed_at).max(),
+    ) else {
+        return DEFAULT_LEDGER_CLOSE_SECS;
+    };
+    if ledgers.len() < 2 || first == last {
+        return DEFAULT_LEDGER_CLOSE_SECS;
+    }
+    (last - first).num_milliseconds() as f64 / 1000.0 / (ledgers.len() - 1) as f64
+}
+
+pub fn estimate_confirmation(
+    ledgers: &[LedgerRecord],
+    fee_stats: &FeeStats,
+    fee_stroops: u32,
+) -> ConfirmationEstimate {
+    let average_ledger_close_secs = average_close_secs(ledgers);
+    let fee_percentile = fee_stats.max_fee.rank_of(fee_stroops);
+    let usage = fee_stats.ledger_capacity_usage;
+    let surge_pricing = usage >= SURGE_CAPACITY_THRESHOLD;
+    let below_base_fee = fee_stroops < fee_stats.last_ledger_base_fee;
+
+    let estimated_ledgers = if below_base_fee {
+        MAX_ESTIMATED_LEDGERS
+    } else if !surge_pricing {
+        1
+    } else {
+        // Only the share of bids at or below ours competes for what is left
+        // of each ledger once higher bidders are served.
+        let share = (fee_percentile as f64 / 100.0).max(0.05);
+        ((usage / share).ceil() as u32).clamp(1, MAX_ESTIMATED_LEDGERS)
+    };
+
+    ConfirmationEstimate {
+        estimate_type: "best_effort",
+        fee_stroops,
+        fee_percentile,
+        base_fee_stroops: fee_stats.last_ledger_base_fee,
+        ledger_capacity_usage: usage,
+        surge_pricing,
+        average_ledger_close_secs,
+        estimated_ledgers,
+        estimated_seconds: estimated_ledgers as f64 * average_ledger_close_secs,
+        below_base_fee,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ledgers() -> Vec<LedgerRecord> {
+        // Newest first, as Horizon returns them with order=desc
+        [
+            (103, "2026-01-01T00:00:18Z"),
+            (102, "2026-01-01T00:00:12Z"),
+            (101, "2026-01-01T00:00:06Z"),
+            (100, "2026-01-01T00:00:00Z"),
+        ]
+        .into_iter()
+        .map(|(sequence, closed_at)| LedgerRecord {
+            sequence,
+            closed_at: closed_at.parse().unwrap(),
+        })
+        .collect()
+    }
+
+    fn fee_stats(capacity: &str) -> FeeStats {
+        serde_json::from_value(serde_json::json!({
+            "last_ledger": "103",
+            "last_ledger_base_fee": "100",
+            "ledger_capacity_usage": capacity,
+            "fee_charged": {
+                "max": "100", "min": "100", "mode": "100",
+                "p10": "100", "p20": "100", "p30": "100", "p40": "100", "p50": "100",
+                "p60": "100", "p70": "100", "p80": "100", "p90": "100", "p95": "100", "p99": "100"
+            },
+            "max_fee": {
+                "max": "100000", "min": "100", "mode": "100",
+                "p10": "100", "p20": "150", "p30": "200", "p40": "300", "p50": "500",
+                "p60": "800", "p70": "1000", "p80": "2000", "p90": "5000", "p95": "10000", "p99": "50000"
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn average_close_time_comes_from_ledger_timestamps() {
+        assert_eq!(average_close_secs(&ledgers()), 6.0);
+        assert_eq!(average_close_secs(&ledgers()[..1]), DEFAULT_LEDGER_CLOSE_SECS);
+    }
+
+    #[test]
+    fn higher_fee_yields_shorter_estimate_under_surge() {
+        let stats = fee_stats("0.97");
+        let low = estimate_confirmation(&ledgers(), &stats, 150);
+        let high = estimate_confirmation(&ledgers(), &stats, 5000);
+
+        assert!(low.surge_pricing);
+        assert_eq!(low.fee_percentile, 20);
+        assert_eq!(high.fee_percentile, 90);
+        assert_eq!(low.estimated_ledgers, 5);
+        assert_eq!(high.estimated_ledgers, 2);
+        assert!(high.estimated_seconds < low.estimated_seconds);
+        assert_eq!(high.estimated_seconds, 12.0);
+        assert_eq!(high.estimate_type, "best_effort");
+    }
+
+    #[test]
+    fn base_fee_clears_next_ledger_without_surge() {
+        let estimate = estimate_confirmation(&ledgers(), &fee_stats("0.4"), 100);
+
+        assert!(!estimate.surge_pricing);
+        assert_eq!(estimate.estimated_ledgers, 1);
+        assert_eq!(estimate.estimated_seconds, 6.0);
+    }
+
+    #[test]
+    fn fee_below_base_fee_is_flagged() {
+        let estimate = estimate_confirmation(&ledgers(), &fee_stats("0.4"), 50);
+
+        assert!(estimate.below_base_fee);
+        assert_eq!(estimate.estimated_ledgers, MAX_ESTIMATED_LEDGERS);
+    }
+}
@@ -1,8 +1,17 @@
+pub mod asset;
 pub mod client;
 pub mod config;
+pub mod demo;
 pub mod errors;
+pub mod eta;
 pub mod payment;
+pub mod problem;
+pub mod risk;
+pub mod sep;
 pub mod service;
+pub mod signer;
+pub mod soroban;
+pub mod submission;
 pub mod trustline;
 pub mod types;
 
@@ -1,7 +1,11 @@
+use crate::chains::stellar::asset::StellarAsset;
 use crate::chains::stellar::client::StellarClient;
 use crate::chains::stellar::errors::{StellarError, StellarResult};
 use crate::chains::stellar::trustline::CngnAssetConfig;
-use crate::chains::stellar::types::{extract_asset_balance, is_valid_stellar_address};
+use crate::chains::stellar::types::{
+    check_text_memo, is_valid_stellar_address, max_text_memo_bytes, AssetBalance,
+    StellarAccountInfo,
+};
 use ed25519_dalek::{Signer, SigningKey};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
@@ -11,15 +15,64 @@ use stellar_strkey::ed25519::{
     PublicKey as StrkeyPublicKey,
 };
 use stellar_xdr::next::{
-    AccountId, AlphaNum12, AlphaNum4, Asset, AssetCode12, AssetCode4, DecoratedSignature, Hash,
-    Limits, Memo, MuxedAccount, MuxedAccountMed25519, Operation, OperationBody, PaymentOp,
+    AccountId, BumpSequenceOp, ChangeTrustAsset, DecoratedSignature, FeeBumpTransactionInnerTx,
+    Hash, Limits, Memo, MuxedAccount, MuxedAccountMed25519, Operation, OperationBody, PaymentOp,
     Preconditions, PublicKey, ReadXdr, SequenceNumber, Signature, SignatureHint, StringM,
-    TimeBounds, TimePoint, Transaction, TransactionEnvelope, TransactionExt, TransactionV1Envelope,
-    Uint256, VecM, WriteXdr,
+    TimeBounds, TimePoint, Transaction, TransactionEnvelope, TransactionExt,
+    TransactionSignaturePayload, TransactionSignaturePayloadTaggedTransaction,
+    TransactionV1Envelope, Uint256, VecM, WriteXdr,
 };
 
-const DEFAULT_BASE_FEE_STROOPS: u32 = 100;
+pub const DEFAULT_BASE_FEE_STROOPS: u32 = 100;
 const DEFAULT_TIMEOUT_SECONDS: u64 = 300;
+/// Stellar's protocol limit on operations in one transaction
+pub const MAX_OPERATIONS_PER_TX: usize = 100;
+/// Highest per-operation fee a batch may bid unless configured otherwise
+const DEFAULT_MAX_FEE_PER_OP_STROOPS: u32 = 10_000;
+
+/// Operations allowed in one built transaction, from `STELLAR_MAX_OPS_PER_TX`.
+/// Never above the protocol limit, which Horizon would reject anyway.
+pub fn max_operations_from_env() -> usize {
+    std::env::var("STELLAR_MAX_OPS_PER_TX")
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .filter(|&n| n > 0)
+        .map_or(MAX_OPERATIONS_PER_TX, |n| n.min(MAX_OPERATIONS_PER_TX))
+}
+
+/// Per-operation fee ceiling, from `STELLAR_MAX_FEE_PER_OP_STROOPS`
+pub fn max_fee_per_op_from_env() -> u32 {
+    std::env::var("STELLAR_MAX_FEE_PER_OP_STROOPS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_MAX_FEE_PER_OP_STROOPS)
+}
+
+/// Check a multi-operation transaction against the operation and fee limits
+/// before building it. Returns the total fee: `fee_per_op_stroops` for each
+/// operation, which must stay within `max_fee_per_op_stroops` per operation.
+pub fn check_batch_limits(
+    operation_count: usize,
+    fee_per_op_stroops: u32,
+    max_operations: usize,
+    max_fee_per_op_stroops: u32,
+) -> StellarResult<u32> {
+    if operation_count == 0 {
+        return Err(StellarError::NoOperations);
+    }
+    if operation_count > max_operations {
+        return Err(StellarError::too_many_operations(operation_count, max_operations));
+    }
+
+    let ops = operation_count as u64;
+    let total = u64::from(fee_per_op_stroops) * ops;
+    // The transaction fee field is a u32, which caps the ceiling too
+    let ceiling = (u64::from(max_fee_per_op_stroops) * ops).min(u64::from(u32::MAX));
+    if total > ceiling {
+        return Err(StellarError::fee_above_ceiling(total, ceiling, operation_count));
+    }
+    Ok(total as u32)
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", content = "value", rename_all = "snake_case")]
@@ -30,6 +83,35 @@ pub enum CngnMemo {
     Hash(String),
 }
 
+/// Largest deposit account id accepted; the memo id mapping is stored as BIGINT
+pub const MAX_DEPOSIT_ACCOUNT_ID: u64 = i64::MAX as u64;
+
+impl CngnMemo {
+    /// Memo id tying an exchange deposit to an internal account. Zero is
+    /// rejected because some wallets send `0` when the user leaves it blank.
+    pub fn deposit(deposit_account_id: u64) -> StellarResult<Self> {
+        if deposit_account_id == 0 || deposit_account_id > MAX_DEPOSIT_ACCOUNT_ID {
+            return Err(StellarError::transaction_failed(format!(
+                "deposit_account_id must be between 1 and {}",
+                MAX_DEPOSIT_ACCOUNT_ID
+            )));
+        }
+        Ok(CngnMemo::Id(deposit_account_id))
+    }
+
+    /// Memo for a build request that may carry either an explicit memo or a
+    /// deposit account id, but not both.
+    pub fn resolve(memo: Option<CngnMemo>, deposit_account_id: Option<u64>) -> StellarResult<Self> {
+        match (memo, deposit_account_id) {
+            (None | Some(CngnMemo::None), Some(id)) => CngnMemo::deposit(id),
+            (Some(_), Some(_)) => Err(StellarError::transaction_failed(
+                "memo and deposit_account_id cannot both be set",
+            )),
+            (memo, None) => Ok(memo.unwrap_or(CngnMemo::None)),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CngnPaymentDraft {
     pub source: String,
@@ -46,6 +128,91 @@ pub struct CngnPaymentDraft {
     pub memo: CngnMemo,
 }
 
+/// Unsigned transaction holding a single bumpSequence operation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BumpSequenceDraft {
+    pub source: String,
+    /// Account sequence reported by Horizon when the draft was built
+    pub current_sequence: i64,
+    pub bump_to: i64,
+    /// Sequence number consumed by this transaction itself
+    pub sequence: i64,
+    pub fee_stroops: u32,
+    pub timeout_seconds: u64,
+    pub created_at: String,
+    pub transaction_hash: String,
+    pub unsigned_envelope_xdr: String,
+}
+
+/// Unsigned transaction holding a single accountMerge operation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountMergeDraft {
+    pub source: String,
+    /// Receives the source's remaining XLM once the merge applies
+    pub destination: String,
+    pub sequence: i64,
+    pub fee_stroops: u32,
+    pub timeout_seconds: u64,
+    pub created_at: String,
+    pub transaction_hash: String,
+    pub unsigned_envelope_xdr: String,
+}
+
+/// Human-readable view of a transaction envelope, for checking a draft
+/// before it is signed or submitted
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnvelopeSummary {
+    /// `tx`, `tx_v0` or `fee_bump`
+    pub envelope_type: String,
+    pub source: String,
+    pub sequence: i64,
+    pub fee_stroops: u32,
+    pub memo: CngnMemo,
+    pub time_bounds: Option<TimeBoundsSummary>,
+    pub operations: Vec<OperationSummary>,
+    pub signature_count: usize,
+    /// Hash of the inner transaction on the builder's network
+    pub transaction_hash: String,
+    pub fee_bump: Option<FeeBumpSummary>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimeBoundsSummary {
+    pub min_time: u64,
+    /// 0 means no upper bound
+    pub max_time: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OperationSummary {
+    /// Horizon operation type, e.g. `payment`, `change_trust`
+    pub r#type: String,
+    pub source: Option<String>,
+    /// Decoded fields for common operations; null for the rest
+    pub details: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeeBumpSummary {
+    pub fee_source: String,
+    pub fee_stroops: i64,
+    pub signature_count: usize,
+    pub transaction_hash: String,
+}
+
+/// Outcome of a submission that was validated but deliberately not broadcast
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaymentDryRun {
+    /// Always false; the envelope never left the backend
+    pub submitted: bool,
+    /// Hash the transaction would have on-chain (the outer hash for fee bumps)
+    pub transaction_hash: String,
+    pub source: String,
+    pub sequence: i64,
+    pub fee_stroops: u32,
+    pub operation_count: usize,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SignedCngnPayment {
     pub draft: CngnPaymentDraft,
@@ -59,6 +226,7 @@ pub struct CngnPaymentBuilder {
     config: CngnAssetConfig,
     base_fee_stroops: u32,
     timeout: Duration,
+    balance_precheck: bool,
 }
 
 impl CngnPaymentBuilder {
@@ -68,6 +236,7 @@ impl CngnPaymentBuilder {
             config: CngnAssetConfig::from_env(),
             base_fee_stroops: DEFAULT_BASE_FEE_STROOPS,
             timeout: Duration::from_secs(DEFAULT_TIMEOUT_SECONDS),
+            balance_precheck: true,
         }
     }
 
@@ -81,6 +250,16 @@ impl CngnPaymentBuilder {
         self
     }
 
+    /// Toggle the source cNGN/XLM balance check in `build_payment`.
+    ///
+    /// On by default; callers that already know the source is funded (or want
+    /// Horizon to be the judge) can turn it off to save the extra validation.
+    pub fn with_balance_precheck(mut self, enabled: bool) -> Self {
+        self.balance_precheck = enabled;
+        self
+    }
+
+    /// Build an unsigned cNGN payment on the client's network.
     pub async fn build_payment(
         &self,
         source: &str,
@@ -88,6 +267,22 @@ impl CngnPaymentBuilder {
         amount: &str,
         memo: CngnMemo,
         fee_stroops: Option<u32>,
+    ) -> StellarResult<CngnPaymentDraft> {
+        let asset = self
+            .config
+            .asset_for_network(self.stellar_client.network())?;
+        self.build_asset_payment(source, destination, &asset, amount, memo, fee_stroops)
+            .await
+    }
+
+    pub async fn build_asset_payment(
+        &self,
+        source: &str,
+        destination: &str,
+        asset: &StellarAsset,
+        amount: &str,
+        memo: CngnMemo,
+        fee_stroops: Option<u32>,
     ) -> StellarResult<CngnPaymentDraft> {
         validate_address(source)?;
         validate_address(destination)?;
@@ -95,24 +290,13 @@ impl CngnPaymentBuilder {
         let source_account = self.stellar_client.get_account(source).await?;
         let destination_account = self.stellar_client.get_account(destination).await?;
 
-        let issuer = self
-            .config
-            .issuer_for_network(self.stellar_client.network())
-            .to_string();
-        let asset_code = self.config.asset_code.clone();
-
-        ensure_destination_has_trustline(&destination_account.balances, &asset_code, &issuer)?;
+        ensure_destination_has_trustline(&destination_account.balances, asset)?;
 
         let amount_stroops = decimal_to_stroops(amount)?;
-        ensure_source_has_cngn_balance(
-            &source_account.balances,
-            amount_stroops,
-            &asset_code,
-            &issuer,
-        )?;
-
         let fee = fee_stroops.unwrap_or(self.base_fee_stroops);
-        ensure_source_has_xlm_for_fee(&source_account.balances, fee)?;
+        if self.balance_precheck {
+            ensure_source_can_pay(&source_account.balances, asset, amount_stroops, fee)?;
+        }
 
         let sequence = source_account.sequence + 1;
         let (tx, envelope) = build_unsigned_transaction(
@@ -123,8 +307,7 @@ impl CngnPaymentBuilder {
             fee,
             self.timeout,
             &memo,
-            &asset_code,
-            &issuer,
+            asset,
         )?;
 
         let network_id = network_id(self.stellar_client.network().network_passphrase());
@@ -140,8 +323,8 @@ impl CngnPaymentBuilder {
             source: source.to_string(),
             destination: destination.to_string(),
             amount: amount.to_string(),
-            asset_code,
-            asset_issuer: issuer,
+            asset_code: asset.code().to_string(),
+            asset_issuer: asset.issuer().unwrap_or_default().to_string(),
             sequence,
             fee_stroops: fee,
             timeout_seconds: self.timeout.as_secs(),
@@ -152,6 +335,125 @@ impl CngnPaymentBuilder {
         })
     }
 
+    /// Build a transaction that moves `source`'s sequence number forward to
+    /// `bump_to`, e.g. to step over a gap left by failed submissions.
+    pub async fn build_bump_sequence(
+        &self,
+        source: &str,
+        bump_to: i64,
+    ) -> StellarResult<BumpSequenceDraft> {
+        validate_address(source)?;
+
+        let source_account = self.stellar_client.get_account(source).await?;
+        let current_sequence = source_account.sequence;
+        if bump_to <= current_sequence {
+            return Err(StellarError::sequence_too_low(current_sequence, bump_to));
+        }
+
+        let fee = self.base_fee_stroops;
+        if self.balance_precheck {
+            ensure_source_has_xlm_for_fee(&source_account.balances, fee)?;
+        }
+
+        let op = Operation {
+            source_account: None,
+            body: OperationBody::BumpSequence(BumpSequenceOp {
+                bump_to: SequenceNumber(bump_to),
+            }),
+        };
+        let sequence = current_sequence + 1;
+        let (tx, envelope) = build_unsigned_envelope(
+            parse_muxed_account(source)?,
+            vec![op],
+            sequence,
+            fee,
+            self.timeout,
+            Memo::None,
+        )?;
+
+        let network_id = network_id(self.stellar_client.network().network_passphrase());
+        let tx_hash = tx
+            .hash(network_id)
+            .map_err(|e| StellarError::serialization_error(e.to_string()))?;
+        let unsigned_envelope_xdr = envelope
+            .to_xdr_base64(Limits::none())
+            .map_err(|e| StellarError::serialization_error(e.to_string()))?;
+
+        Ok(BumpSequenceDraft {
+            source: source.to_string(),
+            current_sequence,
+            bump_to,
+            sequence,
+            fee_stroops: fee,
+            timeout_seconds: self.timeout.as_secs(),
+            created_at: chrono::Utc::now().to_rfc3339(),
+            transaction_hash: hex::encode(tx_hash),
+            unsigned_envelope_xdr,
+        })
+    }
+
+    /// Build a transaction that merges `source` into `destination`, moving its
+    /// remaining XLM across and removing the source account. Horizon rejects
+    /// merges while the source still has subentries or is sponsoring reserves,
+    /// so those are checked here and reported as `AccountNotMergeable`.
+    pub async fn build_account_merge(
+        &self,
+        source: &str,
+        destination: &str,
+    ) -> StellarResult<AccountMergeDraft> {
+        validate_address(source)?;
+        validate_address(destination)?;
+        if source == destination {
+            return Err(StellarError::self_merge(source));
+        }
+
+        let source_account = self.stellar_client.get_account(source).await?;
+        let blockers = merge_blockers(&source_account);
+        if !blockers.is_empty() {
+            return Err(StellarError::account_not_mergeable(source, blockers));
+        }
+
+        let fee = self.base_fee_stroops;
+        if self.balance_precheck {
+            ensure_source_has_xlm_for_fee(&source_account.balances, fee)?;
+        }
+        // Merging into a missing account fails on-ledger with op_no_account
+        self.stellar_client.get_account(destination).await?;
+
+        let op = Operation {
+            source_account: None,
+            body: OperationBody::AccountMerge(parse_muxed_account(destination)?),
+        };
+        let sequence = source_account.sequence + 1;
+        let (tx, envelope) = build_unsigned_envelope(
+            parse_muxed_account(source)?,
+            vec![op],
+            sequence,
+            fee,
+            self.timeout,
+            Memo::None,
+        )?;
+
+        let network_id = network_id(self.stellar_client.network().network_passphrase());
+        let tx_hash = tx
+            .hash(network_id)
+            .map_err(|e| StellarError::serialization_error(e.to_string()))?;
+        let unsigned_envelope_xdr = envelope
+            .to_xdr_base64(Limits::none())
+            .map_err(|e| StellarError::serialization_error(e.to_string()))?;
+
+        Ok(AccountMergeDraft {
+            source: source.to_string(),
+            destination: destination.to_string(),
+            sequence,
+            fee_stroops: fee,
+            timeout_seconds: self.timeout.as_secs(),
+            created_at: chrono::Utc::now().to_rfc3339(),
+            transaction_hash: hex::encode(tx_hash),
+            unsigned_envelope_xdr,
+        })
+    }
+
     pub fn sign_payment(
         &self,
         draft: CngnPaymentDraft,
@@ -203,6 +505,38 @@ impl CngnPaymentBuilder {
         })
     }
 
+    /// Base64 XDR of the `TransactionSignaturePayload` for `draft`: the exact
+    /// bytes whose SHA-256 gets signed, i.e. `draft.transaction_hash`.
+    pub fn signature_base_xdr(&self, draft: &CngnPaymentDraft) -> StellarResult<String> {
+        self.signature_payload(draft)?
+            .to_xdr_base64(Limits::none())
+            .map_err(|e| StellarError::serialization_error(e.to_string()))
+    }
+
+    fn signature_payload(
+        &self,
+        draft: &CngnPaymentDraft,
+    ) -> StellarResult<TransactionSignaturePayload> {
+        let envelope =
+            TransactionEnvelope::from_xdr_base64(&draft.unsigned_envelope_xdr, Limits::none())
+                .map_err(|e| StellarError::serialization_error(e.to_string()))?;
+        let tx = match envelope {
+            TransactionEnvelope::Tx(v1) => v1.tx,
+            _ => {
+                return Err(StellarError::signing_error(
+                    "unsupported envelope type for cNGN payment",
+                ))
+            }
+        };
+
+        Ok(TransactionSignaturePayload {
+            network_id: Hash(network_id(
+                self.stellar_client.network().network_passphrase(),
+            )),
+            tagged_transaction: TransactionSignaturePayloadTaggedTransaction::Tx(tx),
+        })
+    }
+
     pub async fn submit_signed_payment(
         &self,
         signed_envelope_xdr: &str,
@@ -212,6 +546,223 @@ impl CngnPaymentBuilder {
             .submit_transaction_xdr(signed_envelope_xdr)
             .await
     }
+
+    /// Run the checks `submit_signed_payment` relies on without broadcasting:
+    /// signatures present, envelope decodable, and its sequence the next one
+    /// for the source. The sequence is only read, never reserved, so a dry run
+    /// leaves the envelope submittable afterwards.
+    pub async fn dry_run_signed_payment(
+        &self,
+        signed_envelope_xdr: &str,
+    ) -> StellarResult<PaymentDryRun> {
+        validate_signed_envelope_has_signatures(signed_envelope_xdr)?;
+        let summary = self.decode_envelope(signed_envelope_xdr)?;
+
+        let source_account = self
+            .stellar_client
+            .get_account(&account_address(&summary.source)?)
+            .await?;
+        let expected = source_account.sequence + 1;
+        if summary.sequence != expected {
+            return Err(StellarError::transaction_failed(format!(
+                "envelope sequence {} does not match next account sequence {}",
+                summary.sequence, expected
+            )));
+        }
+
+        let transaction_hash = summary
+            .fee_bump
+            .as_ref()
+            .map(|fb| fb.transaction_hash.clone())
+            .unwrap_or(summary.transaction_hash);
+
+        Ok(PaymentDryRun {
+            submitted: false,
+            transaction_hash,
+            source: summary.source,
+            sequence: summary.sequence,
+            fee_stroops: summary.fee_stroops,
+            operation_count: summary.operations.len(),
+        })
+    }
+
+    /// Decode any envelope (signed or not, including fee bumps) into a summary.
+    pub fn decode_envelope(&self, xdr: &str) -> StellarResult<EnvelopeSummary> {
+        let envelope = TransactionEnvelope::from_xdr_base64(xdr.trim(), Limits::none())
+            .map_err(|e| StellarError::serialization_error(format!("invalid xdr: {}", e)))?;
+        let network_id = network_id(self.stellar_client.network().network_passphrase());
+
+        match envelope {
+            TransactionEnvelope::Tx(v1) => {
+                summarize_transaction("tx", &v1.tx, v1.signatures.len(), network_id)
+            }
+            TransactionEnvelope::TxV0(v0) => {
+                let tx = Transaction {
+                    source_account: MuxedAccount::Ed25519(v0.tx.source_account_ed25519.clone()),
+                    fee: v0.tx.fee,
+                    seq_num: v0.tx.seq_num.clone(),
+                    cond: v0
+                        .tx
+                        .time_bounds
+                        .clone()
+                        .map_or(Preconditions::None, Preconditions::Time),
+                    memo: v0.tx.memo.clone(),
+                    operations: v0.tx.operations.clone(),
+                    ext: TransactionExt::V0,
+                };
+                summarize_transaction("tx_v0", &tx, v0.signatures.len(), network_id)
+            }
+            TransactionEnvelope::TxFeeBump(fb) => {
+                let FeeBumpTransactionInnerTx::Tx(inner) = &fb.tx.inner_tx;
+                let mut summary = summarize_transaction(
+                    "fee_bump",
+                    &inner.tx,
+                    inner.signatures.len(),
+                    network_id,
+                )?;
+                let hash = signature_payload_hash(
+                    TransactionSignaturePayloadTaggedTransaction::TxFeeBump(fb.tx.clone()),
+                    network_id,
+                )?;
+                summary.fee_bump = Some(FeeBumpSummary {
+                    fee_source: muxed_account_to_string(&fb.tx.fee_source),
+                    fee_stroops: fb.tx.fee,
+                    signature_count: fb.signatures.len(),
+                    transaction_hash: hex::encode(hash),
+                });
+                Ok(summary)
+            }
+        }
+    }
+}
+
+fn summarize_transaction(
+    envelope_type: &str,
+    tx: &Transaction,
+    signature_count: usize,
+    network_id: [u8; 32],
+) -> StellarResult<EnvelopeSummary> {
+    let hash = signature_payload_hash(
+        TransactionSignaturePayloadTaggedTransaction::Tx(tx.clone()),
+        network_id,
+    )?;
+    let time_bounds = match &tx.cond {
+        Preconditions::None => None,
+        Preconditions::Time(tb) => Some(tb),
+        Preconditions::V2(v2) => v2.time_bounds.as_ref(),
+    }
+    .map(|tb| TimeBoundsSummary {
+        min_time: tb.min_time.0,
+        max_time: tb.max_time.0,
+    });
+
+    Ok(EnvelopeSummary {
+        envelope_type: envelope_type.to_string(),
+        source: muxed_account_to_string(&tx.source_account),
+        sequence: tx.seq_num.0,
+        fee_stroops: tx.fee,
+        memo: memo_from_xdr(&tx.memo),
+        time_bounds,
+        operations: tx.operations.iter().map(summarize_operation).collect(),
+        signature_count,
+        transaction_hash: hex::encode(hash),
+        fee_bump: None,
+    })
+}
+
+fn summarize_operation(op: &Operation) -> OperationSummary {
+    let details = match &op.body {
+        OperationBody::Payment(payment) => serde_json::json!({
+            "destination": muxed_account_to_string(&payment.destination),
+            "asset": asset_label(&payment.asset),
+            "amount": decimal_from_stroops(payment.amount),
+        }),
+        OperationBody::CreateAccount(create) => serde_json::json!({
+            "destination": account_id_to_string(&create.destination),
+            "starting_balance": decimal_from_stroops(create.starting_balance),
+        }),
+        OperationBody::ChangeTrust(change_trust) => serde_json::json!({
+            "asset": match &change_trust.line {
+                ChangeTrustAsset::Native => asset_label(&stellar_xdr::next::Asset::Native),
+                ChangeTrustAsset::CreditAlphanum4(a) => {
+                    asset_label(&stellar_xdr::next::Asset::CreditAlphanum4(a.clone()))
+                }
+                ChangeTrustAsset::CreditAlphanum12(a) => {
+                    asset_label(&stellar_xdr::next::Asset::CreditAlphanum12(a.clone()))
+                }
+                ChangeTrustAsset::PoolShare(_) => "liquidity_pool_shares".to_string(),
+            },
+            "limit": decimal_from_stroops(change_trust.limit),
+        }),
+        OperationBody::BumpSequence(bump) => serde_json::json!({ "bump_to": bump.bump_to.0 }),
+        _ => serde_json::Value::Null,
+    };
+
+    OperationSummary {
+        r#type: snake_case(op.body.name()),
+        source: op.source_account.as_ref().map(muxed_account_to_string),
+        details,
+    }
+}
+
+fn asset_label(asset: &stellar_xdr::next::Asset) -> String {
+    StellarAsset::from_xdr_asset(asset)
+        .map(|a| a.to_horizon_string())
+        .unwrap_or_else(|_| "unknown".to_string())
+}
+
+fn memo_from_xdr(memo: &Memo) -> CngnMemo {
+    match memo {
+        Memo::None => CngnMemo::None,
+        Memo::Text(text) => CngnMemo::Text(String::from_utf8_lossy(text.as_slice()).into_owned()),
+        Memo::Id(id) => CngnMemo::Id(*id),
+        // A return memo is also a 32-byte hash; the summary does not distinguish them
+        Memo::Hash(hash) | Memo::Return(hash) => CngnMemo::Hash(hex::encode(hash.0)),
+    }
+}
+
+fn muxed_account_to_string(account: &MuxedAccount) -> String {
+    match account {
+        MuxedAccount::Ed25519(key) => StrkeyPublicKey(key.0).to_string(),
+        MuxedAccount::MuxedEd25519(muxed) => StrkeyMuxedAccount {
+            ed25519: muxed.ed25519.0,
+            id: muxed.id,
+        }
+        .to_string(),
+    }
+}
+
+fn account_id_to_string(account: &AccountId) -> String {
+    let AccountId(PublicKey::PublicKeyTypeEd25519(key)) = account;
+    StrkeyPublicKey(key.0).to_string()
+}
+
+fn signature_payload_hash(
+    tagged_transaction: TransactionSignaturePayloadTaggedTransaction,
+    network_id: [u8; 32],
+) -> StellarResult<[u8; 32]> {
+    let payload = TransactionSignaturePayload {
+        network_id: Hash(network_id),
+        tagged_transaction,
+    }
+    .to_xdr(Limits::none())
+    .map_err(|e| StellarError::serialization_error(e.to_string()))?;
+    Ok(Sha256::digest(payload).into())
+}
+
+fn snake_case(name: &str) -> String {
+    let mut out = String::with_capacity(name.len() + 4);
+    for (i, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() {
+            if i > 0 {
+                out.push('_');
+            }
+            out.push(c.to_ascii_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+    out
 }
 
 fn validate_address(address: &str) -> StellarResult<()> {
@@ -223,56 +774,116 @@ fn validate_address(address: &str) -> StellarResult<()> {
 }
 
 fn ensure_destination_has_trustline(
-    balances: &[crate::chains::stellar::types::AssetBalance],
-    asset_code: &str,
-    issuer: &str,
+    balances: &[AssetBalance],
+    asset: &StellarAsset,
 ) -> StellarResult<()> {
-    if extract_asset_balance(balances, asset_code, Some(issuer)).is_some() {
+    if asset.is_native() || asset.find_balance(balances).is_some() {
         Ok(())
     } else {
-        Err(StellarError::transaction_failed(
-            "recipient has no cNGN trustline (op_no_trust)",
-        ))
+        Err(StellarError::transaction_failed(format!(
+            "recipient has no {} trustline (op_no_trust)",
+            asset.code()
+        )))
     }
 }
 
-fn ensure_source_has_xlm_for_fee(
-    balances: &[crate::chains::stellar::types::AssetBalance],
+/// Subentries that stop `account` from being merged: trustlines, data entries
+/// and extra signers by name. Horizon doesn't list offers on the account, so
+/// any part of `subentry_count` not explained by the others is reported as
+/// `offers:<n>`; sponsored reserves are reported as `sponsoring:<n>`.
+pub fn merge_blockers(account: &StellarAccountInfo) -> Vec<String> {
+    let mut blockers = Vec::new();
+    let mut counted: u32 = 0;
+
+    for balance in &account.balances {
+        match balance.asset_type.as_str() {
+            "native" => {}
+            // Pool share trustlines count as two subentries
+            "liquidity_pool_shares" => {
+                blockers.push("trustline:liquidity_pool_shares".to_string());
+                counted += 2;
+            }
+            _ => {
+                blockers.push(format!(
+                    "trustline:{}:{}",
+                    balance.asset_code.as_deref().unwrap_or_default(),
+                    balance.asset_issuer.as_deref().unwrap_or_default()
+                ));
+                counted += 1;
+            }
+        }
+    }
+
+    let mut data_keys: Vec<&String> = account.data.keys().collect();
+    data_keys.sort();
+    for key in data_keys {
+        blockers.push(format!("data:{}", key));
+        counted += 1;
+    }
+
+    for signer in account.signers.iter().filter(|s| s.key != account.account_id) {
+        blockers.push(format!("signer:{}", signer.key));
+        counted += 1;
+    }
+
+    let offers = account.subentry_count.saturating_sub(counted);
+    if offers > 0 {
+        blockers.push(format!("offers:{}", offers));
+    }
+
+    // Merging is refused while the account pays reserves for anyone else
+    if account.num_sponsoring > 0 {
+        blockers.push(format!("sponsoring:{}", account.num_sponsoring));
+    }
+
+    blockers
+}
+
+fn ensure_source_has_xlm_for_fee(balances: &[AssetBalance], fee_stroops: u32) -> StellarResult<()> {
+    ensure_source_has_balance(balances, &StellarAsset::native(), i64::from(fee_stroops))
+}
+
+/// The source must hold `amount_stroops` of `asset` plus the fee in XLM; for
+/// native payments both come out of the same balance.
+fn ensure_source_can_pay(
+    balances: &[AssetBalance],
+    asset: &StellarAsset,
+    amount_stroops: i64,
     fee_stroops: u32,
 ) -> StellarResult<()> {
-    let available = balances
-        .iter()
-        .find(|b| b.asset_type == "native")
-        .and_then(|b| b.balance.parse::<f64>().ok())
-        .unwrap_or(0.0);
-    let required = (fee_stroops as f64) / 10_000_000.0;
-    if available >= required {
-        Ok(())
-    } else {
-        Err(StellarError::insufficient_xlm(
-            format!("{:.7} XLM", available),
-            format!("{:.7} XLM", required),
-        ))
+    if asset.is_native() {
+        let required = amount_stroops
+            .checked_add(i64::from(fee_stroops))
+            .ok_or_else(|| StellarError::transaction_failed("amount overflow"))?;
+        return ensure_source_has_balance(balances, asset, required);
     }
+    ensure_source_has_balance(balances, asset, amount_stroops)?;
+    ensure_source_has_xlm_for_fee(balances, fee_stroops)
 }
 
-fn ensure_source_has_cngn_balance(
-    balances: &[crate::chains::stellar::types::AssetBalance],
-    amount_stroops: i64,
-    asset_code: &str,
-    issuer: &str,
+fn ensure_source_has_balance(
+    balances: &[AssetBalance],
+    asset: &StellarAsset,
+    required_stroops: i64,
 ) -> StellarResult<()> {
-    let balance = extract_asset_balance(balances, asset_code, Some(issuer))
-        .unwrap_or_else(|| "0".to_string());
-    let available_stroops = decimal_to_stroops(&balance)?;
-    if available_stroops >= amount_stroops {
+    let balance = asset
+        .find_balance(balances)
+        .map(|b| b.balance.as_str())
+        .unwrap_or("0");
+    ensure_covers(asset.code(), balance, required_stroops)
+}
+
+fn ensure_covers(asset: &str, balance: &str, required_stroops: i64) -> StellarResult<()> {
+    let available_stroops = decimal_to_stroops(balance)?;
+    if available_stroops >= required_stroops {
         Ok(())
     } else {
-        Err(StellarError::transaction_failed(format!(
-            "insufficient cNGN balance: available={}, required={}",
-            balance,
-            decimal_from_stroops(amount_stroops)
-        )))
+        Err(StellarError::insufficient_balance(
+            asset,
+            decimal_from_stroops(available_stroops),
+            decimal_from_stroops(required_stroops),
+            decimal_from_stroops(required_stroops - available_stroops),
+        ))
     }
 }
 
@@ -284,33 +895,49 @@ fn build_unsigned_transaction(
     fee_stroops: u32,
     timeout: Duration,
     memo: &CngnMemo,
-    asset_code: &str,
-    issuer: &str,
+    asset: &StellarAsset,
 ) -> StellarResult<(Transaction, TransactionEnvelope)> {
     let source_account = parse_muxed_account(source)?;
     let destination_account = parse_muxed_account(destination)?;
-    let asset = build_asset(asset_code, issuer)?;
 
     let op = Operation {
         source_account: None,
         body: OperationBody::Payment(PaymentOp {
             destination: destination_account,
-            asset,
+            asset: asset.to_xdr_asset(),
             amount: amount_stroops,
         }),
     };
 
+    build_unsigned_envelope(
+        source_account,
+        vec![op],
+        sequence,
+        fee_stroops,
+        timeout,
+        memo_to_xdr(memo)?,
+    )
+}
+
+fn build_unsigned_envelope(
+    source_account: MuxedAccount,
+    operations: Vec<Operation>,
+    sequence: i64,
+    fee_stroops: u32,
+    timeout: Duration,
+    memo: Memo,
+) -> StellarResult<(Transaction, TransactionEnvelope)> {
     let now = unix_time();
     let tx = Transaction {
-        source_account: source_account,
+        source_account,
         fee: fee_stroops,
         seq_num: SequenceNumber(sequence),
         cond: Preconditions::Time(TimeBounds {
             min_time: TimePoint(now),
             max_time: TimePoint(now + timeout.as_secs()),
         }),
-        memo: memo_to_xdr(memo)?,
-        operations: VecM::try_from(vec![op])
+        memo,
+        operations: VecM::try_from(operations)
             .map_err(|e| StellarError::serialization_error(e.to_string()))?,
         ext: TransactionExt::V0,
     };
@@ -338,38 +965,14 @@ fn parse_muxed_account(address: &str) -> StellarResult<MuxedAccount> {
     }
 }
 
-fn parse_account_id(address: &str) -> StellarResult<AccountId> {
-    let public_key = StrkeyPublicKey::from_string(address)
-        .map_err(|_| StellarError::invalid_address(address))?;
-    Ok(AccountId(PublicKey::PublicKeyTypeEd25519(Uint256(
-        public_key.0,
-    ))))
-}
-
-fn build_asset(asset_code: &str, issuer: &str) -> StellarResult<Asset> {
-    let issuer = parse_account_id(issuer)?;
-    let code = asset_code.trim().to_uppercase();
-    let bytes = code.as_bytes();
-    if code.is_empty() || code.len() > 12 {
-        return Err(StellarError::config_error(
-            "asset code must be 1..=12 characters",
-        ));
-    }
-
-    if code.len() <= 4 {
-        let mut v = [0u8; 4];
-        v[..bytes.len()].copy_from_slice(bytes);
-        Ok(Asset::CreditAlphanum4(AlphaNum4 {
-            asset_code: AssetCode4(v),
-            issuer,
-        }))
+/// G... account behind a possibly muxed address
+fn account_address(address: &str) -> StellarResult<String> {
+    if address.starts_with('M') {
+        let muxed = StrkeyMuxedAccount::from_string(address)
+            .map_err(|_| StellarError::invalid_address(address))?;
+        Ok(StrkeyPublicKey(muxed.ed25519).to_string())
     } else {
-        let mut v = [0u8; 12];
-        v[..bytes.len()].copy_from_slice(bytes);
-        Ok(Asset::CreditAlphanum12(AlphaNum12 {
-            asset_code: AssetCode12(v),
-            issuer,
-        }))
+        Ok(address.to_string())
     }
 }
 
@@ -377,13 +980,11 @@ fn memo_to_xdr(memo: &CngnMemo) -> StellarResult<Memo> {
     match memo {
         CngnMemo::None => Ok(Memo::None),
         CngnMemo::Text(text) => {
-            if text.as_bytes().len() > 28 {
-                return Err(StellarError::transaction_failed(
-                    "memo text must be <= 28 bytes",
-                ));
-            }
-            let v: StringM<28> = text
-                .parse::<StringM<28>>()
+            let max_bytes = max_text_memo_bytes();
+            check_text_memo(text, max_bytes)
+                .map_err(|reason| StellarError::invalid_memo(max_bytes, reason))?;
+            // From raw bytes: `StringM::from_str` would unescape backslashes
+            let v = StringM::<28>::try_from(text.as_bytes().to_vec())
                 .map_err(|e| StellarError::serialization_error(e.to_string()))?;
             Ok(Memo::Text(v))
         }
@@ -493,6 +1094,30 @@ fn unix_time() -> u64 {
         .unwrap_or(0)
 }
 
+/// Total amount of `asset` moved by the payment operations in an envelope, in
+/// stroops. Payments in any other asset don't count.
+pub fn envelope_payment_stroops(xdr: &str, asset: &StellarAsset) -> StellarResult<i64> {
+    let asset = asset.to_xdr_asset();
+    let env = TransactionEnvelope::from_xdr_base64(xdr, Limits::none())
+        .map_err(|e| StellarError::serialization_error(format!("invalid xdr: {}", e)))?;
+    let operations = match &env {
+        TransactionEnvelope::Tx(v1) => v1.tx.operations.as_slice(),
+        TransactionEnvelope::TxV0(v0) => v0.tx.operations.as_slice(),
+        TransactionEnvelope::TxFeeBump(fb) => match &fb.tx.inner_tx {
+            FeeBumpTransactionInnerTx::Tx(inner) => inner.tx.operations.as_slice(),
+        },
+    };
+
+    operations
+        .iter()
+        .filter_map(|op| match &op.body {
+            OperationBody::Payment(payment) if payment.asset == asset => Some(payment.amount),
+            _ => None,
+        })
+        .try_fold(0i64, |total, amount| total.checked_add(amount))
+        .ok_or_else(|| StellarError::transaction_failed("payment amount overflow"))
+}
+
 fn validate_signed_envelope_has_signatures(xdr: &str) -> StellarResult<()> {
     use stellar_xdr::next::ReadXdr;
     let env = TransactionEnvelope::from_xdr_base64(xdr, Limits::none())
@@ -521,6 +1146,106 @@ mod tests {
         assert_eq!(decimal_to_stroops("1.2500000").unwrap(), 12_500_000);
     }
 
+    #[test]
+    fn test_deposit_account_id_becomes_memo_id() {
+        assert!(matches!(
+            CngnMemo::resolve(None, Some(42)).unwrap(),
+            CngnMemo::Id(42)
+        ));
+        assert!(matches!(
+            CngnMemo::resolve(Some(CngnMemo::None), Some(7)).unwrap(),
+            CngnMemo::Id(7)
+        ));
+        assert!(matches!(
+            memo_to_xdr(&CngnMemo::deposit(42).unwrap()).unwrap(),
+            Memo::Id(42)
+        ));
+    }
+
+    #[test]
+    fn test_deposit_account_id_is_validated() {
+        assert!(CngnMemo::deposit(0).is_err());
+        assert!(CngnMemo::deposit(MAX_DEPOSIT_ACCOUNT_ID + 1).is_err());
+        assert!(CngnMemo::resolve(Some(CngnMemo::Text("x".into())), Some(1)).is_err());
+        assert!(matches!(
+            CngnMemo::resolve(Some(CngnMemo::Text("x".into())), None).unwrap(),
+            CngnMemo::Text(_)
+        ));
+    }
+
+    #[test]
+    fn test_batch_limits_allow_full_transaction() {
+        assert_eq!(
+            check_batch_limits(100, 100, MAX_OPERATIONS_PER_TX, 10_000).unwrap(),
+            10_000
+        );
+        assert!(matches!(
+            check_batch_limits(101, 100, MAX_OPERATIONS_PER_TX, 10_000),
+            Err(StellarError::TooManyOperations { count: 101, max: 100 })
+        ));
+        assert!(matches!(
+            check_batch_limits(0, 100, MAX_OPERATIONS_PER_TX, 10_000),
+            Err(StellarError::NoOperations)
+        ));
+    }
+
+    #[test]
+    fn test_batch_fee_ceiling_scales_with_operation_count() {
+        assert_eq!(check_batch_limits(10, 500, 100, 500).unwrap(), 5_000);
+        assert!(matches!(
+            check_batch_limits(10, 501, 100, 500),
+            Err(StellarError::FeeAboveCeiling {
+                fee_stroops: 5_010,
+                max_fee_stroops: 5_000,
+                operations: 10,
+            })
+        ));
+        // Above what the u32 fee field can hold, whatever the configured ceiling
+        assert!(check_batch_limits(100, u32::MAX, 100, u32::MAX).is_err());
+    }
+
+    #[test]
+    fn test_envelope_payment_stroops_counts_only_the_given_asset() {
+        let cngn = StellarAsset::credit("cNGN", &StrkeyPublicKey([7; 32]).to_string()).unwrap();
+        let impostor =
+            StellarAsset::credit("cNGN", &StrkeyPublicKey([8; 32]).to_string()).unwrap();
+        let payment = |asset: &StellarAsset, amount: i64| Operation {
+            source_account: None,
+            body: OperationBody::Payment(PaymentOp {
+                destination: MuxedAccount::Ed25519(Uint256([1; 32])),
+                asset: asset.to_xdr_asset(),
+                amount,
+            }),
+        };
+        let envelope = TransactionEnvelope::Tx(TransactionV1Envelope {
+            tx: Transaction {
+                source_account: MuxedAccount::Ed25519(Uint256([2; 32])),
+                fee: 300,
+                seq_num: SequenceNumber(1),
+                cond: Preconditions::None,
+                memo: Memo::None,
+                operations: vec![
+                    payment(&cngn, 50),
+                    payment(&StellarAsset::native(), 1_000),
+                    payment(&impostor, 2_000),
+                    payment(&cngn, 25),
+                ]
+                .try_into()
+                .unwrap(),
+                ext: TransactionExt::V0,
+            },
+            signatures: VecM::default(),
+        })
+        .to_xdr_base64(Limits::none())
+        .unwrap();
+
+        assert_eq!(envelope_payment_stroops(&envelope, &cngn).unwrap(), 75);
+        assert_eq!(
+            envelope_payment_stroops(&envelope, &StellarAsset::native()).unwrap(),
+            1_000
+        );
+    }
+
     #[test]
     fn test_decimal_to_stroops_invalid() {
         assert!(decimal_to_stroops("-1").is_err());
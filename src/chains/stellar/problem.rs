@@ -0,0 +1,195 @@
+//! Horizon error bodies
+//!
+//! Horizon answers failures with RFC 7807 problem+json: `type`, `title`,
+//! `status`, `detail` and, for rejected transactions, `extras.result_codes`.
+//! Every client call path runs failed responses through [`HorizonProblem`] so
+//! the resulting `StellarError` carries Horizon's own explanation instead of a
+//! bare status line.
+
+use crate::chains::stellar::errors::StellarError;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HorizonProblem {
+    /// URI naming the problem, e.g. `https://stellar.org/horizon-errors/not_found`
+    #[serde(rename = "type", default)]
+    pub problem_type: Option<String>,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub status: Option<u16>,
+    #[serde(default)]
+    pub detail: Option<String>,
+    #[serde(default)]
+    pub extras: Option<HorizonProblemExtras>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HorizonProblemExtras {
+    #[serde(default)]
+    pub result_codes: Option<HorizonResultCodes>,
+    #[serde(default)]
+    pub result_xdr: Option<String>,
+    #[serde(default)]
+    pub envelope_xdr: Option<String>,
+}
+
+/// `tx_*` and per-operation `op_*` codes for a rejected transaction
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HorizonResultCodes {
+    #[serde(default)]
+    pub transaction: Option<String>,
+    #[serde(default)]
+    pub operations: Vec<String>,
+}
+
+impl HorizonProblem {
+    /// Parse a problem body. Anything that isn't one (HTML from a proxy, an
+    /// empty body) becomes a problem carrying just `status`, with the raw
+    /// text as the detail.
+    pub fn parse(status: u16, body: &str) -> Self {
+        match serde_json::from_str::<HorizonProblem>(body) {
+            Ok(problem) => HorizonProblem {
+                status: problem.status.or(Some(status)),
+                ..problem
+            },
+            Err(_) => HorizonProblem {
+                status: Some(status),
+                detail: Some(body.trim().to_string()).filter(|d| !d.is_empty()),
+                ..Default::default()
+            },
+        }
+    }
+
+    pub fn result_codes(&self) -> Option<&HorizonResultCodes> {
+        self.extras.as_ref()?.result_codes.as_ref()
+    }
+
+    /// One line for logs and error messages, e.g.
+    /// `Transaction Failed (400): ... [tx_failed; op_no_trust]`
+    pub fn describe(&self) -> String {
+        let mut message = self.title.clone().unwrap_or_else(|| "Horizon error".to_string());
+        if let Some(status) = self.status {
+            message.push_str(&format!(" ({})", status));
+        }
+        if let Some(detail) = &self.detail {
+            message.push_str(": ");
+            message.push_str(detail);
+        }
+        if let Some(codes) = self.result_codes() {
+            let codes: Vec<&str> = codes
+                .transaction
+                .iter()
+                .chain(codes.operations.iter())
+                .map(String::as_str)
+                .collect();
+            if !codes.is_empty() {
+                message.push_str(&format!(" [{}]", codes.join("; ")));
+            }
+        }
+        message
+    }
+
+    /// The `StellarError` this problem warrants. 404s map to whatever is
+    /// missing for the call (`not_found`); statuses with no dedicated variant
+    /// go to `fallback` with the description. Transaction rejections always
+    /// come back as `TransactionFailed`.
+    pub fn into_error(
+        self,
+        retry_after: Option<u64>,
+        not_found: impl FnOnce() -> StellarError,
+        fallback: impl FnOnce(String) -> StellarError,
+    ) -> StellarError {
+        match self.status {
+            Some(404) => not_found(),
+            Some(429) => StellarError::RateLimitError,
+            Some(503) => StellarError::service_unavailable(retry_after),
+            _ if self.result_codes().is_some() => {
+                StellarError::transaction_failed(self.describe())
+            }
+            _ => fallback(self.describe()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn not_found() -> StellarError {
+        StellarError::account_not_found("GMISSING")
+    }
+
+    #[test]
+    fn bad_request_keeps_result_codes() {
+        let body = r#"{
+            "type": "https://stellar.org/horizon-errors/transaction_failed",
+            "title": "Transaction Failed",
+            "status": 400,
+            "detail": "The transaction failed when submitted to the stellar network.",
+            "extras": {
+                "envelope_xdr": "AAAAAgAAAAA=",
+                "result_codes": {
+                    "transaction": "tx_failed",
+                    "operations": ["op_no_trust"]
+                },
+                "result_xdr": "AAAAAAAAAGT/////AAAAAQAAAAAAAAAB////+gAAAAA="
+            }
+        }"#;
+
+        let problem = HorizonProblem::parse(400, body);
+        let codes = problem.result_codes().expect("result codes");
+        assert_eq!(codes.transaction.as_deref(), Some("tx_failed"));
+        assert_eq!(codes.operations, vec!["op_no_trust".to_string()]);
+
+        match problem.into_error(None, not_found, StellarError::network_error) {
+            StellarError::TransactionFailed { message } => {
+                assert!(message.starts_with("Transaction Failed (400)"));
+                assert!(message.contains("[tx_failed; op_no_trust]"));
+            }
+            other => panic!("expected TransactionFailed, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn not_found_uses_caller_variant() {
+        let body = r#"{
+            "type": "https://stellar.org/horizon-errors/not_found",
+            "title": "Resource Missing",
+            "status": 404,
+            "detail": "The resource at the url requested was not found."
+        }"#;
+
+        let problem = HorizonProblem::parse(404, body);
+        assert_eq!(problem.title.as_deref(), Some("Resource Missing"));
+        assert!(matches!(
+            problem.into_error(None, not_found, StellarError::network_error),
+            StellarError::AccountNotFound { .. }
+        ));
+    }
+
+    #[test]
+    fn service_unavailable_carries_retry_after() {
+        let body = r#"{
+            "type": "https://stellar.org/horizon-errors/service_unavailable",
+            "title": "Service Unavailable",
+            "status": 503,
+            "detail": "The request cannot be serviced at this time."
+        }"#;
+
+        let problem = HorizonProblem::parse(503, body);
+        assert!(matches!(
+            problem.into_error(Some(30), not_found, StellarError::network_error),
+            StellarError::ServiceUnavailable {
+                retry_after: Some(30)
+            }
+        ));
+    }
+
+    #[test]
+    fn non_json_body_becomes_detail() {
+        let problem = HorizonProblem::parse(502, "<html>Bad Gateway</html>");
+        assert_eq!(problem.status, Some(502));
+        assert_eq!(problem.describe(), "Horizon error (502): <html>Bad Gateway</html>");
+    }
+}
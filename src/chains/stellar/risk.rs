@@ -0,0 +1,293 @@
+//! Account-activity risk scoring
+//!
+//! Combines cheap on-chain signals into a 0.0–1.0 score so callers can gate
+//! large transfers to brand-new or empty accounts. Scoring is a pure function
+//! of the signals and config; fetching the signals is left to the caller.
+
+use crate::chains::stellar::types::StellarAccountInfo;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RiskWeights {
+    pub account_age: f64,
+    pub subentries: f64,
+    pub activity: f64,
+    pub balance: f64,
+}
+
+impl Default for RiskWeights {
+    fn default() -> Self {
+        Self {
+            account_age: 0.4,
+            subentries: 0.1,
+            activity: 0.3,
+            balance: 0.2,
+        }
+    }
+}
+
+/// Weights plus the value at which each signal stops contributing risk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RiskConfig {
+    pub weights: RiskWeights,
+    pub mature_account_days: i64,
+    pub established_subentries: u32,
+    pub active_transaction_count: u32,
+    pub funded_xlm_balance: f64,
+    pub recent_window_days: i64,
+    pub medium_threshold: f64,
+    pub high_threshold: f64,
+}
+
+impl Default for RiskConfig {
+    fn default() -> Self {
+        Self {
+            weights: RiskWeights::default(),
+            mature_account_days: 90,
+            established_subentries: 3,
+            active_transaction_count: 20,
+            funded_xlm_balance: 100.0,
+            recent_window_days: 30,
+            medium_threshold: 0.4,
+            high_threshold: 0.7,
+        }
+    }
+}
+
+impl RiskConfig {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            weights: RiskWeights {
+                account_age: env_or("RISK_WEIGHT_ACCOUNT_AGE", defaults.weights.account_age),
+                subentries: env_or("RISK_WEIGHT_SUBENTRIES", defaults.weights.subentries),
+                activity: env_or("RISK_WEIGHT_ACTIVITY", defaults.weights.activity),
+                balance: env_or("RISK_WEIGHT_BALANCE", defaults.weights.balance),
+            },
+            mature_account_days: env_or("RISK_MATURE_ACCOUNT_DAYS", defaults.mature_account_days),
+            established_subentries: env_or(
+                "RISK_ESTABLISHED_SUBENTRIES",
+                defaults.established_subentries,
+            ),
+            active_transaction_count: env_or(
+                "RISK_ACTIVE_TRANSACTION_COUNT",
+                defaults.active_transaction_count,
+            ),
+            funded_xlm_balance: env_or("RISK_FUNDED_XLM_BALANCE", defaults.funded_xlm_balance),
+            recent_window_days: env_or("RISK_RECENT_WINDOW_DAYS", defaults.recent_window_days),
+            medium_threshold: env_or("RISK_MEDIUM_THRESHOLD", defaults.medium_threshold),
+            high_threshold: env_or("RISK_HIGH_THRESHOLD", defaults.high_threshold),
+        }
+    }
+}
+
+fn env_or<T: std::str::FromStr>(key: &str, default: T) -> T {
+    std::env::var(key)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
+/// Raw inputs to the score
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RiskSignals {
+    /// `None` when Horizon did not report a usable creation time
+    pub account_age_days: Option<i64>,
+    pub subentry_count: u32,
+    pub recent_transactions: u32,
+    pub xlm_balance: f64,
+}
+
+impl RiskSignals {
+    /// `created_at` is the time of the account's first operation, see
+    /// `StellarClient::account_created_at`
+    pub fn from_account(
+        account: &StellarAccountInfo,
+        created_at: Option<&str>,
+        recent_transactions: u32,
+        now: DateTime<Utc>,
+    ) -> Self {
+        let account_age_days = created_at
+            .and_then(|ts| DateTime::parse_from_rfc3339(ts).ok())
+            .map(|created| (now - created.with_timezone(&Utc)).num_days().max(0));
+        let xlm_balance = account
+            .balances
+            .iter()
+            .find(|b| b.asset_type == "native")
+            .and_then(|b| b.balance.parse::<f64>().ok())
+            .unwrap_or(0.0);
+
+        Self {
+            account_age_days,
+            subentry_count: account.subentry_count,
+            recent_transactions,
+            xlm_balance,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RiskLevel {
+    Low,
+    Medium,
+    High,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RiskFactor {
+    pub name: String,
+    /// 0.0 (no risk) to 1.0 (maximum risk) for this signal alone
+    pub risk: f64,
+    pub weight: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RiskAssessment {
+    pub score: f64,
+    pub level: RiskLevel,
+    pub signals: RiskSignals,
+    pub factors: Vec<RiskFactor>,
+}
+
+/// Score the signals; missing data is treated as maximum risk for that factor.
+pub fn assess(signals: RiskSignals, config: &RiskConfig) -> RiskAssessment {
+    let weights = &config.weights;
+    let factors = vec![
+        RiskFactor {
+            name: "account_age".to_string(),
+            risk: signals
+                .account_age_days
+                .map(|days| shortfall(days as f64, config.mature_account_days as f64))
+                .unwrap_or(1.0),
+            weight: weights.account_age,
+        },
+        RiskFactor {
+            name: "subentries".to_string(),
+            risk: shortfall(
+                signals.subentry_count as f64,
+                config.established_subentries as f64,
+            ),
+            weight: weights.subentries,
+        },
+        RiskFactor {
+            name: "activity".to_string(),
+            risk: shortfall(
+                signals.recent_transactions as f64,
+                config.active_transaction_count as f64,
+            ),
+            weight: weights.activity,
+        },
+        RiskFactor {
+            name: "balance".to_string(),
+            risk: shortfall(signals.xlm_balance, config.funded_xlm_balance),
+            weight: weights.balance,
+        },
+    ];
+
+    let total_weight: f64 = factors.iter().map(|f| f.weight.max(0.0)).sum();
+    let score = if total_weight > 0.0 {
+        factors
+            .iter()
+            .map(|f| f.risk * f.weight.max(0.0))
+            .sum::<f64>()
+            / total_weight
+    } else {
+        0.0
+    };
+
+    let level = if score >= config.high_threshold {
+        RiskLevel::High
+    } else if score >= config.medium_threshold {
+        RiskLevel::Medium
+    } else {
+        RiskLevel::Low
+    };
+
+    RiskAssessment {
+        score,
+        level,
+        signals,
+        factors,
+    }
+}
+
+/// How far `value` falls short of `target`, as a 0.0–1.0 fraction
+fn shortfall(value: f64, target: f64) -> f64 {
+    if target <= 0.0 {
+        return 0.0;
+    }
+    (1.0 - value / target).clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn brand_new_empty_account_is_high_risk() {
+        let assessment = assess(
+            RiskSignals {
+                account_age_days: Some(0),
+                subentry_count: 0,
+                recent_transactions: 1,
+                xlm_balance: 1.0,
+            },
+            &RiskConfig::default(),
+        );
+        assert_eq!(assessment.level, RiskLevel::High);
+        assert!(assessment.score > 0.9, "score was {}", assessment.score);
+    }
+
+    #[test]
+    fn established_funded_account_is_low_risk() {
+        let assessment = assess(
+            RiskSignals {
+                account_age_days: Some(400),
+                subentry_count: 6,
+                recent_transactions: 45,
+                xlm_balance: 2_500.0,
+            },
+            &RiskConfig::default(),
+        );
+        assert_eq!(assessment.level, RiskLevel::Low);
+        assert_eq!(assessment.score, 0.0);
+    }
+
+    #[test]
+    fn unknown_account_age_counts_as_risky() {
+        let signals = RiskSignals {
+            account_age_days: None,
+            subentry_count: 6,
+            recent_transactions: 45,
+            xlm_balance: 2_500.0,
+        };
+        let assessment = assess(signals, &RiskConfig::default());
+        assert!((assessment.score - 0.4).abs() < 1e-9);
+        assert_eq!(assessment.level, RiskLevel::Medium);
+    }
+
+    #[test]
+    fn weights_are_configurable() {
+        let config = RiskConfig {
+            weights: RiskWeights {
+                account_age: 0.0,
+                subentries: 0.0,
+                activity: 0.0,
+                balance: 1.0,
+            },
+            ..RiskConfig::default()
+        };
+        let assessment = assess(
+            RiskSignals {
+                account_age_days: Some(0),
+                subentry_count: 0,
+                recent_transactions: 0,
+                xlm_balance: 50.0,
+            },
+            &config,
+        );
+        assert!((assessment.score - 0.5).abs() < 1e-9);
+    }
+}
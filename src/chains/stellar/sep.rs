@@ -0,0 +1,274 @@
+//! Bounded fetches for SEP-1 (stellar.toml) and SEP-2 (federation)
+//!
+//! These documents live on arbitrary third-party domains, so every fetch is
+//! HTTPS-only (redirects included), time-limited, and capped in size. A body
+//! that exceeds the cap is abandoned mid-stream rather than buffered.
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use thiserror::Error;
+
+/// Largest stellar.toml or federation response accepted
+pub const DEFAULT_MAX_RESPONSE_BYTES: usize = 100 * 1024;
+pub const DEFAULT_FETCH_TIMEOUT: Duration = Duration::from_secs(5);
+const MAX_REDIRECTS: usize = 3;
+
+#[derive(Debug, Error)]
+pub enum SepFetchError {
+    #[error("refusing to fetch non-HTTPS url: {url}")]
+    InsecureUrl { url: String },
+    #[error("response from {url} exceeds {limit} bytes")]
+    TooLarge { url: String, limit: usize },
+    #[error("fetching {url} timed out after {seconds}s")]
+    Timeout { url: String, seconds: u64 },
+    #[error("{url} returned HTTP {status}")]
+    Status { url: String, status: u16 },
+    #[error("failed to fetch {url}: {message}")]
+    Network { url: String, message: String },
+    #[error("invalid response from {url}: {message}")]
+    InvalidResponse { url: String, message: String },
+}
+
+#[derive(Debug, Clone)]
+pub struct SepFetchConfig {
+    pub max_response_bytes: usize,
+    pub timeout: Duration,
+    /// Only ever disabled by tests talking to a local plain-HTTP mock
+    pub https_only: bool,
+}
+
+impl Default for SepFetchConfig {
+    fn default() -> Self {
+        Self {
+            max_response_bytes: DEFAULT_MAX_RESPONSE_BYTES,
+            timeout: DEFAULT_FETCH_TIMEOUT,
+            https_only: true,
+        }
+    }
+}
+
+/// SEP-2 federation response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FederationRecord {
+    pub stellar_address: Option<String>,
+    pub account_id: String,
+    pub memo_type: Option<String>,
+    pub memo: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SepFetcher {
+    http_client: reqwest::Client,
+    config: SepFetchConfig,
+}
+
+impl SepFetcher {
+    pub fn new(config: SepFetchConfig) -> Result<Self, SepFetchError> {
+        let https_only = config.https_only;
+        let redirect = reqwest::redirect::Policy::custom(move |attempt| {
+            if attempt.previous().len() >= MAX_REDIRECTS {
+                attempt.error("too many redirects")
+            } else if https_only && attempt.url().scheme() != "https" {
+                attempt.error("redirect to non-HTTPS url")
+            } else {
+                attempt.follow()
+            }
+        });
+
+        let http_client = reqwest::Client::builder()
+            .timeout(config.timeout)
+            .redirect(redirect)
+            .user_agent("Aframp-Backend/1.0")
+            .build()
+            .map_err(|e| SepFetchError::Network {
+                url: String::new(),
+                message: e.to_string(),
+            })?;
+
+        Ok(Self {
+            http_client,
+            config,
+        })
+    }
+
+    /// `https://<domain>/.well-known/stellar.toml`, as raw TOML text
+    pub async fn fetch_stellar_toml(&self, domain: &str) -> Result<String, SepFetchError> {
+        let url = format!("https://{}/.well-known/stellar.toml", domain.trim());
+        self.fetch_text(&url).await
+    }
+
+    /// Resolve `q` against a federation server (`type` is `name`, `id`, ...)
+    pub async fn fetch_federation(
+        &self,
+        federation_server: &str,
+        q: &str,
+        query_type: &str,
+    ) -> Result<FederationRecord, SepFetchError> {
+        let mut url = reqwest::Url::parse(federation_server).map_err(|e| {
+            SepFetchError::InvalidResponse {
+                url: federation_server.to_string(),
+                message: e.to_string(),
+            }
+        })?;
+        url.query_pairs_mut()
+            .append_pair("q", q)
+            .append_pair("type", query_type);
+
+        let body = self.fetch_text(url.as_str()).await?;
+        serde_json::from_str(&body).map_err(|e| SepFetchError::InvalidResponse {
+            url: url.to_string(),
+            message: e.to_string(),
+        })
+    }
+
+    /// GET `url`, enforcing the scheme, timeout and size limits
+    pub async fn fetch_text(&self, url: &str) -> Result<String, SepFetchError> {
+        let parsed = reqwest::Url::parse(url).map_err(|_| SepFetchError::InsecureUrl {
+            url: url.to_string(),
+        })?;
+        if self.config.https_only && parsed.scheme() != "https" {
+            return Err(SepFetchError::InsecureUrl {
+                url: url.to_string(),
+            });
+        }
+
+        let limit = self.config.max_response_bytes;
+        let map_err = |e: reqwest::Error| {
+            if e.is_timeout() {
+                SepFetchError::Timeout {
+                    url: url.to_string(),
+                    seconds: self.config.timeout.as_secs(),
+                }
+            } else {
+                SepFetchError::Network {
+                    url: url.to_string(),
+                    message: e.to_string(),
+                }
+            }
+        };
+
+        let mut response = self.http_client.get(parsed).send().await.map_err(map_err)?;
+        if !response.status().is_success() {
+            return Err(SepFetchError::Status {
+                url: url.to_string(),
+                status: response.status().as_u16(),
+            });
+        }
+        if response
+            .content_length()
+            .is_some_and(|len| len > limit as u64)
+        {
+            return Err(SepFetchError::TooLarge {
+                url: url.to_string(),
+                limit,
+            });
+        }
+
+        // Content-Length can be absent or wrong, so count what actually arrives
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await.map_err(map_err)? {
+            if body.len() + chunk.len() > limit {
+                return Err(SepFetchError::TooLarge {
+                    url: url.to_string(),
+                    limit,
+                });
+            }
+            body.extend_from_slice(&chunk);
+        }
+
+        String::from_utf8(body).map_err(|e| SepFetchError::InvalidResponse {
+            url: url.to_string(),
+            message: e.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn local_fetcher(max_response_bytes: usize, timeout: Duration) -> SepFetcher {
+        SepFetcher::new(SepFetchConfig {
+            max_response_bytes,
+            timeout,
+            https_only: false,
+        })
+        .unwrap()
+    }
+
+    async fn serve_toml(server: &MockServer, template: ResponseTemplate) -> String {
+        Mock::given(method("GET"))
+            .and(path("/.well-known/stellar.toml"))
+            .respond_with(template)
+            .mount(server)
+            .await;
+        format!("{}/.well-known/stellar.toml", server.uri())
+    }
+
+    #[tokio::test]
+    async fn small_toml_is_returned() {
+        let server = MockServer::start().await;
+        let url = serve_toml(
+            &server,
+            ResponseTemplate::new(200).set_body_string("FEDERATION_SERVER=\"https://x\"\n"),
+        )
+        .await;
+
+        let body = local_fetcher(1024, Duration::from_secs(5))
+            .fetch_text(&url)
+            .await
+            .unwrap();
+        assert!(body.starts_with("FEDERATION_SERVER"));
+    }
+
+    #[tokio::test]
+    async fn oversized_toml_is_rejected() {
+        let server = MockServer::start().await;
+        let url = serve_toml(
+            &server,
+            ResponseTemplate::new(200).set_body_string("#".repeat(DEFAULT_MAX_RESPONSE_BYTES + 1)),
+        )
+        .await;
+
+        let err = local_fetcher(DEFAULT_MAX_RESPONSE_BYTES, Duration::from_secs(5))
+            .fetch_text(&url)
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, SepFetchError::TooLarge { limit, .. } if limit == DEFAULT_MAX_RESPONSE_BYTES),
+            "got {err:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn slow_server_times_out_with_clear_error() {
+        let server = MockServer::start().await;
+        let url = serve_toml(
+            &server,
+            ResponseTemplate::new(200)
+                .set_body_string("VERSION=\"2.0.0\"")
+                .set_delay(Duration::from_secs(3)),
+        )
+        .await;
+
+        let err = local_fetcher(1024, Duration::from_secs(1))
+            .fetch_text(&url)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SepFetchError::Timeout { .. }), "got {err:?}");
+        assert!(err.to_string().contains("timed out after 1s"));
+    }
+
+    #[tokio::test]
+    async fn plain_http_is_refused_by_default() {
+        let fetcher = SepFetcher::new(SepFetchConfig::default()).unwrap();
+
+        let err = fetcher
+            .fetch_text("http://example.com/.well-known/stellar.toml")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SepFetchError::InsecureUrl { .. }));
+    }
+}
@@ -1,6 +1,7 @@
 use crate::chains::stellar::client::StellarClient;
 use crate::chains::stellar::errors::StellarError;
 use crate::chains::stellar::payment::{CngnMemo, CngnPaymentBuilder};
+use crate::chains::stellar::types::LIQUIDITY_POOL_SHARES;
 use crate::chains::traits::*;
 use async_trait::async_trait;
 use std::collections::HashMap;
@@ -35,12 +36,18 @@ impl From<StellarError> for BlockchainError {
                 BlockchainError::AccountNotFound { address }
             }
             StellarError::InvalidAddress { address } => BlockchainError::InvalidAddress { address },
+            StellarError::InvalidAsset { code, reason } => BlockchainError::ConfigError {
+                message: format!("invalid asset {}: {}", code, reason),
+            },
             StellarError::NetworkError { message } => BlockchainError::NetworkError { message },
             StellarError::TransactionFailed { message } => {
                 BlockchainError::TransactionFailed { message }
             }
             StellarError::TimeoutError { seconds } => BlockchainError::Timeout { seconds },
             StellarError::RateLimitError => BlockchainError::RateLimitExceeded,
+            StellarError::ServiceUnavailable { .. } => BlockchainError::NetworkError {
+                message: "Horizon is temporarily unavailable".to_string(),
+            },
             StellarError::InsufficientXlm {
                 required,
                 available,
@@ -48,6 +55,14 @@ impl From<StellarError> for BlockchainError {
                 required,
                 available,
             },
+            StellarError::InsufficientBalance {
+                required,
+                available,
+                ..
+            } => BlockchainError::InsufficientBalance {
+                required,
+                available,
+            },
             StellarError::ConfigError { message } => BlockchainError::ConfigError { message },
             StellarError::SerializationError { message } => {
                 BlockchainError::SerializationError { message }
@@ -58,6 +73,15 @@ impl From<StellarError> for BlockchainError {
                 message: format!("Trustline already exists for {} and {}", address, asset),
             },
             StellarError::SigningError { message } => BlockchainError::Other { message },
+            StellarError::ContractError { message } => {
+                BlockchainError::TransactionFailed { message }
+            }
+            StellarError::SequenceTooLow { current, requested } => BlockchainError::Other {
+                message: format!(
+                    "sequence {} is not above current sequence {}",
+                    requested, current
+                ),
+            },
         }
     }
 }
@@ -82,11 +106,19 @@ impl BlockchainService for StellarBlockchainService {
             .balances
             .into_iter()
             .map(|b| AssetBalance {
-                asset_code: b.asset_code.unwrap_or_else(|| "XLM".to_string()),
+                // Pool shares have neither code nor issuer; they are not XLM
+                asset_code: b.asset_code.unwrap_or_else(|| {
+                    if b.asset_type == LIQUIDITY_POOL_SHARES {
+                        "LP".to_string()
+                    } else {
+                        "XLM".to_string()
+                    }
+                }),
                 issuer: b.asset_issuer,
                 balance: b.balance,
                 asset_type: b.asset_type,
                 limit: b.limit,
+                liquidity_pool_id: b.liquidity_pool_id,
             })
             .collect();
 
@@ -0,0 +1,70 @@
+//! Keys the backend signs with on its own behalf
+//!
+//! User transactions are always signed client-side; the only transactions the
+//! backend signs itself are issuer operations such as token mint/burn. Those go
+//! through `SignerProvider` so the seed-in-env signer can later be swapped for
+//! an HSM or KMS-backed one without touching the callers.
+
+use crate::chains::stellar::errors::{StellarError, StellarResult};
+use ed25519_dalek::{Signer, SigningKey};
+use stellar_strkey::ed25519::{PrivateKey as StrkeyPrivateKey, PublicKey as StrkeyPublicKey};
+use stellar_xdr::next::{DecoratedSignature, Signature, SignatureHint};
+
+pub trait SignerProvider: Send + Sync {
+    /// G... address of the signing account
+    fn public_key(&self) -> String;
+
+    /// Sign a 32-byte transaction hash
+    fn sign(&self, hash: &[u8; 32]) -> StellarResult<DecoratedSignature>;
+}
+
+/// Signs with an ed25519 secret seed held in memory
+pub struct SecretSeedSigner {
+    signing_key: SigningKey,
+}
+
+impl SecretSeedSigner {
+    pub fn from_secret(secret_seed: &str) -> StellarResult<Self> {
+        let private = StrkeyPrivateKey::from_string(secret_seed.trim())
+            .map_err(|_| StellarError::signing_error("invalid secret seed"))?;
+        Ok(Self {
+            signing_key: SigningKey::from_bytes(&private.0),
+        })
+    }
+
+    /// Reads `SOROBAN_ADMIN_SECRET`. `None` when unset; an invalid seed is an error.
+    pub fn from_env() -> Option<StellarResult<Self>> {
+        std::env::var("SOROBAN_ADMIN_SECRET")
+            .ok()
+            .filter(|s| !s.trim().is_empty())
+            .map(|s| Self::from_secret(&s))
+    }
+}
+
+// Hand-written so the seed can never end up in logs via `{:?}`.
+impl std::fmt::Debug for SecretSeedSigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SecretSeedSigner")
+            .field("public_key", &self.public_key())
+            .finish()
+    }
+}
+
+impl SignerProvider for SecretSeedSigner {
+    fn public_key(&self) -> String {
+        StrkeyPublicKey(self.signing_key.verifying_key().to_bytes()).to_string()
+    }
+
+    fn sign(&self, hash: &[u8; 32]) -> StellarResult<DecoratedSignature> {
+        let public_key = self.signing_key.verifying_key().to_bytes();
+        let hint = SignatureHint::try_from(&public_key[public_key.len() - 4..])
+            .map_err(|e| StellarError::serialization_error(e.to_string()))?;
+        let signature = self
+            .signing_key
+            .try_sign(hash)
+            .map_err(|_| StellarError::signing_error("failed to sign transaction hash"))?;
+        let signature = Signature::try_from(signature.to_bytes().to_vec())
+            .map_err(|e| StellarError::serialization_error(e.to_string()))?;
+        Ok(DecoratedSignature { hint, signature })
+    }
+}
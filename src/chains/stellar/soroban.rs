@@ -0,0 +1,1109 @@
+//! Soroban RPC client
+//!
+//! Thin JSON-RPC wrapper around the Soroban RPC server. Transient failures
+//! (transport errors, timeouts, 429/5xx, RPC internal errors) are retried with
+//! the shared `retry_async` policy the payment providers also use; contract-execution
+//! failures and malformed requests are deterministic and returned immediately.
+//!
+//! Issuer-side token operations (`mint`, `burn`) and storage TTL extensions
+//! are the only Soroban transactions the backend builds and signs itself, with
+//! the key supplied by a [`SignerProvider`].
+
+use crate::chains::stellar::config::StellarNetwork;
+use crate::chains::stellar::errors::{StellarError, StellarResult};
+use crate::chains::stellar::signer::SignerProvider;
+use crate::retry::{retry_async, RetryPolicy};
+use bigdecimal::num_bigint::BigInt;
+use bigdecimal::{BigDecimal, ToPrimitive};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use std::time::Duration;
+use stellar_strkey::{ed25519::PublicKey as StrkeyPublicKey, Contract as StrkeyContract};
+use stellar_xdr::next::{
+    AccountId, ContractDataDurability, ContractId, ExtendFootprintTtlOp, ExtensionPoint, Hash,
+    HostFunction, Int128Parts, InvokeContractArgs, InvokeHostFunctionOp, LedgerEntryData,
+    LedgerFootprint, LedgerKey, LedgerKeyAccount, LedgerKeyContractData, Limits, Memo,
+    MuxedAccount, Operation, OperationBody, Preconditions, PublicKey, ReadXdr, ScAddress, ScSymbol,
+    ScVal, ScVec, SequenceNumber, SorobanAuthorizationEntry, SorobanResources,
+    SorobanTransactionData, SorobanTransactionDataExt, Transaction, TransactionEnvelope,
+    TransactionExt, TransactionSignaturePayload, TransactionSignaturePayloadTaggedTransaction,
+    TransactionV1Envelope, Uint256, VecM, WriteXdr,
+};
+use thiserror::Error;
+use tracing::debug;
+
+const TESTNET_RPC_URL: &str = "https://soroban-testnet.stellar.org";
+/// JSON-RPC "internal error"; every other RPC error code is a request problem
+const JSON_RPC_INTERNAL_ERROR: i64 = -32603;
+/// Inclusion fee bid for admin invocations, on top of the simulated resource fee
+const ADMIN_BASE_FEE_STROOPS: u32 = 100;
+/// Ledgers a TTL extension keeps entries alive for: about 30 days at 5s a ledger
+const DEFAULT_TTL_EXTEND_TO_LEDGERS: u32 = 518_400;
+
+#[derive(Debug, Clone)]
+pub struct SorobanConfig {
+    pub rpc_url: String,
+    pub request_timeout: Duration,
+    pub max_retries: u32,
+}
+
+impl SorobanConfig {
+    /// Reads `SOROBAN_RPC_URL`, `SOROBAN_REQUEST_TIMEOUT` (seconds) and
+    /// `SOROBAN_MAX_RETRIES`. Returns `None` when no RPC URL is available, i.e.
+    /// on mainnet without an explicit `SOROBAN_RPC_URL`.
+    pub fn from_env(network: &StellarNetwork) -> Option<Self> {
+        let rpc_url = std::env::var("SOROBAN_RPC_URL").ok().or(match network {
+            StellarNetwork::Testnet => Some(TESTNET_RPC_URL.to_string()),
+            StellarNetwork::Mainnet => None,
+        })?;
+
+        let request_timeout = std::env::var("SOROBAN_REQUEST_TIMEOUT")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .map(Duration::from_secs)
+            .unwrap_or(Duration::from_secs(15));
+
+        let max_retries = std::env::var("SOROBAN_MAX_RETRIES")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(3);
+
+        Some(Self {
+            rpc_url,
+            request_timeout,
+            max_retries,
+        })
+    }
+}
+
+/// `simulateTransaction` result for a successful contract invocation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SorobanSimulation {
+    pub latest_ledger: u32,
+    #[serde(default)]
+    pub min_resource_fee: Option<String>,
+    #[serde(default)]
+    pub transaction_data: Option<String>,
+    #[serde(default)]
+    pub results: Vec<JsonValue>,
+}
+
+impl SorobanSimulation {
+    /// Decode the first invocation result as a token amount, e.g. the return
+    /// value of a SEP-41 `balance` call, scaled by the token's `decimals`.
+    pub fn amount_result(&self, decimals: u32) -> StellarResult<BigDecimal> {
+        let xdr = self
+            .results
+            .first()
+            .and_then(|r| r.get("xdr"))
+            .and_then(|x| x.as_str())
+            .ok_or_else(|| StellarError::serialization_error("simulation has no result xdr"))?;
+        let value = ScVal::from_xdr_base64(xdr, Limits::none())
+            .map_err(|e| StellarError::serialization_error(e.to_string()))?;
+        match value {
+            ScVal::I128(parts) => Ok(i128_to_bigdecimal(
+                ((parts.hi as i128) << 64) | parts.lo as i128,
+                decimals,
+            )),
+            other => Err(StellarError::serialization_error(format!(
+                "expected an i128 amount, got {:?}",
+                other.discriminant()
+            ))),
+        }
+    }
+}
+
+/// Converting a decimal amount back into a contract's i128 units failed
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum AmountConversionError {
+    #[error("amount {value} does not fit in i128 at {decimals} decimals")]
+    Overflow { value: String, decimals: u32 },
+    #[error("amount {value} has more than {decimals} decimal places")]
+    TooPrecise { value: String, decimals: u32 },
+    #[error("amount {value} must be greater than zero")]
+    NotPositive { value: String },
+}
+
+/// Exact decimal value of a contract amount held in `decimals`-scaled units.
+/// Every i128, including `i128::MIN` and `i128::MAX`, converts without loss.
+pub fn i128_to_bigdecimal(value: i128, decimals: u32) -> BigDecimal {
+    BigDecimal::new(BigInt::from(value), decimals as i64)
+}
+
+/// Inverse of [`i128_to_bigdecimal`]. Refuses to round: amounts finer than
+/// `decimals` or outside the i128 range are errors rather than silently
+/// truncated or wrapped.
+pub fn bigdecimal_to_i128_checked(
+    value: &BigDecimal,
+    decimals: u32,
+) -> Result<i128, AmountConversionError> {
+    let scaled = value.with_scale(decimals as i64);
+    if &scaled != value {
+        return Err(AmountConversionError::TooPrecise {
+            value: value.to_string(),
+            decimals,
+        });
+    }
+    let (units, _) = scaled.as_bigint_and_exponent();
+    units.to_i128().ok_or_else(|| AmountConversionError::Overflow {
+        value: value.to_string(),
+        decimals,
+    })
+}
+
+/// Amount for a mint or burn: strictly positive and exactly representable in
+/// the contract's i128 units.
+pub fn validate_admin_amount(
+    value: &BigDecimal,
+    decimals: u32,
+) -> Result<i128, AmountConversionError> {
+    let units = bigdecimal_to_i128_checked(value, decimals)?;
+    if units <= 0 {
+        return Err(AmountConversionError::NotPositive {
+            value: value.to_string(),
+        });
+    }
+    Ok(units)
+}
+
+/// `ScAddress` for a G... account or C... contract strkey
+pub fn sc_address(address: &str) -> StellarResult<ScAddress> {
+    if let Ok(key) = StrkeyPublicKey::from_string(address) {
+        return Ok(ScAddress::Account(AccountId(
+            PublicKey::PublicKeyTypeEd25519(Uint256(key.0)),
+        )));
+    }
+    StrkeyContract::from_string(address)
+        .map(|contract| ScAddress::Contract(ContractId(Hash(contract.0))))
+        .map_err(|_| StellarError::invalid_address(address))
+}
+
+pub fn i128_to_scval(value: i128) -> ScVal {
+    ScVal::I128(Int128Parts {
+        hi: (value >> 64) as i64,
+        lo: value as u64,
+    })
+}
+
+/// Arguments of the SEP-41 admin `mint(to: Address, amount: i128)`
+pub fn mint_args(to: &str, amount: i128) -> StellarResult<Vec<ScVal>> {
+    Ok(vec![ScVal::Address(sc_address(to)?), i128_to_scval(amount)])
+}
+
+/// Arguments of `burn(from: Address, amount: i128)`
+pub fn burn_args(from: &str, amount: i128) -> StellarResult<Vec<ScVal>> {
+    Ok(vec![ScVal::Address(sc_address(from)?), i128_to_scval(amount)])
+}
+
+/// Key of the contract instance entry, where the token keeps its admin and
+/// total supply
+pub fn instance_ledger_key(contract_id: &str) -> StellarResult<LedgerKey> {
+    Ok(LedgerKey::ContractData(LedgerKeyContractData {
+        contract: sc_address(contract_id)?,
+        key: ScVal::LedgerKeyContractInstance,
+        durability: ContractDataDurability::Persistent,
+    }))
+}
+
+/// Key of `holder`'s persistent `DataKey::Balance(Address)` entry
+pub fn balance_ledger_key(contract_id: &str, holder: &str) -> StellarResult<LedgerKey> {
+    let balance = ScSymbol::try_from("Balance")
+        .map_err(|e| StellarError::serialization_error(e.to_string()))?;
+    let key = ScVec::try_from(vec![ScVal::Symbol(balance), ScVal::Address(sc_address(holder)?)])
+        .map_err(|e| StellarError::serialization_error(e.to_string()))?;
+    Ok(LedgerKey::ContractData(LedgerKeyContractData {
+        contract: sc_address(contract_id)?,
+        key: ScVal::Vec(Some(key)),
+        durability: ContractDataDurability::Persistent,
+    }))
+}
+
+/// The token contract the backend administers and the key it signs with
+#[derive(Clone)]
+pub struct ContractAdmin {
+    pub contract_id: String,
+    pub network_passphrase: String,
+    pub decimals: u32,
+    pub signer: Arc<dyn SignerProvider>,
+    /// Ledgers past the current one that a TTL extension keeps entries alive
+    pub ttl_extend_to: u32,
+}
+
+impl ContractAdmin {
+    /// Reads `SOROBAN_TOKEN_CONTRACT_ID`, `SOROBAN_TOKEN_DECIMALS` (default 7)
+    /// and `SOROBAN_TTL_EXTEND_TO_LEDGERS`. `None` when no contract is configured.
+    pub fn from_env(network: &StellarNetwork, signer: Arc<dyn SignerProvider>) -> Option<Self> {
+        let contract_id = std::env::var("SOROBAN_TOKEN_CONTRACT_ID")
+            .ok()
+            .filter(|s| !s.trim().is_empty())?;
+        let decimals = std::env::var("SOROBAN_TOKEN_DECIMALS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(7);
+        let ttl_extend_to = std::env::var("SOROBAN_TTL_EXTEND_TO_LEDGERS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .filter(|n| *n > 0)
+            .unwrap_or(DEFAULT_TTL_EXTEND_TO_LEDGERS);
+        Some(Self {
+            contract_id: contract_id.trim().to_string(),
+            network_passphrase: network.network_passphrase().to_string(),
+            decimals,
+            signer,
+            ttl_extend_to,
+        })
+    }
+}
+
+impl std::fmt::Debug for ContractAdmin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ContractAdmin")
+            .field("contract_id", &self.contract_id)
+            .field("admin", &self.signer.public_key())
+            .finish()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcEnvelope {
+    #[serde(default)]
+    result: Option<JsonValue>,
+    #[serde(default)]
+    error: Option<RpcError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct SorobanRpcClient {
+    http_client: Client,
+    config: SorobanConfig,
+    retry_policy: RetryPolicy,
+}
+
+impl SorobanRpcClient {
+    pub fn new(config: SorobanConfig) -> StellarResult<Self> {
+        let retry_policy = RetryPolicy {
+            max_retries: config.max_retries,
+            ..RetryPolicy::from_env()
+        };
+        Self::with_retry_policy(config, retry_policy)
+    }
+
+    pub fn with_retry_policy(
+        config: SorobanConfig,
+        retry_policy: RetryPolicy,
+    ) -> StellarResult<Self> {
+        let http_client = Client::builder()
+            .timeout(config.request_timeout)
+            .user_agent("Aframp-Backend/1.0")
+            .build()
+            .map_err(|e| {
+                StellarError::config_error(format!("Failed to create Soroban HTTP client: {}", e))
+            })?;
+
+        Ok(Self {
+            http_client,
+            config,
+            retry_policy,
+        })
+    }
+
+    pub fn config(&self) -> &SorobanConfig {
+        &self.config
+    }
+
+    /// Simulate a contract invocation encoded as a base64 transaction envelope.
+    pub async fn simulate_contract_call(
+        &self,
+        transaction_xdr: &str,
+    ) -> StellarResult<SorobanSimulation> {
+        let result = self
+            .call("simulateTransaction", json!({ "transaction": transaction_xdr }))
+            .await?;
+
+        // The RPC reports contract traps inside a successful response
+        if let Some(error) = result.get("error").and_then(|e| e.as_str()) {
+            return Err(StellarError::contract_error(error));
+        }
+
+        serde_json::from_value(result).map_err(StellarError::from)
+    }
+
+    /// Mint `amount` contract units to `to`. Returns the transaction hash.
+    pub async fn invoke_mint(
+        &self,
+        admin: &ContractAdmin,
+        to: &str,
+        amount: i128,
+    ) -> StellarResult<String> {
+        self.invoke_admin(admin, "mint", mint_args(to, amount)?).await
+    }
+
+    /// Burn `amount` contract units from `from`. Returns the transaction hash.
+    pub async fn invoke_burn(
+        &self,
+        admin: &ContractAdmin,
+        from: &str,
+        amount: i128,
+    ) -> StellarResult<String> {
+        self.invoke_admin(admin, "burn", burn_args(from, amount)?).await
+    }
+
+    /// Ledger up to which each entry currently lives (`liveUntilLedgerSeq`),
+    /// in the order of `keys`; `None` for entries that don't exist or were
+    /// archived.
+    pub async fn entry_ttls(&self, keys: &[LedgerKey]) -> StellarResult<Vec<Option<u32>>> {
+        let encoded = keys
+            .iter()
+            .map(|key| key.to_xdr_base64(Limits::none()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| StellarError::serialization_error(e.to_string()))?;
+
+        let result = self
+            .call("getLedgerEntries", json!({ "keys": encoded }))
+            .await?;
+        let entries = result
+            .get("entries")
+            .and_then(|e| e.as_array())
+            .cloned()
+            .unwrap_or_default();
+
+        Ok(encoded
+            .iter()
+            .map(|key| {
+                entries
+                    .iter()
+                    .find(|entry| entry.get("key").and_then(|k| k.as_str()) == Some(key))
+                    .and_then(|entry| entry.get("liveUntilLedgerSeq"))
+                    .and_then(|ttl| ttl.as_u64())
+                    .map(|ttl| ttl as u32)
+            })
+            .collect())
+    }
+
+    /// Extend the TTL of the contract instance (admin, total supply) and of
+    /// `holder`'s balance entry to `admin.ttl_extend_to` ledgers from now.
+    /// Returns the transaction hash.
+    pub async fn extend_balance_ttl(
+        &self,
+        admin: &ContractAdmin,
+        holder: &str,
+    ) -> StellarResult<String> {
+        let keys = vec![
+            instance_ledger_key(&admin.contract_id)?,
+            balance_ledger_key(&admin.contract_id, holder)?,
+        ];
+        self.extend_ttl(admin, keys, admin.ttl_extend_to).await
+    }
+
+    /// Submit an `extendFootprintTtl` over `keys`, signed by the admin key
+    pub async fn extend_ttl(
+        &self,
+        admin: &ContractAdmin,
+        keys: Vec<LedgerKey>,
+        extend_to: u32,
+    ) -> StellarResult<String> {
+        let source = admin.signer.public_key();
+        let sequence = self.account_sequence(&source).await?;
+
+        let operation = OperationBody::ExtendFootprintTtl(ExtendFootprintTtlOp {
+            ext: ExtensionPoint::V0,
+            extend_to,
+        });
+        // The RPC simulates extensions against the footprint in the draft
+        let mut draft = admin_transaction(&source, sequence + 1, operation.clone())?;
+        draft.ext = TransactionExt::V1(SorobanTransactionData {
+            ext: SorobanTransactionDataExt::V0,
+            resources: SorobanResources {
+                footprint: LedgerFootprint {
+                    read_only: VecM::try_from(keys)
+                        .map_err(|e| StellarError::serialization_error(e.to_string()))?,
+                    read_write: VecM::default(),
+                },
+                instructions: 0,
+                disk_read_bytes: 0,
+                write_bytes: 0,
+            },
+            resource_fee: 0,
+        });
+        let simulation = self
+            .simulate_contract_call(&unsigned_envelope_xdr(&draft)?)
+            .await?;
+
+        let mut tx = admin_transaction(&source, sequence + 1, operation)?;
+        apply_simulation(&mut tx, &simulation)?;
+        self.sign_and_send(admin, tx, "extend_ttl").await
+    }
+
+    /// Build, simulate, sign and send a contract call from the admin account
+    async fn invoke_admin(
+        &self,
+        admin: &ContractAdmin,
+        function: &str,
+        args: Vec<ScVal>,
+    ) -> StellarResult<String> {
+        let source = admin.signer.public_key();
+        let sequence = self.account_sequence(&source).await?;
+
+        let host_function = HostFunction::InvokeContract(InvokeContractArgs {
+            contract_address: sc_address(&admin.contract_id)?,
+            function_name: ScSymbol::try_from(function)
+                .map_err(|e| StellarError::serialization_error(e.to_string()))?,
+            args: VecM::try_from(args)
+                .map_err(|e| StellarError::serialization_error(e.to_string()))?,
+        });
+        let draft = admin_transaction(
+            &source,
+            sequence + 1,
+            invoke_operation(host_function.clone(), VecM::default()),
+        )?;
+        let simulation = self
+            .simulate_contract_call(&unsigned_envelope_xdr(&draft)?)
+            .await?;
+        let auth = simulation
+            .results
+            .first()
+            .and_then(|r| r.get("auth"))
+            .and_then(|a| a.as_array())
+            .map(|entries| {
+                entries
+                    .iter()
+                    .filter_map(|e| e.as_str())
+                    .map(|xdr| SorobanAuthorizationEntry::from_xdr_base64(xdr, Limits::none()))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .transpose()
+            .map_err(|e| StellarError::serialization_error(e.to_string()))?
+            .unwrap_or_default();
+
+        let auth =
+            VecM::try_from(auth).map_err(|e| StellarError::serialization_error(e.to_string()))?;
+        let mut tx =
+            admin_transaction(&source, sequence + 1, invoke_operation(host_function, auth))?;
+        apply_simulation(&mut tx, &simulation)?;
+        self.sign_and_send(admin, tx, function).await
+    }
+
+    /// Sign `tx` with the admin key and hand it to `sendTransaction`
+    async fn sign_and_send(
+        &self,
+        admin: &ContractAdmin,
+        tx: Transaction,
+        function: &str,
+    ) -> StellarResult<String> {
+        let payload = TransactionSignaturePayload {
+            network_id: Hash(Sha256::digest(admin.network_passphrase.as_bytes()).into()),
+            tagged_transaction: TransactionSignaturePayloadTaggedTransaction::Tx(tx.clone()),
+        }
+        .to_xdr(Limits::none())
+        .map_err(|e| StellarError::serialization_error(e.to_string()))?;
+        let signature = admin.signer.sign(&Sha256::digest(payload).into())?;
+
+        let envelope = TransactionEnvelope::Tx(TransactionV1Envelope {
+            tx,
+            signatures: VecM::try_from(vec![signature])
+                .map_err(|e| StellarError::serialization_error(e.to_string()))?,
+        })
+        .to_xdr_base64(Limits::none())
+        .map_err(|e| StellarError::serialization_error(e.to_string()))?;
+
+        let result = self
+            .call("sendTransaction", json!({ "transaction": envelope }))
+            .await?;
+        let hash = result
+            .get("hash")
+            .and_then(|h| h.as_str())
+            .unwrap_or_default()
+            .to_string();
+        match result.get("status").and_then(|s| s.as_str()) {
+            Some("PENDING") | Some("DUPLICATE") => {
+                debug!(function, hash = %hash, "Soroban admin invocation sent");
+                Ok(hash)
+            }
+            Some("TRY_AGAIN_LATER") => Err(StellarError::service_unavailable(None)),
+            Some(status) => Err(StellarError::transaction_failed(format!(
+                "{} rejected with status {}: {}",
+                function,
+                status,
+                result
+                    .get("errorResultXdr")
+                    .and_then(|x| x.as_str())
+                    .unwrap_or("")
+            ))),
+            None => Err(StellarError::serialization_error(
+                "sendTransaction response has no status",
+            )),
+        }
+    }
+
+    /// Current sequence number of `account`, read through `getLedgerEntries`
+    async fn account_sequence(&self, account: &str) -> StellarResult<i64> {
+        let ScAddress::Account(account_id) = sc_address(account)? else {
+            return Err(StellarError::invalid_address(account));
+        };
+        let key = LedgerKey::Account(LedgerKeyAccount { account_id })
+            .to_xdr_base64(Limits::none())
+            .map_err(|e| StellarError::serialization_error(e.to_string()))?;
+
+        let result = self.call("getLedgerEntries", json!({ "keys": [key] })).await?;
+        let xdr = result
+            .get("entries")
+            .and_then(|e| e.as_array())
+            .and_then(|entries| entries.first())
+            .and_then(|entry| entry.get("xdr"))
+            .and_then(|x| x.as_str())
+            .ok_or_else(|| StellarError::account_not_found(account))?;
+        match LedgerEntryData::from_xdr_base64(xdr, Limits::none())
+            .map_err(|e| StellarError::serialization_error(e.to_string()))?
+        {
+            LedgerEntryData::Account(entry) => Ok(entry.seq_num.0),
+            _ => Err(StellarError::serialization_error(
+                "ledger entry is not an account",
+            )),
+        }
+    }
+
+    async fn call(&self, method: &str, params: JsonValue) -> StellarResult<JsonValue> {
+        retry_async(&self.retry_policy, StellarError::retry_class, |_| {
+            self.call_once(method, &params)
+        })
+        .await
+    }
+
+    async fn call_once(&self, method: &str, params: &JsonValue) -> StellarResult<JsonValue> {
+        debug!(method, url = %self.config.rpc_url, "Calling Soroban RPC");
+        let body = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        });
+
+        let response = self
+            .http_client
+            .post(&self.config.rpc_url)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| {
+                if e.is_timeout() {
+                    StellarError::timeout_error(self.config.request_timeout.as_secs())
+                } else {
+                    StellarError::network_error(format!("Soroban RPC request failed: {}", e))
+                }
+            })?;
+
+        let status = response.status();
+        if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            return Err(StellarError::RateLimitError);
+        }
+        if status.is_server_error() {
+            return Err(StellarError::network_error(format!(
+                "Soroban RPC returned {}",
+                status
+            )));
+        }
+        if !status.is_success() {
+            return Err(StellarError::unexpected_error(format!(
+                "Soroban RPC returned {}",
+                status
+            )));
+        }
+
+        let envelope: RpcEnvelope = response.json().await.map_err(|e| {
+            StellarError::serialization_error(format!("Invalid Soroban RPC response: {}", e))
+        })?;
+
+        match (envelope.result, envelope.error) {
+            (_, Some(error)) if error.code == JSON_RPC_INTERNAL_ERROR => {
+                Err(StellarError::network_error(format!(
+                    "Soroban RPC internal error: {}",
+                    error.message
+                )))
+            }
+            (_, Some(error)) => Err(StellarError::contract_error(format!(
+                "{} (code {})",
+                error.message, error.code
+            ))),
+            (Some(result), None) => Ok(result),
+            (None, None) => Err(StellarError::serialization_error(
+                "Soroban RPC response has neither result nor error",
+            )),
+        }
+    }
+}
+
+fn invoke_operation(
+    host_function: HostFunction,
+    auth: VecM<SorobanAuthorizationEntry>,
+) -> OperationBody {
+    OperationBody::InvokeHostFunction(InvokeHostFunctionOp {
+        host_function,
+        auth,
+    })
+}
+
+fn admin_transaction(
+    source: &str,
+    sequence: i64,
+    body: OperationBody,
+) -> StellarResult<Transaction> {
+    let ScAddress::Account(AccountId(PublicKey::PublicKeyTypeEd25519(key))) = sc_address(source)?
+    else {
+        return Err(StellarError::invalid_address(source));
+    };
+    let operation = Operation {
+        source_account: None,
+        body,
+    };
+    Ok(Transaction {
+        source_account: MuxedAccount::Ed25519(key),
+        fee: ADMIN_BASE_FEE_STROOPS,
+        seq_num: SequenceNumber(sequence),
+        cond: Preconditions::None,
+        memo: Memo::None,
+        operations: VecM::try_from(vec![operation])
+            .map_err(|e| StellarError::serialization_error(e.to_string()))?,
+        ext: TransactionExt::V0,
+    })
+}
+
+/// Attach the simulated resources and resource fee to `tx`
+fn apply_simulation(tx: &mut Transaction, simulation: &SorobanSimulation) -> StellarResult<()> {
+    let transaction_data = simulation
+        .transaction_data
+        .as_deref()
+        .ok_or_else(|| StellarError::serialization_error("simulation has no transactionData"))?;
+    let resource_fee: u32 = simulation
+        .min_resource_fee
+        .as_deref()
+        .unwrap_or("0")
+        .parse()
+        .map_err(|_| StellarError::serialization_error("invalid minResourceFee"))?;
+
+    tx.fee = ADMIN_BASE_FEE_STROOPS.saturating_add(resource_fee);
+    tx.ext = TransactionExt::V1(
+        SorobanTransactionData::from_xdr_base64(transaction_data, Limits::none())
+            .map_err(|e| StellarError::serialization_error(e.to_string()))?,
+    );
+    Ok(())
+}
+
+fn unsigned_envelope_xdr(tx: &Transaction) -> StellarResult<String> {
+    TransactionEnvelope::Tx(TransactionV1Envelope {
+        tx: tx.clone(),
+        signatures: VecM::default(),
+    })
+    .to_xdr_base64(Limits::none())
+    .map_err(|e| StellarError::serialization_error(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{body_partial_json, method};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn client(server: &MockServer, max_retries: u32) -> SorobanRpcClient {
+        SorobanRpcClient::with_retry_policy(
+            SorobanConfig {
+                rpc_url: server.uri(),
+                request_timeout: Duration::from_secs(5),
+                max_retries,
+            },
+            RetryPolicy {
+                max_retries,
+                base_delay: Duration::from_millis(1),
+                jitter: Duration::ZERO,
+                max_elapsed: None,
+            },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn i128_extremes_round_trip_through_bigdecimal() {
+        for value in [i128::MAX, i128::MIN, 0, -1, 1] {
+            let decimal = i128_to_bigdecimal(value, 7);
+            assert_eq!(bigdecimal_to_i128_checked(&decimal, 7), Ok(value));
+        }
+        assert_eq!(
+            i128_to_bigdecimal(i128::MAX, 7).to_string(),
+            "17014118346046923173168730371588.4105727"
+        );
+    }
+
+    #[test]
+    fn value_beyond_i128_is_an_overflow_error() {
+        use std::str::FromStr;
+
+        let one_unit = BigDecimal::from_str("0.0000001").unwrap();
+        let too_big = i128_to_bigdecimal(i128::MAX, 7) + one_unit;
+        assert!(matches!(
+            bigdecimal_to_i128_checked(&too_big, 7),
+            Err(AmountConversionError::Overflow { decimals: 7, .. })
+        ));
+
+        let too_small = i128_to_bigdecimal(i128::MIN, 0) - BigDecimal::from(1);
+        assert!(matches!(
+            bigdecimal_to_i128_checked(&too_small, 0),
+            Err(AmountConversionError::Overflow { .. })
+        ));
+    }
+
+    #[test]
+    fn excess_precision_is_rejected_not_truncated() {
+        use std::str::FromStr;
+
+        let amount = BigDecimal::from_str("1.00000001").unwrap();
+        assert!(matches!(
+            bigdecimal_to_i128_checked(&amount, 7),
+            Err(AmountConversionError::TooPrecise { .. })
+        ));
+    }
+
+    #[test]
+    fn amount_result_decodes_i128_balance() {
+        use stellar_xdr::next::{Int128Parts, WriteXdr};
+
+        let xdr = ScVal::I128(Int128Parts {
+            hi: i64::MAX,
+            lo: u64::MAX,
+        })
+        .to_xdr_base64(Limits::none())
+        .unwrap();
+        let simulation = SorobanSimulation {
+            latest_ledger: 1,
+            min_resource_fee: None,
+            transaction_data: None,
+            results: vec![json!({ "xdr": xdr, "auth": [] })],
+        };
+
+        assert_eq!(
+            simulation.amount_result(7).unwrap(),
+            i128_to_bigdecimal(i128::MAX, 7)
+        );
+    }
+
+    #[test]
+    fn mint_args_encode_address_and_i128_amount() {
+        let to = "GCJRI5CIWK5IU67Q6DGA7QW52JDKRO7JEAHQKFNDUJUPEZGURDBX3LDX";
+        let amount = (1i128 << 64) + 5;
+        let args = mint_args(to, amount).unwrap();
+
+        assert_eq!(args.len(), 2);
+        assert_eq!(args[0], ScVal::Address(sc_address(to).unwrap()));
+        assert!(matches!(args[0], ScVal::Address(ScAddress::Account(_))));
+        assert_eq!(args[1], ScVal::I128(Int128Parts { hi: 1, lo: 5 }));
+    }
+
+    #[test]
+    fn burn_args_round_trip_through_xdr() {
+        let from = "GCJRI5CIWK5IU67Q6DGA7QW52JDKRO7JEAHQKFNDUJUPEZGURDBX3LDX";
+        let args = burn_args(from, 12_345_000_000).unwrap();
+
+        let xdr = args[1].to_xdr_base64(Limits::none()).unwrap();
+        let decoded = ScVal::from_xdr_base64(xdr, Limits::none()).unwrap();
+        assert_eq!(decoded, i128_to_scval(12_345_000_000));
+        assert!(burn_args("not-an-address", 1).is_err());
+    }
+
+    #[test]
+    fn admin_amount_must_be_positive_and_fit() {
+        let amount = |s: &str| s.parse::<BigDecimal>().unwrap();
+
+        assert_eq!(validate_admin_amount(&amount("1.5"), 7), Ok(15_000_000));
+        assert!(matches!(
+            validate_admin_amount(&amount("0"), 7),
+            Err(AmountConversionError::NotPositive { .. })
+        ));
+        assert!(matches!(
+            validate_admin_amount(&amount("-2"), 7),
+            Err(AmountConversionError::NotPositive { .. })
+        ));
+        assert!(matches!(
+            validate_admin_amount(&amount("0.00000001"), 7),
+            Err(AmountConversionError::TooPrecise { .. })
+        ));
+        assert!(matches!(
+            validate_admin_amount(&amount("1e40"), 7),
+            Err(AmountConversionError::Overflow { .. })
+        ));
+    }
+
+    fn simulation_body() -> JsonValue {
+        json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": {
+                "latestLedger": 4242,
+                "minResourceFee": "58181",
+                "transactionData": "AAAAAA==",
+                "results": [{ "xdr": "AAAAAQ==", "auth": [] }]
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn simulate_retries_transient_failure_then_succeeds() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(json!({ "method": "simulateTransaction" })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(simulation_body()))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let simulation = client(&server, 2)
+            .simulate_contract_call("AAAAAgAAAAA=")
+            .await
+            .unwrap();
+
+        assert_eq!(simulation.latest_ledger, 4242);
+        assert_eq!(simulation.min_resource_fee.as_deref(), Some("58181"));
+    }
+
+    #[tokio::test]
+    async fn simulate_does_not_retry_contract_error() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": {
+                    "latestLedger": 4242,
+                    "error": "HostError: Error(Contract, #3)"
+                }
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let err = client(&server, 3)
+            .simulate_contract_call("AAAAAgAAAAA=")
+            .await
+            .unwrap_err();
+
+        assert!(
+            matches!(&err, StellarError::ContractError { message } if message.contains("#3")),
+            "unexpected error: {err:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn invalid_params_are_not_retried() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "error": { "code": -32602, "message": "invalid parameters" }
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let err = client(&server, 3)
+            .simulate_contract_call("not-xdr")
+            .await
+            .unwrap_err();
+
+        assert!(!err.is_retryable());
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_max_retries() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(502))
+            .expect(3)
+            .mount(&server)
+            .await;
+
+        let err = client(&server, 2)
+            .simulate_contract_call("AAAAAgAAAAA=")
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, StellarError::NetworkError { .. }));
+    }
+
+    fn test_admin() -> ContractAdmin {
+        use crate::chains::stellar::signer::SecretSeedSigner;
+        use stellar_strkey::ed25519::PrivateKey as StrkeyPrivateKey;
+
+        let secret = StrkeyPrivateKey([7u8; 32]).to_string();
+        ContractAdmin {
+            contract_id: StrkeyContract([1u8; 32]).to_string(),
+            network_passphrase: "Test SDF Network ; September 2015".to_string(),
+            decimals: 7,
+            signer: Arc::new(SecretSeedSigner::from_secret(&secret).unwrap()),
+            ttl_extend_to: 100_000,
+        }
+    }
+
+    fn account_entry_xdr(address: &str, sequence: i64) -> String {
+        use stellar_xdr::next::{AccountEntry, AccountEntryExt, Thresholds};
+
+        let ScAddress::Account(account_id) = sc_address(address).unwrap() else {
+            unreachable!()
+        };
+        LedgerEntryData::Account(AccountEntry {
+            account_id,
+            balance: 100_000_000,
+            seq_num: SequenceNumber(sequence),
+            num_sub_entries: 0,
+            inflation_dest: None,
+            flags: 0,
+            home_domain: Default::default(),
+            thresholds: Thresholds([1, 0, 0, 0]),
+            signers: VecM::default(),
+            ext: AccountEntryExt::V0,
+        })
+        .to_xdr_base64(Limits::none())
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn entry_ttls_follow_key_order_and_flag_missing_entries() {
+        let admin = test_admin();
+        let holder = "GCJRI5CIWK5IU67Q6DGA7QW52JDKRO7JEAHQKFNDUJUPEZGURDBX3LDX";
+        let instance = instance_ledger_key(&admin.contract_id).unwrap();
+        let balance = balance_ledger_key(&admin.contract_id, holder).unwrap();
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(json!({ "method": "getLedgerEntries" })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": {
+                    "latestLedger": 5000,
+                    "entries": [{
+                        "key": instance.to_xdr_base64(Limits::none()).unwrap(),
+                        "xdr": "AAAAAA==",
+                        "liveUntilLedgerSeq": 125_000
+                    }]
+                }
+            })))
+            .mount(&server)
+            .await;
+
+        let ttls = client(&server, 1)
+            .entry_ttls(&[balance, instance])
+            .await
+            .unwrap();
+
+        assert_eq!(ttls, vec![None, Some(125_000)]);
+    }
+
+    #[tokio::test]
+    async fn extend_balance_ttl_sends_footprint_with_instance_and_balance() {
+        let admin = test_admin();
+        let holder = "GCJRI5CIWK5IU67Q6DGA7QW52JDKRO7JEAHQKFNDUJUPEZGURDBX3LDX";
+        let expected_keys = vec![
+            instance_ledger_key(&admin.contract_id).unwrap(),
+            balance_ledger_key(&admin.contract_id, holder).unwrap(),
+        ];
+        let transaction_data = SorobanTransactionData {
+            ext: SorobanTransactionDataExt::V0,
+            resources: SorobanResources {
+                footprint: LedgerFootprint {
+                    read_only: VecM::try_from(expected_keys.clone()).unwrap(),
+                    read_write: VecM::default(),
+                },
+                instructions: 0,
+                disk_read_bytes: 512,
+                write_bytes: 0,
+            },
+            resource_fee: 4_000,
+        }
+        .to_xdr_base64(Limits::none())
+        .unwrap();
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(json!({ "method": "getLedgerEntries" })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": {
+                    "latestLedger": 5000,
+                    "entries": [{ "xdr": account_entry_xdr(&admin.signer.public_key(), 41) }]
+                }
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(json!({ "method": "simulateTransaction" })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": {
+                    "latestLedger": 5000,
+                    "minResourceFee": "4000",
+                    "transactionData": transaction_data,
+                    "results": []
+                }
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(json!({ "method": "sendTransaction" })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": { "status": "PENDING", "hash": "ttl-bump-hash" }
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let hash = client(&server, 1)
+            .extend_balance_ttl(&admin, holder)
+            .await
+            .unwrap();
+        assert_eq!(hash, "ttl-bump-hash");
+
+        // The simulated draft carries the extension and the footprint to extend
+        let requests = server.received_requests().await.unwrap();
+        let simulate: JsonValue = requests
+            .iter()
+            .map(|r| serde_json::from_slice::<JsonValue>(&r.body).unwrap())
+            .find(|body| body["method"] == "simulateTransaction")
+            .unwrap();
+        let envelope = TransactionEnvelope::from_xdr_base64(
+            simulate["params"]["transaction"].as_str().unwrap(),
+            Limits::none(),
+        )
+        .unwrap();
+        let TransactionEnvelope::Tx(v1) = envelope else {
+            panic!("expected a v1 envelope");
+        };
+        assert_eq!(v1.tx.seq_num.0, 42);
+        assert!(matches!(
+            &v1.tx.operations[0].body,
+            OperationBody::ExtendFootprintTtl(op) if op.extend_to == 100_000
+        ));
+        let TransactionExt::V1(data) = &v1.tx.ext else {
+            panic!("draft must carry a footprint");
+        };
+        assert_eq!(data.resources.footprint.read_only.to_vec(), expected_keys);
+    }
+}
@@ -7,7 +7,14 @@
 ///   helpers        – mock HTTP server + JSON fixture builders
 ///   balance_tests  – XLM / cNGN balance parsing, missing trustline, non-existent account
 ///   trustline_tests– creation, duplicate detection, insufficient XLM, submit errors
+///   issuer_flags_tests – issuer auth/clawback flags, derived hint, caching
 ///   payment_tests  – construction, signing, invalid dest, missing trustline, memo, fee
+///   account_cache_tests – single-flight coalescing of concurrent account lookups
+///   afri_deposit_tests – trustline-then-payment deposit orchestration
+///   startup_demo_tests – RUN_STARTUP_DEMO gating of the startup demo
+///   wallet_overview_tests – balance + 30-day activity aggregation, account creation time, missing account
+///   submission_tests – raw signed XDR submission, retries, replay, batches, confirmation polling
+///   failover_tests – fallback Horizon endpoints for reads, submission and payments; per-host metrics
 ///   error_tests    – 429 rate-limit, timeout, 400/500 submit failures, error mapping
 ///   unit_tests     – pure-unit helpers (no network): address validation, strops, config
 #[cfg(test)]
@@ -42,6 +49,7 @@ mod helpers {
         StellarConfig {
             network: StellarNetwork::Testnet,
             horizon_url_override: Some(url.to_string()),
+            horizon_fallback_urls: Vec::new(),
             request_timeout: Duration::from_secs(5),
             max_retries: 1,
             health_check_interval: Duration::from_secs(30),
@@ -110,6 +118,31 @@ mod helpers {
         format!("http://{addr}")
     }
 
+    /// Spawn a server answering every connection with the same response,
+    /// counting how many requests it served.
+    pub async fn mock_counting(
+        status: u16,
+        body: &'static str,
+    ) -> (String, std::sync::Arc<std::sync::atomic::AtomicUsize>) {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let served = std::sync::Arc::new(AtomicUsize::new(0));
+        let counter = served.clone();
+
+        tokio::spawn(async move {
+            while let Ok((mut sock, _)) = listener.accept().await {
+                let mut buf = vec![0u8; 16_384];
+                let _ = sock.read(&mut buf).await;
+                counter.fetch_add(1, Ordering::SeqCst);
+                write_response(&mut sock, status, body).await;
+            }
+        });
+
+        (format!("http://{addr}"), served)
+    }
+
     async fn write_response(sock: &mut tokio::net::TcpStream, status: u16, body: &str) {
         let reason = match status {
             200 => "OK",
@@ -197,6 +230,31 @@ mod balance_tests {
         );
     }
 
+    // ── Partial account payloads ──────────────────────────────────────────────
+
+    #[tokio::test]
+    async fn minimal_account_payload_is_converted_with_defaults() {
+        // Sponsored / freshly created accounts may omit signers, thresholds,
+        // flags and data entirely.
+        let body = leak(format!(
+            r#"{{"account_id":"{SOURCE_ADDR}","sequence":"42","balances":{}}}"#,
+            xlm_only("1.0000000")
+        ));
+        let url = mock_n(200, body, 1).await;
+        let client = StellarClient::new(config_pointing_at(&url)).unwrap();
+
+        let account = client.get_account(SOURCE_ADDR).await.unwrap();
+        assert_eq!(account.account_id, SOURCE_ADDR);
+        assert_eq!(account.sequence, 42);
+        assert!(account.signers.is_empty());
+        assert!(account.data.is_empty());
+        assert_eq!(account.thresholds.low_threshold, 0);
+        assert_eq!(account.thresholds.high_threshold, 0);
+        assert!(!account.flags.auth_required);
+        assert_eq!(account.last_modified_ledger, 0);
+        assert_eq!(account.balances.len(), 1);
+    }
+
     // ── cNGN balance ──────────────────────────────────────────────────────────
 
     #[tokio::test]
@@ -344,6 +402,31 @@ mod balance_tests {
 
         assert_eq!(balance, None);
     }
+
+    #[tokio::test]
+    async fn service_reports_pool_shares_by_pool_id_not_issuer() {
+        use crate::chains::stellar::service::StellarBlockchainService;
+        use crate::chains::traits::BlockchainService;
+
+        let pool_id = "67260c4c1807b262ff851b0a3fe141194936bb0215b2f77447f1df11998eabb9";
+        let balances = format!(
+            r#"[{{"asset_type":"native","balance":"5.0000000"}},{{"asset_type":"liquidity_pool_shares","liquidity_pool_id":"{pool_id}","balance":"12.5000000","limit":"922337203685.4775807"}}]"#
+        );
+        let url = mock_n(200, leak(account_json(SOURCE_ADDR, &balances)), 1).await;
+        let service =
+            StellarBlockchainService::new(StellarClient::new(config_pointing_at(&url)).unwrap());
+
+        let account = service.get_account(SOURCE_ADDR).await.unwrap();
+        let shares = account
+            .balances
+            .iter()
+            .find(|b| b.asset_type == "liquidity_pool_shares")
+            .expect("pool shares listed");
+
+        assert_eq!(shares.liquidity_pool_id.as_deref(), Some(pool_id));
+        assert_eq!(shares.issuer, None);
+        assert_eq!(shares.balance, "12.5000000");
+    }
 }
 
 // ─────────────────────────────────────────────────────────────────────────────
@@ -355,7 +438,7 @@ mod trustline_tests {
     use crate::chains::stellar::{
         client::StellarClient,
         errors::StellarError,
-        trustline::{CngnAssetConfig, CngnTrustlineManager},
+        trustline::{CngnAssetConfig, CngnTrustlineManager, OnboardingStatus},
     };
 
     fn cngn_cfg() -> CngnAssetConfig {
@@ -627,11 +710,202 @@ mod trustline_tests {
             "expected TransactionFailed from Horizon 400, got: {result:?}"
         );
     }
+
+    // ── validate_trustline_limit ──────────────────────────────────────────────
+
+    fn limit_service(url: &str, min_limit: &str) -> crate::services::cngn_trustline::CngnTrustlineService {
+        use crate::services::cngn_trustline::{CngnAssetConfig, CngnTrustlineService};
+        let client = StellarClient::new(config_pointing_at(url)).unwrap();
+        CngnTrustlineService::with_config(
+            client,
+            CngnAssetConfig {
+                asset_code: "cNGN".to_string(),
+                issuer_public_key: DEST_ADDR.to_string(),
+                default_limit: None,
+                min_trustline_limit: Some(min_limit.to_string()),
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn trustline_limit_sufficient_when_above_minimum() {
+        // xlm_and_cngn uses the maximum limit (922337203685.4775807).
+        let body = leak(account_json(
+            SOURCE_ADDR,
+            &xlm_and_cngn("5.0000000", "0.0000000", DEST_ADDR),
+        ));
+        let url = mock_n(200, body, 1).await;
+
+        let check = limit_service(&url, "1000000")
+            .validate_trustline_limit(SOURCE_ADDR)
+            .await
+            .unwrap();
+
+        assert!(check.has_trustline);
+        assert!(check.sufficient);
+        assert_eq!(check.min_limit.as_deref(), Some("1000000"));
+    }
+
+    #[tokio::test]
+    async fn trustline_limit_insufficient_when_below_minimum() {
+        let balances = format!(
+            r#"[{{"asset_type":"credit_alphanum4","asset_code":"cNGN","asset_issuer":"{DEST_ADDR}","balance":"0.0000000","limit":"50.0000000","is_authorized":true,"is_authorized_to_maintain_liabilities":true}}]"#
+        );
+        let body = leak(account_json(SOURCE_ADDR, &balances));
+        let url = mock_n(200, body, 1).await;
+
+        let check = limit_service(&url, "1000000")
+            .validate_trustline_limit(SOURCE_ADDR)
+            .await
+            .unwrap();
+
+        assert!(check.has_trustline);
+        assert!(!check.sufficient);
+        assert_eq!(check.limit.as_deref(), Some("50.0000000"));
+    }
+
+    #[tokio::test]
+    async fn trustline_limit_insufficient_when_no_trustline() {
+        let body = leak(account_json(SOURCE_ADDR, &xlm_only("5.0000000")));
+        let url = mock_n(200, body, 1).await;
+
+        let check = limit_service(&url, "1000000")
+            .validate_trustline_limit(SOURCE_ADDR)
+            .await
+            .unwrap();
+
+        assert!(!check.has_trustline);
+        assert!(!check.sufficient);
+        assert!(check.limit.is_none());
+    }
+
+    // ── onboarding_status ─────────────────────────────────────────────────────
+
+    async fn onboarding(status: u16, body: &'static str) -> OnboardingStatus {
+        let url = mock_n(status, body, 1).await;
+        manager(&url).onboarding_status(SOURCE_ADDR).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn onboarding_missing_account_is_not_ready() {
+        let status = onboarding(404, r#"{"status":404,"title":"Resource Missing"}"#).await;
+
+        assert!(!status.exists && !status.has_trustline && !status.reserves_ok);
+        assert!(!status.ready);
+        assert!(status.error.is_none());
+    }
+
+    #[tokio::test]
+    async fn onboarding_without_trustline_checks_reserve_for_adding_one() {
+        // Adding the trustline needs 2.0 XLM with no other subentries
+        let short = onboarding(200, leak(account_json(SOURCE_ADDR, &xlm_only("1.9000000")))).await;
+        let funded = onboarding(200, leak(account_json(SOURCE_ADDR, &xlm_only("2.0000000")))).await;
+
+        assert!(short.exists && !short.has_trustline && !short.reserves_ok && !short.ready);
+        assert!(funded.exists && !funded.has_trustline && funded.reserves_ok);
+        assert!(!funded.ready);
+    }
+
+    #[tokio::test]
+    async fn onboarding_with_trustline_is_ready_only_when_reserves_cover_it() {
+        let short = onboarding(
+            200,
+            leak(account_json(SOURCE_ADDR, &xlm_and_cngn("1.2000000", "0", DEST_ADDR))),
+        )
+        .await;
+        let funded = onboarding(
+            200,
+            leak(account_json(SOURCE_ADDR, &xlm_and_cngn("5.0000000", "0", DEST_ADDR))),
+        )
+        .await;
+
+        assert!(short.exists && short.has_trustline && !short.reserves_ok && !short.ready);
+        assert!(funded.exists && funded.has_trustline && funded.reserves_ok && funded.ready);
+    }
+
+    #[tokio::test]
+    async fn onboarding_batch_isolates_per_address_errors() {
+        let body = leak(account_json(
+            SOURCE_ADDR,
+            &xlm_and_cngn("5.0000000", "0", DEST_ADDR),
+        ));
+        let url = mock_n(200, body, 1).await;
+
+        let statuses = manager(&url)
+            .onboarding_status_batch(&[SOURCE_ADDR.to_string(), "NOT_AN_ADDRESS".to_string()])
+            .await;
+
+        assert_eq!(statuses.len(), 2);
+        assert_eq!(statuses[0].address, SOURCE_ADDR);
+        assert!(statuses[0].ready);
+        assert_eq!(statuses[1].address, "NOT_AN_ADDRESS");
+        assert!(!statuses[1].ready);
+        assert!(statuses[1].error.is_some());
+    }
 }
 
 // ─────────────────────────────────────────────────────────────────────────────
 // Payment transaction tests
 // ─────────────────────────────────────────────────────────────────────────────
+#[cfg(test)]
+mod issuer_flags_tests {
+    use super::helpers::*;
+    use crate::chains::stellar::client::StellarClient;
+    use std::sync::atomic::Ordering;
+
+    /// Issuer account JSON with the given `auth_required` and clawback flags
+    fn issuer_json(auth_required: bool, clawback: bool) -> &'static str {
+        leak(
+            account_json(DEST_ADDR, &xlm_only("100.0000000"))
+                .replace(
+                    r#""auth_required":false"#,
+                    &format!(r#""auth_required":{auth_required}"#),
+                )
+                .replace(
+                    r#""auth_clawback_enabled":false"#,
+                    &format!(r#""auth_clawback_enabled":{clawback}"#),
+                ),
+        )
+    }
+
+    #[tokio::test]
+    async fn auth_required_issuer_needs_trustline_authorization() {
+        let url = mock_n(200, issuer_json(true, true), 1).await;
+        let client = StellarClient::new(config_pointing_at(&url)).unwrap();
+
+        let flags = client.get_issuer_flags_for(DEST_ADDR).await.unwrap();
+
+        assert_eq!(flags.issuer, DEST_ADDR);
+        assert!(flags.flags.auth_required);
+        assert!(flags.trustline_needs_authorization);
+        assert!(flags.clawback_enabled);
+    }
+
+    #[tokio::test]
+    async fn open_issuer_needs_no_trustline_authorization() {
+        let url = mock_n(200, issuer_json(false, false), 1).await;
+        let client = StellarClient::new(config_pointing_at(&url)).unwrap();
+
+        let flags = client.get_issuer_flags_for(DEST_ADDR).await.unwrap();
+
+        assert!(!flags.flags.auth_required);
+        assert!(!flags.trustline_needs_authorization);
+        assert!(!flags.clawback_enabled);
+    }
+
+    #[tokio::test]
+    async fn issuer_flags_are_cached_across_clones() {
+        let (url, served) = mock_counting(200, issuer_json(true, false)).await;
+        let client = StellarClient::new(config_pointing_at(&url)).unwrap();
+
+        client.get_issuer_flags_for(DEST_ADDR).await.unwrap();
+        let again = client.clone().get_issuer_flags_for(DEST_ADDR).await.unwrap();
+
+        assert!(again.trustline_needs_authorization);
+        assert_eq!(served.load(Ordering::SeqCst), 1);
+    }
+}
+
 #[cfg(test)]
 mod payment_tests {
     use super::helpers::*;
@@ -756,6 +1030,84 @@ mod payment_tests {
         assert_eq!(draft.fee_stroops, 500);
     }
 
+    // ── Source balance precheck ───────────────────────────────────────────────
+
+    #[tokio::test]
+    async fn build_payment_rejects_underfunded_source_with_shortfall() {
+        use crate::error::{AppError, ErrorCode};
+
+        let body = leak(account_json(
+            SOURCE_ADDR,
+            &xlm_and_cngn("10.0000000", "5.0000000", DEST_ADDR),
+        ));
+        let url = mock_n(200, body, 2).await;
+
+        let err = builder(&url)
+            .build_payment(SOURCE_ADDR, DEST_ADDR, "12.5", CngnMemo::None, None)
+            .await
+            .unwrap_err();
+
+        match &err {
+            StellarError::InsufficientBalance {
+                asset,
+                available,
+                required,
+                shortfall,
+            } => {
+                assert_eq!(asset, "cNGN");
+                assert_eq!(available, "5.0000000");
+                assert_eq!(required, "12.5000000");
+                assert_eq!(shortfall, "7.5000000");
+            }
+            other => panic!("expected InsufficientBalance, got: {other:?}"),
+        }
+
+        let app_err: AppError = err.into();
+        assert_eq!(app_err.status_code(), 422);
+        assert_eq!(app_err.error_code(), ErrorCode::InsufficientBalance);
+        assert_eq!(app_err.details().unwrap()["shortfall"], "7.5000000");
+    }
+
+    #[tokio::test]
+    async fn build_payment_rejects_source_short_on_xlm_for_fee() {
+        let body = leak(account_json(
+            SOURCE_ADDR,
+            &xlm_and_cngn("0.0000050", "500.0000000", DEST_ADDR),
+        ));
+        let url = mock_n(200, body, 2).await;
+
+        let result = builder(&url)
+            .build_payment(SOURCE_ADDR, DEST_ADDR, "1", CngnMemo::None, None)
+            .await;
+
+        match result {
+            Err(StellarError::InsufficientBalance {
+                asset, shortfall, ..
+            }) => {
+                assert_eq!(asset, "XLM");
+                assert_eq!(shortfall, "0.0000050");
+            }
+            other => panic!("expected InsufficientBalance for XLM, got: {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn build_payment_skips_balance_precheck_when_disabled() {
+        let body = leak(account_json(
+            SOURCE_ADDR,
+            &xlm_and_cngn("0.0000000", "1.0000000", DEST_ADDR),
+        ));
+        let url = mock_n(200, body, 2).await;
+
+        let draft = builder(&url)
+            .with_balance_precheck(false)
+            .build_payment(SOURCE_ADDR, DEST_ADDR, "100", CngnMemo::None, None)
+            .await
+            .unwrap();
+
+        assert_eq!(draft.amount, "100");
+    }
+
     // ── Invalid / unfunded destination ────────────────────────────────────────
 
     #[tokio::test]
@@ -857,6 +1209,36 @@ mod payment_tests {
         }
     }
 
+    #[tokio::test]
+    async fn predicted_hash_matches_signed_transaction() {
+        use sha2::{Digest, Sha256};
+
+        let body = leak(account_json(
+            SOURCE_ADDR,
+            &xlm_and_cngn("10.0000000", "500.0000000", DEST_ADDR),
+        ));
+        let url = mock_n(200, body, 2).await;
+
+        let b = builder(&url);
+        let draft = b
+            .build_payment(SOURCE_ADDR, DEST_ADDR, "3", CngnMemo::Id(7), None)
+            .await
+            .unwrap();
+
+        let predicted = draft.transaction_hash.clone();
+
+        let base = base64::Engine::decode(
+            &base64::engine::general_purpose::STANDARD,
+            b.signature_base_xdr(&draft).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(hex::encode(Sha256::digest(&base)), predicted);
+
+        let signed = b.sign_payment(draft, SOURCE_SECRET).unwrap();
+        let summary = b.decode_envelope(&signed.signed_envelope_xdr).unwrap();
+        assert_eq!(summary.transaction_hash, predicted);
+    }
+
     #[tokio::test]
     async fn sign_payment_fails_with_mismatched_secret() {
         let body = leak(account_json(
@@ -961,6 +1343,59 @@ mod payment_tests {
         );
     }
 
+    // ── Dry-run submission ────────────────────────────────────────────────────
+
+    #[tokio::test]
+    async fn dry_run_returns_hash_without_submitting_or_burning_sequence() {
+        // Two lookups to build the draft, then one per dry run. The mock stops
+        // accepting after that, so a real submission would fail the test.
+        let body = leak(account_json(
+            SOURCE_ADDR,
+            &xlm_and_cngn("10.0000000", "500.0000000", DEST_ADDR),
+        ));
+        let url = mock_n(200, body, 4).await;
+
+        let b = builder(&url);
+        let draft = b
+            .build_payment(SOURCE_ADDR, DEST_ADDR, "1", CngnMemo::None, None)
+            .await
+            .unwrap();
+        let expected_hash = draft.transaction_hash.clone();
+        let signed = b.sign_payment(draft, SOURCE_SECRET).unwrap();
+
+        for _ in 0..2 {
+            let dry_run = b
+                .dry_run_signed_payment(&signed.signed_envelope_xdr)
+                .await
+                .unwrap();
+            assert!(!dry_run.submitted);
+            assert_eq!(dry_run.transaction_hash, expected_hash);
+            // Still the next sequence: the first dry run consumed nothing
+            assert_eq!(dry_run.sequence, 101);
+        }
+    }
+
+    #[tokio::test]
+    async fn dry_run_rejects_unsigned_envelope_locally() {
+        let body = leak(account_json(
+            SOURCE_ADDR,
+            &xlm_and_cngn("10.0000000", "500.0000000", DEST_ADDR),
+        ));
+        let url = mock_n(200, body, 2).await;
+
+        let b = builder(&url);
+        let draft = b
+            .build_payment(SOURCE_ADDR, DEST_ADDR, "1", CngnMemo::None, None)
+            .await
+            .unwrap();
+
+        let result = b.dry_run_signed_payment(&draft.unsigned_envelope_xdr).await;
+        assert!(
+            matches!(result, Err(StellarError::SigningError { .. })),
+            "expected SigningError for unsigned envelope, got: {result:?}"
+        );
+    }
+
     // ── Memo validation ───────────────────────────────────────────────────────
 
     #[tokio::test]
@@ -982,8 +1417,8 @@ mod payment_tests {
             .await;
 
         assert!(
-            matches!(result, Err(StellarError::TransactionFailed { .. })),
-            "expected TransactionFailed for oversized memo, got: {result:?}"
+            matches!(result, Err(StellarError::InvalidMemo { max_bytes: 28, .. })),
+            "expected InvalidMemo for oversized memo, got: {result:?}"
         );
     }
 
@@ -1005,43 +1440,1299 @@ mod payment_tests {
 
         assert_eq!(draft.fee_stroops, 1000);
     }
-}
-
-// ─────────────────────────────────────────────────────────────────────────────
-// Horizon error-handling tests
-// ─────────────────────────────────────────────────────────────────────────────
-#[cfg(test)]
-mod error_tests {
-    use super::helpers::*;
-    use crate::chains::stellar::{client::StellarClient, errors::StellarError};
-    use std::time::Duration;
 
-    // ── Rate-limit (429) ──────────────────────────────────────────────────────
+    // ── Bump sequence ─────────────────────────────────────────────────────────
 
     #[tokio::test]
-    async fn get_account_returns_rate_limit_error_on_429() {
-        let url = mock_n(429, r#"{"status":429,"title":"Too Many Requests"}"#, 1).await;
-        let client = StellarClient::new(config_pointing_at(&url)).unwrap();
+    async fn build_bump_sequence_encodes_bump_operation() {
+        use stellar_xdr::next::{Limits, OperationBody, ReadXdr, TransactionEnvelope};
 
-        let result = client.get_account(SOURCE_ADDR).await;
-
-        assert!(
-            matches!(result, Err(StellarError::RateLimitError)),
-            "expected RateLimitError, got: {result:?}"
-        );
-    }
+        // Account fixture reports sequence 100
+        let body = leak(account_json(SOURCE_ADDR, &xlm_only("10.0000000")));
+        let url = mock_n(200, body, 1).await;
 
-    #[tokio::test]
-    async fn submit_transaction_returns_rate_limit_error_on_429() {
-        let url = mock_n(429, r#"{"status":429,"title":"Too Many Requests"}"#, 1).await;
-        let client = StellarClient::new(config_pointing_at(&url)).unwrap();
+        let draft = builder(&url)
+            .build_bump_sequence(SOURCE_ADDR, 5_000)
+            .await
+            .unwrap();
 
-        let result = client.submit_transaction_xdr("AAAA").await;
+        assert_eq!(draft.current_sequence, 100);
+        assert_eq!(draft.bump_to, 5_000);
+        assert_eq!(draft.sequence, 101);
 
-        assert!(
-            matches!(result, Err(StellarError::RateLimitError)),
-            "expected RateLimitError on submit 429, got: {result:?}"
-        );
+        let envelope =
+            TransactionEnvelope::from_xdr_base64(&draft.unsigned_envelope_xdr, Limits::none())
+                .unwrap();
+        let TransactionEnvelope::Tx(v1) = envelope else {
+            panic!("expected a v1 envelope");
+        };
+        assert_eq!(v1.tx.operations.len(), 1);
+        match &v1.tx.operations[0].body {
+            OperationBody::BumpSequence(op) => assert_eq!(op.bump_to.0, 5_000),
+            other => panic!("expected bumpSequence, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn build_bump_sequence_rejects_target_not_above_current() {
+        let body = leak(account_json(SOURCE_ADDR, &xlm_only("10.0000000")));
+        let url = mock_n(200, body, 2).await;
+        let builder = builder(&url);
+
+        for bump_to in [100, 42] {
+            let result = builder.build_bump_sequence(SOURCE_ADDR, bump_to).await;
+            assert!(
+                matches!(
+                    result,
+                    Err(StellarError::SequenceTooLow { current: 100, requested }) if requested == bump_to
+                ),
+                "expected SequenceTooLow for {bump_to}, got: {result:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn sequence_too_low_maps_to_400_validation_error() {
+        let err: crate::error::AppError = StellarError::sequence_too_low(100, 42).into();
+        assert_eq!(err.status_code(), 400);
+    }
+
+    // ── Account merge ─────────────────────────────────────────────────────────
+
+    #[tokio::test]
+    async fn build_account_merge_encodes_merge_operation() {
+        use stellar_xdr::next::{Limits, OperationBody, ReadXdr, TransactionEnvelope};
+
+        // Source then destination lookup; neither has subentries
+        let body = leak(account_json(SOURCE_ADDR, &xlm_only("10.0000000")));
+        let url = mock_n(200, body, 2).await;
+
+        let draft = builder(&url)
+            .build_account_merge(SOURCE_ADDR, DEST_ADDR)
+            .await
+            .unwrap();
+
+        assert_eq!(draft.destination, DEST_ADDR);
+        assert_eq!(draft.sequence, 101);
+
+        let envelope =
+            TransactionEnvelope::from_xdr_base64(&draft.unsigned_envelope_xdr, Limits::none())
+                .unwrap();
+        let TransactionEnvelope::Tx(v1) = envelope else {
+            panic!("expected a v1 envelope");
+        };
+        assert_eq!(v1.tx.operations.len(), 1);
+        assert!(matches!(v1.tx.operations[0].body, OperationBody::AccountMerge(_)));
+    }
+
+    #[tokio::test]
+    async fn build_account_merge_is_blocked_by_afri_trustline() {
+        let balances = format!(
+            r#"[{{"asset_type":"native","balance":"10.0000000"}},{{"asset_type":"credit_alphanum4","asset_code":"AFRI","asset_issuer":"{DEST_ADDR}","balance":"0.0000000","limit":"922337203685.4775807","is_authorized":true}}]"#
+        );
+        let body = leak(account_json(SOURCE_ADDR, &balances));
+        let url = mock_n(200, body, 1).await;
+
+        let result = builder(&url)
+            .build_account_merge(SOURCE_ADDR, DEST_ADDR)
+            .await;
+
+        match result {
+            Err(StellarError::AccountNotMergeable { address, blockers }) => {
+                assert_eq!(address, SOURCE_ADDR);
+                assert_eq!(blockers, vec![format!("trustline:AFRI:{DEST_ADDR}")]);
+            }
+            other => panic!("expected AccountNotMergeable, got: {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn build_account_merge_is_blocked_while_sponsoring() {
+        let body = leak(
+            account_json(SOURCE_ADDR, &xlm_only("10.0000000"))
+                .replace(r#""subentry_count":0"#, r#""subentry_count":0,"num_sponsoring":2"#),
+        );
+        let url = mock_n(200, body, 1).await;
+
+        let result = builder(&url)
+            .build_account_merge(SOURCE_ADDR, DEST_ADDR)
+            .await;
+
+        match result {
+            Err(StellarError::AccountNotMergeable { blockers, .. }) => {
+                assert_eq!(blockers, vec!["sponsoring:2".to_string()]);
+            }
+            other => panic!("expected AccountNotMergeable, got: {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn self_merge_is_a_validation_error() {
+        let result = builder("http://127.0.0.1:1")
+            .build_account_merge(SOURCE_ADDR, SOURCE_ADDR)
+            .await;
+
+        let err = result.unwrap_err();
+        assert!(matches!(err, StellarError::SelfMerge { .. }), "got: {err:?}");
+        let app: crate::error::AppError = err.into();
+        assert_eq!(app.status_code(), 400);
+    }
+
+    #[test]
+    fn account_not_mergeable_maps_to_422_with_blockers() {
+        let err: crate::error::AppError = StellarError::account_not_mergeable(
+            SOURCE_ADDR,
+            vec!["trustline:AFRI:GISSUER".to_string()],
+        )
+        .into();
+        assert_eq!(err.status_code(), 422);
+        assert_eq!(err.error_code(), crate::error::ErrorCode::AccountNotMergeable);
+        assert_eq!(
+            err.details().unwrap()["blockers"],
+            serde_json::json!(["trustline:AFRI:GISSUER"])
+        );
+    }
+
+    // ── Batch limits ──────────────────────────────────────────────────────────
+
+    #[test]
+    fn too_many_operations_maps_to_400_with_limit_and_count() {
+        let err: crate::error::AppError = StellarError::too_many_operations(101, 100).into();
+        assert_eq!(err.status_code(), 400);
+        assert_eq!(
+            err.details().unwrap(),
+            serde_json::json!({ "operation_count": 101, "max_operations": 100 })
+        );
+    }
+
+    #[test]
+    fn empty_batch_is_a_validation_error() {
+        let err: crate::error::AppError = StellarError::NoOperations.into();
+        assert_eq!(err.status_code(), 400);
+        assert_eq!(err.error_code(), crate::error::ErrorCode::ValidationError);
+    }
+
+    // ── Envelope decoding ─────────────────────────────────────────────────────
+
+    #[tokio::test]
+    async fn decode_envelope_summarizes_signed_payment() {
+        let body = leak(account_json(
+            SOURCE_ADDR,
+            &xlm_and_cngn("10.0000000", "500.0000000", DEST_ADDR),
+        ));
+        let url = mock_n(200, body, 2).await;
+        let b = builder(&url);
+        let draft = b
+            .build_payment(
+                SOURCE_ADDR,
+                DEST_ADDR,
+                "12.5",
+                CngnMemo::Text("order-42".to_string()),
+                None,
+            )
+            .await
+            .unwrap();
+        let expected_hash = draft.transaction_hash.clone();
+        let unsigned_xdr = draft.unsigned_envelope_xdr.clone();
+        let signed = b.sign_payment(draft, SOURCE_SECRET).unwrap();
+
+        let unsigned = b.decode_envelope(&unsigned_xdr).unwrap();
+        assert_eq!(unsigned.signature_count, 0);
+
+        let summary = b.decode_envelope(&signed.signed_envelope_xdr).unwrap();
+        assert_eq!(summary.envelope_type, "tx");
+        assert_eq!(summary.source, SOURCE_ADDR);
+        assert_eq!(summary.fee_stroops, 100);
+        assert_eq!(summary.signature_count, 1);
+        assert_eq!(summary.transaction_hash, expected_hash);
+        assert!(matches!(summary.memo, CngnMemo::Text(ref t) if t == "order-42"));
+        assert!(summary.time_bounds.is_some());
+        assert!(summary.fee_bump.is_none());
+
+        assert_eq!(summary.operations.len(), 1);
+        let op = &summary.operations[0];
+        assert_eq!(op.r#type, "payment");
+        assert_eq!(op.details["destination"], DEST_ADDR);
+        assert_eq!(op.details["amount"], "12.5000000");
+        assert_eq!(op.details["asset"], format!("CNGN:{}", DEST_ADDR));
+    }
+
+    #[tokio::test]
+    async fn decode_envelope_summarizes_fee_bump() {
+        use stellar_xdr::next::{
+            FeeBumpTransaction, FeeBumpTransactionEnvelope, FeeBumpTransactionExt,
+            FeeBumpTransactionInnerTx, Limits, MuxedAccount, ReadXdr, TransactionEnvelope,
+            Uint256, VecM, WriteXdr,
+        };
+
+        let body = leak(account_json(
+            SOURCE_ADDR,
+            &xlm_and_cngn("10.0000000", "500.0000000", DEST_ADDR),
+        ));
+        let url = mock_n(200, body, 2).await;
+        let b = builder(&url);
+        let draft = b
+            .build_payment(SOURCE_ADDR, DEST_ADDR, "1", CngnMemo::Id(7), None)
+            .await
+            .unwrap();
+        let expected_inner_hash = draft.transaction_hash.clone();
+        let signed = b.sign_payment(draft, SOURCE_SECRET).unwrap();
+
+        let inner = match TransactionEnvelope::from_xdr_base64(
+            &signed.signed_envelope_xdr,
+            Limits::none(),
+        )
+        .unwrap()
+        {
+            TransactionEnvelope::Tx(v1) => v1,
+            other => panic!("unexpected envelope {other:?}"),
+        };
+        let fee_source = stellar_strkey::ed25519::PublicKey::from_string(DEST_ADDR).unwrap();
+        let fee_bump = TransactionEnvelope::TxFeeBump(FeeBumpTransactionEnvelope {
+            tx: FeeBumpTransaction {
+                fee_source: MuxedAccount::Ed25519(Uint256(fee_source.0)),
+                fee: 5_000,
+                inner_tx: FeeBumpTransactionInnerTx::Tx(inner),
+                ext: FeeBumpTransactionExt::V0,
+            },
+            signatures: VecM::default(),
+        })
+        .to_xdr_base64(Limits::none())
+        .unwrap();
+
+        let summary = b.decode_envelope(&fee_bump).unwrap();
+
+        assert_eq!(summary.envelope_type, "fee_bump");
+        assert_eq!(summary.source, SOURCE_ADDR);
+        assert_eq!(summary.signature_count, 1);
+        assert_eq!(summary.transaction_hash, expected_inner_hash);
+        assert!(matches!(summary.memo, CngnMemo::Id(7)));
+        let outer = summary.fee_bump.expect("fee bump details");
+        assert_eq!(outer.fee_source, DEST_ADDR);
+        assert_eq!(outer.fee_stroops, 5_000);
+        assert_eq!(outer.signature_count, 0);
+        assert_ne!(outer.transaction_hash, expected_inner_hash);
+    }
+
+    #[test]
+    fn decode_envelope_rejects_garbage() {
+        let client = StellarClient::new(config_pointing_at("http://127.0.0.1:1")).unwrap();
+        let err = CngnPaymentBuilder::new(client)
+            .decode_envelope("not-xdr")
+            .unwrap_err();
+        assert!(matches!(err, StellarError::SerializationError { .. }));
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Account cache tests
+// ─────────────────────────────────────────────────────────────────────────────
+#[cfg(test)]
+mod account_cache_tests {
+    use super::helpers::*;
+    use crate::chains::stellar::{client::StellarClient, errors::StellarError};
+    use crate::services::account_cache::AccountCache;
+    use std::sync::atomic::Ordering;
+
+    #[tokio::test]
+    async fn concurrent_cold_lookups_share_one_horizon_call() {
+        let body = leak(account_json(SOURCE_ADDR, &xlm_only("25.0000000")));
+        let (url, served) = mock_counting(200, body).await;
+        let cache = AccountCache::new(StellarClient::new(config_pointing_at(&url)).unwrap(), None);
+
+        let lookups = (0..50).map(|_| cache.get_account(SOURCE_ADDR));
+        let results = futures::future::join_all(lookups).await;
+
+        assert_eq!(served.load(Ordering::SeqCst), 1);
+        assert_eq!(results.len(), 50);
+        for result in results {
+            assert_eq!(result.unwrap().account_id, SOURCE_ADDR);
+        }
+    }
+
+    #[tokio::test]
+    async fn concurrent_waiters_share_the_leaders_error() {
+        let (url, served) = mock_counting(404, r#"{"status":404,"title":"Resource Missing"}"#).await;
+        let cache = AccountCache::new(StellarClient::new(config_pointing_at(&url)).unwrap(), None);
+
+        let lookups = (0..10).map(|_| cache.get_account(NONEXISTENT_ADDR));
+        let results = futures::future::join_all(lookups).await;
+
+        assert_eq!(served.load(Ordering::SeqCst), 1);
+        for result in results {
+            assert!(matches!(result, Err(StellarError::AccountNotFound { .. })));
+        }
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Startup demo tests
+// ─────────────────────────────────────────────────────────────────────────────
+#[cfg(test)]
+mod afri_deposit_tests {
+    use super::helpers::*;
+    use crate::chains::stellar::{client::StellarClient, trustline::CngnAssetConfig};
+    use crate::services::afri_deposit::{
+        AfriDepositService, DepositOutcome, DepositRequest, TRUSTLINE_BUILD_ENDPOINT,
+    };
+
+    fn service(url: &str) -> AfriDepositService {
+        let client = StellarClient::new(config_pointing_at(url)).unwrap();
+        AfriDepositService::with_config(
+            client,
+            CngnAssetConfig {
+                asset_code: "cNGN".to_string(),
+                issuer_testnet: DEST_ADDR.to_string(),
+                issuer_mainnet: DEST_ADDR.to_string(),
+                default_limit: None,
+            },
+        )
+    }
+
+    fn request() -> DepositRequest {
+        DepositRequest {
+            source: SOURCE_ADDR.to_string(),
+            destination: DEST_ADDR.to_string(),
+            amount: "25".to_string(),
+            deposit_account_id: Some(42),
+            fee_stroops: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn missing_trustline_returns_instructions() {
+        let body = leak(account_json(DEST_ADDR, &xlm_only("10.0000000")));
+        let url = mock_n(200, body, 1).await;
+
+        let plan = service(&url).prepare(&request()).await.unwrap();
+
+        match &plan.outcome {
+            DepositOutcome::TrustlineRequired {
+                destination,
+                asset_code,
+                issuer,
+                next_step,
+                ..
+            } => {
+                assert_eq!(destination, DEST_ADDR);
+                assert_eq!(asset_code, "cNGN");
+                assert_eq!(issuer, DEST_ADDR);
+                assert_eq!(next_step, TRUSTLINE_BUILD_ENDPOINT);
+            }
+            other => panic!("expected TrustlineRequired, got {:?}", other),
+        }
+
+        let steps: Vec<(&str, &str)> = plan
+            .audit
+            .iter()
+            .map(|e| (e.operation_type.as_str(), e.status.as_str()))
+            .collect();
+        assert_eq!(steps, vec![("verify", "completed"), ("create", "pending")]);
+        assert!(plan.audit.iter().all(|e| e.wallet_address == DEST_ADDR));
+
+        let json = serde_json::to_value(&plan.outcome).unwrap();
+        assert_eq!(json["status"], "trustline_required");
+    }
+
+    #[tokio::test]
+    async fn existing_trustline_builds_payment_draft() {
+        // Trustline check on the destination, then source and destination for
+        // the payment itself
+        let body = leak(account_json(
+            SOURCE_ADDR,
+            &xlm_and_cngn("10.0000000", "500.0000000", DEST_ADDR),
+        ));
+        let url = mock_n(200, body, 3).await;
+
+        let plan = service(&url).prepare(&request()).await.unwrap();
+
+        let draft = match &plan.outcome {
+            DepositOutcome::PaymentDraft { draft } => draft,
+            other => panic!("expected PaymentDraft, got {:?}", other),
+        };
+        assert_eq!(draft.source, SOURCE_ADDR);
+        assert_eq!(draft.destination, DEST_ADDR);
+        assert_eq!(draft.amount, "25");
+        assert_eq!(draft.asset_code, "cNGN");
+        assert!(!draft.unsigned_envelope_xdr.is_empty());
+
+        assert_eq!(plan.audit.len(), 2);
+        assert_eq!(plan.audit[0].metadata["step"], "trustline_check");
+        assert_eq!(plan.audit[1].metadata["step"], "payment_draft");
+        assert_eq!(
+            plan.audit[1].transaction_hash.as_deref(),
+            Some(draft.transaction_hash.as_str())
+        );
+    }
+}
+
+#[cfg(test)]
+mod startup_demo_tests {
+    use super::helpers::*;
+    use crate::chains::stellar::{client::StellarClient, demo::maybe_run_startup_demo};
+    use crate::health::{HealthChecker, ReadinessStatus, StartupState};
+
+    #[tokio::test]
+    async fn demo_skipped_by_default_and_startup_reaches_ready() {
+        std::env::remove_var("RUN_STARTUP_DEMO");
+        // Exactly one response: the readiness probe's health check. A demo
+        // lookup would take it and leave readiness failing.
+        let url = mock_n(200, "{}", 1).await;
+        let client = StellarClient::new(config_pointing_at(&url)).unwrap();
+
+        assert!(!maybe_run_startup_demo(&client).await);
+
+        let startup_state = StartupState::new();
+        let checker =
+            HealthChecker::new(None, None, Some(client)).with_startup_state(startup_state.clone());
+        startup_state.mark_complete();
+
+        let report = checker.check_readiness().await;
+        assert_eq!(report.status, ReadinessStatus::Ready);
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Wallet overview tests
+// ─────────────────────────────────────────────────────────────────────────────
+#[cfg(test)]
+mod wallet_overview_tests {
+    use super::helpers::*;
+    use crate::chains::stellar::{client::StellarClient, errors::StellarError};
+    use crate::services::wallet_overview::WalletOverviewService;
+    use chrono::{Duration as ChronoDuration, SecondsFormat, Utc};
+
+    fn payment_json(id: u32, days_ago: i64) -> String {
+        let created_at = (Utc::now() - ChronoDuration::days(days_ago))
+            .to_rfc3339_opts(SecondsFormat::Secs, true);
+        format!(
+            r#"{{"id":"{id}","paging_token":"{id}","type":"payment","created_at":"{created_at}","transaction_hash":"hash{id}","from":"{DEST_ADDR}","to":"{SOURCE_ADDR}","amount":"5.0000000","asset_type":"native","transaction":{{"memo_type":"none"}}}}"#
+        )
+    }
+
+    #[tokio::test]
+    async fn overview_aggregates_balances_and_recent_activity() {
+        let balances = format!(
+            r#"[{{"asset_type":"native","balance":"25.0000000","limit":null,"is_authorized":false,"is_authorized_to_maintain_liabilities":false}},{{"asset_type":"credit_alphanum4","asset_code":"AFRI","asset_issuer":"{DEST_ADDR}","balance":"120.5000000","limit":"1000.0000000","is_authorized":true,"is_authorized_to_maintain_liabilities":true}}]"#
+        );
+        let account = leak(account_json(SOURCE_ADDR, &balances));
+        // Newest first; the 45-day-old payment falls outside the window
+        let payments = leak(format!(
+            r#"{{"_embedded":{{"records":[{},{},{}]}}}}"#,
+            payment_json(3, 1),
+            payment_json(2, 10),
+            payment_json(1, 45)
+        ));
+        let url = mock_sequence(vec![(200, account), (200, payments)]).await;
+        let client = StellarClient::new(config_pointing_at(&url)).unwrap();
+
+        let overview = WalletOverviewService::new(client, None)
+            .get_overview(SOURCE_ADDR)
+            .await
+            .unwrap();
+
+        assert_eq!(overview.xlm_balance, "25.0000000");
+        assert!(overview.afri_trustline);
+        assert_eq!(overview.afri_balance.as_deref(), Some("120.5000000"));
+        assert_eq!(overview.recent_payment_count, 2);
+        assert!(!overview.activity_truncated);
+        assert!(overview.last_activity_at.is_some());
+        assert!(!overview.cached);
+    }
+
+    #[tokio::test]
+    async fn account_created_at_is_its_first_operation() {
+        let body = leak(format!(
+            r#"{{"_embedded":{{"records":[{{"id":"1","type":"create_account","created_at":"2024-03-01T12:00:00Z","source_account":"{DEST_ADDR}","account":"{SOURCE_ADDR}"}}]}}}}"#
+        ));
+        let url = mock_n(200, body, 1).await;
+        let client = StellarClient::new(config_pointing_at(&url)).unwrap();
+
+        let created_at = client.account_created_at(SOURCE_ADDR).await.unwrap();
+        assert_eq!(created_at.as_deref(), Some("2024-03-01T12:00:00Z"));
+    }
+
+    #[tokio::test]
+    async fn account_without_operations_has_no_creation_time() {
+        let url = mock_n(200, r#"{"_embedded":{"records":[]}}"#, 1).await;
+        let client = StellarClient::new(config_pointing_at(&url)).unwrap();
+
+        assert_eq!(client.account_created_at(SOURCE_ADDR).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn overview_for_missing_account_is_not_found() {
+        use crate::error::{AppError, ErrorCode};
+
+        let url = mock_n(404, r#"{"status":404,"title":"Resource Missing"}"#, 1).await;
+        let client = StellarClient::new(config_pointing_at(&url)).unwrap();
+
+        let err = WalletOverviewService::new(client, None)
+            .get_overview(NONEXISTENT_ADDR)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, StellarError::AccountNotFound { .. }));
+
+        let app_err: AppError = err.into();
+        assert_eq!(app_err.status_code(), 404);
+        assert_eq!(app_err.error_code(), ErrorCode::WalletNotFound);
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Pre-signed XDR submission tests
+// ─────────────────────────────────────────────────────────────────────────────
+#[cfg(test)]
+mod submission_tests {
+    use super::helpers::*;
+    use crate::chains::stellar::{
+        client::StellarClient,
+        errors::StellarError,
+        payment::{CngnMemo, CngnPaymentBuilder, SignedCngnPayment},
+        submission::{
+            BatchItemStatus, BatchSubmitOptions, ConfirmationStatus, FeeBreakdown,
+            SignedTransactionSubmitter, SubmissionLimit, SubmissionRecord, SubmissionRecordStore,
+            SubmitOptions, TransactionStatusEvent,
+        },
+    };
+    use futures::StreamExt;
+    use std::time::Duration;
+
+    /// A payment signed the way a non-custodial client would, captured as XDR
+    async fn signed_payment() -> SignedCngnPayment {
+        std::env::set_var("CNGN_ASSET_CODE", "cNGN");
+        std::env::set_var("CNGN_ISSUER_TESTNET", DEST_ADDR);
+        let body = leak(account_json(
+            SOURCE_ADDR,
+            &xlm_and_cngn("10.0000000", "500.0000000", DEST_ADDR),
+        ));
+        let url = mock_n(200, body, 2).await;
+        let builder =
+            CngnPaymentBuilder::new(StellarClient::new(config_pointing_at(&url)).unwrap());
+        let draft = builder
+            .build_payment(SOURCE_ADDR, DEST_ADDR, "1", CngnMemo::None, None)
+            .await
+            .unwrap();
+        builder.sign_payment(draft, SOURCE_SECRET).unwrap()
+    }
+
+    fn submitter(url: &str, wait_for_confirmation: bool) -> SignedTransactionSubmitter {
+        SignedTransactionSubmitter::new(StellarClient::new(config_pointing_at(url)).unwrap())
+            .with_options(SubmitOptions {
+                max_attempts: 3,
+                retry_backoff: Duration::from_millis(10),
+                wait_for_confirmation,
+                confirmation_timeout: Duration::from_secs(2),
+                poll_interval: Duration::from_millis(10),
+                ..Default::default()
+            })
+    }
+
+    #[tokio::test]
+    async fn submits_captured_signed_xdr() {
+        let signed = signed_payment().await;
+        let url = mock_n(
+            200,
+            r#"{"hash":"abc123","successful":true,"ledger":12345}"#,
+            1,
+        )
+        .await;
+
+        let result = submitter(&url, false)
+            .submit(&signed.signed_envelope_xdr)
+            .await
+            .unwrap();
+
+        assert_eq!(result.attempts, 1);
+        assert_eq!(result.horizon_response["ledger"], 12345);
+        assert_eq!(result.transaction_hash, signed.draft.transaction_hash);
+        assert!(result.confirmation.is_none());
+    }
+
+    #[tokio::test]
+    async fn retries_transient_failure_then_confirms() {
+        let signed = signed_payment().await;
+        let record = leak(format!(
+            r#"{{"hash":"{}","successful":true,"ledger":777}}"#,
+            signed.draft.transaction_hash
+        ));
+        let url = mock_sequence(vec![
+            (503, ""),
+            (200, r#"{"hash":"abc123","successful":true,"ledger":777}"#),
+            (200, record),
+        ])
+        .await;
+
+        let result = submitter(&url, true)
+            .submit(&signed.signed_envelope_xdr)
+            .await
+            .unwrap();
+
+        assert_eq!(result.attempts, 2);
+        assert!(matches!(
+            result.confirmation,
+            Some(ConfirmationStatus::Confirmed { ledger: Some(777) })
+        ));
+    }
+
+    #[tokio::test]
+    async fn rejects_unsigned_xdr_without_network_call() {
+        let signed = signed_payment().await;
+        let unsigned = {
+            use stellar_xdr::next::{Limits, ReadXdr, TransactionEnvelope, VecM, WriteXdr};
+            let mut env =
+                TransactionEnvelope::from_xdr_base64(&signed.signed_envelope_xdr, Limits::none())
+                    .unwrap();
+            if let TransactionEnvelope::Tx(v1) = &mut env {
+                v1.signatures = VecM::default();
+            }
+            env.to_xdr_base64(Limits::none()).unwrap()
+        };
+
+        let result = submitter("http://127.0.0.1:1", false).submit(&unsigned).await;
+
+        assert!(
+            matches!(result, Err(StellarError::SigningError { .. })),
+            "expected SigningError, got: {result:?}"
+        );
+    }
+
+    /// Replay store backed by a map
+    #[derive(Default)]
+    struct InMemoryReplayStore {
+        records: tokio::sync::Mutex<std::collections::HashMap<(String, i64), SubmissionRecord>>,
+        in_flight: tokio::sync::Mutex<std::collections::HashSet<(String, i64)>>,
+    }
+
+    #[async_trait::async_trait]
+    impl SubmissionRecordStore for InMemoryReplayStore {
+        async fn get(&self, source: &str, sequence: i64) -> Option<SubmissionRecord> {
+            self.records
+                .lock()
+                .await
+                .get(&(source.to_string(), sequence))
+                .cloned()
+        }
+
+        async fn put(
+            &self,
+            source: &str,
+            sequence: i64,
+            record: &SubmissionRecord,
+            _ttl: Duration,
+        ) {
+            self.records
+                .lock()
+                .await
+                .insert((source.to_string(), sequence), record.clone());
+        }
+
+        async fn claim(&self, source: &str, sequence: i64, _ttl: Duration) -> bool {
+            self.in_flight
+                .lock()
+                .await
+                .insert((source.to_string(), sequence))
+        }
+
+        async fn release(&self, source: &str, sequence: i64) {
+            self.in_flight
+                .lock()
+                .await
+                .remove(&(source.to_string(), sequence));
+        }
+    }
+
+    #[tokio::test]
+    async fn duplicate_submit_is_answered_from_replay_store() {
+        let signed = signed_payment().await;
+        // Serves exactly one connection: a second Horizon call would fail
+        let url = mock_n(
+            200,
+            r#"{"hash":"abc123","successful":true,"ledger":999}"#,
+            1,
+        )
+        .await;
+        let store = std::sync::Arc::new(InMemoryReplayStore::default());
+        let submitter = submitter(&url, false).with_replay_store(store.clone());
+
+        let first = submitter.submit(&signed.signed_envelope_xdr).await.unwrap();
+        let second = submitter.submit(&signed.signed_envelope_xdr).await.unwrap();
+
+        assert!(!first.replayed);
+        assert!(second.replayed);
+        assert_eq!(second.transaction_hash, first.transaction_hash);
+        assert_eq!(second.horizon_response["ledger"], 999);
+        assert_eq!(store.records.lock().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn concurrent_submit_of_the_same_sequence_is_turned_away() {
+        let signed = signed_payment().await;
+        let store = std::sync::Arc::new(InMemoryReplayStore::default());
+        let (source, sequence) = (&signed.draft.source, signed.draft.sequence);
+        assert!(store.claim(source, sequence, Duration::from_secs(60)).await);
+        // Nothing listens here: reaching Horizon would be a network error
+        let submitter = submitter("http://127.0.0.1:1", false).with_replay_store(store.clone());
+
+        let result = submitter.submit(&signed.signed_envelope_xdr).await;
+
+        assert!(
+            matches!(result, Err(StellarError::SubmissionInProgress { .. })),
+            "expected SubmissionInProgress, got: {result:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn pending_result_is_not_replayed() {
+        let signed = signed_payment().await;
+        let url = mock_sequence(vec![
+            (200, r#"{"hash":"abc123","successful":true}"#),
+            (404, r#"{"status":404}"#),
+        ])
+        .await;
+        let store = std::sync::Arc::new(InMemoryReplayStore::default());
+        let submitter = SignedTransactionSubmitter::new(
+            StellarClient::new(config_pointing_at(&url)).unwrap(),
+        )
+        .with_options(SubmitOptions {
+            max_attempts: 1,
+            wait_for_confirmation: true,
+            confirmation_timeout: Duration::ZERO,
+            ..Default::default()
+        })
+        .with_replay_store(store.clone());
+
+        let result = submitter.submit(&signed.signed_envelope_xdr).await.unwrap();
+
+        assert!(matches!(result.confirmation, Some(ConfirmationStatus::Pending)));
+        assert!(store.records.lock().await.is_empty());
+        assert!(store.in_flight.lock().await.is_empty(), "claim released");
+    }
+
+    /// `count` copies of a signed payment with consecutive sequence numbers
+    async fn signed_batch(count: i64) -> Vec<String> {
+        use stellar_xdr::next::{Limits, ReadXdr, SequenceNumber, TransactionEnvelope, WriteXdr};
+
+        let signed = signed_payment().await;
+        (0..count)
+            .map(|offset| {
+                let mut env = TransactionEnvelope::from_xdr_base64(
+                    &signed.signed_envelope_xdr,
+                    Limits::none(),
+                )
+                .unwrap();
+                if let TransactionEnvelope::Tx(v1) = &mut env {
+                    v1.tx.seq_num = SequenceNumber(v1.tx.seq_num.0 + offset);
+                }
+                env.to_xdr_base64(Limits::none()).unwrap()
+            })
+            .collect()
+    }
+
+    /// Allows `remaining` items, counting what is given back
+    struct CountingLimit {
+        remaining: std::sync::atomic::AtomicI64,
+        released: std::sync::atomic::AtomicUsize,
+    }
+
+    impl CountingLimit {
+        fn new(remaining: i64) -> std::sync::Arc<Self> {
+            std::sync::Arc::new(Self {
+                remaining: remaining.into(),
+                released: 0.into(),
+            })
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl SubmissionLimit for CountingLimit {
+        async fn reserve(
+            &self,
+            _envelope_xdr: &str,
+            summary: &crate::chains::stellar::payment::EnvelopeSummary,
+        ) -> Result<(), String> {
+            use std::sync::atomic::Ordering;
+            if self.remaining.fetch_sub(1, Ordering::SeqCst) > 0 {
+                Ok(())
+            } else {
+                Err(format!("{} is over its limit", summary.source))
+            }
+        }
+
+        async fn release(
+            &self,
+            _envelope_xdr: &str,
+            _summary: &crate::chains::stellar::payment::EnvelopeSummary,
+        ) {
+            self.released
+                .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        }
+    }
+
+    #[tokio::test]
+    async fn batch_submits_one_source_in_sequence_order() {
+        let envelopes = signed_batch(3).await;
+        // Sent out of order; the mock answers in the order calls arrive
+        let shuffled = vec![
+            envelopes[2].clone(),
+            envelopes[0].clone(),
+            envelopes[1].clone(),
+        ];
+        let url = mock_sequence(vec![
+            (200, r#"{"hash":"first","successful":true,"ledger":1}"#),
+            (
+                400,
+                r#"{"type":"https://stellar.org/horizon-errors/transaction_failed","title":"Transaction Failed","status":400,"extras":{"result_codes":{"transaction":"tx_failed","operations":["op_underfunded"]}}}"#,
+            ),
+            (200, r#"{"hash":"third","successful":true,"ledger":1}"#),
+        ])
+        .await;
+        let limit = CountingLimit::new(10);
+        let options = BatchSubmitOptions {
+            limits: vec![limit.clone()],
+            ..Default::default()
+        };
+
+        let results = submitter(&url, false).submit_batch(&shuffled, &options).await;
+
+        let statuses: Vec<_> = results.iter().map(|r| (r.index, r.status)).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                (0, BatchItemStatus::Submitted),
+                (1, BatchItemStatus::Submitted),
+                (2, BatchItemStatus::Failed),
+            ]
+        );
+        assert!(results[1].hash.is_some());
+        assert!(results[2].hash.is_none());
+        assert!(results[2].error.as_deref().unwrap().contains("op_underfunded"));
+        assert_ne!(results[0].hash, results[1].hash);
+        // The failed item's reservation is given back
+        assert_eq!(
+            limit.released.load(std::sync::atomic::Ordering::SeqCst),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn batch_holds_back_items_over_a_limit() {
+        let envelopes = signed_batch(3).await;
+        let mut envelopes_with_garbage = envelopes.clone();
+        envelopes_with_garbage.insert(0, "not-xdr".to_string());
+        let url = mock_n(200, r#"{"hash":"ok","successful":true,"ledger":1}"#, 2).await;
+        let limit = CountingLimit::new(2);
+        let options = BatchSubmitOptions {
+            concurrency: 2,
+            limits: vec![limit.clone()],
+        };
+
+        let results = submitter(&url, false)
+            .submit_batch(&envelopes_with_garbage, &options)
+            .await;
+
+        let statuses: Vec<_> = results.iter().map(|r| r.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                BatchItemStatus::Failed,
+                BatchItemStatus::Submitted,
+                BatchItemStatus::Submitted,
+                BatchItemStatus::RateLimited,
+            ]
+        );
+        assert!(results[3].error.as_deref().unwrap().contains("over its limit"));
+        assert_eq!(
+            limit.released.load(std::sync::atomic::Ordering::SeqCst),
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn batch_holds_back_later_sequences_behind_a_limited_one() {
+        let envelopes = signed_batch(3).await;
+        let url = mock_n(200, r#"{"hash":"ok","successful":true,"ledger":1}"#, 1).await;
+        let options = BatchSubmitOptions {
+            limits: vec![CountingLimit::new(1)],
+            ..Default::default()
+        };
+
+        let results = submitter(&url, false).submit_batch(&envelopes, &options).await;
+
+        let statuses: Vec<_> = results.iter().map(|r| r.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                BatchItemStatus::Submitted,
+                BatchItemStatus::RateLimited,
+                BatchItemStatus::RateLimited,
+            ]
+        );
+        assert!(results[2].error.as_deref().unwrap().contains("held back behind"));
+    }
+
+    #[tokio::test]
+    async fn expired_time_bounds_rejected_without_network_call() {
+        use stellar_xdr::next::{
+            Limits, Preconditions, ReadXdr, TimeBounds, TimePoint, TransactionEnvelope, WriteXdr,
+        };
+
+        let signed = signed_payment().await;
+        let mut env =
+            TransactionEnvelope::from_xdr_base64(&signed.signed_envelope_xdr, Limits::none())
+                .unwrap();
+        if let TransactionEnvelope::Tx(v1) = &mut env {
+            v1.tx.cond = Preconditions::Time(TimeBounds {
+                min_time: TimePoint(0),
+                max_time: TimePoint(1_000),
+            });
+        }
+        let expired = env.to_xdr_base64(Limits::none()).unwrap();
+
+        let result = submitter("http://127.0.0.1:1", false).submit(&expired).await;
+
+        assert!(
+            matches!(result, Err(StellarError::TransactionExpired { max_time: 1_000 })),
+            "expected TransactionExpired, got: {result:?}"
+        );
+    }
+
+    /// `signed_payment` with its time bounds replaced
+    async fn signed_with_time_bounds(min_time: u64, max_time: u64) -> String {
+        use stellar_xdr::next::{
+            Limits, Preconditions, ReadXdr, TimeBounds, TimePoint, TransactionEnvelope, WriteXdr,
+        };
+
+        let signed = signed_payment().await;
+        let mut env =
+            TransactionEnvelope::from_xdr_base64(&signed.signed_envelope_xdr, Limits::none())
+                .unwrap();
+        if let TransactionEnvelope::Tx(v1) = &mut env {
+            v1.tx.cond = Preconditions::Time(TimeBounds {
+                min_time: TimePoint(min_time),
+                max_time: TimePoint(max_time),
+            });
+        }
+        env.to_xdr_base64(Limits::none()).unwrap()
+    }
+
+    fn unix_now() -> u64 {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+    }
+
+    #[tokio::test]
+    async fn min_time_slightly_ahead_validates_within_clock_skew() {
+        let now = unix_now();
+        // Built on a client whose clock runs a few seconds fast
+        let envelope = signed_with_time_bounds(now + 5, now + 300).await;
+
+        let result = submitter("http://127.0.0.1:1", false).validate(&envelope);
+
+        assert!(result.is_ok(), "expected valid within tolerance, got: {result:?}");
+    }
+
+    #[tokio::test]
+    async fn max_time_just_passed_validates_within_clock_skew() {
+        let now = unix_now();
+        let envelope = signed_with_time_bounds(0, now - 5).await;
+
+        assert!(submitter("http://127.0.0.1:1", false).validate(&envelope).is_ok());
+    }
+
+    #[tokio::test]
+    async fn min_time_far_ahead_is_not_yet_valid() {
+        let min_time = unix_now() + 3_600;
+        let envelope = signed_with_time_bounds(min_time, 0).await;
+
+        let result = submitter("http://127.0.0.1:1", false).validate(&envelope);
+
+        assert!(
+            matches!(
+                result,
+                Err(StellarError::TransactionNotYetValid { min_time: m }) if m == min_time
+            ),
+            "expected TransactionNotYetValid, got: {result:?}"
+        );
+    }
+
+    /// Horizon's answer to a submitted three-operation transaction
+    const MULTI_OP_SUBMIT_RESPONSE: &str = r#"{
+        "hash": "3389e9f0f1a65f19736cacf544c2e825313e8447f569233bb8db39aa607c8889",
+        "ledger": 2263391,
+        "successful": true,
+        "source_account": "GCJRI5CIWK5IU67Q6DGA7QW52JDKRO7JEAHQKFNDUJUPEZGURDBX3LDX",
+        "fee_account": "GCJRI5CIWK5IU67Q6DGA7QW52JDKRO7JEAHQKFNDUJUPEZGURDBX3LDX",
+        "fee_charged": "300",
+        "max_fee": "600",
+        "operation_count": 3
+    }"#;
+
+    #[test]
+    fn fee_breakdown_splits_charged_fee_across_operations() {
+        let response: serde_json::Value = serde_json::from_str(MULTI_OP_SUBMIT_RESPONSE).unwrap();
+
+        let fee = FeeBreakdown::from_horizon(&response, 3, Some(300)).unwrap();
+        assert_eq!(fee.operation_count, 3);
+        assert_eq!(fee.fee_charged, 300);
+        assert_eq!(fee.max_fee, Some(600));
+        assert_eq!(fee.fee_per_operation, 100);
+        assert!(!fee.exceeded_estimate);
+
+        let fee = FeeBreakdown::from_horizon(&response, 3, Some(250)).unwrap();
+        assert!(fee.exceeded_estimate);
+
+        let fee = FeeBreakdown::from_horizon(&response, 3, None).unwrap();
+        assert!(!fee.exceeded_estimate);
+
+        let without_fee = serde_json::json!({ "hash": "abc" });
+        assert_eq!(FeeBreakdown::from_horizon(&without_fee, 3, None), None);
+    }
+
+    #[tokio::test]
+    async fn submit_reports_fee_breakdown() {
+        let signed = signed_payment().await;
+        let url = mock_n(
+            200,
+            r#"{"hash":"abc123","successful":true,"ledger":12345,"fee_charged":"100","max_fee":"100"}"#,
+            1,
+        )
+        .await;
+
+        let result = submitter(&url, false)
+            .with_options(SubmitOptions {
+                estimated_fee_stroops: Some(50),
+                ..Default::default()
+            })
+            .submit(&signed.signed_envelope_xdr)
+            .await
+            .unwrap();
+
+        let fee = result.fee.unwrap();
+        assert_eq!(fee.operation_count, 1);
+        assert_eq!(fee.fee_per_operation, 100);
+        assert_eq!(fee.max_fee, Some(100));
+        assert!(fee.exceeded_estimate);
+    }
+
+    const NOT_FOUND: &str = r#"{"status":404,"title":"Resource Missing"}"#;
+
+    #[tokio::test]
+    async fn status_stream_follows_submit_through_confirmation() {
+        let signed = signed_payment().await;
+        let hash = signed.draft.transaction_hash.clone();
+        let record = leak(format!(
+            r#"{{"hash":"{}","successful":true,"ledger":4242}}"#,
+            hash
+        ));
+        // submit, then one poll before the ledger closes, then the record
+        let url = mock_sequence(vec![
+            (200, r#"{"hash":"abc123","successful":true,"ledger":4242}"#),
+            (404, NOT_FOUND),
+            (200, record),
+        ])
+        .await;
+
+        submitter(&url, false)
+            .submit(&signed.signed_envelope_xdr)
+            .await
+            .unwrap();
+        let (_shutdown_tx, shutdown_rx) = tokio::sync::watch::channel(false);
+        let events: Vec<_> = submitter(&url, false)
+            .status_stream(hash.clone(), shutdown_rx)
+            .collect()
+            .await;
+
+        assert_eq!(
+            events,
+            vec![
+                TransactionStatusEvent::Submitted { hash: hash.clone() },
+                TransactionStatusEvent::Included {
+                    hash: hash.clone(),
+                    ledger: Some(4242)
+                },
+                TransactionStatusEvent::Confirmed {
+                    hash: hash.clone(),
+                    ledger: Some(4242)
+                },
+            ]
+        );
+        assert!(events.last().unwrap().is_terminal());
+        assert_eq!(
+            serde_json::to_value(&events[1]).unwrap(),
+            serde_json::json!({"status": "included", "hash": hash, "ledger": 4242})
+        );
+    }
+
+    #[tokio::test]
+    async fn status_stream_times_out_when_never_included() {
+        let url = mock_n(404, NOT_FOUND, 100).await;
+        let (_shutdown_tx, shutdown_rx) = tokio::sync::watch::channel(false);
+        let submitter = SignedTransactionSubmitter::new(
+            StellarClient::new(config_pointing_at(&url)).unwrap(),
+        )
+        .with_options(SubmitOptions {
+            confirmation_timeout: Duration::from_millis(100),
+            poll_interval: Duration::from_millis(10),
+            ..Default::default()
+        });
+
+        let events: Vec<_> = submitter
+            .status_stream("ab".repeat(32), shutdown_rx)
+            .collect()
+            .await;
+
+        assert_eq!(events.first().unwrap().name(), "submitted");
+        assert_eq!(events.last().unwrap().name(), "timeout");
+        assert_eq!(events.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn status_stream_ends_on_shutdown() {
+        let url = mock_n(404, NOT_FOUND, 100).await;
+        let (shutdown_tx, shutdown_rx) = tokio::sync::watch::channel(false);
+        let mut stream = Box::pin(submitter(&url, false).status_stream("cd".repeat(32), shutdown_rx));
+
+        assert_eq!(stream.next().await.unwrap().name(), "submitted");
+        shutdown_tx.send(true).unwrap();
+
+        let rest = tokio::time::timeout(Duration::from_secs(1), stream.collect::<Vec<_>>())
+            .await
+            .expect("stream should close promptly on shutdown");
+        assert!(rest.is_empty(), "no terminal event on shutdown, got {rest:?}");
+    }
+
+    #[tokio::test]
+    async fn rejects_malformed_xdr_without_network_call() {
+        let result = submitter("http://127.0.0.1:1", false)
+            .submit("definitely-not-xdr")
+            .await;
+
+        assert!(
+            matches!(result, Err(StellarError::SerializationError { .. })),
+            "expected SerializationError, got: {result:?}"
+        );
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Horizon error-handling tests
+// ─────────────────────────────────────────────────────────────────────────────
+#[cfg(test)]
+mod failover_tests {
+    use super::helpers::*;
+    use crate::chains::stellar::client::StellarClient;
+    use crate::chains::stellar::config::StellarConfig;
+
+    const MAINTENANCE: &str = r#"{"status":503,"title":"Service Unavailable"}"#;
+
+    /// Primary answering 503, then `fallback` as the only fallback
+    async fn client_failing_over_to(fallback: &str) -> (StellarClient, String) {
+        let primary = mock_n(503, MAINTENANCE, 1).await;
+        let mut config = config_pointing_at(&primary);
+        config.horizon_fallback_urls = vec![fallback.to_string()];
+        (StellarClient::new(config).unwrap(), primary)
+    }
+
+    fn requests(url: &str, result: &str) -> f64 {
+        crate::metrics::stellar::horizon_requests_total()
+            .with_label_values(&[&StellarConfig::horizon_endpoint_label(url), result])
+            .get()
+    }
+
+    #[tokio::test]
+    async fn get_account_fails_over_and_records_per_endpoint_metrics() {
+        let body = leak(account_json(SOURCE_ADDR, &xlm_only("10.0000000")));
+        let fallback = mock_n(200, body, 1).await;
+        let (client, primary) = client_failing_over_to(&fallback).await;
+
+        let account = client.get_account(SOURCE_ADDR).await.unwrap();
+
+        assert_eq!(account.account_id, SOURCE_ADDR);
+        assert_eq!(requests(&primary, "error"), 1.0);
+        assert_eq!(requests(&primary, "success"), 0.0);
+        assert_eq!(requests(&fallback, "success"), 1.0);
+        assert_eq!(requests(&fallback, "error"), 0.0);
+        assert_eq!(
+            crate::metrics::stellar::horizon_request_duration_seconds()
+                .with_label_values(&[&StellarConfig::horizon_endpoint_label(&fallback)])
+                .get_sample_count(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn submission_fails_over_to_the_next_endpoint() {
+        let fallback = mock_n(200, r#"{"hash":"abc","successful":true}"#, 1).await;
+        let (client, primary) = client_failing_over_to(&fallback).await;
+
+        let result = client.submit_transaction_xdr("AAAA").await.unwrap();
+
+        assert_eq!(result["hash"], "abc");
+        assert_eq!(requests(&primary, "error"), 1.0);
+        assert_eq!(requests(&fallback, "success"), 1.0);
+    }
+
+    #[tokio::test]
+    async fn payments_fail_over_to_the_next_endpoint() {
+        let fallback = mock_n(200, r#"{"_embedded":{"records":[]}}"#, 1).await;
+        let (client, primary) = client_failing_over_to(&fallback).await;
+
+        let page = client.list_account_payments(SOURCE_ADDR, 10, None).await.unwrap();
+
+        assert!(page.records.is_empty());
+        assert_eq!(requests(&primary, "error"), 1.0);
+        assert_eq!(requests(&fallback, "success"), 1.0);
+    }
+
+    #[test]
+    fn endpoint_label_is_host_and_port_only() {
+        assert_eq!(
+            StellarConfig::horizon_endpoint_label("https://horizon.stellar.org/api?key=secret"),
+            "horizon.stellar.org"
+        );
+        assert_eq!(
+            StellarConfig::horizon_endpoint_label("http://127.0.0.1:8000"),
+            "127.0.0.1:8000"
+        );
+        assert_eq!(StellarConfig::horizon_endpoint_label("not a url"), "unknown");
+    }
+}
+
+#[cfg(test)]
+mod error_tests {
+    use super::helpers::*;
+    use crate::chains::stellar::{client::StellarClient, errors::StellarError};
+    use std::time::Duration;
+
+    // ── Rate-limit (429) ──────────────────────────────────────────────────────
+
+    #[tokio::test]
+    async fn get_account_returns_rate_limit_error_on_429() {
+        let url = mock_n(429, r#"{"status":429,"title":"Too Many Requests"}"#, 1).await;
+        let client = StellarClient::new(config_pointing_at(&url)).unwrap();
+
+        let result = client.get_account(SOURCE_ADDR).await;
+
+        assert!(
+            matches!(result, Err(StellarError::RateLimitError)),
+            "expected RateLimitError, got: {result:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn submit_transaction_returns_rate_limit_error_on_429() {
+        let url = mock_n(429, r#"{"status":429,"title":"Too Many Requests"}"#, 1).await;
+        let client = StellarClient::new(config_pointing_at(&url)).unwrap();
+
+        let result = client.submit_transaction_xdr("AAAA").await;
+
+        assert!(
+            matches!(result, Err(StellarError::RateLimitError)),
+            "expected RateLimitError on submit 429, got: {result:?}"
+        );
     }
 
     #[tokio::test]
@@ -1057,6 +2748,49 @@ mod error_tests {
         );
     }
 
+    // ── Horizon maintenance (503) ─────────────────────────────────────────────
+
+    #[tokio::test]
+    async fn get_account_returns_service_unavailable_on_503() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(
+                ResponseTemplate::new(503)
+                    .insert_header("Retry-After", "30")
+                    .set_body_string(r#"{"status":503,"title":"Service Unavailable"}"#),
+            )
+            .mount(&server)
+            .await;
+        let client = StellarClient::new(config_pointing_at(&server.uri())).unwrap();
+
+        let result = client.get_account(SOURCE_ADDR).await;
+
+        assert!(
+            matches!(
+                result,
+                Err(StellarError::ServiceUnavailable {
+                    retry_after: Some(30)
+                })
+            ),
+            "expected ServiceUnavailable, got: {result:?}"
+        );
+        assert!(result.unwrap_err().is_retryable());
+    }
+
+    #[tokio::test]
+    async fn service_unavailable_maps_to_upstream_unavailable() {
+        use crate::error::{AppError, ErrorCode};
+
+        let app_err: AppError = StellarError::service_unavailable(Some(30)).into();
+
+        assert_eq!(app_err.status_code(), 503);
+        assert_eq!(app_err.error_code(), ErrorCode::UpstreamUnavailable);
+        assert_eq!(app_err.retry_after(), Some(30));
+    }
+
     // ── Network timeout ───────────────────────────────────────────────────────
 
     #[tokio::test]
@@ -1234,6 +2968,114 @@ mod error_tests {
         assert!(matches!(result, Err(StellarError::InvalidAddress { .. })));
     }
 
+    // ── Account payments with memos ───────────────────────────────────────────
+
+    #[tokio::test]
+    async fn list_account_payments_surfaces_text_id_and_missing_memos() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        let payments = serde_json::json!({"_embedded": {"records": [
+            {
+                "id": "1", "paging_token": "1", "type": "payment", "transaction_hash": "tx_text",
+                "from": DEST_ADDR, "to": SOURCE_ADDR, "amount": "10.0000000", "asset_type": "native",
+                "transaction": {"hash": "tx_text", "memo_type": "text", "memo": "deposit-42"}
+            },
+            {
+                "id": "2", "paging_token": "2", "type": "payment", "transaction_hash": "tx_id",
+                "from": DEST_ADDR, "to": SOURCE_ADDR, "amount": "5.0000000", "asset_type": "native"
+            },
+            {
+                "id": "3", "paging_token": "3", "type": "payment", "transaction_hash": "tx_id",
+                "from": DEST_ADDR, "to": SOURCE_ADDR, "amount": "1.0000000", "asset_type": "native"
+            },
+            {
+                "id": "4", "paging_token": "4", "type": "payment", "transaction_hash": "tx_none",
+                "from": DEST_ADDR, "to": SOURCE_ADDR, "amount": "2.0000000", "asset_type": "native",
+                "transaction": {"hash": "tx_none", "memo_type": "none"}
+            }
+        ]}});
+        Mock::given(method("GET"))
+            .and(path(format!("/accounts/{SOURCE_ADDR}/payments")))
+            .respond_with(ResponseTemplate::new(200).set_body_json(payments))
+            .mount(&server)
+            .await;
+        // Two payments share tx_id without an embedded record: one lookup only
+        Mock::given(method("GET"))
+            .and(path("/transactions/tx_id"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "hash": "tx_id", "successful": true, "memo_type": "id", "memo": "1234567890"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = StellarClient::new(config_pointing_at(&server.uri())).unwrap();
+        let page = client
+            .list_account_payments(SOURCE_ADDR, 10, None)
+            .await
+            .unwrap();
+
+        assert_eq!(page.records.len(), 4);
+        assert_eq!(page.records[0].memo_type.as_deref(), Some("text"));
+        assert_eq!(page.records[0].memo.as_deref(), Some("deposit-42"));
+        for record in &page.records[1..3] {
+            assert_eq!(record.memo_type.as_deref(), Some("id"));
+            assert_eq!(record.memo.as_deref(), Some("1234567890"));
+        }
+        assert!(page.records[3].memo.is_none());
+        assert!(page.records[3].memo_type.is_none());
+
+        let json = serde_json::to_value(&page.records[3]).unwrap();
+        assert!(json["memo"].is_null());
+        assert_eq!(json["type"], "payment");
+    }
+
+    #[tokio::test]
+    async fn list_account_payments_degrades_bad_records_individually() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        let payments = serde_json::json!({"_embedded": {"records": [
+            {"id": "1", "paging_token": "1", "type": "payment"},
+            {
+                "id": "2", "paging_token": "2", "type": "payment", "transaction_hash": "tx_text",
+                "from": DEST_ADDR, "to": SOURCE_ADDR, "amount": "10.0000000", "asset_type": "native",
+                "transaction": {"hash": "tx_text", "memo_type": "text", "memo": "deposit-42"}
+            },
+            {
+                "id": "3", "paging_token": "3", "type": "payment", "transaction_hash": "tx_gone",
+                "from": DEST_ADDR, "to": SOURCE_ADDR, "amount": "5.0000000", "asset_type": "native"
+            }
+        ]}});
+        Mock::given(method("GET"))
+            .and(path(format!("/accounts/{SOURCE_ADDR}/payments")))
+            .respond_with(ResponseTemplate::new(200).set_body_json(payments))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/transactions/tx_gone"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&server)
+            .await;
+
+        let client = StellarClient::new(config_pointing_at(&server.uri())).unwrap();
+        let page = client
+            .list_account_payments(SOURCE_ADDR, 10, None)
+            .await
+            .unwrap();
+
+        assert_eq!(page.skipped, 1);
+        assert_eq!(page.records.len(), 2);
+        assert_eq!(page.records[0].memo.as_deref(), Some("deposit-42"));
+        assert!(!page.records[0].memo_unavailable);
+        assert_eq!(page.records[1].id, "3");
+        assert!(page.records[1].memo_unavailable);
+        assert!(page.records[1].memo.is_none());
+    }
+
     // ── Retry eligibility via AppError ────────────────────────────────────────
 
     #[test]
@@ -1372,6 +3214,7 @@ mod unit_tests {
             is_authorized: true,
             is_authorized_to_maintain_liabilities: true,
             last_modified_ledger: None,
+            liquidity_pool_id: None,
         }
     }
 
@@ -0,0 +1,803 @@
+//! Submission of transactions signed entirely client-side
+//!
+//! Non-custodial clients hold their own keys and only need the backend to get
+//! an already-signed envelope onto the network: check it parses and carries
+//! signatures, submit with retries on transient Horizon failures, and
+//! optionally wait for the transaction to show up in a closed ledger. The same
+//! polling backs the status stream clients can watch instead of polling
+//! themselves.
+//!
+//! Client retries resend the same envelope. With a replay store attached, the
+//! final result of each submission is kept under its source account and
+//! sequence number, and a repeat is answered from there instead of reaching
+//! Horizon. While a submission is in flight its source and sequence are
+//! claimed, so a concurrent retry is turned away rather than submitted twice.
+//!
+//! Batches are submitted side by side across source accounts, each item
+//! succeeding or failing on its own, with one result per item. Items from the
+//! same source go one at a time in sequence order, since the network only
+//! takes a source's transactions in that order. Every item is reserved
+//! against the batch's [`SubmissionLimit`]s first, the same limits a single
+//! submission counts against.
+
+use crate::chains::stellar::client::StellarClient;
+use crate::chains::stellar::errors::{StellarError, StellarResult};
+use crate::chains::stellar::payment::{CngnPaymentBuilder, EnvelopeSummary};
+use crate::retry::{retry_async, RetryPolicy};
+use async_trait::async_trait;
+use futures::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, watch};
+use tracing::{debug, info, warn};
+
+/// How long a submission stays replayable when its transaction has no upper
+/// time bound
+pub const DEFAULT_REPLAY_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// How long an in-flight claim outlives a submitter that died holding it
+pub const IN_FLIGHT_TTL: Duration = Duration::from_secs(2 * 60);
+
+#[derive(Debug, Clone)]
+pub struct SubmitOptions {
+    /// Total submission attempts, including the first
+    pub max_attempts: u32,
+    /// Delay before the first retry; doubled for each further retry
+    pub retry_backoff: Duration,
+    pub wait_for_confirmation: bool,
+    pub confirmation_timeout: Duration,
+    pub poll_interval: Duration,
+    /// Fee the caller expected to pay, in stroops; `fee.exceeded_estimate`
+    /// is set when Horizon charged more
+    pub estimated_fee_stroops: Option<i64>,
+}
+
+impl Default for SubmitOptions {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            retry_backoff: Duration::from_millis(500),
+            wait_for_confirmation: false,
+            confirmation_timeout: Duration::from_secs(30),
+            poll_interval: Duration::from_secs(2),
+            estimated_fee_stroops: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ConfirmationStatus {
+    Confirmed { ledger: Option<i64> },
+    Failed { ledger: Option<i64> },
+    /// Not seen in a closed ledger before the confirmation timeout
+    Pending,
+}
+
+/// A limit each batch item must fit within before it reaches Horizon, e.g. a
+/// wallet's request window or the platform's daily volume
+#[async_trait]
+pub trait SubmissionLimit: Send + Sync {
+    /// Take this item's share of the limit. `Err` holds it back as
+    /// `rate_limited`, with the message as its error.
+    async fn reserve(&self, envelope_xdr: &str, summary: &EnvelopeSummary) -> Result<(), String>;
+
+    /// Give back what `reserve` took, called when the submission then failed
+    /// or was answered from the replay store
+    async fn release(&self, _envelope_xdr: &str, _summary: &EnvelopeSummary) {}
+}
+
+#[derive(Clone)]
+pub struct BatchSubmitOptions {
+    /// Source accounts submitting at once; each source's own items always go
+    /// one at a time
+    pub concurrency: usize,
+    /// Checked in order for every item just before it is submitted
+    pub limits: Vec<Arc<dyn SubmissionLimit>>,
+}
+
+impl Default for BatchSubmitOptions {
+    fn default() -> Self {
+        Self {
+            concurrency: 4,
+            limits: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchItemStatus {
+    Submitted,
+    Failed,
+    RateLimited,
+}
+
+/// Outcome of one envelope in a batch, in request order
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BatchItemResult {
+    pub index: usize,
+    pub status: BatchItemStatus,
+    /// Set for submitted items
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl BatchItemResult {
+    fn failed(index: usize, error: &StellarError) -> Self {
+        Self {
+            index,
+            status: BatchItemStatus::Failed,
+            hash: None,
+            error: Some(error.to_string()),
+        }
+    }
+
+    fn rate_limited(index: usize, error: String) -> Self {
+        Self {
+            index,
+            status: BatchItemStatus::RateLimited,
+            hash: None,
+            error: Some(error),
+        }
+    }
+}
+
+/// One step of a transaction's progress on the status stream
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum TransactionStatusEvent {
+    /// Handed to the network but not yet in a closed ledger
+    Submitted { hash: String },
+    Included { hash: String, ledger: Option<i64> },
+    Confirmed { hash: String, ledger: Option<i64> },
+    Failed { hash: String, ledger: Option<i64> },
+    /// Stopped watching before the transaction reached a ledger
+    Timeout { hash: String },
+}
+
+impl TransactionStatusEvent {
+    /// SSE event name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Submitted { .. } => "submitted",
+            Self::Included { .. } => "included",
+            Self::Confirmed { .. } => "confirmed",
+            Self::Failed { .. } => "failed",
+            Self::Timeout { .. } => "timeout",
+        }
+    }
+
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            Self::Confirmed { .. } | Self::Failed { .. } | Self::Timeout { .. }
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubmittedTransaction {
+    pub transaction_hash: String,
+    pub attempts: u32,
+    pub horizon_response: serde_json::Value,
+    /// Only set when confirmation was requested
+    pub confirmation: Option<ConfirmationStatus>,
+    /// Answered from an earlier submission of the same transaction
+    #[serde(default)]
+    pub replayed: bool,
+    #[serde(default)]
+    pub fee: Option<FeeBreakdown>,
+}
+
+impl SubmittedTransaction {
+    /// Whether the outcome can no longer change: confirmed or failed in a
+    /// ledger, or, without a confirmation wait, Horizon answered with the
+    /// ledger the transaction closed in. Only these are replayed.
+    pub fn is_final(&self) -> bool {
+        match &self.confirmation {
+            Some(ConfirmationStatus::Confirmed { .. } | ConfirmationStatus::Failed { .. }) => true,
+            Some(ConfirmationStatus::Pending) => false,
+            None => self
+                .horizon_response
+                .get("ledger")
+                .is_some_and(|ledger| !ledger.is_null()),
+        }
+    }
+}
+
+/// What a submitted transaction cost, split across its operations
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeeBreakdown {
+    /// Operations the fee is charged over; a fee bump counts as one more
+    pub operation_count: u32,
+    /// Stroops actually charged for the whole transaction
+    pub fee_charged: i64,
+    /// Stroops the transaction bid, i.e. the most it could have been charged
+    pub max_fee: Option<i64>,
+    /// `fee_charged / operation_count`, rounded down
+    pub fee_per_operation: i64,
+    pub estimated_fee_stroops: Option<i64>,
+    pub exceeded_estimate: bool,
+}
+
+impl FeeBreakdown {
+    /// Read `fee_charged` and `max_fee` from a Horizon transaction response.
+    /// Horizon sends both as strings; `None` when `fee_charged` is missing.
+    pub fn from_horizon(
+        response: &serde_json::Value,
+        operation_count: u32,
+        estimated_fee_stroops: Option<i64>,
+    ) -> Option<Self> {
+        let stroops = |field: &str| match response.get(field)? {
+            serde_json::Value::String(s) => s.parse::<i64>().ok(),
+            value => value.as_i64(),
+        };
+        let fee_charged = stroops("fee_charged")?;
+        Some(Self {
+            operation_count,
+            fee_charged,
+            max_fee: stroops("max_fee"),
+            fee_per_operation: fee_charged / i64::from(operation_count.max(1)),
+            estimated_fee_stroops,
+            exceeded_estimate: estimated_fee_stroops.is_some_and(|e| fee_charged > e),
+        })
+    }
+}
+
+/// What the replay store keeps per source account and sequence number
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubmissionRecord {
+    /// Inner transaction hash, so a fee bump of the same transaction still matches
+    pub inner_transaction_hash: String,
+    pub result: SubmittedTransaction,
+}
+
+/// Remembers submitted transactions. Lookups and writes are best-effort: an
+/// unavailable store must not block submissions.
+#[async_trait]
+pub trait SubmissionRecordStore: Send + Sync {
+    async fn get(&self, source: &str, sequence: i64) -> Option<SubmissionRecord>;
+    async fn put(&self, source: &str, sequence: i64, record: &SubmissionRecord, ttl: Duration);
+
+    /// Atomically mark this source + sequence as being submitted. `false`
+    /// when another submission already holds it; a store that can't be
+    /// reached answers `true`.
+    async fn claim(&self, source: &str, sequence: i64, ttl: Duration) -> bool;
+
+    /// Drop the in-flight mark taken by `claim`
+    async fn release(&self, source: &str, sequence: i64);
+}
+
+#[cfg(feature = "cache")]
+#[async_trait]
+impl SubmissionRecordStore for crate::cache::RedisCache {
+    async fn get(&self, source: &str, sequence: i64) -> Option<SubmissionRecord> {
+        use crate::cache::{cache::Cache, keys::transaction::SubmissionKey};
+        let key = SubmissionKey::new(source, sequence).to_string();
+        match <Self as Cache<SubmissionRecord>>::get(self, &key).await {
+            Ok(record) => record,
+            Err(e) => {
+                warn!(key = %key, error = %e, "replay store lookup failed");
+                None
+            }
+        }
+    }
+
+    async fn put(&self, source: &str, sequence: i64, record: &SubmissionRecord, ttl: Duration) {
+        use crate::cache::{cache::Cache, keys::transaction::SubmissionKey};
+        let key = SubmissionKey::new(source, sequence).to_string();
+        if let Err(e) = self.set(&key, record, Some(ttl)).await {
+            warn!(
+                key = %key,
+                error = %e,
+                "failed to record submission for replay protection"
+            );
+        }
+    }
+
+    async fn claim(&self, source: &str, sequence: i64, ttl: Duration) -> bool {
+        use crate::cache::keys::transaction::SubmissionInFlightKey;
+        let key = SubmissionInFlightKey::new(source, sequence).to_string();
+        let mut conn = match self.get_connection().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                warn!(key = %key, error = %e, "in-flight claim skipped");
+                return true;
+            }
+        };
+        // SET NX answers nil when the key is already held
+        let claimed: redis::RedisResult<Option<String>> = redis::cmd("SET")
+            .arg(&key)
+            .arg("1")
+            .arg("NX")
+            .arg("EX")
+            .arg(ttl.as_secs().max(1))
+            .query_async(&mut *conn)
+            .await;
+        match claimed {
+            Ok(reply) => reply.is_some(),
+            Err(e) => {
+                warn!(key = %key, error = %e, "in-flight claim skipped");
+                true
+            }
+        }
+    }
+
+    async fn release(&self, source: &str, sequence: i64) {
+        use crate::cache::{cache::Cache, keys::transaction::SubmissionInFlightKey};
+        let key = SubmissionInFlightKey::new(source, sequence).to_string();
+        if let Err(e) = <Self as Cache<String>>::delete(self, &key).await {
+            warn!(key = %key, error = %e, "failed to release in-flight claim");
+        }
+    }
+}
+
+pub struct SignedTransactionSubmitter {
+    stellar_client: StellarClient,
+    options: SubmitOptions,
+    replay_store: Option<Arc<dyn SubmissionRecordStore>>,
+}
+
+impl SignedTransactionSubmitter {
+    pub fn new(stellar_client: StellarClient) -> Self {
+        Self {
+            stellar_client,
+            options: SubmitOptions::default(),
+            replay_store: None,
+        }
+    }
+
+    pub fn with_options(mut self, options: SubmitOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Answer repeat submissions of the same source + sequence from `store`
+    pub fn with_replay_store(mut self, store: Arc<dyn SubmissionRecordStore>) -> Self {
+        self.replay_store = Some(store);
+        self
+    }
+
+    /// Decode the envelope and check every layer that must be signed is.
+    /// Returns the summary, whose hash is the one the network will report.
+    pub fn validate(&self, envelope_xdr: &str) -> StellarResult<EnvelopeSummary> {
+        let summary = CngnPaymentBuilder::new(self.stellar_client.clone())
+            .decode_envelope(envelope_xdr)?;
+
+        let outer_signatures = summary
+            .fee_bump
+            .as_ref()
+            .map_or(summary.signature_count, |fb| fb.signature_count);
+        if summary.signature_count == 0 || outer_signatures == 0 {
+            return Err(StellarError::signing_error(
+                "envelope_xdr has no signatures",
+            ));
+        }
+        // Horizon would only answer tx_too_late / tx_too_early, after a round
+        // trip. Both checks allow for clock skew, so a client a little ahead
+        // or behind us isn't turned away for a transaction the network takes.
+        let (now, tolerance) = (now_unix(), crate::clock_skew::tolerance_secs());
+        if let Some(max_time) = expired_max_time(&summary, now, tolerance) {
+            return Err(StellarError::transaction_expired(max_time));
+        }
+        if let Some(min_time) = pending_min_time(&summary, now, tolerance) {
+            return Err(StellarError::transaction_not_yet_valid(min_time));
+        }
+        Ok(summary)
+    }
+
+    pub async fn submit(&self, envelope_xdr: &str) -> StellarResult<SubmittedTransaction> {
+        let summary = self.validate(envelope_xdr)?;
+        let transaction_hash = summary
+            .fee_bump
+            .as_ref()
+            .map_or(summary.transaction_hash.clone(), |fb| {
+                fb.transaction_hash.clone()
+            });
+
+        if let Some(store) = &self.replay_store {
+            if !store
+                .claim(&summary.source, summary.sequence, IN_FLIGHT_TTL)
+                .await
+            {
+                return Err(StellarError::submission_in_progress(transaction_hash));
+            }
+            // Looked up under the claim, so a submission that just finished
+            // has already stored its result
+            if let Some(record) = store.get(&summary.source, summary.sequence).await {
+                // Same sequence but a different transaction: Horizon will
+                // reject it as tx_bad_seq, so let that error come back
+                if record.inner_transaction_hash == summary.transaction_hash {
+                    store.release(&summary.source, summary.sequence).await;
+                    info!(
+                        hash = %record.result.transaction_hash,
+                        "repeat submission answered from replay store"
+                    );
+                    return Ok(SubmittedTransaction {
+                        replayed: true,
+                        ..record.result
+                    });
+                }
+            }
+        }
+
+        let result = self.submit_claimed(envelope_xdr, &summary, transaction_hash).await;
+        if let Some(store) = &self.replay_store {
+            if let Ok(result) = &result {
+                if result.is_final() {
+                    let record = SubmissionRecord {
+                        inner_transaction_hash: summary.transaction_hash.clone(),
+                        result: result.clone(),
+                    };
+                    let ttl = replay_ttl(&summary, now_unix());
+                    store
+                        .put(&summary.source, summary.sequence, &record, ttl)
+                        .await;
+                }
+            }
+            store.release(&summary.source, summary.sequence).await;
+        }
+        result
+    }
+
+    /// Submit, confirm if asked and work out the fee, once the replay checks
+    /// have let the envelope through
+    async fn submit_claimed(
+        &self,
+        envelope_xdr: &str,
+        summary: &EnvelopeSummary,
+        transaction_hash: String,
+    ) -> StellarResult<SubmittedTransaction> {
+        let (horizon_response, attempts) =
+            self.submit_with_retry(envelope_xdr.trim(), &transaction_hash).await?;
+
+        let confirmation = if self.options.wait_for_confirmation {
+            Some(self.await_confirmation(&transaction_hash).await)
+        } else {
+            None
+        };
+
+        let operation_count = summary.operations.len() + usize::from(summary.fee_bump.is_some());
+        let fee = FeeBreakdown::from_horizon(
+            &horizon_response,
+            operation_count as u32,
+            self.options.estimated_fee_stroops,
+        );
+        if fee.as_ref().is_some_and(|f| f.exceeded_estimate) {
+            warn!(
+                hash = %transaction_hash,
+                fee = ?fee,
+                "transaction fee charged exceeded the estimate"
+            );
+        }
+
+        Ok(SubmittedTransaction {
+            transaction_hash,
+            attempts,
+            horizon_response,
+            confirmation,
+            replayed: false,
+            fee,
+        })
+    }
+
+    /// Submit a batch of envelopes. Sources are worked through concurrently,
+    /// each source's items serially in sequence order. A failing item never
+    /// stops the others; every envelope gets a result, in input order.
+    pub async fn submit_batch(
+        &self,
+        envelopes: &[String],
+        options: &BatchSubmitOptions,
+    ) -> Vec<BatchItemResult> {
+        // Malformed envelopes are settled before anything reaches Horizon
+        let mut results: Vec<Option<BatchItemResult>> = vec![None; envelopes.len()];
+        let mut by_source: HashMap<String, Vec<(i64, usize, EnvelopeSummary)>> = HashMap::new();
+        for (index, envelope) in envelopes.iter().enumerate() {
+            match self.validate(envelope) {
+                Ok(summary) => by_source
+                    .entry(summary.source.clone())
+                    .or_default()
+                    .push((summary.sequence, index, summary)),
+                Err(e) => results[index] = Some(BatchItemResult::failed(index, &e)),
+            }
+        }
+
+        let submitted: Vec<Vec<BatchItemResult>> = futures::stream::iter(by_source.into_values())
+            .map(|mut items| async move {
+                items.sort_by_key(|(sequence, index, _)| (*sequence, *index));
+                self.submit_source_in_order(envelopes, items, &options.limits)
+                    .await
+            })
+            .buffer_unordered(options.concurrency.max(1))
+            .collect()
+            .await;
+        for result in submitted.into_iter().flatten() {
+            let index = result.index;
+            results[index] = Some(result);
+        }
+
+        results.into_iter().flatten().collect()
+    }
+
+    /// Submit one source's items, already in sequence order. Once an item is
+    /// held back by a limit, the later ones can't be valid on the network and
+    /// are held back with it.
+    async fn submit_source_in_order(
+        &self,
+        envelopes: &[String],
+        items: Vec<(i64, usize, EnvelopeSummary)>,
+        limits: &[Arc<dyn SubmissionLimit>],
+    ) -> Vec<BatchItemResult> {
+        let mut results = Vec::with_capacity(items.len());
+        let mut held_back: Option<i64> = None;
+        for (sequence, index, summary) in items {
+            if let Some(blocked) = held_back {
+                results.push(BatchItemResult::rate_limited(
+                    index,
+                    format!(
+                        "held back behind sequence {} of {}, which was rate limited",
+                        blocked, summary.source
+                    ),
+                ));
+                continue;
+            }
+
+            let envelope = &envelopes[index];
+            let mut reserved = Vec::new();
+            let mut refusal = None;
+            for limit in limits {
+                match limit.reserve(envelope, &summary).await {
+                    Ok(()) => reserved.push(limit),
+                    Err(e) => {
+                        refusal = Some(e);
+                        break;
+                    }
+                }
+            }
+            if let Some(error) = refusal {
+                for limit in reserved {
+                    limit.release(envelope, &summary).await;
+                }
+                held_back = Some(sequence);
+                results.push(BatchItemResult::rate_limited(index, error));
+                continue;
+            }
+
+            results.push(match self.submit(envelope).await {
+                Ok(tx) => {
+                    // A replay was counted when it was first submitted
+                    if tx.replayed {
+                        for limit in reserved {
+                            limit.release(envelope, &summary).await;
+                        }
+                    }
+                    BatchItemResult {
+                        index,
+                        status: BatchItemStatus::Submitted,
+                        hash: Some(tx.transaction_hash),
+                        error: None,
+                    }
+                }
+                Err(e) => {
+                    warn!(index, error = %e, "batch item submission failed");
+                    for limit in reserved {
+                        limit.release(envelope, &summary).await;
+                    }
+                    BatchItemResult::failed(index, &e)
+                }
+            });
+        }
+        results
+    }
+
+    async fn submit_with_retry(
+        &self,
+        envelope_xdr: &str,
+        transaction_hash: &str,
+    ) -> StellarResult<(serde_json::Value, u32)> {
+        let policy = RetryPolicy {
+            max_retries: self.options.max_attempts.max(1) - 1,
+            base_delay: self.options.retry_backoff,
+            jitter: Duration::ZERO,
+            max_elapsed: None,
+        };
+        let mut attempts = 0;
+        let result = retry_async(&policy, StellarError::retry_class, |_| {
+            attempts += 1;
+            self.stellar_client.submit_transaction_xdr(envelope_xdr)
+        })
+        .await;
+
+        match result {
+            Ok(response) => Ok((response, attempts)),
+            Err(e) if attempts > 1 => {
+                // An earlier attempt may have landed even though its response
+                // was lost; the resubmission then fails (e.g. tx_bad_seq)
+                // while the transaction is already on-chain.
+                match self
+                    .stellar_client
+                    .get_transaction_by_hash(transaction_hash)
+                    .await
+                {
+                    Ok(record) => {
+                        info!(hash = %transaction_hash, "earlier submission attempt landed");
+                        let response = serde_json::to_value(&record)
+                            .map_err(|e| StellarError::serialization_error(e.to_string()))?;
+                        Ok((response, attempts))
+                    }
+                    Err(_) => Err(e),
+                }
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn await_confirmation(&self, transaction_hash: &str) -> ConfirmationStatus {
+        let deadline = tokio::time::Instant::now() + self.options.confirmation_timeout;
+        loop {
+            if let Some(status) = self.check_ledger(transaction_hash).await {
+                return status;
+            }
+            if tokio::time::Instant::now() + self.options.poll_interval > deadline {
+                return ConfirmationStatus::Pending;
+            }
+            tokio::time::sleep(self.options.poll_interval).await;
+        }
+    }
+
+    /// Outcome once the transaction is in a closed ledger, `None` before then
+    async fn check_ledger(&self, transaction_hash: &str) -> Option<ConfirmationStatus> {
+        let record = self
+            .stellar_client
+            .get_transaction_by_hash(transaction_hash)
+            .await
+            .ok()?;
+        Some(if record.successful {
+            ConfirmationStatus::Confirmed {
+                ledger: record.ledger,
+            }
+        } else {
+            ConfirmationStatus::Failed {
+                ledger: record.ledger,
+            }
+        })
+    }
+
+    /// Watch `transaction_hash` until it lands in a ledger, emitting
+    /// submitted → included → confirmed/failed, or timeout once
+    /// `confirmation_timeout` passes. Polls at `poll_interval`.
+    ///
+    /// The stream ends without a terminal event when `shutdown` flips to true,
+    /// and polling stops as soon as the consumer drops the stream.
+    pub fn status_stream(
+        self,
+        transaction_hash: String,
+        mut shutdown: watch::Receiver<bool>,
+    ) -> impl Stream<Item = TransactionStatusEvent> + Send + 'static {
+        let (tx, rx) = mpsc::channel(4);
+
+        tokio::spawn(async move {
+            let hash = transaction_hash;
+            if tx
+                .send(TransactionStatusEvent::Submitted { hash: hash.clone() })
+                .await
+                .is_err()
+            {
+                return;
+            }
+
+            let deadline = tokio::time::Instant::now() + self.options.confirmation_timeout;
+            loop {
+                if *shutdown.borrow() {
+                    debug!(hash = %hash, "closing transaction status stream for shutdown");
+                    return;
+                }
+
+                if let Some(status) = self.check_ledger(&hash).await {
+                    let (ledger, terminal) = match status {
+                        ConfirmationStatus::Confirmed { ledger } => (
+                            ledger,
+                            TransactionStatusEvent::Confirmed {
+                                hash: hash.clone(),
+                                ledger,
+                            },
+                        ),
+                        ConfirmationStatus::Failed { ledger } => (
+                            ledger,
+                            TransactionStatusEvent::Failed {
+                                hash: hash.clone(),
+                                ledger,
+                            },
+                        ),
+                        ConfirmationStatus::Pending => unreachable!("check_ledger never pends"),
+                    };
+                    let included = TransactionStatusEvent::Included {
+                        hash: hash.clone(),
+                        ledger,
+                    };
+                    if tx.send(included).await.is_ok() {
+                        let _ = tx.send(terminal).await;
+                    }
+                    return;
+                }
+
+                if tokio::time::Instant::now() + self.options.poll_interval > deadline {
+                    let _ = tx.send(TransactionStatusEvent::Timeout { hash }).await;
+                    return;
+                }
+
+                tokio::select! {
+                    _ = tokio::time::sleep(self.options.poll_interval) => {}
+                    changed = shutdown.changed() => {
+                        // A dropped sender means the server is going away too
+                        if changed.is_err() {
+                            return;
+                        }
+                    }
+                    _ = tx.closed() => {
+                        debug!(hash = %hash, "transaction status stream client disconnected");
+                        return;
+                    }
+                }
+            }
+        });
+
+        futures::stream::unfold(rx, |mut rx| async move {
+            rx.recv().await.map(|event| (event, rx))
+        })
+    }
+}
+
+fn now_unix() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// The upper time bound when it passed more than `tolerance` seconds ago;
+/// 0 means unbounded
+fn expired_max_time(summary: &EnvelopeSummary, now: u64, tolerance: i64) -> Option<u64> {
+    summary
+        .time_bounds
+        .as_ref()
+        .map(|tb| tb.max_time)
+        .filter(|max_time| {
+            *max_time != 0
+                && crate::clock_skew::is_expired(
+                    i64::try_from(*max_time).unwrap_or(i64::MAX),
+                    i64::try_from(now).unwrap_or(i64::MAX),
+                    tolerance,
+                )
+        })
+}
+
+/// The lower time bound when it is more than `tolerance` seconds away
+fn pending_min_time(summary: &EnvelopeSummary, now: u64, tolerance: i64) -> Option<u64> {
+    summary
+        .time_bounds
+        .as_ref()
+        .map(|tb| tb.min_time)
+        .filter(|min_time| {
+            crate::clock_skew::is_not_yet_valid(
+                i64::try_from(*min_time).unwrap_or(i64::MAX),
+                i64::try_from(now).unwrap_or(i64::MAX),
+                tolerance,
+            )
+        })
+}
+
+/// A transaction can't land after its upper time bound, so there's no point
+/// remembering it much longer than that
+fn replay_ttl(summary: &EnvelopeSummary, now: u64) -> Duration {
+    match summary.time_bounds.as_ref().map(|tb| tb.max_time) {
+        Some(max_time) if max_time != 0 => {
+            Duration::from_secs(max_time.saturating_sub(now) + 60).min(DEFAULT_REPLAY_TTL)
+        }
+        _ => DEFAULT_REPLAY_TTL,
+    }
+}
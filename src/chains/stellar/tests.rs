@@ -16,6 +16,7 @@ mod tests {
         StellarConfig {
             network: StellarNetwork::Testnet,
             horizon_url_override: None,
+            horizon_fallback_urls: Vec::new(),
             request_timeout: Duration::from_secs(10),
             max_retries: 3,
             health_check_interval: Duration::from_secs(30),
@@ -290,6 +291,7 @@ mod tests {
                 is_authorized: true,
                 is_authorized_to_maintain_liabilities: true,
                 last_modified_ledger: None,
+                liquidity_pool_id: None,
             },
             AssetBalance {
                 asset_type: "credit_alphanum4".to_string(),
@@ -300,6 +302,7 @@ mod tests {
                 is_authorized: true,
                 is_authorized_to_maintain_liabilities: true,
                 last_modified_ledger: None,
+                liquidity_pool_id: None,
             },
             AssetBalance {
                 asset_type: "credit_alphanum4".to_string(),
@@ -310,6 +313,7 @@ mod tests {
                 is_authorized: true,
                 is_authorized_to_maintain_liabilities: true,
                 last_modified_ledger: None,
+                liquidity_pool_id: None,
             },
         ];
 
@@ -467,4 +471,5 @@ mod tests {
         );
         assert!(request_line.contains("GET /transactions/tx_hash_3/operations?limit=200 "));
     }
+
 }
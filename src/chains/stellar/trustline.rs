@@ -1,3 +1,4 @@
+use crate::chains::stellar::asset::StellarAsset;
 use crate::chains::stellar::client::StellarClient;
 use crate::chains::stellar::config::StellarNetwork;
 use crate::chains::stellar::errors::{StellarError, StellarResult};
@@ -6,16 +7,19 @@ use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use stellar_strkey::ed25519::PublicKey as StrkeyPublicKey;
 use stellar_xdr::next::{
-    AccountId, AlphaNum12, AlphaNum4, AssetCode12, AssetCode4, ChangeTrustAsset, ChangeTrustOp,
-    Limits, MuxedAccount, Operation, OperationBody, Preconditions, PublicKey, SequenceNumber,
-    Transaction, TransactionEnvelope, TransactionExt, TransactionV1Envelope, Uint256, VecM,
-    WriteXdr,
+    ChangeTrustOp, Limits, MuxedAccount, Operation, OperationBody, Preconditions,
+    SequenceNumber, Transaction, TransactionEnvelope, TransactionExt, TransactionV1Envelope,
+    Uint256, VecM, WriteXdr,
 };
 
 const BASE_RESERVE_XLM: f64 = 0.5;
 const TRUSTLINE_RESERVE_XLM: f64 = 0.5;
 const RECOMMENDED_FEE_BUFFER_XLM: f64 = 0.5;
 const DEFAULT_BASE_FEE_STROOPS: u32 = 100;
+/// Upper bound on addresses per onboarding status request
+pub const MAX_ONBOARDING_BATCH: usize = 100;
+/// Horizon lookups in flight at once for an onboarding batch
+const ONBOARDING_CONCURRENCY: usize = 10;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CngnAssetConfig {
@@ -43,6 +47,11 @@ impl CngnAssetConfig {
             StellarNetwork::Mainnet => &self.issuer_mainnet,
         }
     }
+
+    /// The configured asset on `network`, validated.
+    pub fn asset_for_network(&self, network: &StellarNetwork) -> StellarResult<StellarAsset> {
+        StellarAsset::credit(&self.asset_code, self.issuer_for_network(network))
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -66,6 +75,34 @@ pub struct TrustlinePreflight {
     pub reason: Option<String>,
 }
 
+/// Onboarding readiness of one address. `error` is set, and every flag false,
+/// when the address could not be checked; other addresses are unaffected.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct OnboardingStatus {
+    pub address: String,
+    pub exists: bool,
+    pub has_trustline: bool,
+    /// XLM covers the reserves for the account as it will be once onboarded
+    /// (including the trustline if it still has to be added) plus fees
+    pub reserves_ok: bool,
+    pub ready: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl OnboardingStatus {
+    fn failed(address: &str, error: &StellarError) -> Self {
+        Self {
+            address: address.to_string(),
+            exists: false,
+            has_trustline: false,
+            reserves_ok: false,
+            ready: false,
+            error: Some(error.to_string()),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UnsignedTrustlineTransaction {
     pub account_id: String,
@@ -108,19 +145,33 @@ impl CngnTrustlineManager {
             .issuer_for_network(self.stellar_client.network())
     }
 
+    /// The managed asset on the client's network.
+    pub fn asset(&self) -> StellarResult<StellarAsset> {
+        self.config
+            .asset_for_network(self.stellar_client.network())
+    }
+
     pub async fn check_trustline(&self, account_id: &str) -> StellarResult<TrustlineStatus> {
+        self.check_asset_trustline(account_id, &self.asset()?).await
+    }
+
+    pub async fn check_asset_trustline(
+        &self,
+        account_id: &str,
+        asset: &StellarAsset,
+    ) -> StellarResult<TrustlineStatus> {
         if !is_valid_stellar_address(account_id) {
             return Err(StellarError::invalid_address(account_id));
         }
 
         let account = self.stellar_client.get_account(account_id).await?;
-        let issuer = self.issuer().to_string();
-        let trustline = find_trustline(&account.balances, self.asset_code(), &issuer);
+        let issuer = asset.issuer().unwrap_or_default().to_string();
+        let trustline = asset.find_balance(&account.balances);
 
         Ok(match trustline {
             Some(balance) => TrustlineStatus {
                 account_id: account_id.to_string(),
-                asset_code: self.asset_code().to_string(),
+                asset_code: asset.code().to_string(),
                 issuer,
                 has_trustline: true,
                 balance: Some(balance.balance.clone()),
@@ -129,7 +180,7 @@ impl CngnTrustlineManager {
             },
             None => TrustlineStatus {
                 account_id: account_id.to_string(),
-                asset_code: self.asset_code().to_string(),
+                asset_code: asset.code().to_string(),
                 issuer,
                 has_trustline: false,
                 balance: None,
@@ -165,21 +216,89 @@ impl CngnTrustlineManager {
         })
     }
 
+    /// Whether `account_id` exists, trusts the managed asset and holds enough
+    /// XLM to stay above its minimum balance once onboarded.
+    pub async fn onboarding_status(&self, account_id: &str) -> StellarResult<OnboardingStatus> {
+        if !is_valid_stellar_address(account_id) {
+            return Err(StellarError::invalid_address(account_id));
+        }
+        let asset = self.asset()?;
+
+        let account = match self.stellar_client.get_account(account_id).await {
+            Ok(account) => account,
+            Err(StellarError::AccountNotFound { .. }) => {
+                return Ok(OnboardingStatus {
+                    address: account_id.to_string(),
+                    exists: false,
+                    has_trustline: false,
+                    reserves_ok: false,
+                    ready: false,
+                    error: None,
+                })
+            }
+            Err(e) => return Err(e),
+        };
+
+        let has_trustline = asset.find_balance(&account.balances).is_some();
+        let required_xlm = if has_trustline {
+            required_xlm_for_account(account.subentry_count)
+        } else {
+            required_xlm_for_trustline(account.subentry_count)
+        };
+        let reserves_ok = account_xlm_balance(&account.balances) >= required_xlm;
+
+        Ok(OnboardingStatus {
+            address: account_id.to_string(),
+            exists: true,
+            has_trustline,
+            reserves_ok,
+            ready: has_trustline && reserves_ok,
+            error: None,
+        })
+    }
+
+    /// `onboarding_status` for each address, checked concurrently and
+    /// returned in input order. A failed lookup only affects its own entry.
+    pub async fn onboarding_status_batch(&self, addresses: &[String]) -> Vec<OnboardingStatus> {
+        use futures::stream::{self, StreamExt};
+
+        stream::iter(addresses)
+            .map(|address| async move {
+                self.onboarding_status(address)
+                    .await
+                    .unwrap_or_else(|e| OnboardingStatus::failed(address, &e))
+            })
+            .buffered(ONBOARDING_CONCURRENCY)
+            .collect()
+            .await
+    }
+
     pub async fn build_create_trustline_transaction(
         &self,
         account_id: &str,
         limit: Option<&str>,
         fee_stroops: Option<u32>,
+    ) -> StellarResult<UnsignedTrustlineTransaction> {
+        self.build_asset_trustline_transaction(account_id, &self.asset()?, limit, fee_stroops)
+            .await
+    }
+
+    pub async fn build_asset_trustline_transaction(
+        &self,
+        account_id: &str,
+        asset: &StellarAsset,
+        limit: Option<&str>,
+        fee_stroops: Option<u32>,
     ) -> StellarResult<UnsignedTrustlineTransaction> {
         if !is_valid_stellar_address(account_id) {
             return Err(StellarError::invalid_address(account_id));
         }
 
-        let status = self.check_trustline(account_id).await?;
+        let status = self.check_asset_trustline(account_id, asset).await?;
         if status.has_trustline {
             return Err(StellarError::trustline_already_exists(
                 account_id,
-                self.asset_code(),
+                asset.code(),
             ));
         }
 
@@ -203,7 +322,7 @@ impl CngnTrustlineManager {
         };
 
         let source = parse_muxed_account(account_id)?;
-        let trustline_asset = build_change_trust_asset(self.asset_code(), self.issuer())?;
+        let trustline_asset = asset.to_change_trust_asset()?;
         let op = Operation {
             source_account: None,
             body: OperationBody::ChangeTrust(ChangeTrustOp {
@@ -244,8 +363,8 @@ impl CngnTrustlineManager {
 
         Ok(UnsignedTrustlineTransaction {
             account_id: account_id.to_string(),
-            asset_code: self.asset_code().to_string(),
-            issuer: self.issuer().to_string(),
+            asset_code: asset.code().to_string(),
+            issuer: status.issuer,
             fee_stroops: fee,
             sequence,
             transaction_hash: hex::encode(hash),
@@ -285,23 +404,6 @@ fn validate_signed_envelope_has_signatures(xdr: &str) -> StellarResult<()> {
     Ok(())
 }
 
-fn find_trustline<'a>(
-    balances: &'a [AssetBalance],
-    asset_code: &str,
-    issuer: &str,
-) -> Option<&'a AssetBalance> {
-    balances.iter().find(|balance| {
-        matches!(
-            balance.asset_type.as_str(),
-            "credit_alphanum4" | "credit_alphanum12"
-        ) && balance
-            .asset_code
-            .as_deref()
-            .is_some_and(|code| code.eq_ignore_ascii_case(asset_code))
-            && balance.asset_issuer.as_deref() == Some(issuer)
-    })
-}
-
 fn account_xlm_balance(balances: &[AssetBalance]) -> f64 {
     balances
         .iter()
@@ -311,9 +413,13 @@ fn account_xlm_balance(balances: &[AssetBalance]) -> f64 {
 }
 
 fn required_xlm_for_trustline(current_subentries: u32) -> f64 {
+    required_xlm_for_account(current_subentries + 1)
+}
+
+/// Minimum balance for `subentries` plus headroom for fees
+fn required_xlm_for_account(subentries: u32) -> f64 {
     (BASE_RESERVE_XLM * 2.0)
-        + (current_subentries as f64 * TRUSTLINE_RESERVE_XLM)
-        + TRUSTLINE_RESERVE_XLM
+        + (subentries as f64 * TRUSTLINE_RESERVE_XLM)
         + RECOMMENDED_FEE_BUFFER_XLM
 }
 
@@ -323,42 +429,6 @@ fn parse_muxed_account(address: &str) -> StellarResult<MuxedAccount> {
     Ok(MuxedAccount::Ed25519(Uint256(public_key.0)))
 }
 
-fn parse_account_id(address: &str) -> StellarResult<AccountId> {
-    let public_key = StrkeyPublicKey::from_string(address)
-        .map_err(|_| StellarError::invalid_address(address))?;
-    Ok(AccountId(PublicKey::PublicKeyTypeEd25519(Uint256(
-        public_key.0,
-    ))))
-}
-
-fn build_change_trust_asset(asset_code: &str, issuer: &str) -> StellarResult<ChangeTrustAsset> {
-    let issuer = parse_account_id(issuer)?;
-    let code = asset_code.to_uppercase();
-    let bytes = code.as_bytes();
-
-    if code.is_empty() || code.len() > 12 {
-        return Err(StellarError::config_error(
-            "asset code must be between 1 and 12 chars",
-        ));
-    }
-
-    if code.len() <= 4 {
-        let mut code4 = [0u8; 4];
-        code4[..bytes.len()].copy_from_slice(bytes);
-        Ok(ChangeTrustAsset::CreditAlphanum4(AlphaNum4 {
-            asset_code: AssetCode4(code4),
-            issuer,
-        }))
-    } else {
-        let mut code12 = [0u8; 12];
-        code12[..bytes.len()].copy_from_slice(bytes);
-        Ok(ChangeTrustAsset::CreditAlphanum12(AlphaNum12 {
-            asset_code: AssetCode12(code12),
-            issuer,
-        }))
-    }
-}
-
 fn decimal_to_int64_stroops(amount: &str) -> StellarResult<i64> {
     let value = amount.trim();
     if value.is_empty() {
@@ -1,29 +1,37 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use stellar_strkey::ed25519::PublicKey as StrkeyPublicKey;
+use tracing::warn;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StellarAccountInfo {
     pub account_id: String,
     pub sequence: i64,
     pub subentry_count: u32,
+    /// Reserves this account pays for other accounts' entries
+    #[serde(default)]
+    pub num_sponsoring: u32,
     pub thresholds: Thresholds,
     pub flags: AccountFlags,
+    /// Native first, then credit assets by code and issuer; see `sort_balances`
     pub balances: Vec<AssetBalance>,
+    /// Balances in the order Horizon returned them
+    #[serde(default, skip_serializing)]
+    pub horizon_balances: Vec<AssetBalance>,
     pub signers: Vec<Signer>,
     pub data: HashMap<String, String>,
     pub last_modified_ledger: u32,
     pub created_at: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Thresholds {
     pub low_threshold: u8,
     pub med_threshold: u8,
     pub high_threshold: u8,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct AccountFlags {
     pub auth_required: bool,
     pub auth_revocable: bool,
@@ -31,6 +39,29 @@ pub struct AccountFlags {
     pub auth_clawback_enabled: bool,
 }
 
+/// An asset issuer's account flags, with what they mean for holders
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IssuerFlags {
+    pub issuer: String,
+    pub flags: AccountFlags,
+    /// New trustlines stay unauthorized, and can't receive the asset, until
+    /// the issuer approves them (`auth_required`)
+    pub trustline_needs_authorization: bool,
+    /// The issuer can claw back balances from holders
+    pub clawback_enabled: bool,
+}
+
+impl IssuerFlags {
+    pub fn new(issuer: impl Into<String>, flags: AccountFlags) -> Self {
+        Self {
+            issuer: issuer.into(),
+            trustline_needs_authorization: flags.auth_required,
+            clawback_enabled: flags.auth_clawback_enabled,
+            flags,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AssetBalance {
     pub asset_type: String,
@@ -44,6 +75,22 @@ pub struct AssetBalance {
     #[serde(default)]
     pub is_authorized_to_maintain_liabilities: bool,
     pub last_modified_ledger: Option<u32>,
+    /// Set on `liquidity_pool_shares` balances, which have no code or issuer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub liquidity_pool_id: Option<String>,
+}
+
+/// `asset_type` Horizon reports for a liquidity pool share balance
+pub const LIQUIDITY_POOL_SHARES: &str = "liquidity_pool_shares";
+
+/// An account's shares in one liquidity pool
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PoolShareBalance {
+    pub liquidity_pool_id: String,
+    /// Pool shares held, not an amount of either reserve asset
+    pub balance: String,
+    pub limit: Option<String>,
+    pub last_modified_ledger: Option<u32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -55,17 +102,30 @@ pub struct Signer {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HorizonAccount {
+    #[serde(default)]
     pub _links: HashMap<String, serde_json::Value>,
+    #[serde(default)]
     pub id: String,
     pub account_id: String,
     pub sequence: String,
+    #[serde(default)]
     pub subentry_count: u32,
-    pub thresholds: Thresholds,
-    pub flags: AccountFlags,
+    #[serde(default)]
+    pub num_sponsoring: u32,
+    // Newly created or sponsored accounts can come back without some of the
+    // fields below, so they are optional here and defaulted on conversion.
+    #[serde(default)]
+    pub thresholds: Option<Thresholds>,
+    #[serde(default)]
+    pub flags: Option<AccountFlags>,
+    #[serde(default)]
     pub balances: Vec<HorizonBalance>,
-    pub signers: Vec<Signer>,
-    pub data: HashMap<String, String>,
-    pub last_modified_ledger: u64,
+    #[serde(default)]
+    pub signers: Option<Vec<Signer>>,
+    #[serde(default)]
+    pub data: Option<HashMap<String, String>>,
+    #[serde(default)]
+    pub last_modified_ledger: Option<u64>,
     pub created_at: Option<String>,
 }
 
@@ -82,6 +142,8 @@ pub struct HorizonBalance {
     #[serde(default)]
     pub is_authorized_to_maintain_liabilities: bool,
     pub last_modified_ledger: Option<u64>,
+    #[serde(default)]
+    pub liquidity_pool_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -95,20 +157,50 @@ pub struct HealthStatus {
 
 impl From<HorizonAccount> for StellarAccountInfo {
     fn from(account: HorizonAccount) -> Self {
+        let mut defaulted = Vec::new();
+        if account.thresholds.is_none() {
+            defaulted.push("thresholds");
+        }
+        if account.flags.is_none() {
+            defaulted.push("flags");
+        }
+        if account.signers.is_none() {
+            defaulted.push("signers");
+        }
+        if account.data.is_none() {
+            defaulted.push("data");
+        }
+        if account.last_modified_ledger.is_none() {
+            defaulted.push("last_modified_ledger");
+        }
+        if !defaulted.is_empty() {
+            warn!(
+                account_id = %account.account_id,
+                fields = ?defaulted,
+                "Horizon account payload incomplete, applying defaults"
+            );
+        }
+
+        let horizon_balances: Vec<AssetBalance> = account
+            .balances
+            .into_iter()
+            .map(AssetBalance::from)
+            .collect();
+        let mut balances = horizon_balances.clone();
+        sort_balances(&mut balances);
+
         Self {
             account_id: account.account_id,
             sequence: account.sequence.parse().unwrap_or(0),
             subentry_count: account.subentry_count,
-            thresholds: account.thresholds,
-            flags: account.flags,
-            balances: account
-                .balances
-                .into_iter()
-                .map(AssetBalance::from)
-                .collect(),
-            signers: account.signers,
-            data: account.data,
-            last_modified_ledger: account.last_modified_ledger as u32,
+            num_sponsoring: account.num_sponsoring,
+            thresholds: account.thresholds.unwrap_or_default(),
+            flags: account.flags.unwrap_or_default(),
+            balances,
+            horizon_balances,
+            signers: account.signers.unwrap_or_default(),
+            data: account.data.unwrap_or_default(),
+            last_modified_ledger: account.last_modified_ledger.unwrap_or_default() as u32,
             created_at: account
                 .created_at
                 .unwrap_or_else(|| chrono::Utc::now().to_rfc3339()),
@@ -116,6 +208,29 @@ impl From<HorizonAccount> for StellarAccountInfo {
     }
 }
 
+/// Stable order for account balances: native XLM first, then credit assets
+/// by code and issuer, then anything else (e.g. liquidity pool shares) in the
+/// order it arrived.
+pub fn sort_balances(balances: &mut [AssetBalance]) {
+    fn rank(balance: &AssetBalance) -> u8 {
+        match balance.asset_type.as_str() {
+            "native" => 0,
+            "credit_alphanum4" | "credit_alphanum12" => 1,
+            _ => 2,
+        }
+    }
+
+    balances.sort_by(|a, b| {
+        rank(a).cmp(&rank(b)).then_with(|| {
+            if rank(a) == 1 {
+                (&a.asset_code, &a.asset_issuer).cmp(&(&b.asset_code, &b.asset_issuer))
+            } else {
+                std::cmp::Ordering::Equal
+            }
+        })
+    });
+}
+
 impl From<HorizonBalance> for AssetBalance {
     fn from(balance: HorizonBalance) -> Self {
         Self {
@@ -127,10 +242,45 @@ impl From<HorizonBalance> for AssetBalance {
             is_authorized: balance.is_authorized,
             is_authorized_to_maintain_liabilities: balance.is_authorized_to_maintain_liabilities,
             last_modified_ledger: balance.last_modified_ledger.map(|v| v as u32),
+            liquidity_pool_id: balance.liquidity_pool_id,
         }
     }
 }
 
+/// Protocol limit for a `MEMO_TEXT`, counted in bytes of UTF-8 rather than
+/// characters
+pub const MAX_TEXT_MEMO_BYTES: usize = 28;
+
+/// Text memo byte limit, from `STELLAR_MAX_MEMO_BYTES` when set. Operators may
+/// lower it (e.g. to leave room for a reference prefix) but never raise it
+/// past the protocol limit.
+pub fn max_text_memo_bytes() -> usize {
+    std::env::var("STELLAR_MAX_MEMO_BYTES")
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .map(|v| v.min(MAX_TEXT_MEMO_BYTES))
+        .unwrap_or(MAX_TEXT_MEMO_BYTES)
+}
+
+/// Check a text memo against `max_bytes`, explaining an overflow in terms the
+/// user can act on.
+pub fn check_text_memo(text: &str, max_bytes: usize) -> Result<(), String> {
+    let bytes = text.len();
+    if bytes <= max_bytes {
+        return Ok(());
+    }
+    let chars = text.chars().count();
+    let multibyte = if chars < bytes {
+        " (accented letters take 2 bytes and emoji 4)"
+    } else {
+        ""
+    };
+    Err(format!(
+        "memo text is {} bytes of UTF-8 across {} characters{}, but at most {} bytes are allowed; shorten the memo",
+        bytes, chars, multibyte, max_bytes
+    ))
+}
+
 pub fn is_valid_stellar_address(address: &str) -> bool {
     if address.len() != 56 || !address.starts_with('G') {
         return false;
@@ -173,12 +323,198 @@ pub fn extract_asset_balance(
         .map(|balance| balance.balance.clone())
 }
 
-#[allow(dead_code)]
-pub fn extract_afri_balance(balances: &[AssetBalance]) -> Option<String> {
-    extract_asset_balance(balances, "AFRI", None)
+/// AFRI holding of an account. `has_trustline: false` means the account must
+/// add a trustline before it can receive AFRI; a trustline with a zero
+/// balance only needs a deposit.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AfriBalanceStatus {
+    pub has_trustline: bool,
+    pub balance: Option<String>,
+    pub limit: Option<String>,
+}
+
+pub fn extract_afri_balance(balances: &[AssetBalance]) -> AfriBalanceStatus {
+    let trustline = balances.iter().find(|balance| {
+        matches!(
+            balance.asset_type.as_str(),
+            "credit_alphanum4" | "credit_alphanum12"
+        ) && balance
+            .asset_code
+            .as_deref()
+            .is_some_and(|code| code.eq_ignore_ascii_case("AFRI"))
+    });
+
+    AfriBalanceStatus {
+        has_trustline: trustline.is_some(),
+        balance: trustline.map(|t| t.balance.clone()),
+        limit: trustline.and_then(|t| t.limit.clone()),
+    }
+}
+
+/// Liquidity pool share balances, in the order given. Entries without a pool
+/// id are skipped since there is nothing to identify them by.
+pub fn extract_pool_share_balances(balances: &[AssetBalance]) -> Vec<PoolShareBalance> {
+    balances
+        .iter()
+        .filter(|b| b.asset_type == LIQUIDITY_POOL_SHARES)
+        .filter_map(|b| {
+            Some(PoolShareBalance {
+                liquidity_pool_id: b.liquidity_pool_id.clone()?,
+                balance: b.balance.clone(),
+                limit: b.limit.clone(),
+                last_modified_ledger: b.last_modified_ledger,
+            })
+        })
+        .collect()
 }
 
 #[allow(dead_code)]
 pub fn extract_cngn_balance(balances: &[AssetBalance], issuer: Option<&str>) -> Option<String> {
     extract_asset_balance(balances, "cNGN", issuer)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn balances_are_normalized_with_native_first() {
+        let account: HorizonAccount = serde_json::from_value(serde_json::json!({
+            "account_id": "GABC",
+            "sequence": "1",
+            "balances": [
+                { "asset_type": "credit_alphanum4", "asset_code": "USDC", "asset_issuer": "GISSUERB", "balance": "1" },
+                { "asset_type": "liquidity_pool_shares", "balance": "2" },
+                { "asset_type": "credit_alphanum12", "asset_code": "AFRICOIN", "asset_issuer": "GISSUERA", "balance": "3" },
+                { "asset_type": "native", "balance": "4" },
+                { "asset_type": "credit_alphanum4", "asset_code": "USDC", "asset_issuer": "GISSUERA", "balance": "5" },
+                { "asset_type": "credit_alphanum4", "asset_code": "cNGN", "asset_issuer": "GISSUERA", "balance": "6" }
+            ]
+        }))
+        .unwrap();
+
+        let info = StellarAccountInfo::from(account);
+
+        let order: Vec<&str> = info.balances.iter().map(|b| b.balance.as_str()).collect();
+        assert_eq!(order, vec!["4", "3", "5", "1", "6", "2"]);
+        let raw: Vec<&str> = info
+            .horizon_balances
+            .iter()
+            .map(|b| b.balance.as_str())
+            .collect();
+        assert_eq!(raw, vec!["1", "2", "3", "4", "5", "6"]);
+    }
+
+    #[test]
+    fn pool_shares_parse_alongside_afri() {
+        let pool_a = "dd7b1ab831c273310ddbec6f97870aa83c2fbd78ce22aded37ecbf4f3380fac7";
+        let pool_b = "0c7bd1e3cf7ab5e6a3e4c4ed2f2c51c4fa0f9dcf2ba5bde8e6b0cf3e24c0f5d1";
+        let account: HorizonAccount = serde_json::from_value(serde_json::json!({
+            "account_id": "GABC",
+            "sequence": "1",
+            "balances": [
+                { "asset_type": "liquidity_pool_shares", "liquidity_pool_id": pool_b, "balance": "12.5000000", "limit": "922337203685.4775807", "last_modified_ledger": 7 },
+                { "asset_type": "credit_alphanum4", "asset_code": "AFRI", "asset_issuer": "GISSUERA", "balance": "250.0000000", "limit": "1000.0000000" },
+                { "asset_type": "liquidity_pool_shares", "liquidity_pool_id": pool_a, "balance": "3.0000000", "limit": "922337203685.4775807", "last_modified_ledger": 9 },
+                { "asset_type": "native", "balance": "40.0000000" }
+            ]
+        }))
+        .unwrap();
+
+        let info = StellarAccountInfo::from(account);
+
+        let order: Vec<&str> = info.balances.iter().map(|b| b.asset_type.as_str()).collect();
+        assert_eq!(
+            order,
+            vec!["native", "credit_alphanum4", LIQUIDITY_POOL_SHARES, LIQUIDITY_POOL_SHARES]
+        );
+
+        let afri = extract_afri_balance(&info.balances);
+        assert!(afri.has_trustline);
+        assert_eq!(afri.balance.as_deref(), Some("250.0000000"));
+
+        let pools = extract_pool_share_balances(&info.balances);
+        assert_eq!(pools.len(), 2);
+        assert_eq!(pools[0].liquidity_pool_id, pool_b);
+        assert_eq!(pools[0].balance, "12.5000000");
+        assert_eq!(pools[0].last_modified_ledger, Some(7));
+        assert_eq!(pools[1].liquidity_pool_id, pool_a);
+        assert_eq!(pools[1].balance, "3.0000000");
+    }
+
+    fn balance(asset_type: &str, code: Option<&str>, amount: &str) -> AssetBalance {
+        AssetBalance {
+            asset_type: asset_type.to_string(),
+            asset_code: code.map(str::to_string),
+            asset_issuer: code.map(|_| "GISSUERA".to_string()),
+            balance: amount.to_string(),
+            limit: code.map(|_| "922337203685.4775807".to_string()),
+            is_authorized: true,
+            is_authorized_to_maintain_liabilities: true,
+            last_modified_ledger: None,
+            liquidity_pool_id: None,
+        }
+    }
+
+    #[test]
+    fn native_only_account_has_no_afri_trustline() {
+        let status = extract_afri_balance(&[balance("native", None, "10000.0000000")]);
+
+        assert!(!status.has_trustline);
+        assert_eq!(status.balance, None);
+        assert_eq!(status.limit, None);
+    }
+
+    #[test]
+    fn afri_trustline_with_zero_balance_is_reported() {
+        let status = extract_afri_balance(&[
+            balance("native", None, "5.0000000"),
+            balance("credit_alphanum4", Some("AFRI"), "0.0000000"),
+        ]);
+
+        assert!(status.has_trustline);
+        assert_eq!(status.balance.as_deref(), Some("0.0000000"));
+        assert_eq!(status.limit.as_deref(), Some("922337203685.4775807"));
+    }
+
+    #[test]
+    fn afri_trustline_with_positive_balance_is_reported() {
+        let status = extract_afri_balance(&[
+            balance("credit_alphanum4", Some("USDC"), "3.0000000"),
+            balance("credit_alphanum4", Some("AFRI"), "125.5000000"),
+        ]);
+
+        assert!(status.has_trustline);
+        assert_eq!(status.balance.as_deref(), Some("125.5000000"));
+    }
+
+    #[test]
+    fn twenty_eight_ascii_chars_fit_a_text_memo() {
+        let memo = "A".repeat(28);
+        assert!(check_text_memo(&memo, MAX_TEXT_MEMO_BYTES).is_ok());
+    }
+
+    #[test]
+    fn emoji_memo_reports_byte_count() {
+        let memo = "🚀".repeat(15);
+        let err = check_text_memo(&memo, MAX_TEXT_MEMO_BYTES).unwrap_err();
+
+        assert!(err.contains("60 bytes"), "{err}");
+        assert!(err.contains("15 characters"), "{err}");
+        assert!(err.contains("at most 28 bytes"), "{err}");
+        assert!(err.contains("shorten"), "{err}");
+    }
+
+    #[test]
+    fn accented_text_is_measured_in_bytes() {
+        // 20 characters, 40 bytes
+        let memo = "é".repeat(20);
+        let err = check_text_memo(&memo, MAX_TEXT_MEMO_BYTES).unwrap_err();
+        assert!(err.contains("40 bytes"), "{err}");
+    }
+
+    #[test]
+    fn empty_memo_is_accepted() {
+        assert!(check_text_memo("", MAX_TEXT_MEMO_BYTES).is_ok());
+    }
+}
@@ -172,9 +172,9 @@ impl std::error::Error for BlockchainError {}
 /// Represents a balance for a specific asset
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AssetBalance {
-    /// Asset code (e.g., "XLM", "cNGN", "AFRI")
+    /// Asset code (e.g., "XLM", "cNGN", "AFRI"; "LP" for liquidity pool shares)
     pub asset_code: String,
-    /// Asset issuer address (None for native assets)
+    /// Asset issuer address (None for native assets and pool shares)
     pub issuer: Option<String>,
     /// Balance amount as string
     pub balance: String,
@@ -182,6 +182,9 @@ pub struct AssetBalance {
     pub asset_type: String,
     /// Optional limit for trustline
     pub limit: Option<String>,
+    /// Pool the shares belong to, for liquidity pool share balances
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub liquidity_pool_id: Option<String>,
 }
 
 /// Represents account information
@@ -0,0 +1,55 @@
+//! Clock skew tolerance for time-window checks
+//!
+//! Clients and this server never agree exactly on the time. Without some
+//! slack a transaction built a moment ago on a fast client looks not yet
+//! valid here, and a token issued by a slow one looks already expired.
+//! Transaction time bounds, token expiry and signed-request timestamps all
+//! widen their windows by [`tolerance_secs`] on both sides.
+//!
+//! `CLOCK_SKEW_TOLERANCE_SECS` sets the tolerance; it defaults to 30 seconds.
+
+/// Tolerance used when `CLOCK_SKEW_TOLERANCE_SECS` is unset or invalid
+pub const DEFAULT_CLOCK_SKEW_SECS: i64 = 30;
+
+/// Configured tolerance in seconds; negative values count as zero.
+pub fn tolerance_secs() -> i64 {
+    std::env::var("CLOCK_SKEW_TOLERANCE_SECS")
+        .ok()
+        .and_then(|v| v.parse::<i64>().ok())
+        .map_or(DEFAULT_CLOCK_SKEW_SECS, |secs| secs.max(0))
+}
+
+/// Whether something that stops being valid at `expires_at` has expired at
+/// `now`, allowing `tolerance` seconds of skew.
+pub fn is_expired(expires_at: i64, now: i64, tolerance: i64) -> bool {
+    expires_at.saturating_add(tolerance) < now
+}
+
+/// Whether something that becomes valid at `valid_from` is still in the
+/// future at `now`, allowing `tolerance` seconds of skew.
+pub fn is_not_yet_valid(valid_from: i64, now: i64, tolerance: i64) -> bool {
+    valid_from > now.saturating_add(tolerance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_700_000_000;
+
+    #[test]
+    fn expiry_within_tolerance_is_still_valid() {
+        assert!(!is_expired(NOW - 10, NOW, 30));
+        assert!(!is_expired(NOW - 30, NOW, 30));
+        assert!(is_expired(NOW - 31, NOW, 30));
+        assert!(is_expired(NOW - 10, NOW, 0));
+    }
+
+    #[test]
+    fn start_within_tolerance_is_already_valid() {
+        assert!(!is_not_yet_valid(NOW + 5, NOW, 30));
+        assert!(!is_not_yet_valid(NOW + 30, NOW, 30));
+        assert!(is_not_yet_valid(NOW + 31, NOW, 30));
+        assert!(is_not_yet_valid(NOW + 5, NOW, 0));
+    }
+}
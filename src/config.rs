@@ -3,6 +3,11 @@
 
 use std::env;
 
+/// Held by tests that set or read process environment variables, so they
+/// don't see each other's values
+#[cfg(test)]
+pub(crate) static ENV_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
 /// Main application configuration
 #[derive(Debug, Clone)]
 pub struct AppConfig {
@@ -771,6 +776,7 @@ mod tests {
 
     #[test]
     fn test_telemetry_env_var_defaults() {
+        let _env = ENV_LOCK.blocking_lock();
         // Remove all four OTel env vars so defaults are exercised.
         std::env::remove_var("OTEL_SERVICE_NAME");
         std::env::remove_var("APP_ENV");
@@ -788,6 +794,7 @@ mod tests {
 
     #[test]
     fn test_telemetry_env_var_overrides() {
+        let _env = ENV_LOCK.blocking_lock();
         std::env::set_var("OTEL_SERVICE_NAME", "payment-worker");
         std::env::set_var("APP_ENV", "production");
         std::env::set_var("OTEL_SAMPLING_RATE", "0.1");
@@ -810,6 +817,7 @@ mod tests {
 
     #[test]
     fn test_telemetry_invalid_sampling_rate_env_var() {
+        let _env = ENV_LOCK.blocking_lock();
         std::env::set_var("OTEL_SAMPLING_RATE", "not-a-number");
         let result = TelemetryConfig::from_env();
         assert!(result.is_err());
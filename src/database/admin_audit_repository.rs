@@ -0,0 +1,119 @@
+use crate::database::error::DatabaseError;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgConnection, PgPool};
+use uuid::Uuid;
+
+/// One admin action as recorded in `admin_audit_log`
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AdminAuditEntry {
+    pub id: Uuid,
+    pub actor: String,
+    pub action: String,
+    pub target: String,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+    pub request_id: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Input for [`AdminAuditRepository::record`]
+#[derive(Debug, Clone)]
+pub struct NewAdminAuditEntry {
+    pub actor: String,
+    pub action: String,
+    pub target: String,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+    pub request_id: Option<String>,
+}
+
+/// Optional equality filters for audit log listings; unset fields match everything
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AdminAuditFilter {
+    pub actor: Option<String>,
+    pub action: Option<String>,
+    pub target: Option<String>,
+}
+
+/// Shared by `find_filtered` and `count_filtered`. Binds `$1`..`$3` in
+/// `AdminAuditFilter` order.
+const FILTER_PREDICATE: &str = "WHERE ($1::text IS NULL OR actor = $1)
+               AND ($2::text IS NULL OR action = $2)
+               AND ($3::text IS NULL OR target = $3)";
+
+/// Repository for the admin action audit trail
+pub struct AdminAuditRepository {
+    pool: PgPool,
+}
+
+impl AdminAuditRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn record(
+        &self,
+        entry: &NewAdminAuditEntry,
+    ) -> Result<AdminAuditEntry, DatabaseError> {
+        let mut conn = self.pool.acquire().await.map_err(DatabaseError::from_sqlx)?;
+        Self::record_in(&mut conn, entry).await
+    }
+
+    /// Record `entry` on `conn`, typically the transaction making the change it
+    /// describes, so the change and its audit row commit or roll back together.
+    pub async fn record_in(
+        conn: &mut PgConnection,
+        entry: &NewAdminAuditEntry,
+    ) -> Result<AdminAuditEntry, DatabaseError> {
+        sqlx::query_as::<_, AdminAuditEntry>(
+            "INSERT INTO admin_audit_log (actor, action, target, before, after, request_id)
+             VALUES ($1, $2, $3, $4, $5, $6)
+             RETURNING id, actor, action, target, before, after, request_id, created_at",
+        )
+        .bind(&entry.actor)
+        .bind(&entry.action)
+        .bind(&entry.target)
+        .bind(&entry.before)
+        .bind(&entry.after)
+        .bind(&entry.request_id)
+        .fetch_one(conn)
+        .await
+        .map_err(DatabaseError::from_sqlx)
+    }
+
+    /// One page of entries matching `filter`, newest first
+    pub async fn find_filtered(
+        &self,
+        filter: &AdminAuditFilter,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<AdminAuditEntry>, DatabaseError> {
+        let sql = format!(
+            "SELECT id, actor, action, target, before, after, request_id, created_at
+             FROM admin_audit_log {}
+             ORDER BY created_at DESC, id DESC LIMIT $4 OFFSET $5",
+            FILTER_PREDICATE
+        );
+        sqlx::query_as::<_, AdminAuditEntry>(&sql)
+            .bind(&filter.actor)
+            .bind(&filter.action)
+            .bind(&filter.target)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(DatabaseError::from_sqlx)
+    }
+
+    /// Number of entries matching `filter`, ignoring paging
+    pub async fn count_filtered(&self, filter: &AdminAuditFilter) -> Result<i64, DatabaseError> {
+        let sql = format!("SELECT COUNT(*) FROM admin_audit_log {}", FILTER_PREDICATE);
+        sqlx::query_scalar::<_, i64>(&sql)
+            .bind(&filter.actor)
+            .bind(&filter.action)
+            .bind(&filter.target)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(DatabaseError::from_sqlx)
+    }
+}
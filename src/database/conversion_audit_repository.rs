@@ -1,11 +1,43 @@
 use crate::database::error::{DatabaseError, DatabaseErrorKind};
-use crate::database::repository::{Repository, TransactionalRepository};
+use crate::database::repository::{
+    ListQuery, PaginatedRepository, Repository, TransactionalRepository,
+};
 use async_trait::async_trait;
-use sqlx::{FromRow, PgPool};
+use bigdecimal::RoundingMode;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgConnection, PgPool};
 use uuid::Uuid;
 
+/// Scale of the NUMERIC(36, 18) amount, rate and fee columns
+pub const NUMERIC_SCALE: i64 = 18;
+/// Digits left of the decimal point those columns can hold
+pub const NUMERIC_INTEGER_DIGITS: i64 = 36 - NUMERIC_SCALE;
+
+/// Fit `value` to a NUMERIC(36, 18) column before binding it.
+///
+/// Extra fractional digits are rounded half-up (ties away from zero), the same
+/// rule Postgres applies on assignment, so the stored value is predictable
+/// rather than depending on where the rounding happened. Values whose integer
+/// part does not fit are rejected instead of failing the whole insert.
+pub fn fit_numeric(
+    column: &str,
+    value: sqlx::types::BigDecimal,
+) -> Result<sqlx::types::BigDecimal, DatabaseError> {
+    let rounded = value.with_scale_round(NUMERIC_SCALE, RoundingMode::HalfUp);
+    let limit = sqlx::types::BigDecimal::new(1.into(), -NUMERIC_INTEGER_DIGITS);
+    if rounded.abs() >= limit {
+        return Err(DatabaseError::new(DatabaseErrorKind::QueryError {
+            message: format!(
+                "{} value {} exceeds NUMERIC(36, {}): at most {} integer digits",
+                column, value, NUMERIC_SCALE, NUMERIC_INTEGER_DIGITS
+            ),
+        }));
+    }
+    Ok(rounded)
+}
+
 /// Conversion audit entity
-#[derive(Debug, Clone, FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct ConversionAudit {
     pub id: Uuid,
     pub user_id: Option<Uuid>,
@@ -26,6 +58,46 @@ pub struct ConversionAudit {
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
 
+/// Result of [`ConversionAuditRepository::create`]. `created` is false when an
+/// audit for the same transaction and currency pair already existed, in which
+/// case `audit` is that earlier row, unchanged.
+#[derive(Debug, Clone)]
+pub struct CreatedConversionAudit {
+    pub audit: ConversionAudit,
+    pub created: bool,
+}
+
+/// A conversion whose recorded `to_amount` strays from `from_amount * rate`
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ConversionDiscrepancy {
+    #[serde(flatten)]
+    #[sqlx(flatten)]
+    pub audit: ConversionAudit,
+    pub expected_to_amount: sqlx::types::BigDecimal,
+    /// `|to_amount - expected| / expected` in basis points; `None` when nothing was expected
+    pub deviation_bps: Option<sqlx::types::BigDecimal>,
+}
+
+/// Optional equality filters for conversion audit listings; unset fields match everything
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ConversionAuditFilter {
+    pub user_id: Option<Uuid>,
+    pub wallet_address: Option<String>,
+    pub status: Option<String>,
+    pub from_currency: Option<String>,
+    pub to_currency: Option<String>,
+    pub provider: Option<String>,
+}
+
+/// Shared by `find_filtered` and `count_filtered` so totals always describe
+/// the same rows as the page. Binds `$1`..`$6` in `ConversionAuditFilter` order.
+const FILTER_PREDICATE: &str = "WHERE ($1::uuid IS NULL OR user_id = $1)
+               AND ($2::text IS NULL OR wallet_address = $2)
+               AND ($3::text IS NULL OR status = $3)
+               AND ($4::text IS NULL OR from_currency = $4)
+               AND ($5::text IS NULL OR to_currency = $5)
+               AND ($6::text IS NULL OR provider = $6)";
+
 /// Repository for conversion audit trail
 pub struct ConversionAuditRepository {
     pool: PgPool,
@@ -36,7 +108,11 @@ impl ConversionAuditRepository {
         Self { pool }
     }
 
-    /// Create a conversion audit record
+    /// Create a conversion audit record.
+    ///
+    /// Idempotent per `(transaction_id, from_currency, to_currency)`: a repeat
+    /// for a transaction already audited returns the existing row instead of
+    /// inserting another. Audits without a `transaction_id` are always new.
     pub async fn create(
         &self,
         user_id: Option<Uuid>,
@@ -53,11 +129,58 @@ impl ConversionAuditRepository {
         status: &str,
         error_message: Option<&str>,
         metadata: serde_json::Value,
-    ) -> Result<ConversionAudit, DatabaseError> {
-        sqlx::query_as::<_, ConversionAudit>(
+    ) -> Result<CreatedConversionAudit, DatabaseError> {
+        let mut conn = self.pool.acquire().await.map_err(DatabaseError::from_sqlx)?;
+        Self::create_in(
+            &mut conn,
+            user_id,
+            wallet_address,
+            transaction_id,
+            from_currency,
+            to_currency,
+            from_amount,
+            to_amount,
+            rate,
+            fee_amount,
+            fee_currency,
+            provider,
+            status,
+            error_message,
+            metadata,
+        )
+        .await
+    }
+
+    /// [`Self::create`] on `conn`, for callers that record the audit in the
+    /// same transaction as the change it describes
+    pub async fn create_in(
+        conn: &mut PgConnection,
+        user_id: Option<Uuid>,
+        wallet_address: Option<&str>,
+        transaction_id: Option<Uuid>,
+        from_currency: &str,
+        to_currency: &str,
+        from_amount: sqlx::types::BigDecimal,
+        to_amount: sqlx::types::BigDecimal,
+        rate: sqlx::types::BigDecimal,
+        fee_amount: sqlx::types::BigDecimal,
+        fee_currency: Option<&str>,
+        provider: Option<&str>,
+        status: &str,
+        error_message: Option<&str>,
+        metadata: serde_json::Value,
+    ) -> Result<CreatedConversionAudit, DatabaseError> {
+        let from_amount = fit_numeric("from_amount", from_amount)?;
+        let to_amount = fit_numeric("to_amount", to_amount)?;
+        let rate = fit_numeric("rate", rate)?;
+        let fee_amount = fit_numeric("fee_amount", fee_amount)?;
+
+        let inserted = sqlx::query_as::<_, ConversionAudit>(
             "INSERT INTO conversion_audits 
              (user_id, wallet_address, transaction_id, from_currency, to_currency, from_amount, to_amount, rate, fee_amount, fee_currency, provider, status, error_message, metadata) 
              VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14) 
+             ON CONFLICT (transaction_id, from_currency, to_currency) WHERE transaction_id IS NOT NULL
+             DO NOTHING
              RETURNING id, user_id, wallet_address, transaction_id, from_currency, to_currency, from_amount, to_amount, rate, fee_amount, fee_currency, provider, status, error_message, metadata, created_at, updated_at",
         )
         .bind(user_id)
@@ -74,9 +197,34 @@ impl ConversionAuditRepository {
         .bind(status)
         .bind(error_message)
         .bind(metadata)
-        .fetch_one(&self.pool)
+        .fetch_optional(&mut *conn)
         .await
-        .map_err(DatabaseError::from_sqlx)
+        .map_err(DatabaseError::from_sqlx)?;
+
+        if let Some(audit) = inserted {
+            return Ok(CreatedConversionAudit {
+                audit,
+                created: true,
+            });
+        }
+
+        // Only a transaction-keyed insert can conflict
+        let existing = sqlx::query_as::<_, ConversionAudit>(
+            "SELECT id, user_id, wallet_address, transaction_id, from_currency, to_currency, from_amount, to_amount, rate, fee_amount, fee_currency, provider, status, error_message, metadata, created_at, updated_at 
+             FROM conversion_audits 
+             WHERE transaction_id = $1 AND from_currency = $2 AND to_currency = $3",
+        )
+        .bind(transaction_id)
+        .bind(from_currency)
+        .bind(to_currency)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(DatabaseError::from_sqlx)?;
+
+        Ok(CreatedConversionAudit {
+            audit: existing,
+            created: false,
+        })
     }
 
     /// Update status and optional error message
@@ -135,6 +283,121 @@ impl ConversionAuditRepository {
         .await
         .map_err(DatabaseError::from_sqlx)
     }
+
+    /// One page of audits matching `filter`, ordered as `query` asks
+    pub async fn find_filtered(
+        &self,
+        filter: &ConversionAuditFilter,
+        query: &ListQuery,
+    ) -> Result<Vec<ConversionAudit>, DatabaseError> {
+        let sql = format!(
+            "SELECT id, user_id, wallet_address, transaction_id, from_currency, to_currency, from_amount, to_amount, rate, fee_amount, fee_currency, provider, status, error_message, metadata, created_at, updated_at 
+             FROM conversion_audits {} {}",
+            FILTER_PREDICATE,
+            query.order_clause(7)
+        );
+        sqlx::query_as::<_, ConversionAudit>(&sql)
+            .bind(filter.user_id)
+            .bind(&filter.wallet_address)
+            .bind(&filter.status)
+            .bind(&filter.from_currency)
+            .bind(&filter.to_currency)
+            .bind(&filter.provider)
+            .bind(query.limit)
+            .bind(query.offset)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(DatabaseError::from_sqlx)
+    }
+
+    /// Number of audits matching `filter`, ignoring paging
+    pub async fn count_filtered(
+        &self,
+        filter: &ConversionAuditFilter,
+    ) -> Result<i64, DatabaseError> {
+        let sql = format!("SELECT COUNT(*) FROM conversion_audits {}", FILTER_PREDICATE);
+        sqlx::query_scalar::<_, i64>(&sql)
+            .bind(filter.user_id)
+            .bind(&filter.wallet_address)
+            .bind(&filter.status)
+            .bind(&filter.from_currency)
+            .bind(&filter.to_currency)
+            .bind(&filter.provider)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(DatabaseError::from_sqlx)
+    }
+
+    /// Conversions whose `to_amount` deviates from `from_amount * rate` by more
+    /// than `tolerance_bps`, newest first. The comparison runs on NUMERIC in
+    /// Postgres so no precision is lost.
+    pub async fn find_discrepancies(
+        &self,
+        tolerance_bps: i64,
+        limit: i64,
+    ) -> Result<Vec<ConversionDiscrepancy>, DatabaseError> {
+        sqlx::query_as::<_, ConversionDiscrepancy>(
+            "SELECT id, user_id, wallet_address, transaction_id, from_currency, to_currency, from_amount, to_amount, rate, fee_amount, fee_currency, provider, status, error_message, metadata, created_at, updated_at,
+                    from_amount * rate AS expected_to_amount,
+                    ABS(to_amount - from_amount * rate) * 10000 / NULLIF(from_amount * rate, 0) AS deviation_bps
+             FROM conversion_audits
+             WHERE ABS(to_amount - from_amount * rate) * 10000 > $1 * (from_amount * rate)
+             ORDER BY created_at DESC LIMIT $2",
+        )
+        .bind(sqlx::types::BigDecimal::from(tolerance_bps))
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(DatabaseError::from_sqlx)
+    }
+
+    /// The `limit` most recent audits across all users created at least
+    /// `settled_for` ago, oldest first.
+    pub async fn find_recent(
+        &self,
+        limit: i64,
+        settled_for: std::time::Duration,
+    ) -> Result<Vec<ConversionAudit>, DatabaseError> {
+        sqlx::query_as::<_, ConversionAudit>(
+            "SELECT * FROM (
+                 SELECT id, user_id, wallet_address, transaction_id, from_currency, to_currency, from_amount, to_amount, rate, fee_amount, fee_currency, provider, status, error_message, metadata, created_at, updated_at 
+                 FROM conversion_audits 
+                 WHERE created_at <= NOW() - make_interval(secs => $2) 
+                 ORDER BY created_at DESC, id DESC LIMIT $1
+             ) recent
+             ORDER BY created_at ASC, id ASC",
+        )
+        .bind(limit)
+        .bind(settled_for.as_secs_f64())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(DatabaseError::from_sqlx)
+    }
+
+    /// Audits after the `(created_at, id)` cursor and created at least
+    /// `settled_for` ago, oldest first.
+    pub async fn find_created_after(
+        &self,
+        created_at: chrono::DateTime<chrono::Utc>,
+        id: Uuid,
+        settled_for: std::time::Duration,
+        limit: i64,
+    ) -> Result<Vec<ConversionAudit>, DatabaseError> {
+        sqlx::query_as::<_, ConversionAudit>(
+            "SELECT id, user_id, wallet_address, transaction_id, from_currency, to_currency, from_amount, to_amount, rate, fee_amount, fee_currency, provider, status, error_message, metadata, created_at, updated_at 
+             FROM conversion_audits 
+             WHERE (created_at, id) > ($1, $2) 
+               AND created_at <= NOW() - make_interval(secs => $3) 
+             ORDER BY created_at ASC, id ASC LIMIT $4",
+        )
+        .bind(created_at)
+        .bind(id)
+        .bind(settled_for.as_secs_f64())
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(DatabaseError::from_sqlx)
+    }
 }
 
 #[async_trait]
@@ -243,8 +506,71 @@ impl Repository for ConversionAuditRepository {
     }
 }
 
+#[async_trait]
+impl PaginatedRepository for ConversionAuditRepository {
+    const SORTABLE_COLUMNS: &'static [&'static str] = &[
+        "created_at",
+        "updated_at",
+        "from_amount",
+        "to_amount",
+        "status",
+        "provider",
+    ];
+    const DEFAULT_SORT: &'static str = "created_at";
+
+    async fn find_page(&self, query: &ListQuery) -> Result<Vec<Self::Entity>, DatabaseError> {
+        let sql = format!(
+            "SELECT id, user_id, wallet_address, transaction_id, from_currency, to_currency, from_amount, to_amount, rate, fee_amount, fee_currency, provider, status, error_message, metadata, created_at, updated_at 
+             FROM conversion_audits {}",
+            query.order_clause(1)
+        );
+        sqlx::query_as::<_, ConversionAudit>(&sql)
+            .bind(query.limit)
+            .bind(query.offset)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(DatabaseError::from_sqlx)
+    }
+}
+
 impl TransactionalRepository for ConversionAuditRepository {
     fn pool(&self) -> &PgPool {
         &self.pool
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn fit_numeric_rounds_half_up_to_column_scale() {
+        // A computed fee carrying one digit more than the column stores
+        let fee = sqlx::types::BigDecimal::from_str("0.3333333333333333335").unwrap();
+        let fitted = fit_numeric("fee_amount", fee).unwrap();
+        assert_eq!(fitted.to_string(), "0.333333333333333334");
+        assert_eq!(fitted.as_bigint_and_exponent().1, NUMERIC_SCALE);
+
+        let tie = sqlx::types::BigDecimal::from_str("-1.0000000000000000005").unwrap();
+        assert_eq!(
+            fit_numeric("rate", tie).unwrap().to_string(),
+            "-1.000000000000000001"
+        );
+    }
+
+    #[test]
+    fn fit_numeric_rejects_oversized_integer_part() {
+        let max = sqlx::types::BigDecimal::from_str("999999999999999999.999999999999999999").unwrap();
+        assert!(fit_numeric("to_amount", max).is_ok());
+
+        let too_big = sqlx::types::BigDecimal::from_str("1000000000000000000").unwrap();
+        let err = fit_numeric("to_amount", too_big).unwrap_err();
+        assert!(err.to_string().contains("to_amount"));
+
+        // Rounding up may itself overflow the integer part
+        let rounds_over =
+            sqlx::types::BigDecimal::from_str("999999999999999999.9999999999999999995").unwrap();
+        assert!(fit_numeric("to_amount", rounds_over).is_err());
+    }
+}
@@ -0,0 +1,99 @@
+use crate::database::error::{DatabaseError, DatabaseErrorKind};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+
+/// Attribution of a deposit MEMO_ID to the wallet it credits
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct DepositMemo {
+    pub memo_id: i64,
+    pub wallet_address: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Attempts `issue` makes to find an unused random memo id
+const ISSUE_ATTEMPTS: usize = 3;
+
+/// Repository for the memo id → internal account mapping used to attribute
+/// incoming deposits
+pub struct DepositMemoRepository {
+    pool: PgPool,
+}
+
+impl DepositMemoRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// The memo id that attributes deposits to `wallet_address`, issuing one
+    /// if the wallet has none yet.
+    ///
+    /// Ids are chosen here, never by the caller, so nobody can claim a memo id
+    /// ahead of the wallet it would otherwise point at. Callers must have
+    /// authenticated as `wallet_address`.
+    pub async fn issue(&self, wallet_address: &str) -> Result<DepositMemo, DatabaseError> {
+        if let Some(existing) = self.find_by_wallet(wallet_address).await? {
+            return Ok(existing);
+        }
+
+        // A collision among 2^63 random ids is vanishingly rare; retry anyway
+        for _ in 0..ISSUE_ATTEMPTS {
+            let inserted = sqlx::query_as::<_, DepositMemo>(
+                "INSERT INTO deposit_memo_ids (memo_id, wallet_address)
+                 VALUES ($1, $2)
+                 ON CONFLICT (memo_id) DO NOTHING
+                 RETURNING memo_id, wallet_address, created_at",
+            )
+            .bind(random_memo_id())
+            .bind(wallet_address)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(DatabaseError::from_sqlx)?;
+
+            if let Some(row) = inserted {
+                return Ok(row);
+            }
+        }
+
+        Err(DatabaseError::new(DatabaseErrorKind::Unknown {
+            message: format!("no free deposit memo id after {} attempts", ISSUE_ATTEMPTS),
+        }))
+    }
+
+    /// Wallet credited by deposits carrying `memo_id`, if any
+    pub async fn resolve(&self, memo_id: u64) -> Result<Option<DepositMemo>, DatabaseError> {
+        match i64::try_from(memo_id) {
+            Ok(id) => self.find(id).await,
+            // Never issued: issue() only hands out positive BIGINT ids
+            Err(_) => Ok(None),
+        }
+    }
+
+    async fn find_by_wallet(
+        &self,
+        wallet_address: &str,
+    ) -> Result<Option<DepositMemo>, DatabaseError> {
+        sqlx::query_as::<_, DepositMemo>(
+            "SELECT memo_id, wallet_address, created_at FROM deposit_memo_ids
+             WHERE wallet_address = $1 ORDER BY created_at ASC LIMIT 1",
+        )
+        .bind(wallet_address)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(DatabaseError::from_sqlx)
+    }
+
+    async fn find(&self, memo_id: i64) -> Result<Option<DepositMemo>, DatabaseError> {
+        sqlx::query_as::<_, DepositMemo>(
+            "SELECT memo_id, wallet_address, created_at FROM deposit_memo_ids WHERE memo_id = $1",
+        )
+        .bind(memo_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(DatabaseError::from_sqlx)
+    }
+}
+
+fn random_memo_id() -> i64 {
+    // Positive BIGINT: drop the top bit, and never 0
+    ((uuid::Uuid::new_v4().as_u64_pair().0 >> 1) as i64).max(1)
+}
@@ -1,8 +1,12 @@
 use crate::database::error::{DatabaseError, DatabaseErrorKind};
-use crate::database::repository::{Repository, TransactionalRepository};
+use crate::database::query_trace;
+use crate::database::repository::{
+    ListQuery, PaginatedRepository, Repository, TransactionalRepository,
+};
+use crate::chains::stellar::config::StellarNetwork;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, PgPool};
+use sqlx::{FromRow, PgConnection, PgPool};
 use uuid::Uuid;
 
 /// Fee structure entity
@@ -19,18 +23,61 @@ pub struct FeeStructure {
     pub effective_from: chrono::DateTime<chrono::Utc>,
     pub effective_until: Option<chrono::DateTime<chrono::Utc>>,
     pub metadata: serde_json::Value,
+    /// `testnet` / `mainnet`; `None` applies on every network
+    pub network: Option<String>,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
 
+/// Input for creating a fee structure; `id` and timestamps are assigned by the database
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewFeeStructure {
+    pub fee_type: String,
+    pub fee_rate_bps: i32,
+    pub fee_flat: sqlx::types::BigDecimal,
+    #[serde(default)]
+    pub min_fee: Option<sqlx::types::BigDecimal>,
+    #[serde(default)]
+    pub max_fee: Option<sqlx::types::BigDecimal>,
+    #[serde(default)]
+    pub currency: Option<String>,
+    #[serde(default = "default_is_active")]
+    pub is_active: bool,
+    pub effective_from: chrono::DateTime<chrono::Utc>,
+    #[serde(default)]
+    pub effective_until: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(default = "default_metadata")]
+    pub metadata: serde_json::Value,
+    #[serde(default)]
+    pub network: Option<String>,
+}
+
+fn default_is_active() -> bool {
+    true
+}
+
+fn default_metadata() -> serde_json::Value {
+    serde_json::json!({})
+}
+
 /// Repository for fee structure configuration
 pub struct FeeStructureRepository {
     pool: PgPool,
+    network: StellarNetwork,
 }
 
 impl FeeStructureRepository {
+    /// Scoped to the network named by `STELLAR_NETWORK`
     pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+        Self::for_network(pool, StellarNetwork::from_env())
+    }
+
+    pub fn for_network(pool: PgPool, network: StellarNetwork) -> Self {
+        Self { pool, network }
+    }
+
+    pub fn network(&self) -> &StellarNetwork {
+        &self.network
     }
 
     /// Create a fee structure
@@ -47,11 +94,11 @@ impl FeeStructureRepository {
         effective_until: Option<chrono::DateTime<chrono::Utc>>,
         metadata: serde_json::Value,
     ) -> Result<FeeStructure, DatabaseError> {
-        sqlx::query_as::<_, FeeStructure>(
+        let query = sqlx::query_as::<_, FeeStructure>(
             "INSERT INTO fee_structures 
              (fee_type, fee_rate_bps, fee_flat, min_fee, max_fee, currency, is_active, effective_from, effective_until, metadata) 
              VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10) 
-             RETURNING id, fee_type, fee_rate_bps, fee_flat, min_fee, max_fee, currency, is_active, effective_from, effective_until, metadata, created_at, updated_at",
+             RETURNING id, fee_type, fee_rate_bps, fee_flat, min_fee, max_fee, currency, is_active, effective_from, effective_until, metadata, network, created_at, updated_at",
         )
         .bind(fee_type)
         .bind(fee_rate_bps)
@@ -63,45 +110,161 @@ impl FeeStructureRepository {
         .bind(effective_from)
         .bind(effective_until)
         .bind(metadata)
-        .fetch_one(&self.pool)
-        .await
-        .map_err(DatabaseError::from_sqlx)
+        .fetch_one(&self.pool);
+        query_trace::traced("fee_structures.create", query)
+            .await
+            .map_err(DatabaseError::from_sqlx)
+    }
+
+    /// Insert all structures in one transaction; if any insert fails none are kept
+    pub async fn create_batch(
+        &self,
+        structures: &[NewFeeStructure],
+    ) -> Result<Vec<FeeStructure>, DatabaseError> {
+        let mut tx = self.pool.begin().await.map_err(DatabaseError::from_sqlx)?;
+        let created = Self::create_batch_in(&mut tx, structures).await?;
+        tx.commit().await.map_err(DatabaseError::from_sqlx)?;
+        Ok(created)
+    }
+
+    /// [`Self::create_batch`] on `conn`, for callers that record the change in
+    /// the same transaction. `conn` should be a transaction for the batch to
+    /// be all-or-nothing.
+    pub async fn create_batch_in(
+        conn: &mut PgConnection,
+        structures: &[NewFeeStructure],
+    ) -> Result<Vec<FeeStructure>, DatabaseError> {
+        let mut created = Vec::with_capacity(structures.len());
+
+        for structure in structures {
+            let query = sqlx::query_as::<_, FeeStructure>(
+                "INSERT INTO fee_structures 
+                 (fee_type, fee_rate_bps, fee_flat, min_fee, max_fee, currency, is_active, effective_from, effective_until, metadata, network) 
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11) 
+                 RETURNING id, fee_type, fee_rate_bps, fee_flat, min_fee, max_fee, currency, is_active, effective_from, effective_until, metadata, network, created_at, updated_at",
+            )
+            .bind(&structure.fee_type)
+            .bind(structure.fee_rate_bps)
+            .bind(structure.fee_flat.clone())
+            .bind(structure.min_fee.clone())
+            .bind(structure.max_fee.clone())
+            .bind(&structure.currency)
+            .bind(structure.is_active)
+            .bind(structure.effective_from)
+            .bind(structure.effective_until)
+            .bind(&structure.metadata)
+            .bind(&structure.network)
+            .fetch_one(&mut *conn);
+            let row = query_trace::traced("fee_structures.create_batch", query)
+                .await
+                .map_err(DatabaseError::from_sqlx)?;
+            created.push(row);
+        }
+
+        Ok(created)
     }
 
-    /// Get active fee structures for a fee type at a specific time (default now)
+    /// Get active fee structures for a fee type at a specific time (default now).
+    /// Only rows for the repository's network, or with no network, are returned.
     pub async fn get_active_by_type(
         &self,
         fee_type: &str,
         at_time: Option<chrono::DateTime<chrono::Utc>>,
     ) -> Result<Vec<FeeStructure>, DatabaseError> {
         let at_time = at_time.unwrap_or_else(chrono::Utc::now);
-        sqlx::query_as::<_, FeeStructure>(
-            "SELECT id, fee_type, fee_rate_bps, fee_flat, min_fee, max_fee, currency, is_active, effective_from, effective_until, metadata, created_at, updated_at 
+        let query = sqlx::query_as::<_, FeeStructure>(
+            "SELECT id, fee_type, fee_rate_bps, fee_flat, min_fee, max_fee, currency, is_active, effective_from, effective_until, metadata, network, created_at, updated_at 
              FROM fee_structures 
              WHERE fee_type = $1 AND is_active = TRUE 
                AND effective_from <= $2 
                AND (effective_until IS NULL OR effective_until >= $2)
+               AND (network IS NULL OR network = $3)
              ORDER BY effective_from DESC",
         )
         .bind(fee_type)
         .bind(at_time)
-        .fetch_all(&self.pool)
-        .await
-        .map_err(DatabaseError::from_sqlx)
+        .bind(self.network.as_str())
+        .fetch_all(&self.pool);
+        query_trace::traced("fee_structures.get_active_by_type", query)
+            .await
+            .map_err(DatabaseError::from_sqlx)
+    }
+
+    /// Every active structure for a fee type on the repository's network,
+    /// oldest first, whatever its window. These are the rows
+    /// [`Self::get_active_by_type`] chooses from at any given time.
+    pub async fn find_by_type(&self, fee_type: &str) -> Result<Vec<FeeStructure>, DatabaseError> {
+        let query = sqlx::query_as::<_, FeeStructure>(
+            "SELECT id, fee_type, fee_rate_bps, fee_flat, min_fee, max_fee, currency, is_active, effective_from, effective_until, metadata, network, created_at, updated_at 
+             FROM fee_structures 
+             WHERE fee_type = $1 AND is_active = TRUE 
+               AND (network IS NULL OR network = $2)
+             ORDER BY effective_from ASC, created_at ASC",
+        )
+        .bind(fee_type)
+        .bind(self.network.as_str())
+        .fetch_all(&self.pool);
+        query_trace::traced("fee_structures.find_by_type", query)
+            .await
+            .map_err(DatabaseError::from_sqlx)
+    }
+
+    /// Ids of structures for a fee type holding a `NaN` amount, which can't be
+    /// read into a `BigDecimal` and so fails every query that selects the row.
+    pub async fn find_unreadable_ids(&self, fee_type: &str) -> Result<Vec<Uuid>, DatabaseError> {
+        let query = sqlx::query_scalar::<_, Uuid>(
+            "SELECT id FROM fee_structures 
+             WHERE fee_type = $1 
+               AND (fee_flat = 'NaN' OR min_fee = 'NaN' OR max_fee = 'NaN') 
+             ORDER BY effective_from DESC",
+        )
+        .bind(fee_type)
+        .fetch_all(&self.pool);
+        query_trace::traced("fee_structures.find_unreadable_ids", query)
+            .await
+            .map_err(DatabaseError::from_sqlx)
     }
 
     /// Deactivate a fee structure
     pub async fn deactivate(&self, id: Uuid) -> Result<FeeStructure, DatabaseError> {
-        sqlx::query_as::<_, FeeStructure>(
+        let mut conn = self.pool.acquire().await.map_err(DatabaseError::from_sqlx)?;
+        Self::deactivate_in(&mut conn, id).await
+    }
+
+    /// [`Self::deactivate`] on `conn`, for callers that record the change in
+    /// the same transaction
+    pub async fn deactivate_in(
+        conn: &mut PgConnection,
+        id: Uuid,
+    ) -> Result<FeeStructure, DatabaseError> {
+        let query = sqlx::query_as::<_, FeeStructure>(
             "UPDATE fee_structures 
              SET is_active = FALSE, updated_at = NOW() 
              WHERE id = $1 
-             RETURNING id, fee_type, fee_rate_bps, fee_flat, min_fee, max_fee, currency, is_active, effective_from, effective_until, metadata, created_at, updated_at",
+             RETURNING id, fee_type, fee_rate_bps, fee_flat, min_fee, max_fee, currency, is_active, effective_from, effective_until, metadata, network, created_at, updated_at",
         )
         .bind(id)
-        .fetch_one(&self.pool)
-        .await
-        .map_err(DatabaseError::from_sqlx)
+        .fetch_one(conn);
+        query_trace::traced("fee_structures.deactivate", query)
+            .await
+            .map_err(DatabaseError::from_sqlx)
+    }
+
+    /// Read a structure and lock it until `conn`'s transaction ends, so its
+    /// state can't change between being read and being updated
+    pub async fn find_for_update(
+        conn: &mut PgConnection,
+        id: Uuid,
+    ) -> Result<Option<FeeStructure>, DatabaseError> {
+        let query = sqlx::query_as::<_, FeeStructure>(
+            "SELECT id, fee_type, fee_rate_bps, fee_flat, min_fee, max_fee, currency, is_active, effective_from, effective_until, metadata, network, created_at, updated_at 
+             FROM fee_structures WHERE id = $1 FOR UPDATE",
+        )
+        .bind(id)
+        .fetch_optional(conn);
+        query_trace::traced("fee_structures.find_for_update", query)
+            .await
+            .map_err(DatabaseError::from_sqlx)
     }
 }
 
@@ -115,32 +278,34 @@ impl Repository for FeeStructureRepository {
                 message: format!("Invalid UUID: {}", e),
             })
         })?;
-        sqlx::query_as::<_, FeeStructure>(
-            "SELECT id, fee_type, fee_rate_bps, fee_flat, min_fee, max_fee, currency, is_active, effective_from, effective_until, metadata, created_at, updated_at 
+        let query = sqlx::query_as::<_, FeeStructure>(
+            "SELECT id, fee_type, fee_rate_bps, fee_flat, min_fee, max_fee, currency, is_active, effective_from, effective_until, metadata, network, created_at, updated_at 
              FROM fee_structures WHERE id = $1",
         )
         .bind(uuid)
-        .fetch_optional(&self.pool)
-        .await
-        .map_err(DatabaseError::from_sqlx)
+        .fetch_optional(&self.pool);
+        query_trace::traced("fee_structures.find_by_id", query)
+            .await
+            .map_err(DatabaseError::from_sqlx)
     }
 
     async fn find_all(&self) -> Result<Vec<Self::Entity>, DatabaseError> {
-        sqlx::query_as::<_, FeeStructure>(
-            "SELECT id, fee_type, fee_rate_bps, fee_flat, min_fee, max_fee, currency, is_active, effective_from, effective_until, metadata, created_at, updated_at 
+        let query = sqlx::query_as::<_, FeeStructure>(
+            "SELECT id, fee_type, fee_rate_bps, fee_flat, min_fee, max_fee, currency, is_active, effective_from, effective_until, metadata, network, created_at, updated_at 
              FROM fee_structures ORDER BY created_at DESC",
         )
-        .fetch_all(&self.pool)
-        .await
-        .map_err(DatabaseError::from_sqlx)
+        .fetch_all(&self.pool);
+        query_trace::traced("fee_structures.find_all", query)
+            .await
+            .map_err(DatabaseError::from_sqlx)
     }
 
     async fn insert(&self, entity: &Self::Entity) -> Result<Self::Entity, DatabaseError> {
         sqlx::query_as::<_, FeeStructure>(
             "INSERT INTO fee_structures 
-             (id, fee_type, fee_rate_bps, fee_flat, min_fee, max_fee, currency, is_active, effective_from, effective_until, metadata, created_at, updated_at) 
-             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13) 
-             RETURNING id, fee_type, fee_rate_bps, fee_flat, min_fee, max_fee, currency, is_active, effective_from, effective_until, metadata, created_at, updated_at",
+             (id, fee_type, fee_rate_bps, fee_flat, min_fee, max_fee, currency, is_active, effective_from, effective_until, metadata, network, created_at, updated_at) 
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14) 
+             RETURNING id, fee_type, fee_rate_bps, fee_flat, min_fee, max_fee, currency, is_active, effective_from, effective_until, metadata, network, created_at, updated_at",
         )
         .bind(entity.id)
         .bind(&entity.fee_type)
@@ -153,6 +318,7 @@ impl Repository for FeeStructureRepository {
         .bind(entity.effective_from)
         .bind(entity.effective_until)
         .bind(&entity.metadata)
+        .bind(&entity.network)
         .bind(entity.created_at)
         .bind(entity.updated_at)
         .fetch_one(&self.pool)
@@ -168,9 +334,9 @@ impl Repository for FeeStructureRepository {
         })?;
         sqlx::query_as::<_, FeeStructure>(
             "UPDATE fee_structures 
-             SET fee_type = $1, fee_rate_bps = $2, fee_flat = $3, min_fee = $4, max_fee = $5, currency = $6, is_active = $7, effective_from = $8, effective_until = $9, metadata = $10, updated_at = NOW()
-             WHERE id = $11 
-             RETURNING id, fee_type, fee_rate_bps, fee_flat, min_fee, max_fee, currency, is_active, effective_from, effective_until, metadata, created_at, updated_at",
+             SET fee_type = $1, fee_rate_bps = $2, fee_flat = $3, min_fee = $4, max_fee = $5, currency = $6, is_active = $7, effective_from = $8, effective_until = $9, metadata = $10, network = $11, updated_at = NOW()
+             WHERE id = $12 
+             RETURNING id, fee_type, fee_rate_bps, fee_flat, min_fee, max_fee, currency, is_active, effective_from, effective_until, metadata, network, created_at, updated_at",
         )
         .bind(&entity.fee_type)
         .bind(entity.fee_rate_bps)
@@ -182,6 +348,7 @@ impl Repository for FeeStructureRepository {
         .bind(entity.effective_from)
         .bind(entity.effective_until)
         .bind(&entity.metadata)
+        .bind(&entity.network)
         .bind(uuid)
         .fetch_one(&self.pool)
         .await
@@ -203,8 +370,36 @@ impl Repository for FeeStructureRepository {
     }
 }
 
+#[async_trait]
+impl PaginatedRepository for FeeStructureRepository {
+    const SORTABLE_COLUMNS: &'static [&'static str] = &[
+        "created_at",
+        "effective_from",
+        "fee_type",
+        "fee_rate_bps",
+        "updated_at",
+    ];
+    const DEFAULT_SORT: &'static str = "created_at";
+
+    async fn find_page(&self, query: &ListQuery) -> Result<Vec<Self::Entity>, DatabaseError> {
+        let sql = format!(
+            "SELECT id, fee_type, fee_rate_bps, fee_flat, min_fee, max_fee, currency, is_active, effective_from, effective_until, metadata, network, created_at, updated_at 
+             FROM fee_structures {}",
+            query.order_clause(1)
+        );
+        let page = sqlx::query_as::<_, FeeStructure>(&sql)
+            .bind(query.limit)
+            .bind(query.offset)
+            .fetch_all(&self.pool);
+        query_trace::traced("fee_structures.find_page", page)
+            .await
+            .map_err(DatabaseError::from_sqlx)
+    }
+}
+
 impl TransactionalRepository for FeeStructureRepository {
     fn pool(&self) -> &PgPool {
         &self.pool
     }
 }
+
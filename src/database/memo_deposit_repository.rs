@@ -0,0 +1,152 @@
+use crate::database::error::DatabaseError;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgConnection, PgPool};
+use uuid::Uuid;
+
+/// An inbound payment to the deposit address and what the reconciler made of it
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct MemoDeposit {
+    pub payment_id: String,
+    pub transaction_hash: String,
+    pub deposit_address: String,
+    pub source_address: Option<String>,
+    pub asset_code: String,
+    pub asset_issuer: Option<String>,
+    pub amount: sqlx::types::BigDecimal,
+    pub memo_type: Option<String>,
+    pub memo: Option<String>,
+    pub wallet_address: Option<String>,
+    pub conversion_audit_id: Option<Uuid>,
+    /// `credited` or `quarantined`
+    pub status: String,
+    pub quarantine_reason: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Input for [`MemoDepositRepository::claim_in`]
+#[derive(Debug, Clone)]
+pub struct NewMemoDeposit {
+    pub payment_id: String,
+    pub transaction_hash: String,
+    pub deposit_address: String,
+    pub source_address: Option<String>,
+    pub asset_code: String,
+    pub asset_issuer: Option<String>,
+    pub amount: sqlx::types::BigDecimal,
+    pub memo_type: Option<String>,
+    pub memo: Option<String>,
+    pub wallet_address: Option<String>,
+    pub status: String,
+    pub quarantine_reason: Option<String>,
+}
+
+/// Repository for reconciled deposit-address payments
+pub struct MemoDepositRepository {
+    pool: PgPool,
+}
+
+impl MemoDepositRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Record a payment on `conn`. Returns `None` when it was already
+    /// recorded, which is how a payment seen twice is kept from being credited
+    /// twice. Run it in the transaction that credits the payment, so a failed
+    /// credit leaves no claim behind.
+    pub async fn claim_in(
+        conn: &mut PgConnection,
+        deposit: &NewMemoDeposit,
+    ) -> Result<Option<MemoDeposit>, DatabaseError> {
+        sqlx::query_as::<_, MemoDeposit>(
+            "INSERT INTO memo_deposits
+             (payment_id, transaction_hash, deposit_address, source_address, asset_code, asset_issuer, amount, memo_type, memo, wallet_address, status, quarantine_reason)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+             ON CONFLICT (payment_id) DO NOTHING
+             RETURNING payment_id, transaction_hash, deposit_address, source_address, asset_code, asset_issuer, amount, memo_type, memo, wallet_address, conversion_audit_id, status, quarantine_reason, created_at",
+        )
+        .bind(&deposit.payment_id)
+        .bind(&deposit.transaction_hash)
+        .bind(&deposit.deposit_address)
+        .bind(&deposit.source_address)
+        .bind(&deposit.asset_code)
+        .bind(&deposit.asset_issuer)
+        .bind(&deposit.amount)
+        .bind(&deposit.memo_type)
+        .bind(&deposit.memo)
+        .bind(&deposit.wallet_address)
+        .bind(&deposit.status)
+        .bind(&deposit.quarantine_reason)
+        .fetch_optional(conn)
+        .await
+        .map_err(DatabaseError::from_sqlx)
+    }
+
+    pub async fn link_conversion_audit_in(
+        conn: &mut PgConnection,
+        payment_id: &str,
+        conversion_audit_id: Uuid,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query("UPDATE memo_deposits SET conversion_audit_id = $2 WHERE payment_id = $1")
+            .bind(payment_id)
+            .bind(conversion_audit_id)
+            .execute(conn)
+            .await
+            .map_err(DatabaseError::from_sqlx)?;
+        Ok(())
+    }
+
+    pub async fn find(&self, payment_id: &str) -> Result<Option<MemoDeposit>, DatabaseError> {
+        sqlx::query_as::<_, MemoDeposit>(
+            "SELECT payment_id, transaction_hash, deposit_address, source_address, asset_code, asset_issuer, amount, memo_type, memo, wallet_address, conversion_audit_id, status, quarantine_reason, created_at
+             FROM memo_deposits WHERE payment_id = $1",
+        )
+        .bind(payment_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(DatabaseError::from_sqlx)
+    }
+
+    /// Quarantined payments awaiting review, oldest first
+    pub async fn list_quarantined(&self, limit: i64) -> Result<Vec<MemoDeposit>, DatabaseError> {
+        sqlx::query_as::<_, MemoDeposit>(
+            "SELECT payment_id, transaction_hash, deposit_address, source_address, asset_code, asset_issuer, amount, memo_type, memo, wallet_address, conversion_audit_id, status, quarantine_reason, created_at
+             FROM memo_deposits WHERE status = 'quarantined'
+             ORDER BY created_at ASC LIMIT $1",
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(DatabaseError::from_sqlx)
+    }
+
+    /// Paging token to resume `deposit_address`'s payment stream from
+    pub async fn cursor(&self, deposit_address: &str) -> Result<Option<String>, DatabaseError> {
+        sqlx::query_scalar::<_, String>(
+            "SELECT paging_token FROM memo_deposit_cursors WHERE deposit_address = $1",
+        )
+        .bind(deposit_address)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(DatabaseError::from_sqlx)
+    }
+
+    pub async fn save_cursor(
+        &self,
+        deposit_address: &str,
+        paging_token: &str,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query(
+            "INSERT INTO memo_deposit_cursors (deposit_address, paging_token)
+             VALUES ($1, $2)
+             ON CONFLICT (deposit_address)
+             DO UPDATE SET paging_token = EXCLUDED.paging_token, updated_at = now()",
+        )
+        .bind(deposit_address)
+        .bind(paging_token)
+        .execute(&self.pool)
+        .await
+        .map_err(DatabaseError::from_sqlx)?;
+        Ok(())
+    }
+}
@@ -1,11 +1,14 @@
 // This module requires std library (not available in WASM)
 
+pub mod admin_audit_repository;
 pub mod bill_payment_repository;
 pub mod conversion_audit_repository;
+pub mod deposit_memo_repository;
 pub mod error;
 pub mod exchange_rate_repository;
 pub mod fee_structure_repository;
 pub mod kyc_repository;
+pub mod memo_deposit_repository;
 pub mod geo_restriction_repository;
 pub mod ip_reputation_repository;
 pub mod oauth_scope_repository;
@@ -13,6 +16,7 @@ pub mod onramp_quote_repository;
 pub mod payment_method_repository;
 pub mod payment_repository;
 pub mod provider_config_repository;
+pub mod query_trace;
 pub mod recurring_payment_repository;
 pub mod refresh_token_repository;
 pub mod repository;
@@ -0,0 +1,166 @@
+//! Opt-in per-query tracing
+//!
+//! With `DB_QUERY_TRACE` set, queries wrapped in [`traced`] run inside a
+//! `db.query` span that records a static label and the elapsed time. Only
+//! `FeeStructureRepository` is wrapped so far; other repositories need to opt
+//! in query by query. The span nests under the caller's span, so a query is
+//! attributed to the request id of the HTTP request that issued it. SQL text
+//! and bind values are never recorded.
+
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use std::time::Instant;
+use tracing::{debug, field, info_span, Instrument};
+
+fn flag() -> &'static AtomicBool {
+    static ENABLED: OnceLock<AtomicBool> = OnceLock::new();
+    ENABLED.get_or_init(|| {
+        let enabled = std::env::var("DB_QUERY_TRACE")
+            .map(|v| matches!(v.to_ascii_lowercase().as_str(), "1" | "true" | "on"))
+            .unwrap_or(false);
+        AtomicBool::new(enabled)
+    })
+}
+
+pub fn enabled() -> bool {
+    flag().load(Ordering::Relaxed)
+}
+
+/// Override `DB_QUERY_TRACE` at runtime.
+pub fn set_enabled(enabled: bool) {
+    flag().store(enabled, Ordering::Relaxed);
+}
+
+/// Run `query` inside a `db.query` span labelled `label` when tracing is on.
+pub async fn traced<F, T, E>(label: &'static str, query: F) -> Result<T, E>
+where
+    F: Future<Output = Result<T, E>>,
+{
+    if !enabled() {
+        return query.await;
+    }
+
+    let span = info_span!(
+        "db.query",
+        db.label = label,
+        db.duration_ms = field::Empty,
+        db.success = field::Empty,
+    );
+    let start = Instant::now();
+    let result = query.instrument(span.clone()).await;
+    let duration_ms = start.elapsed().as_millis() as u64;
+
+    span.record("db.duration_ms", duration_ms);
+    span.record("db.success", result.is_ok());
+    span.in_scope(|| debug!(label, duration_ms, success = result.is_ok(), "db query finished"));
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::fee_structure_repository::FeeStructureRepository;
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::Subscriber;
+    use tracing_subscriber::layer::{Context, SubscriberExt};
+    use tracing_subscriber::registry::LookupSpan;
+    use tracing_subscriber::Layer;
+
+    type Fields = HashMap<String, String>;
+
+    struct FieldVisitor<'a>(&'a mut Fields);
+
+    impl Visit for FieldVisitor<'_> {
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            self.0.insert(field.name().to_string(), format!("{:?}", value));
+        }
+
+        fn record_str(&mut self, field: &Field, value: &str) {
+            self.0.insert(field.name().to_string(), value.to_string());
+        }
+    }
+
+    /// A closed span: its name, its fields and its parent's fields
+    type Captured = (String, Fields, Option<Fields>);
+
+    /// Collects the fields of every span when it closes
+    #[derive(Clone, Default)]
+    struct CaptureLayer(Arc<Mutex<Vec<Captured>>>);
+
+    impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for CaptureLayer {
+        fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+            let mut fields = Fields::new();
+            attrs.record(&mut FieldVisitor(&mut fields));
+            ctx.span(id).unwrap().extensions_mut().insert(fields);
+        }
+
+        fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+            if let Some(span) = ctx.span(id) {
+                if let Some(fields) = span.extensions_mut().get_mut::<Fields>() {
+                    values.record(&mut FieldVisitor(fields));
+                }
+            }
+        }
+
+        fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+            if let Some(span) = ctx.span(&id) {
+                if let Some(fields) = span.extensions().get::<Fields>() {
+                    let parent = span
+                        .parent()
+                        .and_then(|p| p.extensions().get::<Fields>().cloned());
+                    self.0
+                        .lock()
+                        .unwrap()
+                        .push((span.name().to_string(), fields.clone(), parent));
+                }
+            }
+        }
+    }
+
+    /// Restores the process-wide flag when the test ends, pass or fail
+    struct RestoreFlag(bool);
+
+    impl Drop for RestoreFlag {
+        fn drop(&mut self) {
+            set_enabled(self.0);
+        }
+    }
+
+    #[tokio::test]
+    async fn repository_call_emits_labelled_span_with_duration() {
+        let capture = CaptureLayer::default();
+        let _guard =
+            tracing::subscriber::set_default(tracing_subscriber::registry().with(capture.clone()));
+        let _restore = RestoreFlag(enabled());
+        set_enabled(true);
+
+        // Nothing listens on port 1; the query fails fast but is still traced
+        let pool = sqlx::postgres::PgPoolOptions::new()
+            .acquire_timeout(Duration::from_millis(500))
+            .connect_lazy("postgres://aframp@127.0.0.1:1/unused")
+            .unwrap();
+        let repo = FeeStructureRepository::new(pool);
+        let request_span = info_span!("http.request", request_id = "req-123");
+        let _ = repo
+            .get_active_by_type("onramp", None)
+            .instrument(request_span)
+            .await;
+
+        let spans = capture.0.lock().unwrap();
+        let (_, fields, parent) = spans
+            .iter()
+            .find(|(name, _, _)| name == "db.query")
+            .expect("db.query span recorded");
+        let parent = parent.as_ref().expect("db.query span has a parent");
+        assert_eq!(parent["request_id"], "req-123");
+        assert_eq!(fields["db.label"], "fee_structures.get_active_by_type");
+        assert!(fields["db.duration_ms"].parse::<u64>().is_ok());
+        assert_eq!(fields["db.success"], "false");
+        assert!(fields.values().all(|v| !v.contains("onramp")), "bind values leaked");
+    }
+}
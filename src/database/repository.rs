@@ -1,5 +1,7 @@
 use crate::database::error::DatabaseError;
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::fmt;
 
 /// Base repository trait defining common database operations
 /// All domain-specific repositories should implement this trait
@@ -44,3 +46,306 @@ pub trait TransactionalRepository: Repository {
     /// Get a reference to the connection pool
     fn pool(&self) -> &sqlx::PgPool;
 }
+
+/// Direction applied to the sort column of a list query
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    Asc,
+    #[default]
+    Desc,
+}
+
+impl SortOrder {
+    pub fn as_sql(&self) -> &'static str {
+        match self {
+            SortOrder::Asc => "ASC",
+            SortOrder::Desc => "DESC",
+        }
+    }
+}
+
+/// A column name that has been checked against a repository's allowlist.
+///
+/// Only ever holds one of the `&'static str` entries from the allowlist, so it
+/// is safe to interpolate into `ORDER BY` — user input never reaches the SQL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SortField(&'static str);
+
+impl SortField {
+    pub fn parse(raw: &str, allowed: &'static [&'static str]) -> Result<Self, InvalidSortField> {
+        allowed
+            .iter()
+            .find(|column| column.eq_ignore_ascii_case(raw.trim()))
+            .map(|column| SortField(column))
+            .ok_or_else(|| InvalidSortField {
+                field: raw.to_string(),
+                allowed,
+            })
+    }
+
+    pub fn column(&self) -> &'static str {
+        self.0
+    }
+}
+
+/// Returned when a caller asks to sort by a column outside the allowlist
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidSortField {
+    pub field: String,
+    pub allowed: &'static [&'static str],
+}
+
+impl fmt::Display for InvalidSortField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "cannot sort by '{}'; allowed fields: {}",
+            self.field,
+            self.allowed.join(", ")
+        )
+    }
+}
+
+impl std::error::Error for InvalidSortField {}
+
+/// Bounded, ordered page request for list endpoints
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ListQuery {
+    pub limit: i64,
+    pub offset: i64,
+    pub sort: SortField,
+    pub order: SortOrder,
+}
+
+impl ListQuery {
+    pub const DEFAULT_LIMIT: i64 = 50;
+    pub const MAX_LIMIT: i64 = 200;
+
+    /// Build a query for repository `R`, falling back to its default sort column
+    /// and clamping `limit`/`offset` into range.
+    pub fn for_repository<R: PaginatedRepository + ?Sized>(
+        limit: Option<i64>,
+        offset: Option<i64>,
+        sort: Option<&str>,
+        order: Option<SortOrder>,
+    ) -> Result<Self, InvalidSortField> {
+        let sort = match sort {
+            Some(raw) if !raw.trim().is_empty() => SortField::parse(raw, R::SORTABLE_COLUMNS)?,
+            _ => SortField::parse(R::DEFAULT_SORT, R::SORTABLE_COLUMNS)?,
+        };
+
+        Ok(Self {
+            limit: limit
+                .unwrap_or(Self::DEFAULT_LIMIT)
+                .clamp(1, Self::MAX_LIMIT),
+            offset: offset.unwrap_or(0).max(0),
+            sort,
+            order: order.unwrap_or_default(),
+        })
+    }
+
+    /// `ORDER BY ... LIMIT $n OFFSET $m` fragment; limit and offset are bound
+    /// as parameters `$limit_param` and `$limit_param + 1`.
+    pub fn order_clause(&self, limit_param: usize) -> String {
+        format!(
+            "ORDER BY {} {}, id {} LIMIT ${} OFFSET ${}",
+            self.sort.column(),
+            self.order.as_sql(),
+            self.order.as_sql(),
+            limit_param,
+            limit_param + 1
+        )
+    }
+}
+
+/// One page of a listing plus the totals a UI needs to render paging controls
+#[derive(Debug, Clone, Serialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Rows matching the listing's filter across all pages
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+    pub has_more: bool,
+}
+
+impl<T> Page<T> {
+    pub fn new(items: Vec<T>, total: i64, query: &ListQuery) -> Self {
+        Self {
+            has_more: query.offset + (items.len() as i64) < total,
+            items,
+            total,
+            limit: query.limit,
+            offset: query.offset,
+        }
+    }
+}
+
+/// Repositories whose listings can be paged and sorted by a fixed set of columns
+#[async_trait]
+pub trait PaginatedRepository: Repository {
+    /// Columns callers may sort by
+    const SORTABLE_COLUMNS: &'static [&'static str];
+
+    /// Column used when the caller does not choose one; must be in `SORTABLE_COLUMNS`
+    const DEFAULT_SORT: &'static str;
+
+    /// Fetch one page of entities ordered as requested
+    async fn find_page(&self, query: &ListQuery) -> Result<Vec<Self::Entity>, DatabaseError>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COLUMNS: &[&str] = &["created_at", "amount"];
+
+    #[test]
+    fn sort_field_accepts_allowlisted_column() {
+        let field = SortField::parse("AMOUNT", COLUMNS).unwrap();
+        assert_eq!(field.column(), "amount");
+    }
+
+    #[test]
+    fn sort_field_rejects_unknown_column() {
+        let err = SortField::parse("amount; DROP TABLE users", COLUMNS).unwrap_err();
+        assert_eq!(err.field, "amount; DROP TABLE users");
+        assert!(err.to_string().contains("created_at, amount"));
+    }
+
+    #[test]
+    fn order_clause_uses_allowlisted_column_and_direction() {
+        let query = ListQuery {
+            limit: 10,
+            offset: 20,
+            sort: SortField::parse("amount", COLUMNS).unwrap(),
+            order: SortOrder::Asc,
+        };
+        assert_eq!(
+            query.order_clause(1),
+            "ORDER BY amount ASC, id ASC LIMIT $1 OFFSET $2"
+        );
+    }
+
+    #[test]
+    fn page_reports_more_until_total_is_reached() {
+        let query = ListQuery {
+            limit: 2,
+            offset: 2,
+            sort: SortField::parse("amount", COLUMNS).unwrap(),
+            order: SortOrder::Desc,
+        };
+        assert!(Page::new(vec![1, 2], 5, &query).has_more);
+        assert!(!Page::new(vec![1, 2], 4, &query).has_more);
+
+        let last = ListQuery { offset: 4, ..query };
+        let page = Page::new(vec![5], 5, &last);
+        assert!(!page.has_more);
+        assert_eq!((page.total, page.limit, page.offset), (5, 2, 4));
+    }
+
+    struct ListQueryCase {
+        build: fn(
+            Option<i64>,
+            Option<i64>,
+            Option<&str>,
+            Option<SortOrder>,
+        ) -> Result<ListQuery, InvalidSortField>,
+        limit: Option<i64>,
+        offset: Option<i64>,
+        sort: Option<&'static str>,
+        order: Option<SortOrder>,
+        /// `(column, limit, offset, order)`, or the rejected sort field
+        expected: Result<(&'static str, i64, i64, SortOrder), &'static str>,
+    }
+
+    #[test]
+    fn list_query_for_repositories() {
+        use crate::database::conversion_audit_repository::ConversionAuditRepository;
+        use crate::database::fee_structure_repository::FeeStructureRepository;
+
+        let fees = ListQuery::for_repository::<FeeStructureRepository>;
+        let audits = ListQuery::for_repository::<ConversionAuditRepository>;
+        let cases = [
+            ListQueryCase {
+                build: fees,
+                limit: Some(25),
+                offset: None,
+                sort: Some("effective_from"),
+                order: Some(SortOrder::Asc),
+                expected: Ok(("effective_from", 25, 0, SortOrder::Asc)),
+            },
+            ListQueryCase {
+                build: audits,
+                limit: Some(25),
+                offset: None,
+                sort: Some("updated_at"),
+                order: Some(SortOrder::Asc),
+                expected: Ok(("updated_at", 25, 0, SortOrder::Asc)),
+            },
+            // Defaults to the repository's sort column and clamps paging
+            ListQueryCase {
+                build: fees,
+                limit: Some(10_000),
+                offset: Some(-5),
+                sort: None,
+                order: None,
+                expected: Ok(("created_at", ListQuery::MAX_LIMIT, 0, SortOrder::Desc)),
+            },
+            ListQueryCase {
+                build: audits,
+                limit: Some(10_000),
+                offset: Some(-5),
+                sort: None,
+                order: None,
+                expected: Ok(("created_at", ListQuery::MAX_LIMIT, 0, SortOrder::Desc)),
+            },
+            ListQueryCase {
+                build: fees,
+                limit: None,
+                offset: None,
+                sort: Some("metadata"),
+                order: None,
+                expected: Err("metadata"),
+            },
+            ListQueryCase {
+                build: audits,
+                limit: None,
+                offset: None,
+                sort: Some("metadata"),
+                order: None,
+                expected: Err("metadata"),
+            },
+        ];
+
+        for case in cases {
+            let got = (case.build)(case.limit, case.offset, case.sort, case.order);
+            match (got, case.expected) {
+                (Ok(query), Ok((column, limit, offset, order))) => {
+                    assert_eq!(query.sort.column(), column);
+                    assert_eq!(
+                        (query.limit, query.offset, query.order),
+                        (limit, offset, order)
+                    );
+                    let clause = format!("ORDER BY {} {}", column, order.as_sql());
+                    assert!(query.order_clause(1).starts_with(&clause));
+                }
+                (Err(err), Err(field)) => assert_eq!(err.field, field),
+                (got, expected) => {
+                    panic!(
+                        "sort {:?}: got {:?}, expected {:?}",
+                        case.sort, got, expected
+                    )
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn sort_order_deserializes_lowercase() {
+        let order: SortOrder = serde_json::from_str("\"asc\"").unwrap();
+        assert_eq!(order, SortOrder::Asc);
+        assert_eq!(SortOrder::default(), SortOrder::Desc);
+    }
+}
@@ -103,6 +103,32 @@ impl TrustlineOperationRepository {
         .await
         .map_err(DatabaseError::from_sqlx)
     }
+
+    /// Find latest operations for a wallet on one asset. Asset codes match
+    /// exactly, as on Stellar; `issuer: None` matches any issuer of the code.
+    pub async fn find_by_wallet_and_asset(
+        &self,
+        wallet_address: &str,
+        asset_code: &str,
+        issuer: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<TrustlineOperation>, DatabaseError> {
+        sqlx::query_as::<_, TrustlineOperation>(
+            "SELECT id, wallet_address, asset_code, issuer, operation_type, status, transaction_hash, error_message, metadata, created_at, updated_at 
+             FROM trustline_operations 
+             WHERE wallet_address = $1 
+               AND asset_code = $2 
+               AND ($3::text IS NULL OR issuer = $3) 
+             ORDER BY created_at DESC LIMIT $4",
+        )
+        .bind(wallet_address)
+        .bind(asset_code)
+        .bind(issuer)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(DatabaseError::from_sqlx)
+    }
 }
 
 #[async_trait]
@@ -6,24 +6,41 @@
 //! GET    /api/admin/ddos/attack-history — paginated attack history
 //! POST   /api/admin/ddos/lockdown      — activate emergency lockdown
 //! DELETE /api/admin/ddos/lockdown      — deactivate lockdown
+//!
+//! Mounted inside the admin guard (`middleware::admin::require_admin`). Blocks
+//! and lockdown toggles are recorded in `admin_audit_log` with the state before
+//! and after. They live in memory and Redis rather than the database, so the
+//! entry is written first and a toggle that can't be audited isn't made.
 
 use axum::{
     extract::{Query, State},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
     response::{IntoResponse, Response},
     routing::{delete, get, post},
-    Json, Router,
+    Extension, Json, Router,
 };
 use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
 use std::sync::Arc;
+use tracing::error;
 
+use crate::database::admin_audit_repository::{AdminAuditRepository, NewAdminAuditEntry};
 use crate::ddos::{
     detector::ProtectionMode,
     queue::QueueStats,
     state::{BlockedEntry, DdosState},
 };
+use crate::middleware::admin::{audit_unavailable, AdminActor, AdminAudited};
+use crate::middleware::error::get_request_id_from_headers;
+
+#[derive(Clone)]
+pub struct DdosAdminState {
+    pub ddos: Arc<DdosState>,
+    /// Audit log; toggles are refused without it
+    pub db: Option<Arc<PgPool>>,
+}
 
-pub fn ddos_admin_router(state: Arc<DdosState>) -> Router {
+pub fn ddos_admin_router(ddos: Arc<DdosState>, db: Option<Arc<PgPool>>) -> Router {
     Router::new()
         .route("/api/admin/ddos/status", get(get_status))
         .route("/api/admin/ddos/traffic", get(get_traffic))
@@ -31,7 +48,60 @@ pub fn ddos_admin_router(state: Arc<DdosState>) -> Router {
         .route("/api/admin/ddos/attack-history", get(get_attack_history))
         .route("/api/admin/ddos/lockdown", post(activate_lockdown))
         .route("/api/admin/ddos/lockdown", delete(deactivate_lockdown))
-        .with_state(state)
+        .with_state(DdosAdminState { ddos, db })
+}
+
+/// Record a toggle before it is applied. `Err` is the response to return
+/// instead of applying it.
+async fn record_toggle(
+    state: &DdosAdminState,
+    actor: &AdminActor,
+    action: &str,
+    target: String,
+    before: serde_json::Value,
+    after: serde_json::Value,
+    request_id: Option<String>,
+) -> Result<(), Response> {
+    let Some(pool) = state.db.as_ref() else {
+        error!(
+            actor = %actor,
+            action,
+            "No database for the admin audit log; refusing DDoS toggle"
+        );
+        return Err(audit_unavailable(request_id));
+    };
+    let entry = NewAdminAuditEntry {
+        actor: actor.0.clone(),
+        action: action.to_string(),
+        target,
+        before: Some(before),
+        after: Some(after),
+        request_id: request_id.clone(),
+    };
+    if let Err(e) = AdminAuditRepository::new(pool.as_ref().clone()).record(&entry).await {
+        error!(
+            actor = %actor,
+            action,
+            error = %e,
+            "ALERT: DDoS toggle could not be audited; not applied"
+        );
+        return Err(audit_unavailable(request_id));
+    }
+    Ok(())
+}
+
+fn audited(response: impl IntoResponse) -> Response {
+    let mut response = response.into_response();
+    response.extensions_mut().insert(AdminAudited);
+    response
+}
+
+/// Lockdown and detector mode, as recorded around a lockdown toggle
+async fn lockdown_snapshot(ddos: &DdosState) -> serde_json::Value {
+    serde_json::json!({
+        "lockdown": ddos.lockdown.status().await,
+        "mode": *ddos.detector.mode.read().await,
+    })
 }
 
 // ── Status ────────────────────────────────────────────────────────────────────
@@ -45,7 +115,9 @@ struct StatusResponse {
     current_rps: f64,
 }
 
-async fn get_status(State(state): State<Arc<DdosState>>) -> impl IntoResponse {
+async fn get_status(
+    State(DdosAdminState { ddos: state, .. }): State<DdosAdminState>,
+) -> impl IntoResponse {
     let mode = state.detector.current_mode().await;
     let lockdown = state.lockdown.status().await;
     let active_attacks = state.detector.active_attacks().await;
@@ -70,7 +142,9 @@ struct TrafficResponse {
     global_rps: f64,
 }
 
-async fn get_traffic(State(state): State<Arc<DdosState>>) -> impl IntoResponse {
+async fn get_traffic(
+    State(DdosAdminState { ddos: state, .. }): State<DdosAdminState>,
+) -> impl IntoResponse {
     let endpoint_rps = state.detector.endpoint_rps().await;
     let queue = state.queue.queue_stats();
     let global_rps = state.detector.current_rps().await;
@@ -92,9 +166,11 @@ struct BlockRequest {
 }
 
 async fn block_target(
-    State(state): State<Arc<DdosState>>,
+    State(state): State<DdosAdminState>,
+    Extension(actor): Extension<AdminActor>,
+    headers: HeaderMap,
     Json(req): Json<BlockRequest>,
-) -> impl IntoResponse {
+) -> Response {
     if req.target.is_empty() {
         return (
             StatusCode::BAD_REQUEST,
@@ -103,13 +179,42 @@ async fn block_target(
             .into_response();
     }
 
-    state.block_target(&req.target, &req.reason, &req.blocked_by).await;
+    let existing: Vec<BlockedEntry> = state
+        .ddos
+        .blocked_ips
+        .read()
+        .await
+        .iter()
+        .filter(|e| e.target == req.target)
+        .cloned()
+        .collect();
+    if let Err(response) = record_toggle(
+        &state,
+        &actor,
+        "ddos.block",
+        format!("ddos_block:{}", req.target),
+        serde_json::json!({ "blocked": existing }),
+        serde_json::json!({
+            "target": req.target,
+            "reason": req.reason,
+            "blocked_by": req.blocked_by,
+        }),
+        get_request_id_from_headers(&headers),
+    )
+    .await
+    {
+        return response;
+    }
 
-    (
+    state
+        .ddos
+        .block_target(&req.target, &req.reason, &req.blocked_by)
+        .await;
+
+    audited((
         StatusCode::OK,
         Json(serde_json::json!({ "blocked": req.target })),
-    )
-        .into_response()
+    ))
 }
 
 // ── Attack history ────────────────────────────────────────────────────────────
@@ -121,7 +226,7 @@ struct HistoryQuery {
 }
 
 async fn get_attack_history(
-    State(state): State<Arc<DdosState>>,
+    State(DdosAdminState { ddos: state, .. }): State<DdosAdminState>,
     Query(q): Query<HistoryQuery>,
 ) -> impl IntoResponse {
     let page = q.page.unwrap_or(0);
@@ -138,37 +243,81 @@ struct LockdownRequest {
 }
 
 async fn activate_lockdown(
-    State(state): State<Arc<DdosState>>,
+    State(state): State<DdosAdminState>,
+    Extension(actor): Extension<AdminActor>,
+    headers: HeaderMap,
     Json(req): Json<LockdownRequest>,
-) -> impl IntoResponse {
+) -> Response {
     let trigger = req.trigger.as_deref().unwrap_or("manual_admin");
-    state.lockdown.activate(trigger).await;
+    let ddos = &state.ddos;
+    if let Err(response) = record_toggle(
+        &state,
+        &actor,
+        "ddos.lockdown.activate",
+        "ddos_lockdown".to_string(),
+        lockdown_snapshot(ddos).await,
+        serde_json::json!({
+            "lockdown": { "active": true, "trigger": trigger },
+            "mode": ProtectionMode::EmergencyLockdown,
+        }),
+        get_request_id_from_headers(&headers),
+    )
+    .await
+    {
+        return response;
+    }
+
+    ddos.lockdown.activate(trigger).await;
 
     // Also activate CDN under-attack mode
-    state.cdn.activate_under_attack_mode().await;
+    ddos.cdn.activate_under_attack_mode().await;
 
     // Escalate detector mode
-    *state.detector.mode.write().await = ProtectionMode::EmergencyLockdown;
+    *ddos.detector.mode.write().await = ProtectionMode::EmergencyLockdown;
 
-    tracing::warn!(trigger = trigger, "Emergency lockdown activated via admin API");
+    tracing::warn!(trigger = trigger, actor = %actor, "Emergency lockdown activated via admin API");
 
-    (
-        StatusCode::OK,
-        Json(state.lockdown.status().await),
-    )
-        .into_response()
+    audited((StatusCode::OK, Json(ddos.lockdown.status().await)))
 }
 
-async fn deactivate_lockdown(State(state): State<Arc<DdosState>>) -> impl IntoResponse {
-    state.lockdown.deactivate().await;
-    state.cdn.deactivate_under_attack_mode().await;
+async fn deactivate_lockdown(
+    State(state): State<DdosAdminState>,
+    Extension(actor): Extension<AdminActor>,
+    headers: HeaderMap,
+) -> Response {
+    let ddos = &state.ddos;
+    // Mode only drops back to passive when no attack is still active
+    let mode_after = if ddos.detector.active_attacks().await.is_empty() {
+        ProtectionMode::Passive
+    } else {
+        ddos.detector.mode.read().await.clone()
+    };
+    if let Err(response) = record_toggle(
+        &state,
+        &actor,
+        "ddos.lockdown.deactivate",
+        "ddos_lockdown".to_string(),
+        lockdown_snapshot(ddos).await,
+        serde_json::json!({
+            "lockdown": { "active": false },
+            "mode": mode_after,
+        }),
+        get_request_id_from_headers(&headers),
+    )
+    .await
+    {
+        return response;
+    }
+
+    ddos.lockdown.deactivate().await;
+    ddos.cdn.deactivate_under_attack_mode().await;
 
     // Downgrade mode if no active attacks
-    if state.detector.active_attacks().await.is_empty() {
-        *state.detector.mode.write().await = ProtectionMode::Passive;
+    if ddos.detector.active_attacks().await.is_empty() {
+        *ddos.detector.mode.write().await = ProtectionMode::Passive;
     }
 
-    tracing::info!("Emergency lockdown deactivated via admin API");
+    tracing::info!(actor = %actor, "Emergency lockdown deactivated via admin API");
 
-    (StatusCode::OK, Json(state.lockdown.status().await)).into_response()
+    audited((StatusCode::OK, Json(ddos.lockdown.status().await)))
 }
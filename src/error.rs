@@ -19,6 +19,8 @@ pub enum ErrorCode {
     TrustlineRequired,
     #[serde(rename = "INSUFFICIENT_CNGN_BALANCE")]
     InsufficientCngnBalance,
+    #[serde(rename = "INSUFFICIENT_BALANCE")]
+    InsufficientBalance,
     #[serde(rename = "RATE_EXPIRED")]
     RateExpired,
     #[serde(rename = "INVALID_CNGN_AMOUNT")]
@@ -43,6 +45,10 @@ pub enum ErrorCode {
     InvalidWallet,
     #[serde(rename = "DUPLICATE_TRANSACTION")]
     DuplicateTransaction,
+    #[serde(rename = "VOLUME_LIMIT_EXCEEDED")]
+    VolumeLimitExceeded,
+    #[serde(rename = "ACCOUNT_NOT_MERGEABLE")]
+    AccountNotMergeable,
 
     // Infrastructure errors (5xx)
     #[serde(rename = "DATABASE_ERROR")]
@@ -51,6 +57,9 @@ pub enum ErrorCode {
     CacheError,
     #[serde(rename = "CONFIGURATION_ERROR")]
     ConfigurationError,
+    /// Stored fee structure data can't be used; needs an operator to fix it
+    #[serde(rename = "CORRUPT_FEE_STRUCTURE")]
+    CorruptFeeStructure,
 
     // External errors (502, 503, 504)
     #[serde(rename = "PAYMENT_PROVIDER_ERROR")]
@@ -61,12 +70,28 @@ pub enum ErrorCode {
     RateLimitError,
     #[serde(rename = "EXTERNAL_SERVICE_TIMEOUT")]
     ExternalServiceTimeout,
+    #[serde(rename = "UPSTREAM_UNAVAILABLE")]
+    UpstreamUnavailable,
+    /// A dependency was switched off by configuration (e.g. `SKIP_EXTERNALS`),
+    /// as opposed to being unreachable
+    #[serde(rename = "DEPENDENCY_DISABLED")]
+    DependencyDisabled,
 
     // Generic
     #[serde(rename = "INTERNAL_ERROR")]
     InternalError,
     #[serde(rename = "VALIDATION_ERROR")]
     ValidationError,
+    #[serde(rename = "UNAUTHORIZED")]
+    Unauthorized,
+    #[serde(rename = "FORBIDDEN")]
+    Forbidden,
+    #[serde(rename = "NOT_FOUND")]
+    NotFound,
+    #[serde(rename = "METHOD_NOT_ALLOWED")]
+    MethodNotAllowed,
+    #[serde(rename = "UNSUPPORTED_MEDIA_TYPE")]
+    UnsupportedMediaType,
 }
 
 /// Domain-specific business logic errors
@@ -75,6 +100,13 @@ pub enum ErrorCode {
 pub enum DomainError {
     /// User doesn't have enough CNGN tokens for the operation
     InsufficientBalance { available: String, required: String },
+    /// Source account can't cover a payment amount or its network fee
+    InsufficientFunds {
+        asset: String,
+        available: String,
+        required: String,
+        shortfall: String,
+    },
     /// Wallet hasn't established CNGN trustline
     TrustlineNotFound {
         wallet_address: String,
@@ -101,6 +133,17 @@ pub enum DomainError {
     InsufficientLiquidity { amount: String },
     /// Access forbidden (e.g., transaction doesn't belong to requesting wallet)
     Forbidden { message: String },
+    /// Platform-wide daily volume ceiling reached
+    VolumeLimitExceeded {
+        asset: String,
+        ceiling: String,
+        retry_after_secs: u64,
+    },
+    /// Account still owns subentries, so Horizon would reject an accountMerge
+    AccountNotMergeable {
+        wallet_address: String,
+        blockers: Vec<String>,
+    },
 }
 
 /// Infrastructure-level errors (database, cache, configuration)
@@ -113,6 +156,11 @@ pub enum InfrastructureError {
     Cache { message: String },
     /// Missing or invalid configuration
     Configuration { message: String },
+    /// A stored fee structure holds values fees can't be calculated from
+    CorruptFeeStructure {
+        structure_id: Option<String>,
+        message: String,
+    },
 }
 
 /// External service errors (payment providers, blockchain)
@@ -134,6 +182,11 @@ pub enum ExternalError {
     },
     /// External service timeout
     Timeout { service: String, timeout_secs: u64 },
+    /// Upstream reachable but refusing work (maintenance, overload)
+    ServiceUnavailable {
+        service: String,
+        retry_after: Option<u64>,
+    },
 }
 
 /// Input validation errors
@@ -160,6 +213,14 @@ pub enum ValidationError {
         expected: String,
         got: String,
     },
+    /// More operations than one Stellar transaction may carry
+    TooManyOperations { count: usize, max: usize },
+    /// Requested network fee is above the ceiling for the operation count
+    FeeAboveCeiling {
+        fee_stroops: u64,
+        max_fee_stroops: u64,
+        operations: usize,
+    },
 }
 
 /// Unified application error type
@@ -207,6 +268,7 @@ impl AppError {
                 DomainError::InsufficientLiquidity { .. } => 422,
                 DomainError::AmountTooLow { .. } => 400,
                 DomainError::InsufficientBalance { .. } => 422, // Unprocessable Entity
+                DomainError::InsufficientFunds { .. } => 422,
                 DomainError::TrustlineNotFound { .. } => 422,
                 DomainError::InvalidAmount { .. } => 400,
                 DomainError::TransactionNotFound { .. } => 404,
@@ -215,17 +277,21 @@ impl AppError {
                 DomainError::DuplicateTransaction { .. } => 409, // Conflict
                 DomainError::TrustlineCreationFailed { .. } => 422,
                 DomainError::InsufficientLiquidity { .. } => 409, // Conflict
+                DomainError::VolumeLimitExceeded { .. } => 429,
+                DomainError::AccountNotMergeable { .. } => 422,
             },
             AppErrorKind::Infrastructure(err) => match err {
                 InfrastructureError::Database { .. } => 500,
                 InfrastructureError::Cache { .. } => 500,
                 InfrastructureError::Configuration { .. } => 500,
+                InfrastructureError::CorruptFeeStructure { .. } => 500,
             },
             AppErrorKind::External(err) => match err {
                 ExternalError::PaymentProvider { .. } => 502, // Bad Gateway
                 ExternalError::Blockchain { .. } => 502,
                 ExternalError::RateLimit { .. } => 429, // Too Many Requests
                 ExternalError::Timeout { .. } => 504,   // Gateway Timeout
+                ExternalError::ServiceUnavailable { .. } => 503,
             },
             AppErrorKind::Validation(err) => match err {
                 ValidationError::InvalidWalletAddress { .. } => 400,
@@ -233,6 +299,9 @@ impl AppError {
                 ValidationError::InvalidAmount { .. } => 400,
                 ValidationError::MissingField { .. } => 400,
                 ValidationError::OutOfRange { .. } => 400,
+                ValidationError::InvalidFormat { .. } => 400,
+                ValidationError::TooManyOperations { .. } => 400,
+                ValidationError::FeeAboveCeiling { .. } => 400,
             },
         }
     }
@@ -242,6 +311,7 @@ impl AppError {
         match &self.kind {
             AppErrorKind::Domain(err) => match err {
                 DomainError::InsufficientBalance { .. } => ErrorCode::InsufficientCngnBalance,
+                DomainError::InsufficientFunds { .. } => ErrorCode::InsufficientBalance,
                 DomainError::TrustlineNotFound { .. } => ErrorCode::TrustlineRequired,
                 DomainError::InvalidAmount { .. } => ErrorCode::InvalidCngnAmount,
                 DomainError::TransactionNotFound { .. } => ErrorCode::TransactionNotFound,
@@ -251,17 +321,21 @@ impl AppError {
                 DomainError::TrustlineCreationFailed { .. } => ErrorCode::TrustlineCreationFailed,
                 DomainError::InsufficientLiquidity { .. } => ErrorCode::InsufficientLiquidity,
                 DomainError::AmountTooLow { .. } => ErrorCode::AmountTooLow,
+                DomainError::VolumeLimitExceeded { .. } => ErrorCode::VolumeLimitExceeded,
+                DomainError::AccountNotMergeable { .. } => ErrorCode::AccountNotMergeable,
             },
             AppErrorKind::Infrastructure(err) => match err {
                 InfrastructureError::Database { .. } => ErrorCode::DatabaseError,
                 InfrastructureError::Cache { .. } => ErrorCode::CacheError,
                 InfrastructureError::Configuration { .. } => ErrorCode::ConfigurationError,
+                InfrastructureError::CorruptFeeStructure { .. } => ErrorCode::CorruptFeeStructure,
             },
             AppErrorKind::External(err) => match err {
                 ExternalError::PaymentProvider { .. } => ErrorCode::PaymentProviderError,
                 ExternalError::Blockchain { .. } => ErrorCode::BlockchainError,
                 ExternalError::RateLimit { .. } => ErrorCode::RateLimitError,
                 ExternalError::Timeout { .. } => ErrorCode::ExternalServiceTimeout,
+                ExternalError::ServiceUnavailable { .. } => ErrorCode::UpstreamUnavailable,
             },
             AppErrorKind::Validation(err) => match err {
                 ValidationError::InvalidWalletAddress { .. } => ErrorCode::InvalidWallet,
@@ -283,6 +357,17 @@ impl AppError {
                         available, required
                     )
                 }
+                DomainError::InsufficientFunds {
+                    asset,
+                    available,
+                    required,
+                    shortfall,
+                } => {
+                    format!(
+                        "Insufficient {} balance. Available: {}, Required: {}, Shortfall: {}",
+                        asset, available, required, shortfall
+                    )
+                }
                 DomainError::TrustlineNotFound {
                     wallet_address,
                     asset,
@@ -327,6 +412,23 @@ impl AppError {
                 DomainError::AmountTooLow { .. } => {
                     "Minimum onramp amount is ₦1,000.".to_string()
                 }
+                DomainError::VolumeLimitExceeded {
+                    asset,
+                    retry_after_secs,
+                    ..
+                } => {
+                    format!(
+                        "Daily {} payment volume limit reached. Please try again in {} seconds",
+                        asset, retry_after_secs
+                    )
+                }
+                DomainError::AccountNotMergeable { blockers, .. } => {
+                    format!(
+                        "Account cannot be merged until {} subentr{} removed",
+                        blockers.len(),
+                        if blockers.len() == 1 { "y is" } else { "ies are" }
+                    )
+                }
             },
             AppErrorKind::Infrastructure(_) => {
                 "Service temporarily unavailable. Please try again later".to_string()
@@ -376,6 +478,22 @@ impl AppError {
                             service, timeout_secs
                         )
                     }
+                    ExternalError::ServiceUnavailable {
+                        service,
+                        retry_after,
+                    } => {
+                        if let Some(secs) = retry_after {
+                            format!(
+                                "{} is temporarily unavailable. Please try again in {} seconds",
+                                service, secs
+                            )
+                        } else {
+                            format!(
+                                "{} is temporarily unavailable. Please try again later",
+                                service
+                            )
+                        }
+                    }
                 }
             }
             AppErrorKind::Validation(err) => match err {
@@ -405,24 +523,111 @@ impl AppError {
                         format!("Field '{}' is out of acceptable range", field)
                     }
                 },
+                ValidationError::InvalidFormat {
+                    field,
+                    expected,
+                    got,
+                } => format!("Field '{}' must be {}: {}", field, expected, got),
+                ValidationError::TooManyOperations { count, max } => format!(
+                    "Transaction has {} operations; at most {} are allowed",
+                    count, max
+                ),
+                ValidationError::FeeAboveCeiling {
+                    fee_stroops,
+                    max_fee_stroops,
+                    operations,
+                } => format!(
+                    "Fee of {} stroops for {} operations exceeds the ceiling of {} stroops",
+                    fee_stroops, operations, max_fee_stroops
+                ),
             },
         }
     }
 
+    /// Structured details clients can act on, where the error carries any
+    pub fn details(&self) -> Option<serde_json::Value> {
+        match &self.kind {
+            AppErrorKind::Domain(DomainError::InsufficientFunds {
+                asset,
+                available,
+                required,
+                shortfall,
+            }) => Some(serde_json::json!({
+                "asset": asset,
+                "available": available,
+                "required": required,
+                "shortfall": shortfall,
+            })),
+            AppErrorKind::Domain(DomainError::VolumeLimitExceeded {
+                asset,
+                ceiling,
+                retry_after_secs,
+            }) => Some(serde_json::json!({
+                "asset": asset,
+                "ceiling": ceiling,
+                "retry_after": retry_after_secs,
+            })),
+            AppErrorKind::Domain(DomainError::AccountNotMergeable {
+                wallet_address,
+                blockers,
+            }) => Some(serde_json::json!({
+                "address": wallet_address,
+                "blockers": blockers,
+            })),
+            AppErrorKind::Validation(ValidationError::TooManyOperations { count, max }) => {
+                Some(serde_json::json!({
+                    "operation_count": count,
+                    "max_operations": max,
+                }))
+            }
+            AppErrorKind::Validation(ValidationError::FeeAboveCeiling {
+                fee_stroops,
+                max_fee_stroops,
+                operations,
+            }) => Some(serde_json::json!({
+                "fee_stroops": fee_stroops,
+                "max_fee_stroops": max_fee_stroops,
+                "operation_count": operations,
+            })),
+            AppErrorKind::External(ExternalError::ServiceUnavailable {
+                service,
+                retry_after,
+            }) => Some(serde_json::json!({
+                "service": service,
+                "retry_after": retry_after,
+            })),
+            _ => None,
+        }
+    }
+
+    /// Seconds a client should wait before retrying, for the `Retry-After` header
+    pub fn retry_after(&self) -> Option<u64> {
+        match &self.kind {
+            AppErrorKind::External(ExternalError::ServiceUnavailable { retry_after, .. })
+            | AppErrorKind::External(ExternalError::RateLimit { retry_after, .. }) => *retry_after,
+            AppErrorKind::Domain(DomainError::VolumeLimitExceeded {
+                retry_after_secs, ..
+            }) => Some(*retry_after_secs),
+            _ => None,
+        }
+    }
+
     /// Check if error is retryable
     pub fn is_retryable(&self) -> bool {
         match &self.kind {
-            AppErrorKind::Domain(_) => false,
+            AppErrorKind::Domain(err) => matches!(err, DomainError::VolumeLimitExceeded { .. }),
             AppErrorKind::Infrastructure(err) => match err {
                 InfrastructureError::Database { is_retryable, .. } => *is_retryable,
                 InfrastructureError::Cache { .. } => true,
                 InfrastructureError::Configuration { .. } => false,
+                InfrastructureError::CorruptFeeStructure { .. } => false,
             },
             AppErrorKind::External(err) => match err {
                 ExternalError::PaymentProvider { is_retryable, .. } => *is_retryable,
                 ExternalError::Blockchain { is_retryable, .. } => *is_retryable,
                 ExternalError::RateLimit { .. } => true,
                 ExternalError::Timeout { .. } => true,
+                ExternalError::ServiceUnavailable { .. } => true,
             },
             AppErrorKind::Validation(_) => false,
         }
@@ -457,10 +662,22 @@ impl From<StellarError> for AppError {
                     reason: "Invalid Stellar address format".to_string(),
                 })
             }
+            SE::InvalidAsset { code, reason } => {
+                AppErrorKind::Validation(ValidationError::InvalidCurrency {
+                    currency: code,
+                    reason,
+                })
+            }
             SE::RateLimitError => AppErrorKind::External(ExternalError::RateLimit {
                 service: "Stellar".to_string(),
                 retry_after: Some(60),
             }),
+            SE::ServiceUnavailable { retry_after } => {
+                AppErrorKind::External(ExternalError::ServiceUnavailable {
+                    service: "Stellar".to_string(),
+                    retry_after,
+                })
+            }
             SE::TimeoutError { seconds } => AppErrorKind::External(ExternalError::Timeout {
                 service: "Stellar".to_string(),
                 timeout_secs: seconds,
@@ -478,12 +695,25 @@ impl From<StellarError> for AppError {
                 available,
                 required,
             }),
+            SE::InsufficientBalance {
+                asset,
+                available,
+                required,
+                shortfall,
+            } => AppErrorKind::Domain(DomainError::InsufficientFunds {
+                asset,
+                available,
+                required,
+                shortfall,
+            }),
             SE::TrustlineAlreadyExists { address, asset } => {
                 AppErrorKind::Domain(DomainError::DuplicateTransaction {
                     transaction_id: format!("trustline:{}:{}", address, asset),
                 })
             }
-            SE::TransactionFailed { message } | SE::SigningError { message } => {
+            SE::TransactionFailed { message }
+            | SE::SigningError { message }
+            | SE::ContractError { message } => {
                 AppErrorKind::External(ExternalError::Blockchain {
                     message,
                     is_retryable: false,
@@ -492,6 +722,71 @@ impl From<StellarError> for AppError {
             SE::ConfigError { message } => {
                 AppErrorKind::Infrastructure(InfrastructureError::Configuration { message })
             }
+            SE::SequenceTooLow { current, .. } => {
+                AppErrorKind::Validation(ValidationError::OutOfRange {
+                    field: "bump_to".to_string(),
+                    min: Some((current + 1).to_string()),
+                    max: None,
+                })
+            }
+            SE::TransactionExpired { max_time } => {
+                AppErrorKind::Validation(ValidationError::OutOfRange {
+                    field: "time_bounds.max_time".to_string(),
+                    min: Some(chrono::Utc::now().timestamp().to_string()),
+                    max: None,
+                })
+            }
+            SE::TransactionNotYetValid { .. } => {
+                AppErrorKind::Validation(ValidationError::OutOfRange {
+                    field: "time_bounds.min_time".to_string(),
+                    min: None,
+                    max: Some(
+                        (chrono::Utc::now().timestamp() + crate::clock_skew::tolerance_secs())
+                            .to_string(),
+                    ),
+                })
+            }
+            SE::InvalidMemo { max_bytes, reason } => {
+                AppErrorKind::Validation(ValidationError::InvalidFormat {
+                    field: "memo".to_string(),
+                    expected: format!("at most {} bytes of UTF-8 text", max_bytes),
+                    got: reason,
+                })
+            }
+            SE::NoOperations => AppErrorKind::Validation(ValidationError::OutOfRange {
+                field: "operations".to_string(),
+                min: Some("1".to_string()),
+                max: None,
+            }),
+            SE::TooManyOperations { count, max } => {
+                AppErrorKind::Validation(ValidationError::TooManyOperations { count, max })
+            }
+            SE::SubmissionInProgress { hash } => {
+                AppErrorKind::Domain(DomainError::DuplicateTransaction {
+                    transaction_id: hash,
+                })
+            }
+            SE::FeeAboveCeiling {
+                fee_stroops,
+                max_fee_stroops,
+                operations,
+            } => AppErrorKind::Validation(ValidationError::FeeAboveCeiling {
+                fee_stroops,
+                max_fee_stroops,
+                operations,
+            }),
+            SE::SelfMerge { address } => {
+                AppErrorKind::Validation(ValidationError::InvalidWalletAddress {
+                    address,
+                    reason: "Cannot merge an account into itself".to_string(),
+                })
+            }
+            SE::AccountNotMergeable { address, blockers } => {
+                AppErrorKind::Domain(DomainError::AccountNotMergeable {
+                    wallet_address: address,
+                    blockers,
+                })
+            }
             _ => AppErrorKind::External(ExternalError::Blockchain {
                 message: err.to_string(),
                 is_retryable: false,
@@ -558,4 +853,12 @@ mod tests {
         assert_eq!(error.error_code(), ErrorCode::ValidationError);
         assert!(!error.is_retryable());
     }
+
+    #[test]
+    fn test_oversized_memo_is_a_validation_error() {
+        let error = AppError::from(StellarError::invalid_memo(28, "memo text is 29 bytes"));
+
+        assert_eq!(error.status_code(), 400);
+        assert_eq!(error.error_code(), ErrorCode::ValidationError);
+    }
 }
@@ -3,19 +3,25 @@
 
 use serde::Serialize;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::time::timeout;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
 use crate::cache::RedisCache;
 use crate::cache::warmer::WarmingState;
 use crate::chains::stellar::client::StellarClient;
+use crate::chains::stellar::errors::StellarError;
 
 /// Health status response
 #[derive(Debug, Serialize, Clone)]
 pub struct HealthStatus {
     pub status: HealthState,
     pub checks: HashMap<String, ComponentHealth>,
+    /// Set when `SKIP_EXTERNALS=true` switched off the database, cache and
+    /// Stellar clients; disabled components are then intentional, not down.
+    pub externals_skipped: bool,
     pub timestamp: chrono::DateTime<chrono::Utc>,
 }
 
@@ -48,6 +54,7 @@ impl HealthStatus {
         Self {
             status: HealthState::Healthy,
             checks: HashMap::new(),
+            externals_skipped: false,
             timestamp: chrono::Utc::now(),
         }
     }
@@ -83,6 +90,61 @@ impl ComponentHealth {
     }
 }
 
+/// Set once every dependency handed to the router has been initialized and
+/// the listener is bound.
+#[derive(Clone, Default)]
+pub struct StartupState {
+    complete: Arc<AtomicBool>,
+}
+
+impl StartupState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn mark_complete(&self) {
+        self.complete.store(true, Ordering::Release);
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.complete.load(Ordering::Acquire)
+    }
+}
+
+/// Readiness outcome
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ReadinessStatus {
+    Ready,
+    /// Startup has not finished; dependencies were not probed
+    Starting,
+    /// Startup finished but a dependency is down
+    Unhealthy,
+}
+
+/// Readiness probe body
+#[derive(Debug, Serialize, Clone)]
+pub struct ReadinessReport {
+    pub status: ReadinessStatus,
+    /// First failing dependency when `status` is `unhealthy`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub component: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub checks: HashMap<String, ComponentHealth>,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+}
+
+impl ReadinessReport {
+    pub fn is_ready(&self) -> bool {
+        self.status == ReadinessStatus::Ready
+    }
+}
+
+/// Order in which failing components are reported
+const READINESS_COMPONENTS: &[&str] = &["database", "cache", "stellar", "cache_warming"];
+
 /// Health checker for the application
 #[derive(Clone)]
 pub struct HealthChecker {
@@ -91,6 +153,10 @@ pub struct HealthChecker {
     stellar_client: Option<StellarClient>,
     /// Readiness gate: unhealthy until cache warming completes.
     pub warming_state: Option<WarmingState>,
+    /// Readiness gate: `starting` until startup completes.
+    pub startup_state: Option<StartupState>,
+    /// Reported on `/health` so an accidental `SKIP_EXTERNALS=true` is visible.
+    pub externals_skipped: bool,
 }
 
 impl HealthChecker {
@@ -104,20 +170,83 @@ impl HealthChecker {
             cache,
             stellar_client,
             warming_state: None,
+            startup_state: None,
+            externals_skipped: false,
         }
     }
 
+    /// Record that external dependencies were skipped at startup.
+    pub fn with_externals_skipped(mut self, skipped: bool) -> Self {
+        self.externals_skipped = skipped;
+        self
+    }
+
     /// Attach a warming state so the readiness probe blocks until warming is done.
     pub fn with_warming_state(mut self, state: WarmingState) -> Self {
         self.warming_state = Some(state);
         self
     }
 
+    /// Attach a startup flag so readiness reports `starting` until it is set.
+    pub fn with_startup_state(mut self, state: StartupState) -> Self {
+        self.startup_state = Some(state);
+        self
+    }
+
+    /// Readiness: `starting` during startup, otherwise the dependency checks
+    /// from `check_health` with the first failing component named.
+    pub async fn check_readiness(&self) -> ReadinessReport {
+        if let Some(ref startup) = self.startup_state {
+            if !startup.is_complete() {
+                return ReadinessReport {
+                    status: ReadinessStatus::Starting,
+                    component: None,
+                    reason: Some("Service is still initializing".to_string()),
+                    checks: HashMap::new(),
+                    timestamp: chrono::Utc::now(),
+                };
+            }
+        }
+
+        let health = self.check_health().await;
+        if !matches!(health.status, HealthState::Unhealthy) {
+            return ReadinessReport {
+                status: ReadinessStatus::Ready,
+                component: None,
+                reason: None,
+                checks: health.checks,
+                timestamp: health.timestamp,
+            };
+        }
+
+        let failing = READINESS_COMPONENTS.iter().find_map(|name| {
+            health
+                .checks
+                .get(*name)
+                .filter(|c| matches!(c.status, ComponentState::Down))
+                .map(|c| (name.to_string(), c.details.clone()))
+        });
+        let (component, reason) = match failing {
+            Some((name, details)) => (Some(name), details),
+            None => (None, None),
+        };
+
+        ReadinessReport {
+            status: ReadinessStatus::Unhealthy,
+            component,
+            reason,
+            checks: health.checks,
+            timestamp: health.timestamp,
+        }
+    }
+
     /// Perform comprehensive health check
     pub async fn check_health(&self) -> HealthStatus {
         let mut health_status = HealthStatus::new();
+        health_status.externals_skipped = self.externals_skipped;
         let mut overall_healthy = true;
         let mut any_disabled = false;
+        let mut any_degraded = false;
 
         // Check database health
         if let Some(db_pool) = &self.db_pool {
@@ -209,6 +338,15 @@ impl HealthChecker {
                         );
                         info!("Stellar health check: OK ({}ms)", response_time);
                     }
+                    Err(e) if is_upstream_unavailable(e.as_ref()) => {
+                        // Horizon is reachable but in maintenance; keep serving
+                        any_degraded = true;
+                        health_status.checks.insert(
+                            "stellar".to_string(),
+                            ComponentHealth::warning(None, Some(e.to_string())),
+                        );
+                        warn!("Stellar health check degraded: {}", e);
+                    }
                     Err(e) => {
                         overall_healthy = false;
                         health_status.checks.insert(
@@ -237,7 +375,7 @@ impl HealthChecker {
 
         // Set overall status
         health_status.status = if overall_healthy {
-            if any_disabled {
+            if any_disabled || any_degraded {
                 HealthState::Degraded
             } else {
                 HealthState::Healthy
@@ -299,6 +437,14 @@ pub async fn check_cache_health(
     }
 }
 
+/// Horizon 503s mean maintenance, not an outage on our side
+fn is_upstream_unavailable(err: &(dyn std::error::Error + Send + Sync + 'static)) -> bool {
+    matches!(
+        err.downcast_ref::<StellarError>(),
+        Some(StellarError::ServiceUnavailable { .. })
+    )
+}
+
 // Add a function to check Stellar health
 pub async fn check_stellar_health(
     stellar_client: &crate::chains::stellar::client::StellarClient,
@@ -342,4 +488,87 @@ mod tests {
         assert_eq!(warning_health.response_time_ms, Some(500));
         assert_eq!(warning_health.details, Some("Slow response".to_string()));
     }
+
+    #[tokio::test]
+    async fn test_readiness_reports_starting_until_startup_completes() {
+        let startup = StartupState::new();
+        let checker = HealthChecker::new(None, None, None).with_startup_state(startup.clone());
+
+        let report = checker.check_readiness().await;
+        assert_eq!(report.status, ReadinessStatus::Starting);
+        assert!(report.component.is_none());
+        assert!(!report.is_ready());
+
+        startup.mark_complete();
+        let report = checker.check_readiness().await;
+        assert_eq!(report.status, ReadinessStatus::Ready);
+    }
+
+    #[tokio::test]
+    async fn test_readiness_names_failing_dependency_after_startup() {
+        // Nothing listens on port 1, so the database check fails fast
+        let pool = sqlx::postgres::PgPoolOptions::new()
+            .acquire_timeout(Duration::from_secs(1))
+            .connect_lazy("postgres://aframp@127.0.0.1:1/unused")
+            .unwrap();
+        let startup = StartupState::new();
+        startup.mark_complete();
+        let checker = HealthChecker::new(Some(pool), None, None).with_startup_state(startup);
+
+        let report = checker.check_readiness().await;
+        assert_eq!(report.status, ReadinessStatus::Unhealthy);
+        assert_eq!(report.component.as_deref(), Some("database"));
+
+        let body = serde_json::to_value(&report).unwrap();
+        assert_eq!(body["status"], "unhealthy");
+        assert_eq!(body["component"], "database");
+    }
+
+    #[tokio::test]
+    async fn test_horizon_503_marks_stellar_degraded() {
+        use crate::chains::stellar::config::{StellarConfig, StellarNetwork};
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(
+                ResponseTemplate::new(503)
+                    .insert_header("Retry-After", "30")
+                    .set_body_string(r#"{"status":503,"title":"Service Unavailable"}"#),
+            )
+            .mount(&server)
+            .await;
+
+        let client = StellarClient::new(StellarConfig {
+            network: StellarNetwork::Testnet,
+            horizon_url_override: Some(server.uri()),
+            horizon_fallback_urls: Vec::new(),
+            request_timeout: Duration::from_secs(5),
+            max_retries: 1,
+            health_check_interval: Duration::from_secs(30),
+        })
+        .unwrap();
+        let checker = HealthChecker::new(None, None, Some(client));
+
+        let health = checker.check_health().await;
+        assert!(matches!(health.status, HealthState::Degraded));
+        assert!(matches!(
+            health.checks["stellar"].status,
+            ComponentState::Warning
+        ));
+        assert!(checker.check_readiness().await.is_ready());
+    }
+
+    #[tokio::test]
+    async fn test_health_reports_skipped_externals() {
+        let checker = HealthChecker::new(None, None, None).with_externals_skipped(true);
+
+        let body = serde_json::to_value(checker.check_health().await).unwrap();
+        assert_eq!(body["externals_skipped"], true);
+        assert_eq!(body["checks"]["database"]["details"], "Disabled by configuration");
+
+        let plain = HealthChecker::new(None, None, None).check_health().await;
+        assert!(!plain.externals_skipped);
+    }
 }
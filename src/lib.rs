@@ -77,6 +77,18 @@ pub mod health;
 #[cfg(feature = "database")]
 pub mod workers;
 
+// Retry with backoff shared by network clients
+#[cfg(feature = "database")]
+pub mod retry;
+
+// Clock skew tolerance for time-window checks
+#[cfg(feature = "database")]
+pub mod clock_skew;
+
+// Bounded graceful shutdown
+#[cfg(feature = "database")]
+pub mod shutdown;
+
 // Recurring payment frequency helpers
 #[cfg(feature = "database")]
 pub mod recurring;
@@ -153,6 +165,7 @@ pub enum DataKey {
     FeeTreasury,
     IsPaused,
     DisputeResolver,
+    Initialized,
 }
 
 #[cfg(not(feature = "database"))]
@@ -170,7 +183,8 @@ impl EscrowContract {
         fee_treasury: Address,
         dispute_resolver: Address,
     ) -> Result<(), Error> {
-        if env.storage().instance().has(&DataKey::Admin) {
+        // Deployments from before the flag existed are recognised by their admin
+        if Self::is_initialized(env.clone()) || env.storage().instance().has(&DataKey::Admin) {
             return Err(Error::AlreadyInitialized);
         }
         if fee_rate > 1000 {
@@ -187,9 +201,18 @@ impl EscrowContract {
             .set(&DataKey::DisputeResolver, &dispute_resolver);
         env.storage().instance().set(&DataKey::IsPaused, &false);
         env.storage().instance().set(&DataKey::OrderCount, &0u64);
+        env.storage().instance().set(&DataKey::Initialized, &true);
         Ok(())
     }
 
+    /// Whether `initialize` has already run; a second call is rejected
+    pub fn is_initialized(env: Env) -> bool {
+        env.storage()
+            .instance()
+            .get(&DataKey::Initialized)
+            .unwrap_or(false)
+    }
+
     /// Transfer admin rights to a new address
     pub fn set_admin(env: Env, new_admin: Address) -> Result<(), Error> {
         let admin: Address = env
@@ -476,6 +499,42 @@ mod tests {
         assert_eq!(result, Err(Error::AlreadyInitialized));
     }
 
+    #[test]
+    fn test_reinitialization_cannot_replace_admin() {
+        let env = create_env();
+        let contract_id = env.register_contract(None, EscrowContract);
+        let (admin, treasury, resolver, attacker) = create_addresses(&env);
+
+        let before = env.as_contract(&contract_id, || EscrowContract::is_initialized(env.clone()));
+        assert!(!before);
+
+        env.as_contract(&contract_id, || {
+            EscrowContract::initialize(
+                env.clone(),
+                admin.clone(),
+                50,
+                treasury.clone(),
+                resolver.clone(),
+            )
+            .unwrap();
+        });
+        let result = env.as_contract(&contract_id, || {
+            EscrowContract::initialize(
+                env.clone(),
+                attacker.clone(),
+                0,
+                attacker.clone(),
+                attacker.clone(),
+            )
+        });
+        assert_eq!(result, Err(Error::AlreadyInitialized));
+
+        env.as_contract(&contract_id, || {
+            assert!(EscrowContract::is_initialized(env.clone()));
+            assert_eq!(EscrowContract::get_admin(env.clone()).unwrap(), admin);
+        });
+    }
+
     #[test]
     fn test_set_fee_rate() {
         let env = create_env();
@@ -3,6 +3,7 @@ mod api_keys;
 mod auth;
 mod cache;
 mod chains;
+mod clock_skew;
 mod config;
 mod config_validation;
 mod database;
@@ -16,7 +17,9 @@ mod middleware;
 mod oauth;
 mod payments;
 mod recurring;
+mod retry;
 mod services;
+mod shutdown;
 mod telemetry;
 mod workers;
 
@@ -87,6 +90,26 @@ async fn shutdown_signal_with_notify(shutdown_tx: watch::Sender<bool>) {
     let _ = shutdown_tx.send(true);
 }
 
+/// Soroban client, token contract and admin key for the mint/burn routes.
+/// `None` when any of them is not configured.
+fn token_admin_state(
+    stellar_client: Option<&StellarClient>,
+) -> Option<Result<api::admin::token::TokenAdminState, chains::stellar::errors::StellarError>> {
+    use chains::stellar::signer::{SecretSeedSigner, SignerProvider};
+    use chains::stellar::soroban::{ContractAdmin, SorobanConfig, SorobanRpcClient};
+
+    let network = stellar_client?.network().clone();
+    let soroban_config = SorobanConfig::from_env(&network)?;
+    let signer = match SecretSeedSigner::from_env()? {
+        Ok(signer) => std::sync::Arc::new(signer) as std::sync::Arc<dyn SignerProvider>,
+        Err(e) => return Some(Err(e)),
+    };
+    let admin = ContractAdmin::from_env(&network, signer)?;
+    Some(SorobanRpcClient::new(soroban_config).map(|soroban| {
+        api::admin::token::TokenAdminState { soroban, admin }
+    }))
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     // -------------------------------------------------------------------------
@@ -148,6 +171,15 @@ async fn main() -> anyhow::Result<()> {
         .to_lowercase()
         == "true";
 
+    if skip_externals {
+        // Easy to leave on by accident, and everything then 503s; make it loud
+        tracing::warn!("⚠️  ================================================================");
+        tracing::warn!("⚠️  SKIP_EXTERNALS=true: database, cache and Stellar are DISABLED.");
+        tracing::warn!("⚠️  Endpoints needing them return 503 DEPENDENCY_DISABLED.");
+        tracing::warn!("⚠️  Intended for smoke tests only; unset it in real deployments.");
+        tracing::warn!("⚠️  ================================================================");
+    }
+
     info!(
         version = env!("CARGO_PKG_VERSION"),
         environment = %app_config.telemetry.environment,
@@ -277,6 +309,23 @@ async fn main() -> anyhow::Result<()> {
         Some(redis_cache)
     };
 
+    let maintenance_enabled = std::env::var("MAINTENANCE_WORKER_ENABLED")
+        .unwrap_or_else(|_| "true".to_string())
+        .to_lowercase()
+        != "false";
+
+    // build_info + feature flags, emitted once so dashboards can join on them.
+    // The maintenance worker needs the database, so it reports 0 without one.
+    metrics::build::record_startup(
+        chains::stellar::config::StellarNetwork::from_env().as_str(),
+        &[
+            ("cache", redis_cache.is_some()),
+            ("metrics", true),
+            ("otel", cfg!(feature = "telemetry")),
+            ("maintenance", maintenance_enabled && db_pool.is_some()),
+        ],
+    );
+
     // Initialize Stellar client
     let stellar_client = if skip_externals {
         info!("⏭️  Skipping Stellar initialization (SKIP_EXTERNALS=true)");
@@ -304,69 +353,47 @@ async fn main() -> anyhow::Result<()> {
 
         // Health check Stellar
         info!("🏥 Performing Stellar health check...");
-        let health_status = stellar_client.health_check().await?;
-        if health_status.is_healthy {
-            info!(
-                response_time_ms = health_status.response_time_ms,
-                "✅ Stellar Horizon is healthy"
-            );
-        } else {
-            error!(
-                error = health_status
-                    .error_message
-                    .as_deref()
-                    .unwrap_or("Unknown error"),
-                "❌ Stellar Horizon health check failed"
-            );
-        }
-
-        // Demo functionality
-        info!("🧪 Demo: Testing Stellar functionality");
-        let test_address = "GCJRI5CIWK5IU67Q6DGA7QW52JDKRO7JEAHQKFNDUJUPEZGURDBX3LDX";
-
-        match stellar_client.account_exists(test_address).await {
-            Ok(exists) => {
-                if exists {
-                    info!(address = test_address, "✅ Test account exists");
-                    match stellar_client.get_account(test_address).await {
-                        Ok(account) => {
-                            info!(
-                                account_id = %account.account_id,
-                                sequence = account.sequence,
-                                balances = account.balances.len(),
-                                "✅ Successfully fetched account details"
-                            );
-                            for balance in &account.balances {
-                                info!(
-                                    balance = %balance.balance,
-                                    asset_type = %balance.asset_type,
-                                    "Account balance"
-                                );
-                            }
-                        }
-                        Err(e) => {
-                            info!(error = %e, "⚠️  Account exists but couldn't fetch details")
-                        }
-                    }
-                } else {
-                    info!(
-                        address = test_address,
-                        "ℹ️  Test account does not exist (expected)"
-                    );
-                }
+        match stellar_client.health_check().await {
+            Ok(health_status) if health_status.is_healthy => {
+                info!(
+                    response_time_ms = health_status.response_time_ms,
+                    "✅ Stellar Horizon is healthy"
+                );
+            }
+            Ok(health_status) => {
+                error!(
+                    error = health_status
+                        .error_message
+                        .as_deref()
+                        .unwrap_or("Unknown error"),
+                    "❌ Stellar Horizon health check failed"
+                );
             }
-            Err(e) => info!(error = %e, "ℹ️  Error checking account existence (expected for test)"),
+            Err(chains::stellar::errors::StellarError::ServiceUnavailable { retry_after }) => {
+                tracing::warn!(
+                    retry_after = ?retry_after,
+                    "⚠️  Stellar Horizon is temporarily unavailable (503), continuing degraded"
+                );
+            }
+            Err(e) => return Err(e.into()),
         }
 
+        // Off unless RUN_STARTUP_DEMO=true; production starts skip the extra
+        // Horizon round trips
+        chains::stellar::demo::maybe_run_startup_demo(&stellar_client).await;
+
         Some(stellar_client)
     };
 
     // Initialize health checker
     info!("🏥 Initializing health checker...");
     let warming_state = WarmingState::new();
+    let startup_state = crate::health::StartupState::new();
     let health_checker =
         HealthChecker::new(db_pool.clone(), redis_cache.clone(), stellar_client.clone())
-            .with_warming_state(warming_state.clone());
+            .with_warming_state(warming_state.clone())
+            .with_startup_state(startup_state.clone())
+            .with_externals_skipped(skip_externals);
         HealthChecker::new(db_pool.clone(), redis_cache.clone(), stellar_client.clone());
 
     // Spawn background task to update DB pool connection gauge every 15 seconds
@@ -481,6 +508,43 @@ async fn main() -> anyhow::Result<()> {
         info!("Offramp processor worker disabled (OFFRAMP_PROCESSOR_ENABLED=false)");
     }
 
+    // Start Memo Deposit Reconciliation Worker
+    let memo_deposit_enabled = std::env::var("MEMO_DEPOSIT_WORKER_ENABLED")
+        .unwrap_or_else(|_| "true".to_string())
+        .to_lowercase()
+        != "false";
+    let mut memo_deposit_handle = None;
+    if memo_deposit_enabled {
+        if let (Some(pool), Some(client)) = (db_pool.clone(), stellar_client.clone()) {
+            match workers::memo_deposit_reconciler::MemoDepositConfig::from_env(client.network()) {
+                Some(config) => {
+                    info!(
+                        deposit_address = %config.deposit_address,
+                        poll_interval_secs = config.poll_interval.as_secs(),
+                        batch_size = config.batch_size,
+                        "Starting memo deposit reconciliation worker"
+                    );
+                    let reconciler = workers::memo_deposit_reconciler::MemoDepositReconciler::new(
+                        pool,
+                        notification_service.clone(),
+                        config,
+                    );
+                    let worker =
+                        workers::memo_deposit_reconciler::MemoDepositWorker::new(client, reconciler);
+                    memo_deposit_handle =
+                        Some(tokio::spawn(worker.run(worker_shutdown_rx.clone())));
+                }
+                None => info!(
+                    "Skipping memo deposit reconciliation worker (MEMO_DEPOSIT_ADDRESS not set)"
+                ),
+            }
+        } else {
+            info!("Skipping memo deposit reconciliation worker (missing db pool or stellar client)");
+        }
+    } else {
+        info!("Memo deposit reconciliation worker disabled (MEMO_DEPOSIT_WORKER_ENABLED=false)");
+    }
+
     // Start Stellar Confirmation Polling Worker
     let stellar_confirm_enabled = std::env::var("STELLAR_CONFIRM_WORKER_ENABLED")
         .unwrap_or_else(|_| "true".to_string())
@@ -636,6 +700,21 @@ async fn main() -> anyhow::Result<()> {
         info!("Payment poller worker disabled (PAYMENT_POLLER_ENABLED=false)");
     }
 
+    // Start Database Maintenance Worker
+    let mut maintenance_handle = None;
+    if maintenance_enabled {
+        if let Some(pool) = db_pool.clone() {
+            let config = workers::maintenance::MaintenanceConfig::from_env();
+            let worker =
+                workers::maintenance::MaintenanceWorker::new(pool, redis_cache.clone(), config);
+            maintenance_handle = Some(tokio::spawn(worker.run(worker_shutdown_rx.clone())));
+        } else {
+            info!("⏭️  Skipping database maintenance worker (missing db pool)");
+        }
+    } else {
+        info!("Database maintenance worker disabled (MAINTENANCE_WORKER_ENABLED=false)");
+    }
+
     // Initialize webhook processor and retry worker
     let webhook_routes = if let Some(pool) = db_pool.clone() {
         let webhook_repo = std::sync::Arc::new(
@@ -1184,6 +1263,63 @@ async fn main() -> anyhow::Result<()> {
         Router::new()
     };
 
+    // ── Admin provider credential rotation ───────────────────────────────────
+    let credential_routes = match PaymentProviderFactory::from_env() {
+        Ok(factory) => Router::new()
+            .route(
+                "/api/admin/payments/providers/{provider}/credentials",
+                post(api::admin::credentials::rotate_provider_credentials),
+            )
+            .with_state(api::admin::credentials::CredentialRotationState {
+                factory: std::sync::Arc::new(factory),
+            }),
+        Err(e) => {
+            tracing::warn!("⏭️  Skipping credential rotation routes: {}", e);
+            Router::new()
+        }
+    };
+
+    // ── Admin token mint/burn ────────────────────────────────────────────────
+    let token_admin_routes = match token_admin_state(stellar_client.as_ref()) {
+        Some(Ok(state)) => Router::new()
+            .route("/api/admin/afri/mint", post(api::admin::token::mint))
+            .route("/api/admin/afri/burn", post(api::admin::token::burn))
+            .route("/api/admin/afri/ttl/bump", post(api::admin::token::bump_balance_ttl))
+            .with_state(state),
+        Some(Err(e)) => {
+            tracing::warn!("⏭️  Skipping token admin routes: {}", e);
+            Router::new()
+        }
+        None => {
+            info!("Skipping token admin routes (no Soroban contract or admin key configured)");
+            Router::new()
+        }
+    };
+
+    let rate_limit_config = std::sync::Arc::new(crate::middleware::rate_limit::RateLimitConfig::load("rate_limits.yaml").unwrap_or_else(|e| {
+        tracing::warn!("Failed to load rate_limits.yaml, using defaults: {}", e);
+        crate::middleware::rate_limit::RateLimitConfig {
+            endpoints: std::collections::HashMap::new(),
+            default: crate::middleware::rate_limit::EndpointLimits {
+                per_ip: Some(crate::middleware::rate_limit::LimitConfig { limit: 100, window: 60 }),
+                per_wallet: None,
+            }
+        }
+    }));
+
+    // ── Admin configuration dump ─────────────────────────────────────────────
+    let admin_config_routes = Router::new()
+        .route("/api/admin/config", get(api::admin::config::get_config))
+        .with_state(api::admin::config::AdminConfigState {
+            config: std::sync::Arc::new(app_config.clone()),
+            rate_limits: rate_limit_config.clone(),
+            dependencies: api::admin::config::DependencyStatus {
+                database: db_pool.is_some(),
+                cache: redis_cache.is_some(),
+                stellar: stellar_client.is_some(),
+            },
+        });
+
     // ── Admin scope management routes (Issue #132) ───────────────────────────
     let admin_routes = if let Some(pool) = db_pool.clone() {
         let scopes_state = api::admin::scopes::ScopesState {
@@ -1263,6 +1399,27 @@ async fn main() -> anyhow::Result<()> {
                     )
                     .with_state(ip_reputation_state),
             )
+            .merge(
+                Router::new()
+                    .route(
+                        "/api/fees/structures/batch",
+                        post(api::admin::fees::create_fee_structure_batch),
+                    )
+                    .route(
+                        "/api/admin/fees/structures/{id}/deactivate",
+                        post(api::admin::fees::deactivate_fee_structure),
+                    )
+                    .with_state(api::admin::fees::AdminFeesState {
+                        db: std::sync::Arc::new(pool.clone()),
+                    }),
+            )
+            .merge(
+                Router::new()
+                    .route("/api/admin/audit-log", get(api::admin::audit_log::list_audit_log))
+                    .with_state(api::admin::audit_log::AuditLogState {
+                        db: std::sync::Arc::new(pool.clone()),
+                    }),
+            )
     } else {
         info!("Skipping admin routes (no database)");
         Router::new()
@@ -1281,7 +1438,10 @@ async fn main() -> anyhow::Result<()> {
                 loop { ticker.tick().await; s.sync_cdn_blocklist().await; }
             });
         }
-        let routes = ddos::admin::ddos_admin_router(state.clone());
+        let routes = ddos::admin::ddos_admin_router(
+            state.clone(),
+            db_pool.clone().map(std::sync::Arc::new),
+        );
         info!("✅ DDoS protection enabled");
         (Some(state), routes)
     } else {
@@ -1289,6 +1449,55 @@ async fn main() -> anyhow::Result<()> {
         (None, Router::new())
     };
 
+    // ── Admin guard: every route above requires an admin JWT or API key ──────
+    let admin_guard_state = {
+        let jwt_secret = std::env::var("JWT_SECRET").unwrap_or_default();
+        (jwt_secret.len() >= 32).then(|| middleware::admin::AdminGuardState {
+            jwt_secret,
+            db: db_pool.clone().map(std::sync::Arc::new),
+            redis_cache: redis_cache.clone(),
+        })
+    };
+    let admin_routes = if let Some(guard_state) = admin_guard_state.clone() {
+        Router::new()
+            .merge(admin_routes)
+            .merge(credential_routes)
+            .merge(token_admin_routes)
+            .merge(admin_config_routes)
+            .merge(ddos_admin_routes)
+            .route_layer(axum::middleware::from_fn_with_state(
+                guard_state,
+                middleware::admin::require_admin,
+            ))
+    } else {
+        info!("⏭️  Skipping admin routes (JWT_SECRET not set or too short)");
+        Router::new()
+    };
+
+    // ── Admin reporting routes served from the shared app state ──────────────
+    let app_admin_routes: Router<AppState> = if let Some(guard_state) = admin_guard_state.clone() {
+        Router::new()
+            .route("/api/admin/fees/structures", get(list_fee_structures))
+            .route("/api/admin/conversions/audits", get(list_conversion_audits))
+            .route(
+                "/api/conversions/discrepancies",
+                get(list_conversion_discrepancies),
+            )
+            .route("/api/conversions/feed", get(stream_conversion_feed))
+            .route_layer(axum::middleware::from_fn_with_state(
+                guard_state,
+                middleware::admin::require_admin,
+            ))
+    } else {
+        // These need the admin guard, so without it they are left out entirely
+        tracing::warn!(
+            routes = "/api/admin/fees/structures, /api/admin/conversions/audits, \
+                      /api/conversions/discrepancies, /api/conversions/feed",
+            "Admin reporting routes not registered (JWT_SECRET not set or too short)"
+        );
+        Router::new()
+    };
+
     // ── Key rotation routes (Issue #137) ─────────────────────────────────────
     let key_rotation_routes = if let Some(pool) = db_pool.clone() {
         let rotation_state = api::key_rotation::KeyRotationState {
@@ -1317,6 +1526,32 @@ async fn main() -> anyhow::Result<()> {
         Router::new()
     };
 
+    // ── Deposit memo issuance: authenticated, bound to the caller's wallet ──
+    let deposit_memo_routes = match (
+        db_pool.clone(),
+        std::env::var("JWT_SECRET").ok().filter(|s| s.len() >= 32),
+    ) {
+        (Some(pool), Some(jwt_secret)) => Router::new()
+            .route(
+                "/api/deposits/memo",
+                post(api::deposit_memos::issue_deposit_memo),
+            )
+            .with_state(api::deposit_memos::DepositMemoState {
+                db: std::sync::Arc::new(pool),
+            })
+            .route_layer(axum::middleware::from_fn_with_state(
+                auth::middleware::AuthState {
+                    jwt_secret,
+                    redis_cache: redis_cache.clone(),
+                },
+                auth::middleware::require_auth,
+            )),
+        _ => {
+            info!("⏭️  Skipping deposit memo routes (no database or JWT_SECRET)");
+            Router::new()
+        }
+    };
+
     // ── OpenAPI / Swagger UI (Issue #114) ────────────────────────────────────
     let openapi_routes = api::openapi::openapi_routes();
 
@@ -1355,6 +1590,23 @@ async fn main() -> anyhow::Result<()> {
         .route("/health/live", get(liveness))
         .route("/metrics", get(metrics::handler::metrics_handler))
         .route("/api/stellar/account/{address}", get(get_stellar_account))
+        .route(
+            "/api/stellar/account/{address}/risk",
+            get(get_stellar_account_risk),
+        )
+        .route(
+            "/api/stellar/account/{address}/payments",
+            get(list_stellar_account_payments),
+        )
+        .route(
+            "/api/stellar/account/{address}/pools",
+            get(list_stellar_account_pools),
+        )
+        .route("/api/stellar/network/eta", get(estimate_stellar_confirmation))
+        .route(
+            "/api/stellar/addresses/validate",
+            post(validate_stellar_addresses),
+        )
         .route(
             "/api/trustlines/operations",
             post(create_trustline_operation),
@@ -1368,11 +1620,16 @@ async fn main() -> anyhow::Result<()> {
             get(list_trustline_operations_by_wallet),
         )
         .route("/api/fees/calculate", post(calculate_fee))
+        .route("/api/fees/{fee_type}/timeline", get(get_fee_timeline))
         .route("/api/cngn/trustlines/check", post(check_cngn_trustline))
         .route(
             "/api/cngn/trustlines/preflight",
             post(preflight_cngn_trustline),
         )
+        .route(
+            "/api/cngn/trustlines/limit",
+            post(validate_cngn_trustline_limit),
+        )
         .route("/api/cngn/trustlines/build", post(build_cngn_trustline))
         .route("/api/cngn/trustlines/submit", post(submit_cngn_trustline))
         .route(
@@ -1382,6 +1639,33 @@ async fn main() -> anyhow::Result<()> {
         .route("/api/cngn/payments/build", post(build_cngn_payment))
         .route("/api/cngn/payments/sign", post(sign_cngn_payment))
         .route("/api/cngn/payments/submit", post(submit_cngn_payment))
+        .route("/api/afri/transactions/submit", post(submit_signed_transaction))
+        .route(
+            "/api/afri/payments/batch-submit",
+            post(batch_submit_signed_payments),
+        )
+        .route(
+            "/api/afri/transactions/{hash}/stream",
+            get(stream_transaction_status),
+        )
+        .route("/api/afri/onboarding/status", post(get_onboarding_status))
+        .route("/api/afri/deposit", post(prepare_afri_deposit))
+        .route("/api/afri/issuer/flags", get(get_afri_issuer_flags))
+        .route("/api/afri/accounts/merge", post(build_account_merge))
+        .route(
+            "/api/afri/wallet/{address}/overview",
+            get(get_afri_wallet_overview),
+        )
+        .route("/api/afri/payments/decode", post(decode_cngn_envelope))
+        .route("/api/cngn/payments/decode", post(decode_cngn_envelope))
+        .route(
+            "/api/afri/transactions/bump-sequence",
+            post(build_cngn_bump_sequence),
+        )
+        .route(
+            "/api/cngn/transactions/bump-sequence",
+            post(build_cngn_bump_sequence),
+        )
         .route("/api/payments/initiate", post(initiate_payment))
         .merge(onramp_routes)
         .merge(offramp_routes)
@@ -1393,6 +1677,7 @@ async fn main() -> anyhow::Result<()> {
         .merge(auth_routes)
         .merge(batch_routes)
         .merge(admin_routes)
+        .merge(app_admin_routes.clone())
         .merge(key_rotation_routes)
         .merge(openapi_routes)
         .merge(recurring_routes)
@@ -1403,6 +1688,23 @@ async fn main() -> anyhow::Result<()> {
         .route("/health/live", get(liveness))
         .route("/metrics", get(metrics::handler::metrics_handler))
         .route("/api/stellar/account/{address}", get(get_stellar_account))
+        .route(
+            "/api/stellar/account/{address}/risk",
+            get(get_stellar_account_risk),
+        )
+        .route(
+            "/api/stellar/account/{address}/payments",
+            get(list_stellar_account_payments),
+        )
+        .route(
+            "/api/stellar/account/{address}/pools",
+            get(list_stellar_account_pools),
+        )
+        .route("/api/stellar/network/eta", get(estimate_stellar_confirmation))
+        .route(
+            "/api/stellar/addresses/validate",
+            post(validate_stellar_addresses),
+        )
         .route(
             "/api/trustlines/operations",
             post(create_trustline_operation),
@@ -1416,11 +1718,16 @@ async fn main() -> anyhow::Result<()> {
             get(list_trustline_operations_by_wallet),
         )
         .route("/api/fees/calculate", post(calculate_fee))
+        .route("/api/fees/{fee_type}/timeline", get(get_fee_timeline))
         .route("/api/cngn/trustlines/check", post(check_cngn_trustline))
         .route(
             "/api/cngn/trustlines/preflight",
             post(preflight_cngn_trustline),
         )
+        .route(
+            "/api/cngn/trustlines/limit",
+            post(validate_cngn_trustline_limit),
+        )
         .route("/api/cngn/trustlines/build", post(build_cngn_trustline))
         .route("/api/cngn/trustlines/submit", post(submit_cngn_trustline))
         .route(
@@ -1430,6 +1737,33 @@ async fn main() -> anyhow::Result<()> {
         .route("/api/cngn/payments/build", post(build_cngn_payment))
         .route("/api/cngn/payments/sign", post(sign_cngn_payment))
         .route("/api/cngn/payments/submit", post(submit_cngn_payment))
+        .route("/api/afri/transactions/submit", post(submit_signed_transaction))
+        .route(
+            "/api/afri/payments/batch-submit",
+            post(batch_submit_signed_payments),
+        )
+        .route(
+            "/api/afri/transactions/{hash}/stream",
+            get(stream_transaction_status),
+        )
+        .route("/api/afri/onboarding/status", post(get_onboarding_status))
+        .route("/api/afri/deposit", post(prepare_afri_deposit))
+        .route("/api/afri/issuer/flags", get(get_afri_issuer_flags))
+        .route("/api/afri/accounts/merge", post(build_account_merge))
+        .route(
+            "/api/afri/wallet/{address}/overview",
+            get(get_afri_wallet_overview),
+        )
+        .route("/api/afri/payments/decode", post(decode_cngn_envelope))
+        .route("/api/cngn/payments/decode", post(decode_cngn_envelope))
+        .route(
+            "/api/afri/transactions/bump-sequence",
+            post(build_cngn_bump_sequence),
+        )
+        .route(
+            "/api/cngn/transactions/bump-sequence",
+            post(build_cngn_bump_sequence),
+        )
         .route("/api/payments/initiate", post(initiate_payment))
         .merge(onramp_routes)
         .merge(offramp_routes)
@@ -1441,20 +1775,31 @@ async fn main() -> anyhow::Result<()> {
         .merge(auth_routes)
         .merge(batch_routes)
         .merge(admin_routes)
+        .merge(app_admin_routes)
         .merge(key_rotation_routes)
         .merge(openapi_routes)
         .merge(recurring_routes)
         .merge(developer_routes)
+        .merge(deposit_memo_routes)
         .merge(oauth_routes)
         .merge(history_routes)
-        .merge(ddos_admin_routes)
         .merge(developer_portal::routes::register_developer_portal_routes(Router::new(), db_pool.clone()))
+        .fallback(crate::middleware::error::not_found_fallback)
+        .method_not_allowed_fallback(crate::middleware::error::method_not_allowed_fallback)
+        .layer(axum::middleware::from_fn(
+            crate::middleware::error::normalize_rejections_middleware,
+        ))
         .with_state(AppState {
+            account_cache: stellar_client.clone().map(|client| {
+                services::account_cache::AccountCache::new(client, redis_cache.clone())
+            }),
             db_pool,
             redis_cache,
             stellar_client,
             health_checker,
             warming_state: Some(warming_state),
+            shutdown: worker_shutdown_rx.clone(),
+            rate_limits: rate_limit_config.clone(),
         });
 
     // Apply middleware conditionally based on available services
@@ -1522,17 +1867,6 @@ async fn main() -> anyhow::Result<()> {
         )
     };
 
-    let rate_limit_config = std::sync::Arc::new(crate::middleware::rate_limit::RateLimitConfig::load("rate_limits.yaml").unwrap_or_else(|e| {
-        tracing::warn!("Failed to load rate_limits.yaml, using defaults: {}", e);
-        crate::middleware::rate_limit::RateLimitConfig {
-            endpoints: std::collections::HashMap::new(),
-            default: crate::middleware::rate_limit::EndpointLimits {
-                per_ip: Some(crate::middleware::rate_limit::LimitConfig { limit: 100, window: 60 }),
-                per_wallet: None,
-            }
-        }
-    }));
-
     let app = if let Some(cache) = redis_cache.clone() {
 
         let rate_limit_state = crate::middleware::rate_limit::RateLimitState {
@@ -1565,6 +1899,11 @@ async fn main() -> anyhow::Result<()> {
         app
     };
 
+    // Outermost, so rate-limit and DDoS rejections get the envelope too
+    let app = app.layer(axum::middleware::from_fn_with_state(
+        crate::middleware::envelope::EnvelopeConfig::from_env(),
+        crate::middleware::envelope::response_envelope_middleware,
+    ));
 
     info!("✅ Routes configured");
 
@@ -1575,6 +1914,9 @@ async fn main() -> anyhow::Result<()> {
         error!("❌ Failed to bind to address {}: {}", addr, e);
         e
     })?;
+    // Dependencies are initialized and the port is open; only now can the
+    // startup probe report ready
+    startup_state.mark_complete();
 
     // Print a prominent banner with server information
     println!("\n╔══════════════════════════════════════════════════════════════╗");
@@ -1625,10 +1967,22 @@ async fn main() -> anyhow::Result<()> {
     );
     info!("✅ Server is ready to accept connections");
 
-    axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal_with_notify(worker_shutdown_tx.clone()))
-        .await
-        .unwrap();
+    let shutdown_timeout = shutdown::shutdown_timeout_from_env();
+    match shutdown::serve_with_shutdown_timeout(
+        listener,
+        app,
+        shutdown_signal_with_notify(worker_shutdown_tx.clone()),
+        shutdown_timeout,
+    )
+    .await?
+    {
+        shutdown::ShutdownOutcome::Graceful => info!("All in-flight requests drained"),
+        shutdown::ShutdownOutcome::TimedOut { aborted } => error!(
+            aborted = aborted.len(),
+            timeout_secs = shutdown_timeout.as_secs(),
+            "Server stopped with requests still in flight"
+        ),
+    }
 
     let _ = worker_shutdown_tx.send(true);
     if let Some(handle) = monitor_handle {
@@ -1641,14 +1995,30 @@ async fn main() -> anyhow::Result<()> {
             error!(error = %e, "Timed out waiting for offramp worker shutdown");
         }
     }
+    if let Some(handle) = memo_deposit_handle {
+        if let Err(e) = tokio::time::timeout(std::time::Duration::from_secs(5), handle).await {
+            error!(error = %e, "Timed out waiting for memo deposit worker shutdown");
+        }
+    }
+    if let Some(handle) = maintenance_handle {
+        if let Err(e) = tokio::time::timeout(std::time::Duration::from_secs(5), handle).await {
+            error!(error = %e, "Timed out waiting for maintenance worker shutdown");
+        }
+    }
 
     info!("👋 Server shutdown complete");
 
     // -------------------------------------------------------------------------
     // Flush all buffered spans to the OTLP exporter before the process exits.
     // Must be the very last call so no spans are lost during shutdown.   (Issue #104)
+    // Prometheus is scraped rather than pushed, so there is nothing to flush
+    // there; log what the final scrape would have seen.
     // -------------------------------------------------------------------------
-    shutdown_tracer();
+    info!(
+        metric_series = metrics::series_count(),
+        "Prometheus registry final state"
+    );
+    shutdown_tracer(shutdown::telemetry_flush_timeout_from_env()).await;
 
     Ok(())
 }
@@ -1661,6 +2031,11 @@ struct AppState {
     stellar_client: Option<StellarClient>,
     health_checker: HealthChecker,
     warming_state: Option<WarmingState>,
+    /// Flips to true on shutdown so long-lived streams can end
+    shutdown: watch::Receiver<bool>,
+    rate_limits: std::sync::Arc<crate::middleware::rate_limit::RateLimitConfig>,
+    /// Present whenever `stellar_client` is
+    account_cache: Option<services::account_cache::AccountCache>,
 }
 
 // Handlers
@@ -1691,16 +2066,20 @@ async fn health(
 /// Readiness probe - checks if the service is ready to accept traffic
 async fn readiness(
     axum::extract::State(state): axum::extract::State<AppState>,
-) -> Result<Json<HealthStatus>, (axum::http::StatusCode, String)> {
+) -> (axum::http::StatusCode, Json<crate::health::ReadinessReport>) {
     info!("🔍 Readiness probe requested");
-    // Readiness checks all dependencies
-    let result = health(axum::extract::State(state)).await;
-    if result.is_ok() {
+    let report = state.health_checker.check_readiness().await;
+    if report.is_ready() {
         info!("✅ Readiness check passed");
+        (axum::http::StatusCode::OK, Json(report))
     } else {
-        error!("❌ Readiness check failed");
+        error!(
+            status = ?report.status,
+            component = ?report.component,
+            "❌ Readiness check failed"
+        );
+        (axum::http::StatusCode::SERVICE_UNAVAILABLE, Json(report))
     }
-    result
 }
 
 /// Liveness probe - checks if the service is alive (basic check)
@@ -1713,59 +2092,49 @@ async fn liveness() -> Result<&'static str, (axum::http::StatusCode, String)> {
 
 async fn get_stellar_account(
     axum::extract::State(state): axum::extract::State<AppState>,
-    axum::extract::Path(address): axum::extract::Path<String>,
-) -> Result<String, (axum::http::StatusCode, String)> {
+    address: crate::api::validation::ValidStellarAddress,
+    headers: axum::http::HeaderMap,
+) -> Result<([(&'static str, &'static str); 1], String), crate::middleware::error::HandlerError> {
+    let address = address.account_id();
     info!(address = %address, "🔍 Stellar account lookup requested");
 
-    let stellar_client = match state.stellar_client.as_ref() {
-        Some(client) => client,
+    let request_id = crate::middleware::error::get_request_id_from_headers(&headers);
+    // Shared across requests so a burst for one account makes one Horizon call
+    let account_cache = match state.account_cache.as_ref() {
+        Some(cache) => cache,
         None => {
-            return Err((
-                axum::http::StatusCode::SERVICE_UNAVAILABLE,
-                "Stellar client disabled by configuration".to_string(),
-            ))
+            return Err(crate::middleware::error::dependency_disabled_response(
+                "Stellar client",
+                request_id,
+            )
+            .into())
         }
     };
 
-    match stellar_client.account_exists(&address).await {
-        Ok(exists) => {
-            if exists {
-                info!(address = %address, "✅ Account exists, fetching details");
-                match stellar_client.get_account(&address).await {
-                    Ok(account) => {
-                        info!(
-                            address = %address,
-                            balances = account.balances.len(),
-                            "✅ Account details fetched successfully"
-                        );
-                        Ok(format!(
-                            "Account: {}, Balances: {}",
-                            account.account_id,
-                            account.balances.len()
-                        ))
-                    }
-                    Err(e) => {
-                        error!(address = %address, error = %e, "❌ Failed to fetch account details");
-                        Err((
-                            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-                            format!("Failed to fetch account: {}", e),
-                        ))
-                    }
-                }
-            } else {
-                info!(address = %address, "ℹ️  Account not found");
-                Err((
-                    axum::http::StatusCode::NOT_FOUND,
-                    "Account not found".to_string(),
-                ))
-            }
+    match account_cache.lookup(&address).await {
+        Ok((account, freshness)) => {
+            info!(
+                address = %address,
+                balances = account.balances.len(),
+                freshness = freshness.as_str(),
+                "✅ Account details fetched successfully"
+            );
+            Ok((
+                [(services::balance::DATA_FRESHNESS_HEADER, freshness.as_str())],
+                format!(
+                    "Account: {}, Balances: {}",
+                    account.account_id,
+                    account.balances.len()
+                ),
+            ))
+        }
+        Err(e @ chains::stellar::errors::StellarError::AccountNotFound { .. }) => {
+            info!(address = %address, "ℹ️  Account not found");
+            Err(app_error_response(e.into(), request_id))
         }
         Err(e) => {
-            error!(address = %address, error = %e, "❌ Error checking account existence");
-            Err((
-                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Error checking account: {}", e),
-            ))
+            error!(address = %address, error = %e, "❌ Failed to fetch account details");
+            Err(app_error_response(e.into(), request_id))
         }
     }
 }
@@ -1792,6 +2161,66 @@ struct TrustlineOperationStatusUpdate {
 #[derive(Debug, Deserialize)]
 struct TrustlineOperationQuery {
     limit: Option<i64>,
+    /// Only operations on this asset
+    asset_code: Option<String>,
+    /// Narrows `asset_code` to one issuer; requires `asset_code`
+    issuer: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AccountPaymentsQuery {
+    limit: Option<usize>,
+    cursor: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ConfirmationEtaQuery {
+    /// Fee offered per operation; defaults to the `percentile` bid
+    fee_stroops: Option<u32>,
+    /// Percentile of recent max-fee bids to price at when no fee is given
+    percentile: Option<u8>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AddressValidationRequest {
+    addresses: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ConversionDiscrepancyQuery {
+    tolerance_bps: Option<i64>,
+    limit: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ConversionFeedQuery {
+    /// Recent conversions to send before live ones; ignored when resuming
+    /// with `Last-Event-ID`
+    backfill: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListQueryParams {
+    limit: Option<i64>,
+    offset: Option<i64>,
+    sort: Option<String>,
+    order: Option<crate::database::repository::SortOrder>,
+}
+
+impl ListQueryParams {
+    fn into_list_query<R: crate::database::repository::PaginatedRepository>(
+        self,
+    ) -> Result<
+        crate::database::repository::ListQuery,
+        crate::database::repository::InvalidSortField,
+    > {
+        crate::database::repository::ListQuery::for_repository::<R>(
+            self.limit,
+            self.offset,
+            self.sort.as_deref(),
+            self.order,
+        )
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -1832,36 +2261,25 @@ impl TrustlineOperationStatus {
 
 #[derive(Debug, Deserialize)]
 struct FeeCalculationRequest {
-    fee_type: FeeType,
+    /// Parsed by the fee service so an unknown type gets the supported list back
+    fee_type: String,
     amount: String,
     currency: Option<String>,
-}
-
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "snake_case")]
-enum FeeType {
-    Onramp,
-    Offramp,
-    BillPayment,
-    Exchange,
-    Transfer,
-}
-
-impl FeeType {
-    fn as_str(&self) -> &'static str {
-        match self {
-            FeeType::Onramp => "onramp",
-            FeeType::Offramp => "offramp",
-            FeeType::BillPayment => "bill_payment",
-            FeeType::Exchange => "exchange",
-            FeeType::Transfer => "transfer",
-        }
-    }
+    /// Defaults to `exclusive`: the fee is charged on top of `amount`
+    #[serde(default)]
+    fee_mode: crate::services::fee_structure::FeeMode,
+    /// Reconstruct the fee as charged at this past moment, including
+    /// structures that have since been superseded
+    #[serde(default)]
+    at_time: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 #[derive(Debug, Serialize)]
 struct FeeCalculationResponse {
     fee: String,
+    fee_mode: crate::services::fee_structure::FeeMode,
+    net_amount: String,
+    gross_amount: String,
     rate_bps: i32,
     flat_fee: String,
     min_fee: Option<String>,
@@ -1912,7 +2330,12 @@ struct CngnPaymentBuildRequest {
     destination: String,
     amount: String,
     memo: Option<crate::chains::stellar::payment::CngnMemo>,
+    /// Deposit memo id issued by `POST /api/deposits/memo`; sent as the
+    /// transaction's memo id
+    deposit_account_id: Option<u64>,
     fee_stroops: Option<u32>,
+    #[serde(default)]
+    skip_balance_precheck: bool,
 }
 
 #[derive(Debug, Deserialize)]
@@ -1925,18 +2348,70 @@ struct CngnPaymentSignRequest {
 struct CngnPaymentSubmitRequest {
     signed_envelope_xdr: String,
     transaction_id: Option<String>,
+    /// Validate everything but skip the Horizon submission
+    #[serde(default)]
+    dry_run: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct OnboardingStatusRequest {
+    addresses: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SignedTransactionSubmitRequest {
+    /// Fully signed envelope; the backend never sees the signing key
+    envelope_xdr: String,
+    #[serde(default)]
+    wait_for_confirmation: bool,
+    /// Fee the client expected to pay, in stroops; flagged in the response
+    /// when Horizon charged more
+    estimated_fee_stroops: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BatchSubmitRequest {
+    /// Fully signed, independent envelopes; each succeeds or fails on its own
+    envelopes: Vec<String>,
 }
 
 #[derive(Debug, Serialize)]
 struct CngnPaymentBuildResponse {
     draft: crate::chains::stellar::payment::CngnPaymentDraft,
     transaction_id: Option<String>,
+    /// Hash the transaction will carry on-chain once signed
+    transaction_hash: String,
+    /// Base64 XDR of the signature payload; its SHA-256 is `transaction_hash`
+    signature_base_xdr: String,
 }
 
-#[derive(Debug, Serialize)]
-struct CngnPaymentSubmitResponse {
-    horizon_response: serde_json::Value,
-    transaction_id: Option<String>,
+#[derive(Debug, Deserialize)]
+struct EnvelopeDecodeRequest {
+    envelope_xdr: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct BumpSequenceRequest {
+    source: String,
+    bump_to: i64,
+    fee_stroops: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AccountMergeRequest {
+    source: String,
+    destination: String,
+    fee_stroops: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+struct CngnPaymentSubmitResponse {
+    /// `null` for dry runs
+    horizon_response: serde_json::Value,
+    transaction_id: Option<String>,
+    submitted: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dry_run: Option<crate::chains::stellar::payment::PaymentDryRun>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -1952,26 +2427,343 @@ struct InitiatePaymentApiRequest {
     provider: Option<String>,
 }
 
+async fn list_stellar_account_payments(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    address: crate::api::validation::ValidStellarAddress,
+    axum::extract::Query(query): axum::extract::Query<AccountPaymentsQuery>,
+    headers: axum::http::HeaderMap,
+) -> Result<
+    Json<crate::chains::stellar::client::HorizonPaymentsPage>,
+    crate::middleware::error::HandlerError,
+> {
+    let address = address.account_id();
+    let request_id = crate::middleware::error::get_request_id_from_headers(&headers);
+    let stellar_client = match state.stellar_client.as_ref() {
+        Some(client) => client,
+        None => {
+            return Err(crate::middleware::error::dependency_disabled_response(
+                "Stellar client",
+                request_id,
+            )
+            .into())
+        }
+    };
+
+    let limit = query.limit.unwrap_or(50).clamp(1, 200);
+    stellar_client
+        .list_account_payments(&address, limit, query.cursor.as_deref())
+        .await
+        .map(Json)
+        .map_err(|e| app_error_response(e.into(), request_id))
+}
+
+/// Liquidity pool shares held by an account, one entry per pool.
+async fn list_stellar_account_pools(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    address: crate::api::validation::ValidStellarAddress,
+    headers: axum::http::HeaderMap,
+) -> Result<
+    Json<Vec<crate::chains::stellar::types::PoolShareBalance>>,
+    crate::middleware::error::HandlerError,
+> {
+    let address = address.account_id();
+    let request_id = crate::middleware::error::get_request_id_from_headers(&headers);
+    let account_cache = match state.account_cache.as_ref() {
+        Some(cache) => cache,
+        None => {
+            return Err(crate::middleware::error::dependency_disabled_response(
+                "Stellar client",
+                request_id,
+            )
+            .into())
+        }
+    };
+
+    account_cache
+        .get_account(&address)
+        .await
+        .map(|account| {
+            Json(crate::chains::stellar::types::extract_pool_share_balances(
+                &account.balances,
+            ))
+        })
+        .map_err(|e| app_error_response(e.into(), request_id))
+}
+
+/// AFRI/XLM balances and 30-day payment activity for a wallet in one call.
+async fn get_afri_wallet_overview(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    address: crate::api::validation::ValidStellarAddress,
+    headers: axum::http::HeaderMap,
+) -> Result<
+    Json<crate::services::wallet_overview::WalletOverview>,
+    crate::middleware::error::HandlerError,
+> {
+    let address = address.account_id();
+    let request_id = crate::middleware::error::get_request_id_from_headers(&headers);
+    let stellar_client = match state.stellar_client.as_ref() {
+        Some(client) => client,
+        None => {
+            return Err(crate::middleware::error::dependency_disabled_response(
+                "Stellar client",
+                request_id,
+            )
+            .into())
+        }
+    };
+
+    crate::services::wallet_overview::WalletOverviewService::new(
+        stellar_client.clone(),
+        state.redis_cache.clone(),
+    )
+    .get_overview(&address)
+    .await
+    .map(Json)
+    .map_err(|e| app_error_response(e.into(), request_id))
+}
+
+/// The AFRI issuer's account flags, so clients know before adding a
+/// trustline whether it will need the issuer's authorization.
+async fn get_afri_issuer_flags(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    headers: axum::http::HeaderMap,
+) -> Result<
+    Json<crate::chains::stellar::types::IssuerFlags>,
+    crate::middleware::error::HandlerError,
+> {
+    let request_id = crate::middleware::error::get_request_id_from_headers(&headers);
+    let stellar_client = match state.stellar_client.as_ref() {
+        Some(client) => client,
+        None => {
+            return Err(crate::middleware::error::dependency_disabled_response(
+                "Stellar client",
+                request_id,
+            )
+            .into())
+        }
+    };
+
+    stellar_client
+        .get_issuer_flags()
+        .await
+        .map(Json)
+        .map_err(|e| app_error_response(e.into(), request_id))
+}
+
+/// Trustline-then-payment deposit: tells the client to have the recipient add
+/// a trustline, or returns the unsigned payment once it exists. Each step is
+/// written to the trustline operation log when the database is available.
+async fn prepare_afri_deposit(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    headers: axum::http::HeaderMap,
+    Json(payload): Json<crate::services::afri_deposit::DepositRequest>,
+) -> Result<
+    Json<crate::services::afri_deposit::DepositOutcome>,
+    crate::middleware::error::HandlerError,
+> {
+    let request_id = crate::middleware::error::get_request_id_from_headers(&headers);
+    let stellar_client = match state.stellar_client.as_ref() {
+        Some(client) => client,
+        None => {
+            return Err(crate::middleware::error::dependency_disabled_response(
+                "Stellar client",
+                request_id,
+            )
+            .into())
+        }
+    };
+
+    if payload.source.trim().is_empty()
+        || payload.destination.trim().is_empty()
+        || payload.amount.trim().is_empty()
+    {
+        return Err(crate::middleware::error::json_error_response(
+            axum::http::StatusCode::BAD_REQUEST,
+            "source, destination and amount are required",
+            request_id,
+        )
+        .into());
+    }
+
+    let plan = crate::services::afri_deposit::AfriDepositService::new(stellar_client.clone())
+        .prepare(&payload)
+        .await
+        .map_err(|e| app_error_response(e.into(), request_id.clone()))?;
+
+    if let Some(pool) = state.db_pool.as_ref() {
+        let audit = crate::services::trustline_operation::TrustlineOperationService::new(
+            crate::database::trustline_operation_repository::TrustlineOperationRepository::new(
+                pool.clone(),
+            ),
+        );
+        for entry in plan.audit {
+            // The deposit itself doesn't depend on the log, so a failed write
+            // is reported rather than failing the request
+            if let Err(e) = audit.record(entry).await {
+                tracing::warn!(
+                    destination = %payload.destination,
+                    error = %e,
+                    "Failed to record deposit audit entry"
+                );
+            }
+        }
+    }
+
+    Ok(Json(plan.outcome))
+}
+
+/// Best-effort estimate of how long a transaction at the given fee will wait
+/// for inclusion, from recent ledger close times and `/fee_stats`.
+async fn estimate_stellar_confirmation(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    axum::extract::Query(query): axum::extract::Query<ConfirmationEtaQuery>,
+    headers: axum::http::HeaderMap,
+) -> Result<
+    Json<crate::chains::stellar::eta::ConfirmationEstimate>,
+    crate::middleware::error::HandlerError,
+> {
+    let request_id = crate::middleware::error::get_request_id_from_headers(&headers);
+    let stellar_client = match state.stellar_client.as_ref() {
+        Some(client) => client,
+        None => {
+            return Err(crate::middleware::error::dependency_disabled_response(
+                "Stellar client",
+                request_id,
+            )
+            .into())
+        }
+    };
+
+    let (fee_stats, ledgers) = tokio::try_join!(
+        stellar_client.get_fee_stats(),
+        stellar_client.get_recent_ledgers(20)
+    )
+    .map_err(|e| app_error_response(e.into(), request_id.clone()))?;
+
+    let fee_stroops = match query.fee_stroops {
+        Some(fee) => fee,
+        None => {
+            let percentile = query.percentile.unwrap_or(50);
+            match fee_stats.max_fee.percentile(percentile) {
+                Some(fee) => fee,
+                None => {
+                    return Err(crate::middleware::error::json_error_response(
+                        axum::http::StatusCode::BAD_REQUEST,
+                        "percentile must be one of 10, 20, ..., 90, 95, 99",
+                        request_id,
+                    )
+                    .into())
+                }
+            }
+        }
+    };
+
+    Ok(Json(crate::chains::stellar::eta::estimate_confirmation(
+        &ledgers,
+        &fee_stats,
+        fee_stroops,
+    )))
+}
+
+/// Checksum-validate up to 1000 addresses without touching Horizon, e.g.
+/// before submitting a batch payout.
+async fn validate_stellar_addresses(
+    headers: axum::http::HeaderMap,
+    Json(payload): Json<AddressValidationRequest>,
+) -> Result<
+    Json<Vec<crate::api::validation::AddressCheck>>,
+    crate::middleware::error::HandlerError,
+> {
+    use crate::api::validation::{AddressCheck, ArrayBounds};
+
+    let request_id = crate::middleware::error::get_request_id_from_headers(&headers);
+    ArrayBounds::new(1000)
+        .check("addresses", &payload.addresses)
+        .map_err(|e| {
+            (
+                axum::http::StatusCode::BAD_REQUEST,
+                Json(e.to_error_response(request_id.clone())),
+            )
+        })?;
+
+    Ok(Json(
+        payload
+            .addresses
+            .iter()
+            .map(|address| AddressCheck::of(address))
+            .collect(),
+    ))
+}
+
+async fn get_stellar_account_risk(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    address: crate::api::validation::ValidStellarAddress,
+    headers: axum::http::HeaderMap,
+) -> Result<
+    Json<crate::chains::stellar::risk::RiskAssessment>,
+    crate::middleware::error::HandlerError,
+> {
+    let address = address.account_id();
+    use crate::chains::stellar::risk::{assess, RiskConfig, RiskSignals};
+
+    let request_id = crate::middleware::error::get_request_id_from_headers(&headers);
+    let stellar_client = match state.stellar_client.as_ref() {
+        Some(client) => client,
+        None => {
+            return Err(crate::middleware::error::dependency_disabled_response(
+                "Stellar client",
+                request_id,
+            )
+            .into())
+        }
+    };
+
+    let config = RiskConfig::from_env();
+    let account = stellar_client
+        .get_account(&address)
+        .await
+        .map_err(|e| app_error_response(e.into(), request_id.clone()))?;
+
+    let now = chrono::Utc::now();
+    let window_start = now - chrono::Duration::days(config.recent_window_days);
+    let recent_transactions = stellar_client
+        .list_recent_account_transactions(&address, 200)
+        .await
+        .map_err(|e| app_error_response(e.into(), request_id.clone()))?
+        .records
+        .iter()
+        .filter_map(|tx| tx.created_at.as_deref())
+        .filter_map(|ts| chrono::DateTime::parse_from_rfc3339(ts).ok())
+        .filter(|created| created.with_timezone(&chrono::Utc) >= window_start)
+        .count() as u32;
+
+    let created_at = stellar_client
+        .account_created_at(&address)
+        .await
+        .map_err(|e| app_error_response(e.into(), request_id.clone()))?;
+
+    let signals =
+        RiskSignals::from_account(&account, created_at.as_deref(), recent_transactions, now);
+    Ok(Json(assess(signals, &config)))
+}
+
 async fn create_trustline_operation(
     axum::extract::State(state): axum::extract::State<AppState>,
     headers: axum::http::HeaderMap,
     Json(payload): Json<TrustlineOperationRequest>,
 ) -> Result<
     Json<crate::database::trustline_operation_repository::TrustlineOperation>,
-    (
-        axum::http::StatusCode,
-        Json<crate::middleware::error::ErrorResponse>,
-    ),
+    crate::middleware::error::HandlerError,
 > {
     let request_id = crate::middleware::error::get_request_id_from_headers(&headers);
     let pool = match state.db_pool.as_ref() {
         Some(pool) => pool,
         None => {
-            return Err(crate::middleware::error::json_error_response(
-                axum::http::StatusCode::SERVICE_UNAVAILABLE,
-                "Database disabled by configuration",
+            return Err(crate::middleware::error::dependency_disabled_response(
+                "Database",
                 request_id,
-            ))
+            )
+            .into())
         }
     };
 
@@ -1980,14 +2772,16 @@ async fn create_trustline_operation(
             axum::http::StatusCode::BAD_REQUEST,
             "wallet_address is required",
             request_id,
-        ));
+        )
+        .into());
     }
     if payload.asset_code.trim().is_empty() {
         return Err(crate::middleware::error::json_error_response(
             axum::http::StatusCode::BAD_REQUEST,
             "asset_code is required",
             request_id,
-        ));
+        )
+        .into());
     }
 
     let repo = crate::database::trustline_operation_repository::TrustlineOperationRepository::new(
@@ -2018,19 +2812,14 @@ async fn create_trustline_operation(
             e.to_string(),
             request_id,
         )
+        .into()
     })
 }
 
 async fn initiate_payment(
     headers: axum::http::HeaderMap,
     Json(payload): Json<InitiatePaymentApiRequest>,
-) -> Result<
-    Json<crate::payments::types::PaymentResponse>,
-    (
-        axum::http::StatusCode,
-        Json<crate::middleware::error::ErrorResponse>,
-    ),
-> {
+) -> Result<Json<crate::payments::types::PaymentResponse>, crate::middleware::error::HandlerError> {
     let request_id = crate::middleware::error::get_request_id_from_headers(&headers);
 
     if payload.transaction_reference.trim().is_empty() {
@@ -2038,14 +2827,16 @@ async fn initiate_payment(
             axum::http::StatusCode::BAD_REQUEST,
             "transaction_reference is required",
             request_id,
-        ));
+        )
+        .into());
     }
     if payload.email.as_deref().unwrap_or("").trim().is_empty() {
         return Err(crate::middleware::error::json_error_response(
             axum::http::StatusCode::BAD_REQUEST,
             "email is required for payment initialization",
             request_id,
-        ));
+        )
+        .into());
     }
 
     let payment_method = match payload
@@ -2133,20 +2924,17 @@ async fn update_trustline_operation_status(
     Json(payload): Json<TrustlineOperationStatusUpdate>,
 ) -> Result<
     Json<crate::database::trustline_operation_repository::TrustlineOperation>,
-    (
-        axum::http::StatusCode,
-        Json<crate::middleware::error::ErrorResponse>,
-    ),
+    crate::middleware::error::HandlerError,
 > {
     let request_id = crate::middleware::error::get_request_id_from_headers(&headers);
     let pool = match state.db_pool.as_ref() {
         Some(pool) => pool,
         None => {
-            return Err(crate::middleware::error::json_error_response(
-                axum::http::StatusCode::SERVICE_UNAVAILABLE,
-                "Database disabled by configuration",
+            return Err(crate::middleware::error::dependency_disabled_response(
+                "Database",
                 request_id,
-            ))
+            )
+            .into())
         }
     };
 
@@ -2178,48 +2966,52 @@ async fn update_trustline_operation_status(
                 e.to_string(),
                 request_id.clone(),
             )
+            .into()
         })
 }
 
 async fn list_trustline_operations_by_wallet(
     axum::extract::State(state): axum::extract::State<AppState>,
-    axum::extract::Path(address): axum::extract::Path<String>,
+    address: crate::api::validation::ValidStellarAddress,
     headers: axum::http::HeaderMap,
     axum::extract::Query(query): axum::extract::Query<TrustlineOperationQuery>,
 ) -> Result<
     Json<Vec<crate::database::trustline_operation_repository::TrustlineOperation>>,
-    (
-        axum::http::StatusCode,
-        Json<crate::middleware::error::ErrorResponse>,
-    ),
+    crate::middleware::error::HandlerError,
 > {
+    let address = address.account_id();
     let request_id = crate::middleware::error::get_request_id_from_headers(&headers);
     let pool = match state.db_pool.as_ref() {
         Some(pool) => pool,
         None => {
-            return Err(crate::middleware::error::json_error_response(
-                axum::http::StatusCode::SERVICE_UNAVAILABLE,
-                "Database disabled by configuration",
+            return Err(crate::middleware::error::dependency_disabled_response(
+                "Database",
                 request_id,
-            ))
+            )
+            .into())
         }
     };
 
-    if address.trim().is_empty() {
-        return Err(crate::middleware::error::json_error_response(
-            axum::http::StatusCode::BAD_REQUEST,
-            "wallet address is required",
-            request_id,
-        ));
-    }
-
     let repo = crate::database::trustline_operation_repository::TrustlineOperationRepository::new(
         pool.clone(),
     );
 
     let limit = query.limit.unwrap_or(50).clamp(1, 200);
-    repo.find_by_wallet(&address, limit)
-        .await
+    let operations = match (query.asset_code.as_deref(), query.issuer.as_deref()) {
+        (Some(asset_code), issuer) => {
+            repo.find_by_wallet_and_asset(&address, asset_code, issuer, limit).await
+        }
+        (None, Some(_)) => {
+            return Err(crate::middleware::error::json_error_response(
+                axum::http::StatusCode::BAD_REQUEST,
+                "issuer filter requires asset_code",
+                request_id,
+            )
+            .into())
+        }
+        (None, None) => repo.find_by_wallet(&address, limit).await,
+    };
+    operations
         .map(Json)
         .map_err(|e| {
             crate::middleware::error::json_error_response(
@@ -2227,9 +3019,257 @@ async fn list_trustline_operations_by_wallet(
                 e.to_string(),
                 request_id,
             )
+            .into()
         })
 }
 
+async fn list_fee_structures(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    headers: axum::http::HeaderMap,
+    axum::extract::Query(params): axum::extract::Query<ListQueryParams>,
+) -> Result<
+    Json<Vec<crate::database::fee_structure_repository::FeeStructure>>,
+    crate::middleware::error::HandlerError,
+> {
+    use crate::database::fee_structure_repository::FeeStructureRepository;
+    use crate::database::repository::PaginatedRepository;
+
+    let request_id = crate::middleware::error::get_request_id_from_headers(&headers);
+    let pool = match state.db_pool.as_ref() {
+        Some(pool) => pool,
+        None => {
+            return Err(crate::middleware::error::dependency_disabled_response(
+                "Database",
+                request_id,
+            )
+            .into())
+        }
+    };
+
+    let query = params
+        .into_list_query::<FeeStructureRepository>()
+        .map_err(|e| {
+            crate::middleware::error::json_error_response(
+                axum::http::StatusCode::BAD_REQUEST,
+                e.to_string(),
+                request_id.clone(),
+            )
+        })?;
+
+    FeeStructureRepository::new(pool.clone())
+        .find_page(&query)
+        .await
+        .map(Json)
+        .map_err(|e| app_error_response(e.into(), request_id))
+}
+
+async fn get_fee_timeline(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    headers: axum::http::HeaderMap,
+    axum::extract::Path(fee_type): axum::extract::Path<String>,
+) -> Result<
+    Json<Vec<crate::services::fee_structure::FeeTimelineEntry>>,
+    crate::middleware::error::HandlerError,
+> {
+    use crate::services::fee_structure::FeeStructureService;
+
+    let request_id = crate::middleware::error::get_request_id_from_headers(&headers);
+    let pool = match state.db_pool.as_ref() {
+        Some(pool) => pool,
+        None => {
+            return Err(crate::middleware::error::dependency_disabled_response(
+                "Database",
+                request_id,
+            )
+            .into())
+        }
+    };
+
+    let repo = crate::database::fee_structure_repository::FeeStructureRepository::new(pool.clone());
+    let service = FeeStructureService::new(repo);
+    let fee_type = fee_type
+        .parse::<crate::services::fee_structure::FeeType>()
+        .map_err(|e| unknown_fee_type_response(&e, request_id.clone()))?;
+
+    service
+        .timeline(fee_type)
+        .await
+        .map(Json)
+        .map_err(|e| app_error_response(e.into(), request_id))
+}
+
+async fn list_conversion_audits(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    headers: axum::http::HeaderMap,
+    axum::extract::Query(params): axum::extract::Query<ListQueryParams>,
+    axum::extract::Query(filter): axum::extract::Query<
+        crate::database::conversion_audit_repository::ConversionAuditFilter,
+    >,
+) -> Result<
+    Json<
+        crate::database::repository::Page<
+            crate::database::conversion_audit_repository::ConversionAudit,
+        >,
+    >,
+    crate::middleware::error::HandlerError,
+> {
+    use crate::database::conversion_audit_repository::ConversionAuditRepository;
+    use crate::database::repository::Page;
+
+    let request_id = crate::middleware::error::get_request_id_from_headers(&headers);
+    let pool = match state.db_pool.as_ref() {
+        Some(pool) => pool,
+        None => {
+            return Err(crate::middleware::error::dependency_disabled_response(
+                "Database",
+                request_id,
+            )
+            .into())
+        }
+    };
+
+    let query = params
+        .into_list_query::<ConversionAuditRepository>()
+        .map_err(|e| {
+            crate::middleware::error::json_error_response(
+                axum::http::StatusCode::BAD_REQUEST,
+                e.to_string(),
+                request_id.clone(),
+            )
+        })?;
+
+    let repo = ConversionAuditRepository::new(pool.clone());
+    let (items, total) = tokio::try_join!(
+        repo.find_filtered(&filter, &query),
+        repo.count_filtered(&filter)
+    )
+    .map_err(|e| app_error_response(e.into(), request_id))?;
+
+    Ok(Json(Page::new(items, total, &query)))
+}
+
+async fn stream_conversion_feed(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    headers: axum::http::HeaderMap,
+    axum::extract::Query(query): axum::extract::Query<ConversionFeedQuery>,
+) -> Result<
+    axum::response::sse::Sse<
+        impl futures::Stream<Item = Result<axum::response::sse::Event, std::convert::Infallible>>,
+    >,
+    crate::middleware::error::HandlerError,
+> {
+    use crate::database::conversion_audit_repository::ConversionAuditRepository;
+    use crate::database::repository::Repository;
+    use crate::services::conversion_feed::{
+        ConversionFeed, FeedCursor, FeedStart, SubscriberLimit, DEFAULT_BACKFILL, MAX_BACKFILL,
+    };
+    use axum::response::sse::{Event, KeepAlive, Sse};
+    use futures::StreamExt;
+
+    let request_id = crate::middleware::error::get_request_id_from_headers(&headers);
+    let pool = match state.db_pool.as_ref() {
+        Some(pool) => pool,
+        None => {
+            return Err(crate::middleware::error::dependency_disabled_response(
+                "Database",
+                request_id,
+            )
+            .into())
+        }
+    };
+
+    let backfill = query.backfill.unwrap_or(DEFAULT_BACKFILL);
+    if !(0..=MAX_BACKFILL).contains(&backfill) {
+        return Err(crate::middleware::error::json_error_response(
+            axum::http::StatusCode::BAD_REQUEST,
+            format!("backfill must be between 0 and {}", MAX_BACKFILL),
+            request_id,
+        )
+        .into());
+    }
+
+    // Every subscriber polls the database, so their number is capped
+    let Some(slot) = SubscriberLimit::global().try_acquire() else {
+        return Err(app_error_response(
+            crate::error::AppError::new(crate::error::AppErrorKind::External(
+                crate::error::ExternalError::ServiceUnavailable {
+                    service: "conversion_feed".to_string(),
+                    retry_after: Some(5),
+                },
+            )),
+            request_id,
+        ));
+    };
+
+    // A reconnecting EventSource sends the id of the last audit it received
+    let last_event_id = headers
+        .get("last-event-id")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| Uuid::parse_str(v.trim()).ok());
+    let start = match last_event_id {
+        Some(id) => ConversionAuditRepository::new(pool.clone())
+            .find_by_id(&id.to_string())
+            .await
+            .map_err(|e| app_error_response(e.into(), request_id.clone()))?
+            .map(|audit| FeedStart::After(FeedCursor::from(&audit)))
+            .unwrap_or(FeedStart::Backfill(backfill)),
+        None => FeedStart::Backfill(backfill),
+    };
+
+    let events = ConversionFeed::new(pool.clone())
+        .with_slot(slot)
+        .stream(start, state.shutdown.clone())
+        .map(|audit| {
+            let data = serde_json::to_string(&audit).unwrap_or_default();
+            Ok(Event::default()
+                .event("conversion")
+                .id(audit.id.to_string())
+                .data(data))
+        });
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+async fn list_conversion_discrepancies(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    headers: axum::http::HeaderMap,
+    axum::extract::Query(query): axum::extract::Query<ConversionDiscrepancyQuery>,
+) -> Result<
+    Json<Vec<crate::database::conversion_audit_repository::ConversionDiscrepancy>>,
+    crate::middleware::error::HandlerError,
+> {
+    use crate::database::conversion_audit_repository::ConversionAuditRepository;
+
+    let request_id = crate::middleware::error::get_request_id_from_headers(&headers);
+    let pool = match state.db_pool.as_ref() {
+        Some(pool) => pool,
+        None => {
+            return Err(crate::middleware::error::dependency_disabled_response(
+                "Database",
+                request_id,
+            )
+            .into())
+        }
+    };
+
+    let tolerance_bps = query.tolerance_bps.unwrap_or(1);
+    if tolerance_bps < 0 {
+        return Err(crate::middleware::error::json_error_response(
+            axum::http::StatusCode::BAD_REQUEST,
+            "tolerance_bps must not be negative",
+            request_id,
+        )
+        .into());
+    }
+    let limit = query.limit.unwrap_or(100).clamp(1, 500);
+
+    ConversionAuditRepository::new(pool.clone())
+        .find_discrepancies(tolerance_bps, limit)
+        .await
+        .map(Json)
+        .map_err(|e| app_error_response(e.into(), request_id))
+}
+
 async fn create_onramp_quote(
     axum::extract::State(quote_service): axum::extract::State<
         std::sync::Arc<services::onramp_quote::OnrampQuoteService>,
@@ -2238,10 +3278,7 @@ async fn create_onramp_quote(
     Json(payload): Json<services::onramp_quote::OnrampQuoteRequest>,
 ) -> Result<
     Json<services::onramp_quote::OnrampQuoteResponse>,
-    (
-        axum::http::StatusCode,
-        Json<middleware::error::ErrorResponse>,
-    ),
+    crate::middleware::error::HandlerError,
 > {
     let request_id = middleware::error::get_request_id_from_headers(&headers);
 
@@ -2256,56 +3293,66 @@ async fn calculate_fee(
     axum::extract::State(state): axum::extract::State<AppState>,
     headers: axum::http::HeaderMap,
     Json(payload): Json<FeeCalculationRequest>,
-) -> Result<
-    Json<FeeCalculationResponse>,
-    (
-        axum::http::StatusCode,
-        Json<crate::middleware::error::ErrorResponse>,
-    ),
-> {
+) -> Result<Json<FeeCalculationResponse>, crate::middleware::error::HandlerError> {
     let request_id = crate::middleware::error::get_request_id_from_headers(&headers);
     let pool = match state.db_pool.as_ref() {
         Some(pool) => pool,
         None => {
-            return Err(crate::middleware::error::json_error_response(
-                axum::http::StatusCode::SERVICE_UNAVAILABLE,
-                "Database disabled by configuration",
+            return Err(crate::middleware::error::dependency_disabled_response(
+                "Database",
                 request_id,
-            ))
+            )
+            .into())
         }
     };
 
     let repo = crate::database::fee_structure_repository::FeeStructureRepository::new(pool.clone());
-    let service = crate::services::fee_structure::FeeStructureService::new(repo);
+    let service = crate::services::fee_structure::FeeStructureService::new(repo)
+        .with_currencies(crate::services::fee_structure::FeeCurrencies::from_env());
+
+    let fee_type = match payload
+        .fee_type
+        .parse::<crate::services::fee_structure::FeeType>()
+    {
+        Ok(fee_type) => fee_type,
+        Err(e) => return Err(unknown_fee_type_response(&e, request_id)),
+    };
 
-    let amount = crate::services::fee_structure::parse_amount(&payload.amount);
+    // A malformed amount is the caller's fault (400); corrupt stored fee data
+    // below is ours (500, CORRUPT_FEE_STRUCTURE)
+    let amount = crate::services::fee_structure::parse_input_amount(&payload.amount)
+        .map_err(|e| app_error_response(e.into(), request_id.clone()))?;
     if amount <= bigdecimal::BigDecimal::from(0) {
         return Err(crate::middleware::error::json_error_response(
             axum::http::StatusCode::BAD_REQUEST,
             "amount must be greater than 0",
             request_id,
-        ));
+        )
+        .into());
     }
 
-    let result = service
-        .calculate_fee(crate::services::fee_structure::FeeCalculationInput {
-            fee_type: payload.fee_type.as_str().to_string(),
-            amount,
-            currency: payload.currency,
-            at_time: None,
-        })
-        .await
-        .map_err(|e| {
-            crate::middleware::error::json_error_response(
-                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-                e.to_string(),
-                request_id.clone(),
-            )
-        })?;
+    let input = crate::services::fee_structure::FeeCalculationInput {
+        fee_type,
+        amount,
+        currency: payload.currency,
+        at_time: None,
+        fee_mode: payload.fee_mode,
+    };
+    if let Err(e) = input.check_currency(service.currencies()) {
+        return Err(unsupported_fee_currency_response(&e, request_id));
+    }
+    let result = match payload.at_time {
+        Some(at_time) => service.calculate_fee_at(input, at_time).await,
+        None => service.calculate_fee(input).await,
+    }
+    .map_err(|e| app_error_response(e.into(), request_id.clone()))?;
 
     match result {
         Some(calc) => Ok(Json(FeeCalculationResponse {
             fee: calc.fee.to_string(),
+            fee_mode: calc.fee_mode,
+            net_amount: calc.net_amount.to_string(),
+            gross_amount: calc.gross_amount.to_string(),
             rate_bps: calc.rate_bps,
             flat_fee: calc.flat_fee.to_string(),
             min_fee: calc.min_fee.map(|v| v.to_string()),
@@ -2317,29 +3364,84 @@ async fn calculate_fee(
             axum::http::StatusCode::NOT_FOUND,
             "No active fee structure found",
             request_id.clone(),
-        )),
+        )
+        .into()),
     }
 }
 
+/// 400 naming the rejected currency and the supported set
+fn unsupported_fee_currency_response(
+    err: &crate::services::fee_structure::UnsupportedFeeCurrency,
+    request_id: Option<String>,
+) -> crate::middleware::error::HandlerError {
+    let message = err.to_string();
+    let response =
+        crate::middleware::error::ErrorResponse::validation_error(request_id, "currency", &message)
+            .with_details(serde_json::json!({
+                "field": "currency",
+                "error": message,
+                "currency": err.currency,
+                "supported_currencies": err.supported,
+            }));
+    (axum::http::StatusCode::BAD_REQUEST, Json(response)).into()
+}
+
+fn unknown_fee_type_response(
+    err: &crate::services::fee_structure::UnknownFeeType,
+    request_id: Option<String>,
+) -> crate::middleware::error::HandlerError {
+    let message = err.to_string();
+    let response =
+        crate::middleware::error::ErrorResponse::validation_error(request_id, "fee_type", &message)
+            .with_details(serde_json::json!({
+                "field": "fee_type",
+                "error": message,
+                "fee_type": err.fee_type,
+                "supported_fee_types": err.supported,
+            }));
+    (axum::http::StatusCode::BAD_REQUEST, Json(response)).into()
+}
+
 fn app_error_response(
     err: crate::error::AppError,
     request_id: Option<String>,
-) -> (
-    axum::http::StatusCode,
-    Json<crate::middleware::error::ErrorResponse>,
-) {
-    let err = match request_id {
-        Some(req_id) => err.with_request_id(req_id),
-        None => err,
-    };
-    let status = axum::http::StatusCode::from_u16(err.status_code())
-        .unwrap_or(axum::http::StatusCode::INTERNAL_SERVER_ERROR);
-    (
-        status,
-        Json(crate::middleware::error::ErrorResponse::from_app_error(
-            &err,
-        )),
-    )
+) -> crate::middleware::error::HandlerError {
+    crate::middleware::error::HandlerError::from_app_error(err, request_id)
+}
+
+async fn get_onboarding_status(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    headers: axum::http::HeaderMap,
+    Json(payload): Json<OnboardingStatusRequest>,
+) -> Result<
+    Json<Vec<crate::chains::stellar::trustline::OnboardingStatus>>,
+    crate::middleware::error::HandlerError,
+> {
+    use crate::chains::stellar::trustline::MAX_ONBOARDING_BATCH;
+
+    let request_id = crate::middleware::error::get_request_id_from_headers(&headers);
+    let stellar_client = match state.stellar_client.as_ref() {
+        Some(client) => client,
+        None => {
+            return Err(crate::middleware::error::dependency_disabled_response(
+                "Stellar client",
+                request_id,
+            )
+            .into())
+        }
+    };
+
+    crate::api::validation::ArrayBounds::new(MAX_ONBOARDING_BATCH)
+        .check("addresses", &payload.addresses)
+        .map_err(|e| {
+            (
+                axum::http::StatusCode::BAD_REQUEST,
+                Json(e.to_error_response(request_id.clone())),
+            )
+        })?;
+
+    let manager = crate::chains::stellar::trustline::CngnTrustlineManager::new(stellar_client.clone());
+    Ok(Json(manager.onboarding_status_batch(&payload.addresses).await))
 }
 
 async fn check_cngn_trustline(
@@ -2347,21 +3449,56 @@ async fn check_cngn_trustline(
     headers: axum::http::HeaderMap,
     Json(payload): Json<TrustlineAccountRequest>,
 ) -> Result<
-    Json<crate::chains::stellar::trustline::TrustlineStatus>,
-    (
-        axum::http::StatusCode,
-        Json<crate::middleware::error::ErrorResponse>,
-    ),
+    Json<crate::chains::stellar::trustline::TrustlineStatus>,
+    crate::middleware::error::HandlerError,
+> {
+    let request_id = crate::middleware::error::get_request_id_from_headers(&headers);
+    let stellar_client = match state.stellar_client.as_ref() {
+        Some(client) => client,
+        None => {
+            return Err(crate::middleware::error::dependency_disabled_response(
+                "Stellar client",
+                request_id,
+            )
+            .into())
+        }
+    };
+
+    if payload.account_id.trim().is_empty() {
+        return Err(crate::middleware::error::json_error_response(
+            axum::http::StatusCode::BAD_REQUEST,
+            "account_id is required",
+            request_id,
+        )
+        .into());
+    }
+
+    let manager =
+        crate::chains::stellar::trustline::CngnTrustlineManager::new(stellar_client.clone());
+    manager
+        .check_trustline(&payload.account_id)
+        .await
+        .map(Json)
+        .map_err(|e| app_error_response(e.into(), request_id))
+}
+
+async fn preflight_cngn_trustline(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    headers: axum::http::HeaderMap,
+    Json(payload): Json<TrustlineAccountRequest>,
+) -> Result<
+    Json<crate::chains::stellar::trustline::TrustlinePreflight>,
+    crate::middleware::error::HandlerError,
 > {
     let request_id = crate::middleware::error::get_request_id_from_headers(&headers);
     let stellar_client = match state.stellar_client.as_ref() {
         Some(client) => client,
         None => {
-            return Err(crate::middleware::error::json_error_response(
-                axum::http::StatusCode::SERVICE_UNAVAILABLE,
-                "Stellar client disabled by configuration",
+            return Err(crate::middleware::error::dependency_disabled_response(
+                "Stellar client",
                 request_id,
-            ))
+            )
+            .into())
         }
     };
 
@@ -2370,38 +3507,36 @@ async fn check_cngn_trustline(
             axum::http::StatusCode::BAD_REQUEST,
             "account_id is required",
             request_id,
-        ));
+        )
+        .into());
     }
 
     let manager =
         crate::chains::stellar::trustline::CngnTrustlineManager::new(stellar_client.clone());
     manager
-        .check_trustline(&payload.account_id)
+        .preflight_trustline_creation(&payload.account_id)
         .await
         .map(Json)
         .map_err(|e| app_error_response(e.into(), request_id))
 }
 
-async fn preflight_cngn_trustline(
+async fn validate_cngn_trustline_limit(
     axum::extract::State(state): axum::extract::State<AppState>,
     headers: axum::http::HeaderMap,
     Json(payload): Json<TrustlineAccountRequest>,
 ) -> Result<
-    Json<crate::chains::stellar::trustline::TrustlinePreflight>,
-    (
-        axum::http::StatusCode,
-        Json<crate::middleware::error::ErrorResponse>,
-    ),
+    Json<crate::services::cngn_trustline::TrustlineLimitCheck>,
+    crate::middleware::error::HandlerError,
 > {
     let request_id = crate::middleware::error::get_request_id_from_headers(&headers);
     let stellar_client = match state.stellar_client.as_ref() {
         Some(client) => client,
         None => {
-            return Err(crate::middleware::error::json_error_response(
-                axum::http::StatusCode::SERVICE_UNAVAILABLE,
-                "Stellar client disabled by configuration",
+            return Err(crate::middleware::error::dependency_disabled_response(
+                "Stellar client",
                 request_id,
-            ))
+            )
+            .into())
         }
     };
 
@@ -2410,38 +3545,33 @@ async fn preflight_cngn_trustline(
             axum::http::StatusCode::BAD_REQUEST,
             "account_id is required",
             request_id,
-        ));
+        )
+        .into());
     }
 
-    let manager =
-        crate::chains::stellar::trustline::CngnTrustlineManager::new(stellar_client.clone());
-    manager
-        .preflight_trustline_creation(&payload.account_id)
+    let service =
+        crate::services::cngn_trustline::CngnTrustlineService::new(stellar_client.clone());
+    service
+        .validate_trustline_limit(&payload.account_id)
         .await
         .map(Json)
-        .map_err(|e| app_error_response(e.into(), request_id))
+        .map_err(|e| app_error_response(e, request_id))
 }
 
 async fn build_cngn_trustline(
     axum::extract::State(state): axum::extract::State<AppState>,
     headers: axum::http::HeaderMap,
     Json(payload): Json<CngnTrustlineBuildRequest>,
-) -> Result<
-    Json<CngnTrustlineBuildResponse>,
-    (
-        axum::http::StatusCode,
-        Json<crate::middleware::error::ErrorResponse>,
-    ),
-> {
+) -> Result<Json<CngnTrustlineBuildResponse>, crate::middleware::error::HandlerError> {
     let request_id = crate::middleware::error::get_request_id_from_headers(&headers);
     let stellar_client = match state.stellar_client.as_ref() {
         Some(client) => client,
         None => {
-            return Err(crate::middleware::error::json_error_response(
-                axum::http::StatusCode::SERVICE_UNAVAILABLE,
-                "Stellar client disabled by configuration",
+            return Err(crate::middleware::error::dependency_disabled_response(
+                "Stellar client",
                 request_id,
-            ))
+            )
+            .into())
         }
     };
 
@@ -2450,7 +3580,8 @@ async fn build_cngn_trustline(
             axum::http::StatusCode::BAD_REQUEST,
             "account_id is required",
             request_id,
-        ));
+        )
+        .into());
     }
 
     let manager =
@@ -2507,22 +3638,16 @@ async fn submit_cngn_trustline(
     axum::extract::State(state): axum::extract::State<AppState>,
     headers: axum::http::HeaderMap,
     Json(payload): Json<CngnTrustlineSubmitRequest>,
-) -> Result<
-    Json<CngnTrustlineSubmitResponse>,
-    (
-        axum::http::StatusCode,
-        Json<crate::middleware::error::ErrorResponse>,
-    ),
-> {
+) -> Result<Json<CngnTrustlineSubmitResponse>, crate::middleware::error::HandlerError> {
     let request_id = crate::middleware::error::get_request_id_from_headers(&headers);
     let stellar_client = match state.stellar_client.as_ref() {
         Some(client) => client,
         None => {
-            return Err(crate::middleware::error::json_error_response(
-                axum::http::StatusCode::SERVICE_UNAVAILABLE,
-                "Stellar client disabled by configuration",
+            return Err(crate::middleware::error::dependency_disabled_response(
+                "Stellar client",
                 request_id,
-            ))
+            )
+            .into())
         }
     };
 
@@ -2531,7 +3656,8 @@ async fn submit_cngn_trustline(
             axum::http::StatusCode::BAD_REQUEST,
             "signed_envelope_xdr is required",
             request_id,
-        ));
+        )
+        .into());
     }
 
     let manager =
@@ -2570,20 +3696,17 @@ async fn retry_cngn_trustline(
     headers: axum::http::HeaderMap,
 ) -> Result<
     Json<crate::database::trustline_operation_repository::TrustlineOperation>,
-    (
-        axum::http::StatusCode,
-        Json<crate::middleware::error::ErrorResponse>,
-    ),
+    crate::middleware::error::HandlerError,
 > {
     let request_id = crate::middleware::error::get_request_id_from_headers(&headers);
     let pool = match state.db_pool.as_ref() {
         Some(pool) => pool,
         None => {
-            return Err(crate::middleware::error::json_error_response(
-                axum::http::StatusCode::SERVICE_UNAVAILABLE,
-                "Database disabled by configuration",
+            return Err(crate::middleware::error::dependency_disabled_response(
+                "Database",
                 request_id,
-            ))
+            )
+            .into())
         }
     };
 
@@ -2599,6 +3722,7 @@ async fn retry_cngn_trustline(
                 e.to_string(),
                 request_id,
             )
+            .into()
         })
 }
 
@@ -2606,22 +3730,16 @@ async fn build_cngn_payment(
     axum::extract::State(state): axum::extract::State<AppState>,
     headers: axum::http::HeaderMap,
     Json(payload): Json<CngnPaymentBuildRequest>,
-) -> Result<
-    Json<CngnPaymentBuildResponse>,
-    (
-        axum::http::StatusCode,
-        Json<crate::middleware::error::ErrorResponse>,
-    ),
-> {
+) -> Result<Json<CngnPaymentBuildResponse>, crate::middleware::error::HandlerError> {
     let request_id = crate::middleware::error::get_request_id_from_headers(&headers);
     let stellar_client = match state.stellar_client.as_ref() {
         Some(client) => client,
         None => {
-            return Err(crate::middleware::error::json_error_response(
-                axum::http::StatusCode::SERVICE_UNAVAILABLE,
-                "Stellar client disabled by configuration",
+            return Err(crate::middleware::error::dependency_disabled_response(
+                "Stellar client",
                 request_id,
-            ))
+            )
+            .into())
         }
     };
 
@@ -2633,23 +3751,62 @@ async fn build_cngn_payment(
             axum::http::StatusCode::BAD_REQUEST,
             "source, destination and amount are required",
             request_id,
-        ));
+        )
+        .into());
     }
 
-    let builder = crate::chains::stellar::payment::CngnPaymentBuilder::new(stellar_client.clone());
+    let memo = crate::chains::stellar::payment::CngnMemo::resolve(
+        payload.memo.clone(),
+        payload.deposit_account_id,
+    )
+    .map_err(|e| {
+        crate::middleware::error::json_error_response(
+            axum::http::StatusCode::BAD_REQUEST,
+            e.to_string(),
+            request_id.clone(),
+        )
+    })?;
+
+    // Memo ids are only issued through POST /api/deposits/memo; building with
+    // one that was never issued would produce a deposit nobody can be credited for
+    if let (Some(deposit_account_id), Some(pool)) =
+        (payload.deposit_account_id, state.db_pool.as_ref())
+    {
+        let issued = crate::database::deposit_memo_repository::DepositMemoRepository::new(
+            pool.clone(),
+        )
+        .resolve(deposit_account_id)
+        .await
+        .map_err(|e| app_error_response(e.into(), request_id.clone()))?;
+        if issued.is_none() {
+            return Err(crate::middleware::error::json_error_response(
+                axum::http::StatusCode::BAD_REQUEST,
+                "deposit_account_id has not been issued; request one from /api/deposits/memo",
+                request_id,
+            )
+            .into());
+        }
+    }
+
+    let builder = crate::chains::stellar::payment::CngnPaymentBuilder::new(stellar_client.clone())
+        .with_balance_precheck(!payload.skip_balance_precheck);
     let draft = builder
         .build_payment(
             &payload.source,
             &payload.destination,
             &payload.amount,
-            payload
-                .memo
-                .unwrap_or(crate::chains::stellar::payment::CngnMemo::None),
+            memo,
             payload.fee_stroops,
         )
         .await
         .map_err(|e| app_error_response(e.into(), request_id.clone()))?;
 
+    // build_payment already hashed the envelope for this network
+    let transaction_hash = draft.transaction_hash.clone();
+    let signature_base_xdr = builder
+        .signature_base_xdr(&draft)
+        .map_err(|e| app_error_response(e.into(), request_id.clone()))?;
+
     let mut transaction_id = None;
     if let Some(pool) = state.db_pool.as_ref() {
         let repo =
@@ -2703,29 +3860,141 @@ async fn build_cngn_payment(
     Ok(Json(CngnPaymentBuildResponse {
         draft,
         transaction_id,
+        transaction_hash,
+        signature_base_xdr,
     }))
 }
 
+async fn build_cngn_bump_sequence(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    headers: axum::http::HeaderMap,
+    Json(payload): Json<BumpSequenceRequest>,
+) -> Result<
+    Json<crate::chains::stellar::payment::BumpSequenceDraft>,
+    crate::middleware::error::HandlerError,
+> {
+    let request_id = crate::middleware::error::get_request_id_from_headers(&headers);
+    let stellar_client = match state.stellar_client.as_ref() {
+        Some(client) => client,
+        None => {
+            return Err(crate::middleware::error::dependency_disabled_response(
+                "Stellar client",
+                request_id,
+            )
+            .into())
+        }
+    };
+
+    let mut builder = crate::chains::stellar::payment::CngnPaymentBuilder::new(stellar_client.clone());
+    if let Some(fee) = payload.fee_stroops {
+        builder = builder.with_base_fee(fee);
+    }
+
+    builder
+        .build_bump_sequence(payload.source.trim(), payload.bump_to)
+        .await
+        .map(Json)
+        .map_err(|e| app_error_response(e.into(), request_id))
+}
+
+/// Unsigned accountMerge of `source` into `destination`; 422 listing the
+/// blocking subentries when the source isn't empty yet
+async fn build_account_merge(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    headers: axum::http::HeaderMap,
+    Json(payload): Json<AccountMergeRequest>,
+) -> Result<
+    Json<crate::chains::stellar::payment::AccountMergeDraft>,
+    crate::middleware::error::HandlerError,
+> {
+    let request_id = crate::middleware::error::get_request_id_from_headers(&headers);
+    let stellar_client = match state.stellar_client.as_ref() {
+        Some(client) => client,
+        None => {
+            return Err(crate::middleware::error::dependency_disabled_response(
+                "Stellar client",
+                request_id,
+            )
+            .into())
+        }
+    };
+
+    let mut builder = crate::chains::stellar::payment::CngnPaymentBuilder::new(stellar_client.clone());
+    if let Some(fee) = payload.fee_stroops {
+        builder = builder.with_base_fee(fee);
+    }
+
+    builder
+        .build_account_merge(payload.source.trim(), payload.destination.trim())
+        .await
+        .map(Json)
+        .map_err(|e| app_error_response(e.into(), request_id))
+}
+
+async fn decode_cngn_envelope(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    headers: axum::http::HeaderMap,
+    Json(payload): Json<EnvelopeDecodeRequest>,
+) -> Result<
+    Json<crate::chains::stellar::payment::EnvelopeSummary>,
+    crate::middleware::error::HandlerError,
+> {
+    let request_id = crate::middleware::error::get_request_id_from_headers(&headers);
+    let stellar_client = match state.stellar_client.as_ref() {
+        Some(client) => client,
+        None => {
+            return Err(crate::middleware::error::dependency_disabled_response(
+                "Stellar client",
+                request_id,
+            )
+            .into())
+        }
+    };
+
+    if payload.envelope_xdr.trim().is_empty() {
+        return Err(crate::middleware::error::json_error_response(
+            axum::http::StatusCode::BAD_REQUEST,
+            "envelope_xdr is required",
+            request_id,
+        )
+        .into());
+    }
+
+    let builder = crate::chains::stellar::payment::CngnPaymentBuilder::new(stellar_client.clone());
+    builder
+        .decode_envelope(&payload.envelope_xdr)
+        .map(Json)
+        .map_err(|e| match e {
+            // Undecodable XDR is a bad request, not an upstream failure
+            crate::chains::stellar::errors::StellarError::SerializationError { message } => {
+                crate::middleware::error::json_error_response(
+                    axum::http::StatusCode::BAD_REQUEST,
+                    message,
+                    request_id,
+                )
+                .into()
+            }
+            e => app_error_response(e.into(), request_id),
+        })
+}
+
 async fn sign_cngn_payment(
     axum::extract::State(state): axum::extract::State<AppState>,
     headers: axum::http::HeaderMap,
     Json(payload): Json<CngnPaymentSignRequest>,
 ) -> Result<
     Json<crate::chains::stellar::payment::SignedCngnPayment>,
-    (
-        axum::http::StatusCode,
-        Json<crate::middleware::error::ErrorResponse>,
-    ),
+    crate::middleware::error::HandlerError,
 > {
     let request_id = crate::middleware::error::get_request_id_from_headers(&headers);
     let stellar_client = match state.stellar_client.as_ref() {
         Some(client) => client,
         None => {
-            return Err(crate::middleware::error::json_error_response(
-                axum::http::StatusCode::SERVICE_UNAVAILABLE,
-                "Stellar client disabled by configuration",
+            return Err(crate::middleware::error::dependency_disabled_response(
+                "Stellar client",
                 request_id,
-            ))
+            )
+            .into())
         }
     };
 
@@ -2736,26 +4005,224 @@ async fn sign_cngn_payment(
         .map_err(|e| app_error_response(e.into(), request_id))
 }
 
-async fn submit_cngn_payment(
+async fn submit_signed_transaction(
     axum::extract::State(state): axum::extract::State<AppState>,
     headers: axum::http::HeaderMap,
-    Json(payload): Json<CngnPaymentSubmitRequest>,
+    Json(payload): Json<SignedTransactionSubmitRequest>,
 ) -> Result<
-    Json<CngnPaymentSubmitResponse>,
-    (
-        axum::http::StatusCode,
-        Json<crate::middleware::error::ErrorResponse>,
-    ),
+    Json<crate::chains::stellar::submission::SubmittedTransaction>,
+    crate::middleware::error::HandlerError,
 > {
     let request_id = crate::middleware::error::get_request_id_from_headers(&headers);
     let stellar_client = match state.stellar_client.as_ref() {
         Some(client) => client,
         None => {
-            return Err(crate::middleware::error::json_error_response(
-                axum::http::StatusCode::SERVICE_UNAVAILABLE,
-                "Stellar client disabled by configuration",
+            return Err(crate::middleware::error::dependency_disabled_response(
+                "Stellar client",
                 request_id,
-            ))
+            )
+            .into())
+        }
+    };
+
+    let mut submitter =
+        crate::chains::stellar::submission::SignedTransactionSubmitter::new(stellar_client.clone())
+            .with_options(crate::chains::stellar::submission::SubmitOptions {
+                wait_for_confirmation: payload.wait_for_confirmation,
+                estimated_fee_stroops: payload.estimated_fee_stroops,
+                ..Default::default()
+            });
+    // Client retries of the same envelope get the first result back
+    if let Some(cache) = state.redis_cache.clone() {
+        submitter = submitter.with_replay_store(std::sync::Arc::new(cache));
+    }
+
+    // Malformed or unsigned envelopes are the caller's fault, not Horizon's
+    if let Err(e) = submitter.validate(&payload.envelope_xdr) {
+        return Err(crate::middleware::error::json_error_response(
+            axum::http::StatusCode::BAD_REQUEST,
+            format!("envelope_xdr rejected: {}", e),
+            request_id,
+        )
+        .into());
+    }
+
+    // Count the payment against the platform-wide daily ceiling before it
+    // reaches Horizon, as cNGN payment submission does
+    let volume_reservation = match (
+        crate::services::volume_limit::DailyVolumeLimitConfig::from_env(),
+        state.redis_cache.as_ref(),
+    ) {
+        (Some(config), Some(cache)) => {
+            let amount =
+                crate::chains::stellar::payment::CngnPaymentBuilder::new(stellar_client.clone())
+                    .cngn_payment_stroops(&payload.envelope_xdr)
+                    .map_err(|e| app_error_response(e.into(), request_id.clone()))?;
+            let limiter = crate::services::volume_limit::DailyVolumeLimiter::new(
+                std::sync::Arc::new(cache.clone()),
+                config,
+            );
+            let check = limiter
+                .record(amount)
+                .await
+                .map_err(|e| app_error_response(e.into(), request_id.clone()))?;
+            Some((limiter, check, amount))
+        }
+        _ => None,
+    };
+
+    let submit_result = submitter.submit(&payload.envelope_xdr).await;
+
+    // A failed submission moved nothing, and a replayed one was counted the
+    // first time, so either way the reservation is returned
+    if submit_result.as_ref().map_or(true, |tx| tx.replayed) {
+        if let Some((limiter, check, amount)) = volume_reservation.as_ref() {
+            limiter.release(check, *amount).await;
+        }
+    }
+
+    submit_result
+        .map(Json)
+        .map_err(|e| app_error_response(e.into(), request_id))
+}
+
+async fn batch_submit_signed_payments(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    headers: axum::http::HeaderMap,
+    Json(payload): Json<BatchSubmitRequest>,
+) -> Result<
+    Json<Vec<crate::chains::stellar::submission::BatchItemResult>>,
+    crate::middleware::error::HandlerError,
+> {
+    use crate::chains::stellar::submission::{
+        BatchSubmitOptions, SignedTransactionSubmitter, SubmissionLimit,
+    };
+
+    let request_id = crate::middleware::error::get_request_id_from_headers(&headers);
+    let stellar_client = match state.stellar_client.as_ref() {
+        Some(client) => client,
+        None => {
+            return Err(crate::middleware::error::dependency_disabled_response(
+                "Stellar client",
+                request_id,
+            )
+            .into())
+        }
+    };
+
+    crate::api::validation::ArrayBounds::from_env("BATCH_SUBMIT_MAX_ITEMS", 100)
+        .check("envelopes", &payload.envelopes)
+        .map_err(|e| {
+            (
+                axum::http::StatusCode::BAD_REQUEST,
+                Json(e.to_error_response(request_id.clone())),
+            )
+        })?;
+
+    let mut submitter = SignedTransactionSubmitter::new(stellar_client.clone());
+    if let Some(cache) = state.redis_cache.clone() {
+        submitter = submitter.with_replay_store(std::sync::Arc::new(cache));
+    }
+
+    // Each item counts as one single-transaction submission: against its
+    // source wallet's window on the submit endpoint, and against the daily
+    // volume ceiling
+    let mut limits: Vec<std::sync::Arc<dyn SubmissionLimit>> = Vec::new();
+    if let Some(cache) = state.redis_cache.as_ref() {
+        const SUBMIT_PATH: &str = "/api/afri/transactions/submit";
+        if let Some(limit) = state.rate_limits.get_limits(SUBMIT_PATH).per_wallet {
+            limits.push(std::sync::Arc::new(
+                crate::middleware::rate_limit::WalletWindowLimit {
+                    cache: std::sync::Arc::new(cache.clone()),
+                    path: SUBMIT_PATH.to_string(),
+                    limit,
+                },
+            ));
+        }
+        if let Some(config) = crate::services::volume_limit::DailyVolumeLimitConfig::from_env() {
+            let asset = crate::chains::stellar::trustline::CngnAssetConfig::from_env()
+                .asset_for_network(stellar_client.network())
+                .map_err(|e| app_error_response(e.into(), request_id.clone()))?;
+            let limiter = crate::services::volume_limit::DailyVolumeLimiter::new(
+                std::sync::Arc::new(cache.clone()),
+                config,
+            );
+            limits.push(std::sync::Arc::new(
+                crate::services::volume_limit::VolumeSubmissionLimit::new(limiter, asset),
+            ));
+        }
+    }
+    let options = BatchSubmitOptions {
+        concurrency: std::env::var("BATCH_SUBMIT_CONCURRENCY")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok())
+            .filter(|n| *n > 0)
+            .unwrap_or(4),
+        limits,
+    };
+
+    Ok(Json(submitter.submit_batch(&payload.envelopes, &options).await))
+}
+
+async fn stream_transaction_status(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    headers: axum::http::HeaderMap,
+    axum::extract::Path(hash): axum::extract::Path<String>,
+) -> Result<
+    axum::response::sse::Sse<
+        impl futures::Stream<Item = Result<axum::response::sse::Event, std::convert::Infallible>>,
+    >,
+    crate::middleware::error::HandlerError,
+> {
+    use axum::response::sse::{Event, KeepAlive, Sse};
+    use futures::StreamExt;
+
+    let request_id = crate::middleware::error::get_request_id_from_headers(&headers);
+    let stellar_client = match state.stellar_client.as_ref() {
+        Some(client) => client,
+        None => {
+            return Err(crate::middleware::error::dependency_disabled_response(
+                "Stellar client",
+                request_id,
+            )
+            .into())
+        }
+    };
+
+    if hash.len() != 64 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(crate::middleware::error::json_error_response(
+            axum::http::StatusCode::BAD_REQUEST,
+            "hash must be a 64-character hex transaction hash",
+            request_id,
+        )
+        .into());
+    }
+
+    let events =
+        crate::chains::stellar::submission::SignedTransactionSubmitter::new(stellar_client.clone())
+            .status_stream(hash.to_ascii_lowercase(), state.shutdown.clone())
+            .map(|event| {
+                let data = serde_json::to_string(&event).unwrap_or_default();
+                Ok(Event::default().event(event.name()).data(data))
+            });
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+async fn submit_cngn_payment(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    headers: axum::http::HeaderMap,
+    Json(payload): Json<CngnPaymentSubmitRequest>,
+) -> Result<Json<CngnPaymentSubmitResponse>, crate::middleware::error::HandlerError> {
+    let request_id = crate::middleware::error::get_request_id_from_headers(&headers);
+    let stellar_client = match state.stellar_client.as_ref() {
+        Some(client) => client,
+        None => {
+            return Err(crate::middleware::error::dependency_disabled_response(
+                "Stellar client",
+                request_id,
+            )
+            .into())
         }
     };
 
@@ -2764,14 +4231,75 @@ async fn submit_cngn_payment(
             axum::http::StatusCode::BAD_REQUEST,
             "signed_envelope_xdr is required",
             request_id,
-        ));
+        )
+        .into());
     }
 
     let builder = crate::chains::stellar::payment::CngnPaymentBuilder::new(stellar_client.clone());
+
+    // Count the payment against the platform-wide daily ceiling before it
+    // reaches Horizon; the reservation is returned if submission fails. A dry
+    // run only checks the headroom, so it never holds volume other
+    // submissions need.
+    let volume_limit = match (
+        crate::services::volume_limit::DailyVolumeLimitConfig::from_env(),
+        state.redis_cache.as_ref(),
+    ) {
+        (Some(config), Some(cache)) => {
+            let amount = builder
+                .cngn_payment_stroops(&payload.signed_envelope_xdr)
+                .map_err(|e| app_error_response(e.into(), request_id.clone()))?;
+            let limiter = crate::services::volume_limit::DailyVolumeLimiter::new(
+                std::sync::Arc::new(cache.clone()),
+                config,
+            );
+            Some((limiter, amount))
+        }
+        _ => None,
+    };
+
+    if payload.dry_run {
+        if let Some((limiter, amount)) = volume_limit.as_ref() {
+            limiter
+                .check(*amount)
+                .await
+                .map_err(|e| app_error_response(e.into(), request_id.clone()))?;
+        }
+        return builder
+            .dry_run_signed_payment(&payload.signed_envelope_xdr)
+            .await
+            .map(|dry_run| {
+                Json(CngnPaymentSubmitResponse {
+                    horizon_response: serde_json::Value::Null,
+                    transaction_id: payload.transaction_id,
+                    submitted: false,
+                    dry_run: Some(dry_run),
+                })
+            })
+            .map_err(|e| app_error_response(e.into(), request_id));
+    }
+
+    let volume_reservation = match volume_limit {
+        Some((limiter, amount)) => {
+            let check = limiter
+                .record(amount)
+                .await
+                .map_err(|e| app_error_response(e.into(), request_id.clone()))?;
+            Some((limiter, check, amount))
+        }
+        None => None,
+    };
+
     let submit_result = builder
         .submit_signed_payment(&payload.signed_envelope_xdr)
         .await;
 
+    if submit_result.is_err() {
+        if let Some((limiter, check, amount)) = volume_reservation.as_ref() {
+            limiter.release(check, *amount).await;
+        }
+    }
+
     match submit_result {
         Ok(horizon_response) => {
             if let (Some(pool), Some(tx_id)) =
@@ -2798,6 +4326,8 @@ async fn submit_cngn_payment(
             Ok(Json(CngnPaymentSubmitResponse {
                 horizon_response,
                 transaction_id: payload.transaction_id,
+                submitted: true,
+                dry_run: None,
             }))
         }
         Err(e) => {
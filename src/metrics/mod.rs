@@ -29,6 +29,15 @@ pub fn registry() -> &'static Registry {
     })
 }
 
+/// Number of time series currently held by the registry.
+pub fn series_count() -> usize {
+    registry()
+        .gather()
+        .iter()
+        .map(|family| family.get_metric().len())
+        .sum()
+}
+
 /// Render all metrics in Prometheus text exposition format.
 pub fn render() -> String {
     use prometheus::Encoder;
@@ -117,6 +126,7 @@ pub mod cngn {
     static CNGN_TRANSACTIONS_TOTAL: OnceLock<CounterVec> = OnceLock::new();
     static CNGN_TRANSACTION_VOLUME: OnceLock<HistogramVec> = OnceLock::new();
     static CNGN_TRANSACTION_DURATION_SECONDS: OnceLock<HistogramVec> = OnceLock::new();
+    static CNGN_DAILY_VOLUME_LIMIT_EVENTS_TOTAL: OnceLock<CounterVec> = OnceLock::new();
 
     pub fn transactions_total() -> &'static CounterVec {
         CNGN_TRANSACTIONS_TOTAL
@@ -136,6 +146,12 @@ pub mod cngn {
             .expect("metrics not initialised")
     }
 
+    pub fn daily_volume_limit_events_total() -> &'static CounterVec {
+        CNGN_DAILY_VOLUME_LIMIT_EVENTS_TOTAL
+            .get()
+            .expect("metrics not initialised")
+    }
+
     pub(super) fn register(r: &Registry) {
         CNGN_TRANSACTIONS_TOTAL
             .set(
@@ -177,6 +193,18 @@ pub mod cngn {
                 .unwrap(),
             )
             .ok();
+
+        CNGN_DAILY_VOLUME_LIMIT_EVENTS_TOTAL
+            .set(
+                register_counter_vec_with_registry!(
+                    "aframp_cngn_daily_volume_limit_events_total",
+                    "Submissions that pushed daily cNGN volume past the warning or hard limit",
+                    &["level"],
+                    r
+                )
+                .unwrap(),
+            )
+            .ok();
     }
 }
 
@@ -259,6 +287,8 @@ pub mod stellar {
     static STELLAR_TX_SUBMISSIONS_TOTAL: OnceLock<CounterVec> = OnceLock::new();
     static STELLAR_TX_SUBMISSION_DURATION_SECONDS: OnceLock<HistogramVec> = OnceLock::new();
     static STELLAR_TRUSTLINE_ATTEMPTS_TOTAL: OnceLock<CounterVec> = OnceLock::new();
+    static HORIZON_REQUESTS_TOTAL: OnceLock<CounterVec> = OnceLock::new();
+    static HORIZON_REQUEST_DURATION_SECONDS: OnceLock<HistogramVec> = OnceLock::new();
 
     pub fn tx_submissions_total() -> &'static CounterVec {
         STELLAR_TX_SUBMISSIONS_TOTAL
@@ -278,6 +308,19 @@ pub mod stellar {
             .expect("metrics not initialised")
     }
 
+    /// Labelled by configured Horizon host (`host[:port]`) and `success`/`error`
+    pub fn horizon_requests_total() -> &'static CounterVec {
+        HORIZON_REQUESTS_TOTAL
+            .get()
+            .expect("metrics not initialised")
+    }
+
+    pub fn horizon_request_duration_seconds() -> &'static HistogramVec {
+        HORIZON_REQUEST_DURATION_SECONDS
+            .get()
+            .expect("metrics not initialised")
+    }
+
     pub(super) fn register(r: &Registry) {
         STELLAR_TX_SUBMISSIONS_TOTAL
             .set(
@@ -315,6 +358,31 @@ pub mod stellar {
                 .unwrap(),
             )
             .ok();
+
+        HORIZON_REQUESTS_TOTAL
+            .set(
+                register_counter_vec_with_registry!(
+                    "aframp_horizon_requests_total",
+                    "Total Horizon requests by endpoint and result",
+                    &["endpoint", "result"],
+                    r
+                )
+                .unwrap(),
+            )
+            .ok();
+
+        HORIZON_REQUEST_DURATION_SECONDS
+            .set(
+                register_histogram_vec_with_registry!(
+                    "aframp_horizon_request_duration_seconds",
+                    "Horizon request duration in seconds by endpoint",
+                    &["endpoint"],
+                    vec![0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0],
+                    r
+                )
+                .unwrap(),
+            )
+            .ok();
     }
 }
 
@@ -415,8 +483,56 @@ pub mod cache {
 
     static CACHE_HITS_TOTAL: OnceLock<CounterVec> = OnceLock::new();
     static CACHE_MISSES_TOTAL: OnceLock<CounterVec> = OnceLock::new();
+    static CACHE_ERRORS_TOTAL: OnceLock<CounterVec> = OnceLock::new();
     static CACHE_OPERATION_DURATION_SECONDS: OnceLock<HistogramVec> = OnceLock::new();
 
+    /// Logical cache a Redis key belongs to, used as the `cache` label
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum CacheName {
+        /// Wallet balances and trustlines (`v1:wallet:*`)
+        Account,
+        /// Fee structures and API fee responses (`v1:fee:*`, `api:fees:*`)
+        Fee,
+        /// Exchange rates and conversions (`v1:rate:*`, `api:rates:*`)
+        Rate,
+        Other,
+    }
+
+    impl CacheName {
+        pub fn from_key(key: &str) -> Self {
+            let mut segments = key.split(':');
+            match (segments.next(), segments.next()) {
+                (_, Some("wallet")) => Self::Account,
+                (_, Some("fee")) | (Some("api"), Some("fees")) => Self::Fee,
+                (_, Some("rate")) | (Some("api"), Some("rates")) => Self::Rate,
+                _ => Self::Other,
+            }
+        }
+
+        pub fn as_str(&self) -> &'static str {
+            match self {
+                Self::Account => "account",
+                Self::Fee => "fee",
+                Self::Rate => "rate",
+                Self::Other => "other",
+            }
+        }
+    }
+
+    pub fn record_hit(cache: CacheName) {
+        hits_total().with_label_values(&[cache.as_str()]).inc();
+    }
+
+    pub fn record_miss(cache: CacheName) {
+        misses_total().with_label_values(&[cache.as_str()]).inc();
+    }
+
+    /// A failed lookup is served from the source, so it also counts as a miss
+    pub fn record_error(cache: CacheName) {
+        errors_total().with_label_values(&[cache.as_str()]).inc();
+        record_miss(cache);
+    }
+
     pub fn hits_total() -> &'static CounterVec {
         CACHE_HITS_TOTAL.get().expect("metrics not initialised")
     }
@@ -425,6 +541,10 @@ pub mod cache {
         CACHE_MISSES_TOTAL.get().expect("metrics not initialised")
     }
 
+    pub fn errors_total() -> &'static CounterVec {
+        CACHE_ERRORS_TOTAL.get().expect("metrics not initialised")
+    }
+
     pub fn operation_duration_seconds() -> &'static HistogramVec {
         CACHE_OPERATION_DURATION_SECONDS
             .get()
@@ -436,8 +556,8 @@ pub mod cache {
             .set(
                 register_counter_vec_with_registry!(
                     "aframp_cache_hits_total",
-                    "Total Redis cache hits by key prefix",
-                    &["key_prefix"],
+                    "Total Redis cache hits by cache",
+                    &["cache"],
                     r
                 )
                 .unwrap(),
@@ -448,8 +568,20 @@ pub mod cache {
             .set(
                 register_counter_vec_with_registry!(
                     "aframp_cache_misses_total",
-                    "Total Redis cache misses by key prefix",
-                    &["key_prefix"],
+                    "Total Redis cache misses by cache, including failed lookups",
+                    &["cache"],
+                    r
+                )
+                .unwrap(),
+            )
+            .ok();
+
+        CACHE_ERRORS_TOTAL
+            .set(
+                register_counter_vec_with_registry!(
+                    "aframp_cache_errors_total",
+                    "Total Redis cache lookups that failed by cache",
+                    &["cache"],
                     r
                 )
                 .unwrap(),
@@ -717,6 +849,63 @@ pub mod ip_detection {
     }
 }
 
+// ---------------------------------------------------------------------------
+// Build / feature metrics
+// ---------------------------------------------------------------------------
+
+pub mod build {
+    use super::*;
+
+    static BUILD_INFO: OnceLock<GaugeVec> = OnceLock::new();
+    static FEATURE_ENABLED: OnceLock<GaugeVec> = OnceLock::new();
+
+    pub fn build_info() -> &'static GaugeVec {
+        BUILD_INFO.get().expect("metrics not initialised")
+    }
+
+    pub fn feature_enabled() -> &'static GaugeVec {
+        FEATURE_ENABLED.get().expect("metrics not initialised")
+    }
+
+    /// Emit the build_info series and one gauge per feature flag. Called once at startup.
+    pub fn record_startup(network: &str, features: &[(&str, bool)]) {
+        build_info()
+            .with_label_values(&[env!("CARGO_PKG_VERSION"), env!("GIT_COMMIT"), network])
+            .set(1.0);
+        for (feature, enabled) in features {
+            feature_enabled()
+                .with_label_values(&[feature])
+                .set(if *enabled { 1.0 } else { 0.0 });
+        }
+    }
+
+    pub(super) fn register(r: &Registry) {
+        BUILD_INFO
+            .set(
+                register_gauge_vec_with_registry!(
+                    "aframp_build_info",
+                    "Build metadata; always 1",
+                    &["version", "git_commit", "network"],
+                    r
+                )
+                .unwrap(),
+            )
+            .ok();
+
+        FEATURE_ENABLED
+            .set(
+                register_gauge_vec_with_registry!(
+                    "aframp_feature_enabled",
+                    "Whether a feature flag is enabled at runtime (1) or not (0)",
+                    &["feature"],
+                    r
+                )
+                .unwrap(),
+            )
+            .ok();
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Register all metrics
 // ---------------------------------------------------------------------------
@@ -732,12 +921,5 @@ fn register_all(r: &Registry) {
     security::register(r);
     ip_detection::register(r);
     crate::ddos::metrics::register(r);
-}
-
-// ---------------------------------------------------------------------------
-// Helper: extract key prefix from a Redis key (first colon-delimited segment)
-// ---------------------------------------------------------------------------
-
-pub fn key_prefix(key: &str) -> &str {
-    key.find(':').map(|i| &key[..i]).unwrap_or(key)
+    build::register(r);
 }
@@ -140,7 +140,7 @@ mod tests {
         register_counter_vec_with_registry!(
             "test_cache_hits_total",
             "test",
-            &["key_prefix"],
+            &["cache"],
             r
         )
         .unwrap()
@@ -150,7 +150,7 @@ mod tests {
         register_counter_vec_with_registry!(
             "test_cache_misses_total",
             "test",
-            &["key_prefix"],
+            &["cache"],
             r
         )
         .unwrap()
@@ -420,15 +420,16 @@ mod tests {
         assert_eq!(gauge.with_label_values(&["primary"]).get(), 8.0);
     }
 
-    // -----------------------------------------------------------------------
-    // key_prefix helper test
-    // -----------------------------------------------------------------------
-
     #[test]
-    fn test_key_prefix_extraction() {
-        assert_eq!(crate::metrics::key_prefix("rates:NGN:USD"), "rates");
-        assert_eq!(crate::metrics::key_prefix("wallet:GXXX"), "wallet");
-        assert_eq!(crate::metrics::key_prefix("no_colon"), "no_colon");
+    fn test_cache_name_from_key() {
+        use crate::metrics::cache::CacheName;
+
+        assert_eq!(CacheName::from_key("v1:wallet:balance:GXXX"), CacheName::Account);
+        assert_eq!(CacheName::from_key("v1:fee:structure:onramp"), CacheName::Fee);
+        assert_eq!(CacheName::from_key("api:fees:onramp:all:100"), CacheName::Fee);
+        assert_eq!(CacheName::from_key("v1:rate:CNGN:USD"), CacheName::Rate);
+        assert_eq!(CacheName::from_key("api:rates:USD:NGN"), CacheName::Rate);
+        assert_eq!(CacheName::from_key("v1:auth:session:abc"), CacheName::Other);
     }
 
     // -----------------------------------------------------------------------
@@ -445,4 +446,26 @@ mod tests {
         // Must contain at least one metric family header
         assert!(output.contains("# HELP") || output.is_empty());
     }
+
+    #[tokio::test]
+    async fn test_metrics_endpoint_exposes_build_info() {
+        crate::metrics::registry();
+        crate::metrics::build::record_startup("testnet", &[("cache", true), ("otel", false)]);
+
+        let response = crate::metrics::handler::metrics_handler().await;
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+
+        let line = body
+            .lines()
+            .find(|l| l.starts_with("aframp_build_info{"))
+            .expect("build_info series missing");
+        assert!(line.contains(&format!("version=\"{}\"", env!("CARGO_PKG_VERSION"))));
+        assert!(line.contains("network=\"testnet\""));
+        assert!(line.ends_with(" 1"));
+        assert!(body.contains("aframp_feature_enabled{feature=\"cache\"} 1"));
+        assert!(body.contains("aframp_feature_enabled{feature=\"otel\"} 0"));
+    }
 }
@@ -0,0 +1,261 @@
+//! Admin guard for `/api/admin/*` routes.
+//!
+//! Accepts either credential an operator may hold:
+//!   - a JWT access token whose `scope` is `admin` and that hasn't been revoked
+//!   - an API key issued to an `admin_dashboard` consumer
+//!
+//! Missing or invalid credentials are 401; valid credentials without the admin
+//! role are 403. Both use the standard `ErrorResponse` body.
+//!
+//! Authenticated requests carry an [`AdminActor`]. Every successful request
+//! that isn't a read is written to `admin_audit_log`, unless the handler
+//! recorded a fuller entry itself and marked the response [`AdminAudited`].
+//! The trail fails closed: without a database no mutating request is let
+//! through, and a failed audit write turns the response into a 500.
+
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::{HeaderMap, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::Utc;
+use sqlx::PgPool;
+use std::sync::Arc;
+use tracing::{error, warn};
+
+use crate::auth::jwt::{is_access_token_blacklisted, validate_token, JwtError, Scope, TokenType};
+use crate::cache::RedisCache;
+use crate::database::admin_audit_repository::{AdminAuditRepository, NewAdminAuditEntry};
+use crate::error::ErrorCode;
+use crate::middleware::error::{get_request_id_from_headers, ErrorResponse};
+
+/// API key consumer type granted admin access
+pub const ADMIN_CONSUMER_TYPE: &str = "admin_dashboard";
+
+#[derive(Clone)]
+pub struct AdminGuardState {
+    pub jwt_secret: String,
+    /// API keys are only checked when a database is available
+    pub db: Option<Arc<PgPool>>,
+    /// Access token blacklist; revocation is only enforced when Redis is available
+    pub redis_cache: Option<RedisCache>,
+}
+
+/// Who an admin request was made by, as recorded in the audit log
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdminActor(pub String);
+
+impl AdminActor {
+    pub fn jwt(subject: &str) -> Self {
+        Self(format!("jwt:{}", subject))
+    }
+
+    pub fn api_key(key_id: uuid::Uuid) -> Self {
+        Self(format!("api_key:{}", key_id))
+    }
+}
+
+impl std::fmt::Display for AdminActor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Response extension set by handlers that wrote their own audit entry, so the
+/// guard doesn't record a second, less detailed one
+#[derive(Debug, Clone, Copy)]
+pub struct AdminAudited;
+
+fn guard_error(
+    status: StatusCode,
+    error: ErrorCode,
+    message: &str,
+    request_id: Option<String>,
+) -> Response {
+    (
+        status,
+        Json(ErrorResponse {
+            error,
+            message: message.to_string(),
+            request_id,
+            timestamp: Utc::now().to_rfc3339(),
+            details: None,
+            retryable: Some(false),
+        }),
+    )
+        .into_response()
+}
+
+fn unauthorized(request_id: Option<String>) -> Response {
+    guard_error(
+        StatusCode::UNAUTHORIZED,
+        ErrorCode::Unauthorized,
+        "Valid admin credentials are required",
+        request_id,
+    )
+}
+
+fn forbidden(request_id: Option<String>) -> Response {
+    guard_error(
+        StatusCode::FORBIDDEN,
+        ErrorCode::Forbidden,
+        "This operation requires admin privileges",
+        request_id,
+    )
+}
+
+fn extract_credential(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get("authorization")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .or_else(|| headers.get("x-api-key").and_then(|v| v.to_str().ok()))
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+/// Whether an access token's JTI is on the logout blacklist. Same policy as
+/// `auth::middleware::require_auth`: a cache failure doesn't block the request.
+async fn is_revoked(state: &AdminGuardState, jti: Option<&str>) -> bool {
+    let (Some(cache), Some(jti)) = (state.redis_cache.as_ref(), jti) else {
+        return false;
+    };
+    match is_access_token_blacklisted(cache, jti).await {
+        Ok(revoked) => revoked,
+        Err(e) => {
+            warn!(error = %e, "Blacklist check failed in admin guard; allowing token");
+            false
+        }
+    }
+}
+
+/// Let the request through only for an admin JWT or an admin API key.
+pub async fn require_admin(
+    State(state): State<AdminGuardState>,
+    mut req: Request,
+    next: Next,
+) -> Response {
+    let request_id = get_request_id_from_headers(req.headers());
+    let path = req.uri().path().to_string();
+
+    let Some(credential) = extract_credential(req.headers()) else {
+        return unauthorized(request_id);
+    };
+
+    match validate_token(&credential, &state.jwt_secret) {
+        // Refresh tokens only buy new access tokens, never admin access
+        Ok(claims) if claims.token_type != TokenType::Access => {
+            warn!(subject = %claims.sub, path = %path, "Refresh token rejected by admin guard");
+            return unauthorized(request_id);
+        }
+        Ok(claims) if claims.scope == Scope::Admin => {
+            if is_revoked(&state, claims.jti.as_deref()).await {
+                return unauthorized(request_id);
+            }
+            let actor = AdminActor::jwt(&claims.sub);
+            req.extensions_mut().insert(claims);
+            return run_audited(&state, actor, req, next).await;
+        }
+        Ok(claims) => {
+            warn!(subject = %claims.sub, path = %path, "Non-admin token rejected by admin guard");
+            return forbidden(request_id);
+        }
+        // Well-formed but stale or revoked tokens are never API keys
+        Err(JwtError::TokenExpired) | Err(JwtError::TokenRevoked) => {
+            return unauthorized(request_id);
+        }
+        Err(_) => {}
+    }
+
+    let Some(pool) = state.db.as_ref() else {
+        return unauthorized(request_id);
+    };
+    match crate::middleware::api_key::resolve_api_key(pool, &credential).await {
+        Some(key) if key.consumer_type == ADMIN_CONSUMER_TYPE => {
+            let actor = AdminActor::api_key(key.key_id);
+            req.extensions_mut().insert(key);
+            run_audited(&state, actor, req, next).await
+        }
+        Some(key) => {
+            warn!(
+                consumer_id = %key.consumer_id,
+                key_id = %key.key_id,
+                path = %path,
+                "Non-admin API key rejected by admin guard"
+            );
+            forbidden(request_id)
+        }
+        None => unauthorized(request_id),
+    }
+}
+
+/// Run an authenticated admin request and record it if it changed anything.
+/// Only the method and route are recorded here, never the body, which may hold
+/// credentials.
+async fn run_audited(
+    state: &AdminGuardState,
+    actor: AdminActor,
+    mut req: Request,
+    next: Next,
+) -> Response {
+    let method = req.method().clone();
+    let path = req.uri().path().to_string();
+    let route = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_else(|| path.clone());
+    let request_id = get_request_id_from_headers(req.headers());
+    req.extensions_mut().insert(actor.clone());
+
+    let is_read = matches!(method, Method::GET | Method::HEAD | Method::OPTIONS);
+    if is_read {
+        return next.run(req).await;
+    }
+    // A change that can't be audited isn't made
+    let Some(pool) = state.db.as_ref() else {
+        error!(
+            actor = %actor,
+            path = %path,
+            "No database for the admin audit log; refusing admin action"
+        );
+        return audit_unavailable(request_id);
+    };
+
+    let response = next.run(req).await;
+    if !response.status().is_success() || response.extensions().get::<AdminAudited>().is_some() {
+        return response;
+    }
+
+    let entry = NewAdminAuditEntry {
+        actor: actor.0.clone(),
+        action: format!("{} {}", method, route),
+        target: path.clone(),
+        before: None,
+        after: None,
+        request_id: request_id.clone(),
+    };
+    match AdminAuditRepository::new(pool.as_ref().clone()).record(&entry).await {
+        Ok(_) => response,
+        Err(e) => {
+            error!(
+                actor = %actor,
+                path = %path,
+                error = %e,
+                "ALERT: admin action completed but could not be audited"
+            );
+            audit_unavailable(request_id)
+        }
+    }
+}
+
+/// 500 for an admin change that couldn't be written to the audit log
+pub fn audit_unavailable(request_id: Option<String>) -> Response {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(ErrorResponse::internal_error(request_id)),
+    )
+        .into_response()
+}
@@ -0,0 +1,236 @@
+//! Optional `{ data, meta }` / `{ error, meta }` response envelope
+//!
+//! Handlers return bare JSON objects. Consumers that prefer one shape for
+//! every endpoint can get it without any handler changes, either for the whole
+//! deploy (`RESPONSE_ENVELOPE=true`) or per request by sending
+//! `Accept: application/vnd.aframp.envelope+json`. Successful bodies move under
+//! `data`, `ErrorResponse` bodies under `error`, and `meta` carries the request
+//! id and a timestamp. Only `application/json` responses are touched, so event
+//! streams, CSV exports and other bodies pass through as they are.
+//!
+//! The layer sits outermost so rate-limit and DDoS rejections are wrapped
+//! too; the request id is therefore read from the response, where the inner
+//! request-id layers have put it.
+
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use chrono::{SecondsFormat, Utc};
+use serde_json::json;
+
+/// `Accept` media type that opts a single request into the envelope
+pub const ENVELOPE_MEDIA_TYPE: &str = "application/vnd.aframp.envelope+json";
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EnvelopeConfig {
+    /// Wrap every JSON response, not only those whose request asked for it
+    pub always: bool,
+}
+
+impl EnvelopeConfig {
+    /// `RESPONSE_ENVELOPE=true|1` turns the envelope on for the whole deploy.
+    pub fn from_env() -> Self {
+        let always = std::env::var("RESPONSE_ENVELOPE")
+            .map(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "true" | "1"))
+            .unwrap_or(false);
+        Self { always }
+    }
+
+    fn applies_to(&self, request: &Request) -> bool {
+        self.always
+            || request
+                .headers()
+                .get_all(header::ACCEPT)
+                .iter()
+                .filter_map(|v| v.to_str().ok())
+                .any(|v| v.contains(ENVELOPE_MEDIA_TYPE))
+    }
+}
+
+pub async fn response_envelope_middleware(
+    State(config): State<EnvelopeConfig>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if !config.applies_to(&request) {
+        return next.run(request).await;
+    }
+
+    let sent_request_id = crate::middleware::error::get_request_id_from_headers(request.headers());
+    let response = next.run(request).await;
+    let request_id = crate::middleware::error::get_request_id_from_headers(response.headers())
+        .or(sent_request_id);
+
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("application/json"));
+    if !is_json {
+        return response;
+    }
+
+    let success = response.status().is_success();
+    let (mut parts, body) = response.into_parts();
+    let bytes = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(_) => {
+            // The original length no longer describes the body
+            parts.headers.remove(header::CONTENT_LENGTH);
+            return Response::from_parts(parts, axum::body::Body::empty());
+        }
+    };
+    let payload: serde_json::Value = match serde_json::from_slice(&bytes) {
+        Ok(payload) => payload,
+        // Labelled JSON but isn't; hand it back untouched
+        Err(_) => return Response::from_parts(parts, axum::body::Body::from(bytes)),
+    };
+
+    let meta = json!({
+        "request_id": request_id,
+        "timestamp": Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+    });
+    let wrapped = if success {
+        json!({ "data": payload, "meta": meta })
+    } else {
+        json!({ "error": payload, "meta": meta })
+    };
+
+    parts.headers.remove(header::CONTENT_LENGTH);
+    parts.headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("application/json"),
+    );
+    Response::from_parts(parts, axum::body::Body::from(wrapped.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{http::StatusCode, routing::get, Json, Router};
+    use tower::ServiceExt;
+
+    fn router(config: EnvelopeConfig) -> Router {
+        Router::new()
+            .route(
+                "/api/rates",
+                get(|| async { Json(json!({ "pair": "NGN/cNGN", "rate": "1.0" })) }),
+            )
+            .route(
+                "/api/missing",
+                get(|| async {
+                    crate::middleware::error::json_error_response(
+                        StatusCode::NOT_FOUND,
+                        "nothing here",
+                        Some("req_err".to_string()),
+                    )
+                }),
+            )
+            .route(
+                "/api/export.csv",
+                get(|| async {
+                    ([(header::CONTENT_TYPE, "text/csv")], "pair,rate\nNGN/cNGN,1.0\n")
+                }),
+            )
+            .layer(axum::middleware::from_fn_with_state(
+                config,
+                response_envelope_middleware,
+            ))
+    }
+
+    async fn call(config: EnvelopeConfig, request: Request) -> (StatusCode, String) {
+        let response = router(config).oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, String::from_utf8(bytes.to_vec()).unwrap())
+    }
+
+    fn get_request(uri: &str) -> Request {
+        Request::get(uri)
+            .header("x-request-id", "req_123")
+            .body(axum::body::Body::empty())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn same_endpoint_wrapped_only_when_configured() {
+        let (_, bare) = call(EnvelopeConfig { always: false }, get_request("/api/rates")).await;
+        let bare: serde_json::Value = serde_json::from_str(&bare).unwrap();
+        assert_eq!(bare, json!({ "pair": "NGN/cNGN", "rate": "1.0" }));
+
+        let (status, wrapped) =
+            call(EnvelopeConfig { always: true }, get_request("/api/rates")).await;
+        let wrapped: serde_json::Value = serde_json::from_str(&wrapped).unwrap();
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(wrapped["data"], bare);
+        assert_eq!(wrapped["meta"]["request_id"], "req_123");
+        assert!(wrapped["meta"]["timestamp"].is_string());
+        assert!(wrapped.get("error").is_none());
+    }
+
+    #[tokio::test]
+    async fn accept_header_opts_a_single_request_in() {
+        let request = Request::get("/api/rates")
+            .header(header::ACCEPT, ENVELOPE_MEDIA_TYPE)
+            .body(axum::body::Body::empty())
+            .unwrap();
+
+        let (_, body) = call(EnvelopeConfig { always: false }, request).await;
+        let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+
+        assert_eq!(body["data"]["pair"], "NGN/cNGN");
+    }
+
+    #[tokio::test]
+    async fn error_response_kept_under_error() {
+        let (status, body) =
+            call(EnvelopeConfig { always: true }, get_request("/api/missing")).await;
+        let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["error"]["details"]["message"], "nothing here");
+        assert_eq!(body["error"]["request_id"], "req_err");
+        assert!(body.get("data").is_none());
+    }
+
+    #[tokio::test]
+    async fn request_id_taken_from_the_response() {
+        let app = Router::new()
+            .route(
+                "/api/rates",
+                get(|| async {
+                    (
+                        [("x-request-id", "req_assigned")],
+                        Json(json!({ "pair": "NGN/cNGN" })),
+                    )
+                }),
+            )
+            .layer(axum::middleware::from_fn_with_state(
+                EnvelopeConfig { always: true },
+                response_envelope_middleware,
+            ));
+        let request = Request::get("/api/rates")
+            .body(axum::body::Body::empty())
+            .unwrap();
+
+        let response = app.oneshot(request).await.unwrap();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+
+        assert_eq!(body["meta"]["request_id"], "req_assigned");
+    }
+
+    #[tokio::test]
+    async fn csv_passes_through_unwrapped() {
+        let (_, body) = call(EnvelopeConfig { always: true }, get_request("/api/export.csv")).await;
+
+        assert_eq!(body, "pair,rate\nNGN/cNGN,1.0\n");
+    }
+}
@@ -54,7 +54,7 @@ impl ErrorResponse {
             message: error.user_message(),
             request_id: error.request_id.clone(),
             timestamp: Utc::now().to_rfc3339(),
-            details: None,
+            details: error.details(),
             retryable: Some(error.is_retryable()),
         }
     }
@@ -119,7 +119,13 @@ impl IntoResponse for AppError {
         }
 
         let error_response = ErrorResponse::from_app_error(&self);
-        (status_code, Json(error_response)).into_response()
+        let mut response = (status_code, Json(error_response)).into_response();
+        if let Some(secs) = self.retry_after() {
+            response
+                .headers_mut()
+                .insert(axum::http::header::RETRY_AFTER, secs.into());
+        }
+        response
     }
 }
 
@@ -229,12 +235,272 @@ pub fn json_error_response(
     (status, Json(error_response))
 }
 
+/// 503 for a handler whose dependency is switched off by configuration.
+/// Coded `DEPENDENCY_DISABLED` so clients and operators can tell it apart
+/// from a real outage, which retrying might fix.
+#[cfg(feature = "database")]
+pub fn dependency_disabled_response(
+    dependency: &str,
+    request_id: Option<String>,
+) -> (StatusCode, Json<ErrorResponse>) {
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        Json(ErrorResponse {
+            error: ErrorCode::DependencyDisabled,
+            message: format!("{} disabled by configuration", dependency),
+            request_id,
+            timestamp: Utc::now().to_rfc3339(),
+            details: Some(serde_json::json!({ "dependency": dependency })),
+            retryable: Some(false),
+        }),
+    )
+}
+
+/// Error half of a handler's `Result`. Carries the same status and body as
+/// the `(StatusCode, Json<ErrorResponse>)` helpers above, which convert into
+/// it, plus the `Retry-After` hint an [`AppError`] may have.
+#[cfg(feature = "database")]
+#[derive(Debug)]
+pub struct HandlerError {
+    pub status: StatusCode,
+    pub body: Json<ErrorResponse>,
+    pub retry_after: Option<u64>,
+}
+
+#[cfg(feature = "database")]
+impl HandlerError {
+    /// Response for `err`, tagged with `request_id` when there is one
+    pub fn from_app_error(err: AppError, request_id: Option<String>) -> Self {
+        let err = match request_id {
+            Some(req_id) => err.with_request_id(req_id),
+            None => err,
+        };
+        Self {
+            status: StatusCode::from_u16(err.status_code())
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+            body: Json(ErrorResponse::from_app_error(&err)),
+            retry_after: err.retry_after(),
+        }
+    }
+}
+
+#[cfg(feature = "database")]
+impl From<(StatusCode, Json<ErrorResponse>)> for HandlerError {
+    fn from((status, body): (StatusCode, Json<ErrorResponse>)) -> Self {
+        Self {
+            status,
+            body,
+            retry_after: None,
+        }
+    }
+}
+
+#[cfg(feature = "database")]
+impl IntoResponse for HandlerError {
+    fn into_response(self) -> Response {
+        let mut response = (self.status, self.body).into_response();
+        if let Some(secs) = self.retry_after {
+            response
+                .headers_mut()
+                .insert(axum::http::header::RETRY_AFTER, secs.into());
+        }
+        response
+    }
+}
+
+/// Structured body for a request axum rejected before reaching a handler
+#[cfg(feature = "database")]
+pub fn rejection_response(
+    status: StatusCode,
+    message: impl Into<String>,
+    request_id: Option<String>,
+) -> (StatusCode, Json<ErrorResponse>) {
+    let error = match status {
+        StatusCode::NOT_FOUND => ErrorCode::NotFound,
+        StatusCode::METHOD_NOT_ALLOWED => ErrorCode::MethodNotAllowed,
+        StatusCode::UNSUPPORTED_MEDIA_TYPE => ErrorCode::UnsupportedMediaType,
+        _ => ErrorCode::ValidationError,
+    };
+
+    (
+        status,
+        Json(ErrorResponse {
+            error,
+            message: message.into(),
+            request_id,
+            timestamp: Utc::now().to_rfc3339(),
+            details: None,
+            retryable: Some(false),
+        }),
+    )
+}
+
+/// Router fallback for paths with no matching route
+#[cfg(feature = "database")]
+pub async fn not_found_fallback(
+    headers: axum::http::HeaderMap,
+    uri: axum::http::Uri,
+) -> (StatusCode, Json<ErrorResponse>) {
+    rejection_response(
+        StatusCode::NOT_FOUND,
+        format!("No route for {}", uri.path()),
+        get_request_id_from_headers(&headers),
+    )
+}
+
+/// Router fallback for a known path called with an unsupported method.
+/// axum still adds the `Allow` header to this response.
+#[cfg(feature = "database")]
+pub async fn method_not_allowed_fallback(
+    headers: axum::http::HeaderMap,
+    method: axum::http::Method,
+    uri: axum::http::Uri,
+) -> (StatusCode, Json<ErrorResponse>) {
+    rejection_response(
+        StatusCode::METHOD_NOT_ALLOWED,
+        format!("Method {} is not allowed for {}", method, uri.path()),
+        get_request_id_from_headers(&headers),
+    )
+}
+
+/// Rewrites the plain-text bodies of axum's extractor rejections (e.g. a JSON
+/// body sent without `Content-Type: application/json`) into `ErrorResponse`,
+/// keeping the status and any headers such as `Allow`.
+#[cfg(feature = "database")]
+pub async fn normalize_rejections_middleware(
+    request: Request,
+    next: axum::middleware::Next,
+) -> Response {
+    let request_id = get_request_id_from_headers(request.headers());
+    let response = next.run(request).await;
+
+    let status = response.status();
+    let is_json = response
+        .headers()
+        .get(axum::http::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("application/json"));
+    if is_json
+        || !matches!(
+            status,
+            StatusCode::METHOD_NOT_ALLOWED | StatusCode::UNSUPPORTED_MEDIA_TYPE
+        )
+    {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let text = axum::body::to_bytes(body, 4096)
+        .await
+        .map(|b| String::from_utf8_lossy(&b).trim().to_string())
+        .unwrap_or_default();
+    let message = if text.is_empty() {
+        status
+            .canonical_reason()
+            .unwrap_or("Request rejected")
+            .to_string()
+    } else {
+        text
+    };
+
+    let rewritten = rejection_response(status, message, request_id).into_response();
+    parts.headers.remove(axum::http::header::CONTENT_LENGTH);
+    parts.headers.extend(rewritten.headers().clone());
+    Response::from_parts(parts, rewritten.into_body())
+}
+
 #[cfg(all(test, feature = "database"))]
 mod tests {
     use super::*;
     use crate::error::{AppError, AppErrorKind, DomainError, ValidationError};
     use axum::{http::StatusCode, response::IntoResponse};
 
+    fn rejection_router() -> axum::Router {
+        axum::Router::new()
+            .route(
+                "/api/things",
+                axum::routing::post(|Json(body): Json<serde_json::Value>| async move {
+                    Json(body)
+                }),
+            )
+            .fallback(not_found_fallback)
+            .method_not_allowed_fallback(method_not_allowed_fallback)
+            .layer(axum::middleware::from_fn(normalize_rejections_middleware))
+    }
+
+    async fn send(
+        request: axum::http::Request<axum::body::Body>,
+    ) -> (StatusCode, serde_json::Value) {
+        use tower::ServiceExt;
+
+        let response = rejection_router().oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_unknown_path_returns_structured_not_found() {
+        let request = axum::http::Request::get("/api/nope")
+            .header("x-request-id", "req_404")
+            .body(axum::body::Body::empty())
+            .unwrap();
+
+        let (status, body) = send(request).await;
+
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["error"], "NOT_FOUND");
+        assert_eq!(body["request_id"], "req_404");
+        assert_eq!(body["message"], "No route for /api/nope");
+    }
+
+    #[tokio::test]
+    async fn test_wrong_method_returns_structured_method_not_allowed() {
+        let request = axum::http::Request::get("/api/things")
+            .header("x-request-id", "req_405")
+            .body(axum::body::Body::empty())
+            .unwrap();
+
+        let (status, body) = send(request).await;
+
+        assert_eq!(status, StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(body["error"], "METHOD_NOT_ALLOWED");
+        assert_eq!(body["request_id"], "req_405");
+    }
+
+    #[tokio::test]
+    async fn test_missing_content_type_returns_structured_unsupported_media_type() {
+        let request = axum::http::Request::post("/api/things")
+            .header("x-request-id", "req_415")
+            .body(axum::body::Body::from(r#"{"a":1}"#))
+            .unwrap();
+
+        let (status, body) = send(request).await;
+
+        assert_eq!(status, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        assert_eq!(body["error"], "UNSUPPORTED_MEDIA_TYPE");
+        assert_eq!(body["request_id"], "req_415");
+        assert!(body["message"]
+            .as_str()
+            .unwrap()
+            .contains("application/json"));
+    }
+
+    #[test]
+    fn test_dependency_disabled_is_distinct_from_outage() {
+        let (status, Json(body)) =
+            dependency_disabled_response("Database", Some("req_9".to_string()));
+
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body.error, ErrorCode::DependencyDisabled);
+        assert_eq!(body.retryable, Some(false));
+        let json = serde_json::to_value(&body).unwrap();
+        assert_eq!(json["error"], "DEPENDENCY_DISABLED");
+        assert_eq!(json["message"], "Database disabled by configuration");
+    }
+
     #[test]
     fn test_error_response_from_app_error() {
         let app_error = AppError::new(AppErrorKind::Domain(DomainError::InsufficientBalance {
@@ -261,6 +527,79 @@ mod tests {
         assert_eq!(response.status(), StatusCode::BAD_REQUEST);
     }
 
+    #[test]
+    fn test_upstream_unavailable_sets_retry_after() {
+        use crate::error::ExternalError;
+
+        let app_error = AppError::new(AppErrorKind::External(ExternalError::ServiceUnavailable {
+            service: "Stellar".to_string(),
+            retry_after: Some(30),
+        }));
+        assert_eq!(app_error.error_code(), ErrorCode::UpstreamUnavailable);
+
+        let response = app_error.into_response();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(
+            response.headers().get(axum::http::header::RETRY_AFTER).unwrap(),
+            "30"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_handler_error_sets_retry_after_for_upstream_unavailable() {
+        use crate::chains::stellar::errors::StellarError;
+        use tower::ServiceExt;
+
+        async fn lookup() -> Result<Json<()>, HandlerError> {
+            Err(HandlerError::from_app_error(
+                StellarError::ServiceUnavailable {
+                    retry_after: Some(30),
+                }
+                .into(),
+                Some("req_503".to_string()),
+            ))
+        }
+        async fn disabled() -> Result<Json<()>, HandlerError> {
+            Err(dependency_disabled_response("Stellar client", None).into())
+        }
+        let router = axum::Router::new()
+            .route("/lookup", axum::routing::get(lookup))
+            .route("/disabled", axum::routing::get(disabled));
+
+        let response = router
+            .clone()
+            .oneshot(
+                axum::http::Request::get("/lookup")
+                    .body(axum::body::Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(
+            response.headers().get(axum::http::header::RETRY_AFTER).unwrap(),
+            "30"
+        );
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(body["error"], "UPSTREAM_UNAVAILABLE");
+        assert_eq!(body["request_id"], "req_503");
+
+        // A disabled dependency won't come back by waiting, so no hint
+        let response = router
+            .oneshot(
+                axum::http::Request::get("/disabled")
+                    .body(axum::body::Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert!(response.headers().get(axum::http::header::RETRY_AFTER).is_none());
+    }
+
     #[test]
     fn test_internal_error_response() {
         let error = ErrorResponse::internal_error(Some("req_456".to_string()));
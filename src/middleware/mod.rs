@@ -2,9 +2,15 @@
 //!
 //! Provides request/response logging and error handling middleware
 
+#[cfg(feature = "database")]
+pub mod admin;
+
 #[cfg(feature = "database")]
 pub mod api_key;
 
+#[cfg(feature = "database")]
+pub mod envelope;
+
 #[cfg(feature = "database")]
 pub mod error;
 
@@ -108,7 +108,7 @@ pub async fn rate_limit_middleware(
 
     let (redis_key, limit_conf) = if let Some(ref w) = wallet_address {
         if let Some(wl) = limits.per_wallet {
-            (format!("rate_limit:wallet:{}:{}", w, path), wl)
+            (wallet_window_key(w, &path), wl)
         } else if let Some(il) = limits.per_ip {
             // Fallback to IP if no wallet limit exists
             (format!("rate_limit:ip:{}:{}", ip, path), il)
@@ -124,42 +124,23 @@ pub async fn rate_limit_middleware(
     };
 
     // 3. Sliding Window Algorithm Pipeline
-    let mut conn = match state.cache.get_connection().await {
-        Ok(c) => c,
-        Err(e) => {
-            error!("Redis connection failed in rate_limit_middleware: {}", e);
-            let body = Json(json!({"error": "Internal server error"}));
-            return Err((StatusCode::INTERNAL_SERVER_ERROR, body).into_response());
-        }
-    };
-
     let now_ms = Utc::now().timestamp_millis();
-    let window_start_ms = now_ms - (limit_conf.window * 1000);
-    let req_id = Uuid::new_v4().to_string();
-
-    // Pipeline:
-    // ZREMRANGEBYSCORE key -inf window_start_ms
-    // ZCOUNT key -inf +inf
-    let (removed, count): (i64, i64) = match redis::pipe()
-        .atomic()
-        .cmd("ZREMRANGEBYSCORE").arg(&redis_key).arg("-inf").arg(window_start_ms)
-        .cmd("ZCARD").arg(&redis_key)
-        .query_async(&mut *conn).await 
-    {
-        Ok(res) => res,
+    let usage = match consume_window(&state.cache, &redis_key, &limit_conf).await {
+        Ok(usage) => usage,
         Err(e) => {
-            error!("Redis pipe error in rate_limit_middleware: {}", e);
+            error!("Redis error in rate_limit_middleware: {}", e);
             let body = Json(json!({"error": "Internal server error"}));
             return Err((StatusCode::INTERNAL_SERVER_ERROR, body).into_response());
         }
     };
+    let count = usage.count;
 
     let remaining = limit_conf.limit - count;
 
     let reset_at = (now_ms / 1000) + limit_conf.window;
     let mut retry_after = limit_conf.window;
 
-    if count >= limit_conf.limit {
+    if !usage.admitted {
         warn!(key = %redis_key, count = count, limit = limit_conf.limit, "Rate limit exceeded");
         
         let response_body = json!({
@@ -185,17 +166,6 @@ pub async fn rate_limit_middleware(
         return Err(res);
     }
 
-    // Allow path: Add current request
-    let _: () = match redis::pipe()
-        .atomic()
-        .cmd("ZADD").arg(&redis_key).arg(now_ms).arg(&req_id)
-        .cmd("EXPIRE").arg(&redis_key).arg(limit_conf.window)
-        .query_async::<()>(&mut *conn).await
-    {
-        Ok(_) => (),
-        Err(e) => error!("Failed to add to sorted set for rate_limit_middleware: {}", e),
-    };
-
     // Forward the request to the next logical layer
     let mut res = next.run(req).await.into_response();
 
@@ -207,3 +177,88 @@ pub async fn rate_limit_middleware(
 
     Ok(res)
 }
+
+/// Key of `wallet`'s sliding window for requests to `path`
+pub fn wallet_window_key(wallet: &str, path: &str) -> String {
+    format!("rate_limit:wallet:{}:{}", wallet, path)
+}
+
+/// Requests in a window before this one, and whether this one was counted
+#[derive(Debug, Clone, Copy)]
+pub struct WindowUsage {
+    pub count: i64,
+    pub admitted: bool,
+}
+
+/// Drop entries older than the window, then count this request in `key`'s
+/// window if it is still under the limit.
+pub async fn consume_window(
+    cache: &RedisCache,
+    key: &str,
+    limit_conf: &LimitConfig,
+) -> Result<WindowUsage, String> {
+    let mut conn = cache.get_connection().await.map_err(|e| e.to_string())?;
+
+    let now_ms = Utc::now().timestamp_millis();
+    let window_start_ms = now_ms - (limit_conf.window * 1000);
+
+    // Pipeline:
+    // ZREMRANGEBYSCORE key -inf window_start_ms
+    // ZCOUNT key -inf +inf
+    let (_removed, count): (i64, i64) = redis::pipe()
+        .atomic()
+        .cmd("ZREMRANGEBYSCORE").arg(key).arg("-inf").arg(window_start_ms)
+        .cmd("ZCARD").arg(key)
+        .query_async(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if count >= limit_conf.limit {
+        return Ok(WindowUsage { count, admitted: false });
+    }
+
+    // Allow path: Add current request
+    let req_id = Uuid::new_v4().to_string();
+    if let Err(e) = redis::pipe()
+        .atomic()
+        .cmd("ZADD").arg(key).arg(now_ms).arg(&req_id)
+        .cmd("EXPIRE").arg(key).arg(limit_conf.window)
+        .query_async::<()>(&mut *conn)
+        .await
+    {
+        error!("Failed to add to sorted set for rate limit window: {}", e);
+    }
+
+    Ok(WindowUsage { count, admitted: true })
+}
+
+/// Counts batch items against the per-wallet window of the endpoint that
+/// submits them one at a time, keyed by each envelope's source account
+pub struct WalletWindowLimit {
+    pub cache: Arc<RedisCache>,
+    /// Endpoint whose window is shared, e.g. `/api/afri/transactions/submit`
+    pub path: String,
+    pub limit: LimitConfig,
+}
+
+#[async_trait::async_trait]
+impl crate::chains::stellar::submission::SubmissionLimit for WalletWindowLimit {
+    async fn reserve(
+        &self,
+        _envelope_xdr: &str,
+        summary: &crate::chains::stellar::payment::EnvelopeSummary,
+    ) -> Result<(), String> {
+        let key = wallet_window_key(&summary.source, &self.path);
+        match consume_window(&self.cache, &key, &self.limit).await {
+            Ok(usage) if usage.admitted => Ok(()),
+            Ok(_) => Err(format!(
+                "{} has used its {} submissions per {}s",
+                summary.source, self.limit.limit, self.limit.window
+            )),
+            Err(e) => {
+                error!("Redis error checking batch submission window: {}", e);
+                Err("rate limit unavailable".to_string())
+            }
+        }
+    }
+}
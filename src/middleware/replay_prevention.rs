@@ -44,7 +44,8 @@ use crate::cache::keys::replay::NonceKey;
 pub struct ReplayConfig {
     /// Maximum age of a request timestamp (seconds). Default: 300 (5 minutes).
     pub timestamp_window_secs: i64,
-    /// How far into the future a timestamp may be (seconds). Default: 30.
+    /// How far into the future a timestamp may be (seconds). Default: the
+    /// shared clock skew tolerance, 30 unless `CLOCK_SKEW_TOLERANCE_SECS` is set.
     pub future_tolerance_secs: i64,
     /// Extra TTL buffer added to the Redis nonce key beyond the timestamp window (seconds).
     pub nonce_ttl_buffer_secs: u64,
@@ -59,7 +60,7 @@ impl Default for ReplayConfig {
     fn default() -> Self {
         Self {
             timestamp_window_secs: 300,
-            future_tolerance_secs: 30,
+            future_tolerance_secs: crate::clock_skew::tolerance_secs(),
             nonce_ttl_buffer_secs: 60,
             clock_skew_alert_threshold_secs: 60.0,
             replay_alert_threshold: 5,
@@ -78,10 +79,12 @@ impl ReplayConfig {
     /// | Variable                          | Default | Description                                      |
     /// |-----------------------------------|---------|--------------------------------------------------|
     /// | `REPLAY_TIMESTAMP_WINDOW_SECS`    | 300     | Max age of a request timestamp (seconds).        |
-    /// | `REPLAY_FUTURE_TOLERANCE_SECS`    | 30      | Max future skew allowed (seconds).               |
+    /// | `REPLAY_FUTURE_TOLERANCE_SECS`    | 30 [^1] | Max future skew allowed (seconds).               |
     /// | `REPLAY_NONCE_TTL_BUFFER_SECS`    | 60      | Extra TTL buffer on top of the window (seconds). |
     /// | `REPLAY_CLOCK_SKEW_ALERT_SECS`    | 60      | Clock skew alert threshold (seconds).            |
     /// | `REPLAY_ATTEMPT_ALERT_THRESHOLD`  | 5       | Replay attempts before alerting.                 |
+    ///
+    /// [^1]: Falls back to `CLOCK_SKEW_TOLERANCE_SECS`, see [`crate::clock_skew`].
     pub fn from_env() -> Self {
         let default = Self::default();
         Self {
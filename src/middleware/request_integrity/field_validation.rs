@@ -6,6 +6,7 @@ use serde_json::Value;
 use tracing::warn;
 use uuid::Uuid;
 
+use crate::api::validation::ArrayBounds;
 use crate::cache::cache::Cache;
 use crate::chains::stellar::types::is_valid_stellar_address;
 use crate::database::provider_config_repository::ProviderConfigRepository;
@@ -121,11 +122,11 @@ fn validate_batch_cngn(payload: &Value, ctx: &mut ValidationContext) -> Result<(
         .get("transfers")
         .and_then(Value::as_array)
         .ok_or_else(|| IntegrityError::field("INVALID_TRANSFERS", "transfers must be an array", Some("transfers".to_string())))?;
-    if transfers.is_empty() {
+    if let Err(e) = ArrayBounds::new(usize::MAX).check("transfers", transfers) {
         return Err(IntegrityError::field(
-            "EMPTY_BATCH",
-            "Batch must contain at least one transfer",
-            Some("transfers".to_string()),
+            "VALIDATION_ERROR",
+            e.to_string(),
+            Some(e.field().to_string()),
         ));
     }
 
@@ -160,11 +161,11 @@ fn validate_batch_fiat(payload: &Value, ctx: &mut ValidationContext) -> Result<(
         .get("payouts")
         .and_then(Value::as_array)
         .ok_or_else(|| IntegrityError::field("INVALID_PAYOUTS", "payouts must be an array", Some("payouts".to_string())))?;
-    if payouts.is_empty() {
+    if let Err(e) = ArrayBounds::new(usize::MAX).check("payouts", payouts) {
         return Err(IntegrityError::field(
-            "EMPTY_BATCH",
-            "Batch must contain at least one payout",
-            Some("payouts".to_string()),
+            "VALIDATION_ERROR",
+            e.to_string(),
+            Some(e.field().to_string()),
         ));
     }
 
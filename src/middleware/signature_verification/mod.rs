@@ -51,9 +51,6 @@ const SIGNING_KEY_CACHE_TTL_SECS: u64 = 300;
 /// Replay-prevention window (seconds). Requests older than this are rejected.
 const TIMESTAMP_WINDOW_SECS: i64 = 300;
 
-/// Future-tolerance (seconds). Requests this far ahead of server time are rejected.
-const FUTURE_TOLERANCE_SECS: i64 = 30;
-
 // ---------------------------------------------------------------------------
 // Algorithm policy
 // ---------------------------------------------------------------------------
@@ -160,7 +157,9 @@ pub(crate) fn validate_timestamp(request_ts: i64, server_ts: i64) -> Result<(),
     if delta > TIMESTAMP_WINDOW_SECS {
         return Err(VerifyFailReason::ExpiredTimestamp);
     }
-    if -delta > FUTURE_TOLERANCE_SECS {
+    // Requests further ahead of server time than the clock skew tolerance
+    let tolerance = crate::clock_skew::tolerance_secs();
+    if crate::clock_skew::is_not_yet_valid(request_ts, server_ts, tolerance) {
         return Err(VerifyFailReason::ExpiredTimestamp);
     }
     Ok(())
@@ -428,6 +427,7 @@ fn emit_failure_weak_alg(consumer_id: &str, endpoint: &str, start: Instant) -> R
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::clock_skew::DEFAULT_CLOCK_SKEW_SECS;
     use crate::middleware::hmac_signing::{sign_request, HmacAlgorithm};
 
     // ── Timestamp validation ─────────────────────────────────────────────────
@@ -457,7 +457,7 @@ mod tests {
     fn rejects_timestamp_too_far_in_future() {
         let server = 1_000_000i64;
         assert_eq!(
-            validate_timestamp(server + FUTURE_TOLERANCE_SECS + 1, server),
+            validate_timestamp(server + DEFAULT_CLOCK_SKEW_SECS + 1, server),
             Err(VerifyFailReason::ExpiredTimestamp)
         );
     }
@@ -465,7 +465,7 @@ mod tests {
     #[test]
     fn accepts_timestamp_within_future_tolerance() {
         let server = 1_000_000i64;
-        assert!(validate_timestamp(server + FUTURE_TOLERANCE_SECS, server).is_ok());
+        assert!(validate_timestamp(server + DEFAULT_CLOCK_SKEW_SECS, server).is_ok());
     }
 
     // ── Constant-time comparison ─────────────────────────────────────────────
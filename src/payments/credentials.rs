@@ -0,0 +1,82 @@
+//! Runtime provider credential overrides
+//!
+//! Lets operators rotate a provider secret without a redeploy. Overrides are
+//! held in an `ArcSwap` so readers never block while a rotation is in flight,
+//! and take precedence over the secrets loaded from the environment.
+
+use crate::payments::types::ProviderName;
+use arc_swap::ArcSwap;
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+
+#[derive(Default)]
+pub struct ProviderCredentialStore {
+    secrets: ArcSwap<HashMap<ProviderName, String>>,
+}
+
+impl ProviderCredentialStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Process-wide store shared by every factory built with `from_env`.
+    pub fn global() -> Arc<Self> {
+        static GLOBAL: OnceLock<Arc<ProviderCredentialStore>> = OnceLock::new();
+        GLOBAL.get_or_init(|| Arc::new(Self::new())).clone()
+    }
+
+    pub fn secret_for(&self, provider: &ProviderName) -> Option<String> {
+        self.secrets.load().get(provider).cloned()
+    }
+
+    pub fn set_secret(&self, provider: ProviderName, secret: String) {
+        self.secrets.rcu(|current| {
+            let mut next = HashMap::clone(current);
+            next.insert(provider.clone(), secret.clone());
+            next
+        });
+    }
+}
+
+impl std::fmt::Debug for ProviderCredentialStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let providers: Vec<String> = self
+            .secrets
+            .load()
+            .keys()
+            .map(|p| p.to_string())
+            .collect();
+        f.debug_struct("ProviderCredentialStore")
+            .field("overridden_providers", &providers)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_secret_replaces_previous_value() {
+        let store = ProviderCredentialStore::new();
+        assert!(store.secret_for(&ProviderName::Paystack).is_none());
+
+        store.set_secret(ProviderName::Paystack, "sk_old".to_string());
+        store.set_secret(ProviderName::Paystack, "sk_new".to_string());
+
+        assert_eq!(
+            store.secret_for(&ProviderName::Paystack).as_deref(),
+            Some("sk_new")
+        );
+        assert!(store.secret_for(&ProviderName::Flutterwave).is_none());
+    }
+
+    #[test]
+    fn debug_output_does_not_leak_secrets() {
+        let store = ProviderCredentialStore::new();
+        store.set_secret(ProviderName::Paystack, "sk_live_super_secret".to_string());
+        let rendered = format!("{:?}", store);
+        assert!(rendered.contains("paystack"));
+        assert!(!rendered.contains("sk_live_super_secret"));
+    }
+}
@@ -31,6 +31,13 @@ pub enum PaymentError {
     #[error("Webhook verification failed: {message}")]
     WebhookVerificationError { message: String },
 
+    #[error("Payment provider {provider} is not configured")]
+    ProviderNotConfigured { provider: String },
+
+    /// Credentials are present but unusable, e.g. a malformed key
+    #[error("Payment provider {provider} is misconfigured: {message}")]
+    ConfigurationError { provider: String, message: String },
+
     #[error("Provider error: provider={provider}, message={message}")]
     ProviderError {
         provider: String,
@@ -49,6 +56,8 @@ impl PaymentError {
             PaymentError::NetworkError { .. } => true,
             PaymentError::RateLimitError { .. } => true,
             PaymentError::WebhookVerificationError { .. } => false,
+            PaymentError::ProviderNotConfigured { .. } => false,
+            PaymentError::ConfigurationError { .. } => false,
             PaymentError::ProviderError { retryable, .. } => *retryable,
         }
     }
@@ -61,6 +70,8 @@ impl PaymentError {
             PaymentError::NetworkError { .. } => 503,
             PaymentError::RateLimitError { .. } => 429,
             PaymentError::WebhookVerificationError { .. } => 401,
+            PaymentError::ProviderNotConfigured { .. } => 503,
+            PaymentError::ConfigurationError { .. } => 500,
             PaymentError::ProviderError { .. } => 502,
         }
     }
@@ -83,6 +94,12 @@ impl PaymentError {
             PaymentError::WebhookVerificationError { .. } => {
                 "Invalid webhook signature".to_string()
             }
+            PaymentError::ProviderNotConfigured { provider } => {
+                format!("Payment provider {} is not available", provider)
+            }
+            PaymentError::ConfigurationError { .. } => {
+                "Payment provider is misconfigured".to_string()
+            }
             PaymentError::ProviderError { .. } => "Payment provider returned an error".to_string(),
         }
     }
@@ -90,7 +107,15 @@ impl PaymentError {
 
 impl From<PaymentError> for crate::error::AppError {
     fn from(err: PaymentError) -> Self {
-        use crate::error::{AppError, AppErrorKind, ExternalError};
+        use crate::error::{AppError, AppErrorKind, ExternalError, InfrastructureError};
+
+        if let PaymentError::ConfigurationError { .. } = err {
+            return AppError::new(AppErrorKind::Infrastructure(
+                InfrastructureError::Configuration {
+                    message: err.to_string(),
+                },
+            ));
+        }
 
         AppError::new(AppErrorKind::External(ExternalError::PaymentProvider {
             provider: "payments".to_string(),
@@ -124,6 +149,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn configuration_error_is_not_a_missing_provider() {
+        let err = PaymentError::ConfigurationError {
+            provider: "paystack".to_string(),
+            message: "PAYSTACK_SECRET_KEY must start with sk_live_".to_string(),
+        };
+        assert_eq!(err.http_status_code(), 500);
+        assert!(!err.is_retryable());
+
+        let app_err = crate::error::AppError::from(err);
+        assert_eq!(app_err.status_code(), 500);
+        assert_eq!(
+            app_err.error_code(),
+            crate::error::ErrorCode::ConfigurationError
+        );
+    }
+
     #[test]
     fn retryable_flags_are_set() {
         assert!(PaymentError::NetworkError {
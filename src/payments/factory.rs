@@ -1,9 +1,15 @@
+use crate::payments::credentials::ProviderCredentialStore;
 use crate::payments::error::{PaymentError, PaymentResult};
 use crate::payments::provider::PaymentProvider;
+use crate::payments::providers::flutterwave::FlutterwaveConfig;
+use crate::payments::providers::mpesa::MpesaConfig;
+use crate::payments::providers::paystack::PaystackConfig;
 use crate::payments::providers::{FlutterwaveProvider, MpesaProvider, PaystackProvider, MockProvider};
 use crate::payments::types::ProviderName;
 use std::collections::HashMap;
 use std::str::FromStr;
+use std::sync::Arc;
+use tracing::{debug, error, info, warn};
 
 #[derive(Debug, Clone)]
 pub struct PaymentFactoryConfig {
@@ -67,18 +73,114 @@ impl PaymentFactoryConfig {
     }
 }
 
+/// Which enabled providers can actually be built from the current configuration
+#[derive(Debug, Clone, Default)]
+pub struct ProviderAvailability {
+    pub available: Vec<ProviderName>,
+    pub unconfigured: Vec<ProviderName>,
+    /// Credentials present but rejected, e.g. a malformed key
+    pub misconfigured: Vec<ProviderName>,
+}
+
 pub struct PaymentProviderFactory {
     config: PaymentFactoryConfig,
+    credentials: Arc<ProviderCredentialStore>,
 }
 
 impl PaymentProviderFactory {
     pub fn from_env() -> PaymentResult<Self> {
         let config = PaymentFactoryConfig::from_env()?;
-        Ok(Self { config })
+        Ok(Self {
+            config,
+            credentials: ProviderCredentialStore::global(),
+        })
     }
 
     pub fn with_config(config: PaymentFactoryConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            credentials: ProviderCredentialStore::global(),
+        }
+    }
+
+    pub fn with_credential_store(
+        config: PaymentFactoryConfig,
+        credentials: Arc<ProviderCredentialStore>,
+    ) -> Self {
+        Self {
+            config,
+            credentials,
+        }
+    }
+
+    fn paystack_config(&self) -> PaymentResult<PaystackConfig> {
+        let mut config =
+            PaystackConfig::from_env().map_err(|e| config_error(ProviderName::Paystack, e))?;
+        if let Some(secret) = self.credentials.secret_for(&ProviderName::Paystack) {
+            config.secret_key = secret;
+        }
+        Ok(config)
+    }
+
+    fn flutterwave_config(&self) -> PaymentResult<FlutterwaveConfig> {
+        let mut config = FlutterwaveConfig::from_env()
+            .map_err(|e| config_error(ProviderName::Flutterwave, e))?;
+        if let Some(secret) = self.credentials.secret_for(&ProviderName::Flutterwave) {
+            config.secret_key = secret;
+        }
+        Ok(config)
+    }
+
+    /// Replace a provider's secret key at runtime.
+    ///
+    /// The new key is checked with an authenticated call against the provider
+    /// before it is swapped in; on failure the current key stays active.
+    pub async fn rotate_credential(
+        &self,
+        provider: ProviderName,
+        new_secret: &str,
+    ) -> PaymentResult<()> {
+        if new_secret.trim().is_empty() {
+            return Err(PaymentError::ValidationError {
+                message: "secret_key must not be empty".to_string(),
+                field: Some("secret_key".to_string()),
+            });
+        }
+        if !self.config.enabled_providers.contains(&provider) {
+            return Err(PaymentError::ValidationError {
+                message: format!("provider {} is disabled", provider),
+                field: Some("provider".to_string()),
+            });
+        }
+
+        let candidate: Box<dyn PaymentProvider> = match provider {
+            ProviderName::Paystack => {
+                let mut config = self.paystack_config()?;
+                config.secret_key = new_secret.to_string();
+                Box::new(PaystackProvider::new(config)?)
+            }
+            ProviderName::Flutterwave => {
+                let mut config = self.flutterwave_config()?;
+                config.secret_key = new_secret.to_string();
+                Box::new(FlutterwaveProvider::new(config)?)
+            }
+            ProviderName::Mpesa | ProviderName::Mock => {
+                return Err(PaymentError::ValidationError {
+                    message: format!("credential rotation is not supported for {}", provider),
+                    field: Some("provider".to_string()),
+                });
+            }
+        };
+
+        if let Err(e) = candidate.validate_credentials().await {
+            warn!(provider = %provider, error = %e, "rejected credential rotation");
+            return Err(e);
+        }
+
+        self.credentials
+            .set_secret(provider.clone(), new_secret.to_string());
+        info!(provider = %provider, "provider credentials rotated");
+        Ok(())
     }
 
     pub fn get_provider(&self, provider: ProviderName) -> PaymentResult<Box<dyn PaymentProvider>> {
@@ -90,9 +192,13 @@ impl PaymentProviderFactory {
         }
 
         match provider {
-            ProviderName::Paystack => Ok(Box::new(PaystackProvider::from_env()?)),
-            ProviderName::Flutterwave => Ok(Box::new(FlutterwaveProvider::from_env()?)),
-            ProviderName::Mpesa => Ok(Box::new(MpesaProvider::from_env()?)),
+            ProviderName::Paystack => Ok(Box::new(PaystackProvider::new(self.paystack_config()?)?)),
+            ProviderName::Flutterwave => Ok(Box::new(FlutterwaveProvider::new(
+                self.flutterwave_config()?,
+            )?)),
+            ProviderName::Mpesa => Ok(Box::new(MpesaProvider::new(
+                MpesaConfig::from_env().map_err(|e| config_error(ProviderName::Mpesa, e))?,
+            )?)),
             ProviderName::Mock => Ok(Box::new(MockProvider::new())),
         }
     }
@@ -137,6 +243,65 @@ impl PaymentProviderFactory {
     pub fn list_available_providers(&self) -> Vec<ProviderName> {
         self.config.enabled_providers.clone()
     }
+
+    /// Try to build every enabled provider, sorting them into those that
+    /// initialized, those whose configuration is missing and those whose
+    /// configuration is present but invalid.
+    pub fn provider_availability(&self) -> ProviderAvailability {
+        let mut availability = ProviderAvailability::default();
+        for provider in &self.config.enabled_providers {
+            match self.get_provider(provider.clone()) {
+                Ok(_) => availability.available.push(provider.clone()),
+                Err(PaymentError::ProviderNotConfigured { .. }) => {
+                    availability.unconfigured.push(provider.clone())
+                }
+                Err(PaymentError::ConfigurationError { .. }) => {
+                    availability.misconfigured.push(provider.clone())
+                }
+                Err(e) => {
+                    warn!(provider = %provider, error = %e, "payment provider failed to initialize")
+                }
+            }
+        }
+        availability
+    }
+
+    /// Enabled providers that can be built from the current configuration
+    pub fn available_providers(&self) -> Vec<ProviderName> {
+        self.provider_availability().available
+    }
+}
+
+/// Credentials a provider needs; with none of them set the provider simply
+/// isn't configured
+fn required_env(provider: &ProviderName) -> &'static [&'static str] {
+    match provider {
+        ProviderName::Paystack => &["PAYSTACK_SECRET_KEY"],
+        ProviderName::Flutterwave => &["FLUTTERWAVE_SECRET_KEY"],
+        ProviderName::Mpesa => &["MPESA_CONSUMER_KEY", "MPESA_CONSUMER_SECRET", "MPESA_PASSKEY"],
+        ProviderName::Mock => &[],
+    }
+}
+
+/// A provider whose credentials are all absent is not configured; one whose
+/// credentials are set but rejected is a configuration error, which must not
+/// pass for a provider that was never set up.
+fn config_error(provider: ProviderName, cause: PaymentError) -> PaymentError {
+    let unset = required_env(&provider)
+        .iter()
+        .all(|key| std::env::var(key).map_or(true, |v| v.trim().is_empty()));
+    if unset {
+        debug!(provider = %provider, reason = %cause, "payment provider not configured");
+        return PaymentError::ProviderNotConfigured {
+            provider: provider.to_string(),
+        };
+    }
+
+    error!(provider = %provider, reason = %cause, "payment provider configuration is invalid");
+    PaymentError::ConfigurationError {
+        provider: provider.to_string(),
+        message: cause.to_string(),
+    }
 }
 
 #[cfg(test)]
@@ -162,4 +327,196 @@ mod tests {
         let providers = factory.list_available_providers();
         assert_eq!(providers.len(), 2);
     }
+
+    // Provider configs are read from the environment, so tests that set
+    // PAYSTACK_* variables must not run concurrently.
+    use crate::config::ENV_LOCK;
+
+    fn paystack_factory(store: Arc<ProviderCredentialStore>) -> PaymentProviderFactory {
+        PaymentProviderFactory::with_credential_store(
+            PaymentFactoryConfig {
+                default_provider: ProviderName::Paystack,
+                enabled_providers: vec![ProviderName::Paystack],
+                provider_fee_bps: HashMap::new(),
+            },
+            store,
+        )
+    }
+
+    fn set_paystack_env(base_url: &str) {
+        std::env::set_var("PAYSTACK_SECRET_KEY", "sk_old");
+        std::env::set_var("PAYSTACK_BASE_URL", base_url);
+        std::env::set_var("PAYSTACK_MAX_RETRIES", "0");
+    }
+
+    async fn mount_verify(server: &wiremock::MockServer, token: &str, status: u16) {
+        use wiremock::matchers::{header, method, path};
+        use wiremock::{Mock, ResponseTemplate};
+
+        Mock::given(method("GET"))
+            .and(path("/transaction/verify/ref_1"))
+            .and(header("Authorization", format!("Bearer {}", token).as_str()))
+            .respond_with(ResponseTemplate::new(status).set_body_json(serde_json::json!({
+                "status": true,
+                "message": "Verification successful",
+                "data": { "status": "success", "amount": 1000, "currency": "NGN", "channel": "card" }
+            })))
+            .mount(server)
+            .await;
+    }
+
+    async fn mount_balance(server: &wiremock::MockServer, token: &str, status: u16) {
+        use wiremock::matchers::{header, method, path};
+        use wiremock::{Mock, ResponseTemplate};
+
+        Mock::given(method("GET"))
+            .and(path("/balance"))
+            .and(header("Authorization", format!("Bearer {}", token).as_str()))
+            .respond_with(ResponseTemplate::new(status).set_body_json(serde_json::json!({
+                "status": status == 200,
+                "message": "Balances retrieved",
+                "data": []
+            })))
+            .mount(server)
+            .await;
+    }
+
+    fn status_request() -> crate::payments::types::StatusRequest {
+        crate::payments::types::StatusRequest {
+            transaction_reference: None,
+            provider_reference: Some("ref_1".to_string()),
+        }
+    }
+
+    #[tokio::test]
+    async fn only_configured_providers_are_available() {
+        let _guard = ENV_LOCK.lock().await;
+        std::env::set_var("PAYSTACK_SECRET_KEY", "sk_test");
+        std::env::remove_var("FLUTTERWAVE_SECRET_KEY");
+        for key in ["MPESA_CONSUMER_KEY", "MPESA_CONSUMER_SECRET", "MPESA_PASSKEY"] {
+            std::env::remove_var(key);
+        }
+
+        let factory = PaymentProviderFactory::with_credential_store(
+            PaymentFactoryConfig {
+                default_provider: ProviderName::Paystack,
+                enabled_providers: vec![
+                    ProviderName::Paystack,
+                    ProviderName::Flutterwave,
+                    ProviderName::Mpesa,
+                ],
+                provider_fee_bps: HashMap::new(),
+            },
+            Arc::new(ProviderCredentialStore::new()),
+        );
+
+        let availability = factory.provider_availability();
+        assert_eq!(availability.available, vec![ProviderName::Paystack]);
+        assert_eq!(
+            availability.unconfigured,
+            vec![ProviderName::Flutterwave, ProviderName::Mpesa]
+        );
+        assert_eq!(factory.available_providers(), vec![ProviderName::Paystack]);
+
+        assert!(factory.get_provider(ProviderName::Paystack).is_ok());
+        let err = factory
+            .get_provider(ProviderName::Flutterwave)
+            .err()
+            .expect("flutterwave has no credentials");
+        assert!(matches!(
+            err,
+            PaymentError::ProviderNotConfigured { ref provider } if provider == "flutterwave"
+        ));
+        assert_eq!(err.http_status_code(), 503);
+    }
+
+    #[tokio::test]
+    async fn malformed_credentials_are_a_configuration_error() {
+        let _guard = ENV_LOCK.lock().await;
+        std::env::set_var("PAYSTACK_SECRET_KEY", "sk_test");
+        std::env::set_var("PAYSTACK_MODE", "bogus");
+
+        let factory = PaymentProviderFactory::with_credential_store(
+            PaymentFactoryConfig {
+                default_provider: ProviderName::Paystack,
+                enabled_providers: vec![ProviderName::Paystack],
+                provider_fee_bps: HashMap::new(),
+            },
+            Arc::new(ProviderCredentialStore::new()),
+        );
+
+        let availability = factory.provider_availability();
+        std::env::remove_var("PAYSTACK_MODE");
+        assert!(availability.available.is_empty());
+        assert!(availability.unconfigured.is_empty());
+        assert_eq!(availability.misconfigured, vec![ProviderName::Paystack]);
+
+        std::env::set_var("PAYSTACK_MODE", "bogus");
+        let err = factory
+            .get_provider(ProviderName::Paystack)
+            .err()
+            .expect("paystack mode is invalid");
+        std::env::remove_var("PAYSTACK_MODE");
+        assert!(matches!(
+            err,
+            PaymentError::ConfigurationError { ref provider, .. } if provider == "paystack"
+        ));
+        assert_eq!(err.http_status_code(), 500);
+        let app_err = crate::error::AppError::from(err);
+        assert_eq!(
+            app_err.error_code(),
+            crate::error::ErrorCode::ConfigurationError
+        );
+    }
+
+    #[tokio::test]
+    async fn rotation_swaps_in_validated_key() {
+        let _guard = ENV_LOCK.lock().await;
+        let server = wiremock::MockServer::start().await;
+        set_paystack_env(&server.uri());
+        mount_balance(&server, "sk_new", 200).await;
+        mount_verify(&server, "sk_old", 401).await;
+        mount_verify(&server, "sk_new", 200).await;
+
+        let store = Arc::new(ProviderCredentialStore::new());
+        let factory = paystack_factory(store.clone());
+
+        let before = factory.get_provider(ProviderName::Paystack).unwrap();
+        assert!(before.get_payment_status(status_request()).await.is_err());
+
+        factory
+            .rotate_credential(ProviderName::Paystack, "sk_new")
+            .await
+            .expect("rotation should succeed");
+
+        let after = factory.get_provider(ProviderName::Paystack).unwrap();
+        assert!(after.get_payment_status(status_request()).await.is_ok());
+        assert_eq!(
+            store.secret_for(&ProviderName::Paystack).as_deref(),
+            Some("sk_new")
+        );
+    }
+
+    #[tokio::test]
+    async fn rotation_rejected_when_new_key_fails_validation() {
+        let _guard = ENV_LOCK.lock().await;
+        let server = wiremock::MockServer::start().await;
+        set_paystack_env(&server.uri());
+        mount_balance(&server, "sk_bad", 401).await;
+        mount_verify(&server, "sk_old", 200).await;
+
+        let store = Arc::new(ProviderCredentialStore::new());
+        let factory = paystack_factory(store.clone());
+
+        let err = factory
+            .rotate_credential(ProviderName::Paystack, "sk_bad")
+            .await
+            .expect_err("invalid key must be rejected");
+        assert!(matches!(err, PaymentError::ProviderError { .. }));
+        assert!(store.secret_for(&ProviderName::Paystack).is_none());
+
+        // The original key keeps working.
+        let provider = factory.get_provider(ProviderName::Paystack).unwrap();
+        assert!(provider.get_payment_status(status_request()).await.is_ok());
+    }
 }
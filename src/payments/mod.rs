@@ -3,11 +3,15 @@
 //! This module provides a unified interface for payment providers (Paystack, Flutterwave, M-Pesa)
 //! to support fiat transactions in African markets.
 
+#[cfg(feature = "database")]
+pub mod credentials;
 #[cfg(feature = "database")]
 pub mod error;
 #[cfg(feature = "database")]
 pub mod factory;
 #[cfg(feature = "database")]
+pub mod mode;
+#[cfg(feature = "database")]
 pub mod provider;
 #[cfg(feature = "database")]
 pub mod providers;
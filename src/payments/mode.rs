@@ -0,0 +1,184 @@
+//! Sandbox / live selection for payment providers
+//!
+//! Every provider issues separate sandbox and live credentials, and some serve
+//! them from separate hosts. `PAYSTACK_MODE`, `FLUTTERWAVE_MODE` and
+//! `MPESA_MODE` (`sandbox` | `live`) pick the provider's default base URL; an
+//! explicit `*_BASE_URL` still takes precedence. Secret keys that carry a mode
+//! prefix are checked against the selected mode at startup so a test key is
+//! never put behind a live provider in production.
+
+use crate::payments::error::{PaymentError, PaymentResult};
+use crate::payments::types::ProviderName;
+use std::str::FromStr;
+use tracing::warn;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProviderMode {
+    Sandbox,
+    Live,
+}
+
+impl ProviderMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProviderMode::Sandbox => "sandbox",
+            ProviderMode::Live => "live",
+        }
+    }
+
+    /// Reads the mode from `var`. Unset means live, the endpoints providers
+    /// used before modes were configurable.
+    pub fn from_env(var: &str) -> PaymentResult<Self> {
+        match std::env::var(var) {
+            Ok(value) if !value.trim().is_empty() => {
+                value.parse().map_err(|_| PaymentError::ValidationError {
+                    message: format!("{} must be 'sandbox' or 'live', got '{}'", var, value),
+                    field: Some(var.to_string()),
+                })
+            }
+            _ => Ok(ProviderMode::Live),
+        }
+    }
+
+    /// Picks the URL for this mode.
+    pub fn select<'a>(&self, sandbox: &'a str, live: &'a str) -> &'a str {
+        match self {
+            ProviderMode::Sandbox => sandbox,
+            ProviderMode::Live => live,
+        }
+    }
+}
+
+impl FromStr for ProviderMode {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "sandbox" | "test" => Ok(ProviderMode::Sandbox),
+            "live" | "production" => Ok(ProviderMode::Live),
+            _ => Err(()),
+        }
+    }
+}
+
+impl std::fmt::Display for ProviderMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Prefixes a provider puts on its sandbox and live secret keys
+#[derive(Debug, Clone, Copy)]
+pub struct KeyPrefixes {
+    pub sandbox: &'static str,
+    pub live: &'static str,
+}
+
+impl KeyPrefixes {
+    /// Mode the key was issued for, if its prefix says.
+    fn mode_of(&self, key: &str) -> Option<ProviderMode> {
+        // Checked first: a sandbox prefix may extend the live one
+        if key.starts_with(self.sandbox) {
+            Some(ProviderMode::Sandbox)
+        } else if key.starts_with(self.live) {
+            Some(ProviderMode::Live)
+        } else {
+            None
+        }
+    }
+}
+
+/// Whether `APP_ENV` is `production`.
+pub fn is_production() -> bool {
+    std::env::var("APP_ENV").is_ok_and(|v| v == "production")
+}
+
+/// Checks that `key` was issued for `mode`.
+///
+/// A live key in sandbox mode is always rejected, since with providers that
+/// share one host it would move real money. A test key in live mode is
+/// rejected in production and only warned about elsewhere. Keys without a
+/// recognised prefix can't be checked and pass.
+pub fn validate_key_prefix(
+    provider: ProviderName,
+    var: &str,
+    mode: ProviderMode,
+    key: &str,
+    prefixes: KeyPrefixes,
+    production: bool,
+) -> PaymentResult<()> {
+    let key_mode = match prefixes.mode_of(key) {
+        Some(key_mode) if key_mode != mode => key_mode,
+        _ => return Ok(()),
+    };
+
+    if mode == ProviderMode::Live && !production {
+        warn!(
+            provider = %provider,
+            "{} is a {} key but the provider is in live mode",
+            var,
+            key_mode
+        );
+        return Ok(());
+    }
+
+    Err(PaymentError::ValidationError {
+        message: format!(
+            "{} is a {} key but {} is configured for {} mode",
+            var, key_mode, provider, mode
+        ),
+        field: Some(var.to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PREFIXES: KeyPrefixes = KeyPrefixes {
+        sandbox: "sk_test_",
+        live: "sk_live_",
+    };
+
+    fn validate(mode: ProviderMode, key: &str, production: bool) -> PaymentResult<()> {
+        validate_key_prefix(
+            ProviderName::Paystack,
+            "PAYSTACK_SECRET_KEY",
+            mode,
+            key,
+            PREFIXES,
+            production,
+        )
+    }
+
+    #[test]
+    fn parses_mode_names() {
+        assert_eq!("sandbox".parse(), Ok(ProviderMode::Sandbox));
+        assert_eq!(" LIVE ".parse(), Ok(ProviderMode::Live));
+        assert!("staging".parse::<ProviderMode>().is_err());
+    }
+
+    #[test]
+    fn matching_keys_pass() {
+        assert!(validate(ProviderMode::Sandbox, "sk_test_abc", true).is_ok());
+        assert!(validate(ProviderMode::Live, "sk_live_abc", true).is_ok());
+        assert!(validate(ProviderMode::Live, "unprefixed", true).is_ok());
+    }
+
+    #[test]
+    fn test_key_in_live_mode_rejected_only_in_production() {
+        match validate(ProviderMode::Live, "sk_test_abc", true) {
+            Err(PaymentError::ValidationError { field, .. }) => {
+                assert_eq!(field.as_deref(), Some("PAYSTACK_SECRET_KEY"))
+            }
+            other => panic!("expected a validation error, got {:?}", other),
+        }
+        assert!(validate(ProviderMode::Live, "sk_test_abc", false).is_ok());
+    }
+
+    #[test]
+    fn live_key_in_sandbox_mode_always_rejected() {
+        assert!(validate(ProviderMode::Sandbox, "sk_live_abc", false).is_err());
+        assert!(validate(ProviderMode::Sandbox, "sk_live_abc", true).is_err());
+    }
+}
@@ -18,6 +18,12 @@ pub trait PaymentProvider: Send + Sync {
 
     async fn get_payment_status(&self, request: StatusRequest) -> PaymentResult<StatusResponse>;
 
+    /// Lightweight authenticated call used to confirm the configured
+    /// credentials are accepted by the provider.
+    async fn validate_credentials(&self) -> PaymentResult<()> {
+        Ok(())
+    }
+
     fn name(&self) -> ProviderName;
 
     fn supported_currencies(&self) -> &'static [&'static str];
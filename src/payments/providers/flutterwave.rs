@@ -1,16 +1,25 @@
 use crate::payments::error::{PaymentError, PaymentResult};
+use crate::payments::mode::{self, KeyPrefixes, ProviderMode};
 use crate::payments::provider::PaymentProvider;
 use crate::payments::types::{
     Money, PaymentMethod, PaymentRequest, PaymentResponse, PaymentState, ProviderName,
     StatusRequest, StatusResponse, WebhookEvent, WebhookVerificationResult, WithdrawalMethod,
     WithdrawalRequest, WithdrawalResponse,
 };
-use crate::payments::utils::{secure_eq, PaymentHttpClient};
+use crate::payments::utils::{decode_webhook_object, secure_eq, PaymentHttpClient};
 use async_trait::async_trait;
 use serde::Deserialize;
 use serde_json::Value as JsonValue;
 use std::time::Duration;
-use tracing::info;
+use tracing::{info, warn};
+
+/// Flutterwave v3 serves sandbox and live from one host; the key picks the mode
+pub const FLUTTERWAVE_SANDBOX_BASE_URL: &str = "https://api.flutterwave.com/v3";
+pub const FLUTTERWAVE_LIVE_BASE_URL: &str = "https://api.flutterwave.com/v3";
+pub const FLUTTERWAVE_KEY_PREFIXES: KeyPrefixes = KeyPrefixes {
+    sandbox: "FLWSECK_TEST-",
+    live: "FLWSECK-",
+};
 
 #[derive(Debug, Clone)]
 pub struct FlutterwaveConfig {
@@ -28,6 +37,15 @@ impl FlutterwaveConfig {
                 message: "FLUTTERWAVE_SECRET_KEY environment variable is required".to_string(),
                 field: Some("FLUTTERWAVE_SECRET_KEY".to_string()),
             })?;
+        let mode = ProviderMode::from_env("FLUTTERWAVE_MODE")?;
+        mode::validate_key_prefix(
+            ProviderName::Flutterwave,
+            "FLUTTERWAVE_SECRET_KEY",
+            mode,
+            &secret_key,
+            FLUTTERWAVE_KEY_PREFIXES,
+            mode::is_production(),
+        )?;
 
         Ok(Self {
             secret_key,
@@ -35,7 +53,7 @@ impl FlutterwaveConfig {
                 .ok()
                 .or_else(|| std::env::var("FLUTTERWAVE_WEBHOOK_HASH").ok()),
             base_url: std::env::var("FLUTTERWAVE_BASE_URL")
-                .unwrap_or_else(|_| "https://api.flutterwave.com/v3".to_string()),
+                .unwrap_or_else(|_| Self::base_url_for(mode).to_string()),
             timeout_secs: std::env::var("FLUTTERWAVE_TIMEOUT_SECS")
                 .ok()
                 .and_then(|v| v.parse::<u64>().ok())
@@ -51,6 +69,11 @@ impl FlutterwaveConfig {
                 .unwrap_or(2),
         })
     }
+
+    /// Default base URL for `mode` when `FLUTTERWAVE_BASE_URL` isn't set.
+    pub fn base_url_for(mode: ProviderMode) -> &'static str {
+        mode.select(FLUTTERWAVE_SANDBOX_BASE_URL, FLUTTERWAVE_LIVE_BASE_URL)
+    }
 }
 
 pub struct FlutterwaveProvider {
@@ -421,6 +444,23 @@ impl PaymentProvider for FlutterwaveProvider {
         self.verify_payment(request).await
     }
 
+    async fn validate_credentials(&self) -> PaymentResult<()> {
+        let raw: FlutterwaveEnvelope = self
+            .http
+            .request_json(
+                reqwest::Method::GET,
+                &self.endpoint("/balances"),
+                Some(&self.config.secret_key),
+                None,
+                &[],
+            )
+            .await?;
+        if raw.status.to_lowercase() != "success" {
+            return Err(Self::map_message_error(raw.message));
+        }
+        Ok(())
+    }
+
     fn name(&self) -> ProviderName {
         ProviderName::Flutterwave
     }
@@ -455,11 +495,10 @@ impl PaymentProvider for FlutterwaveProvider {
     }
 
     fn parse_webhook_event(&self, payload: &[u8]) -> PaymentResult<WebhookEvent> {
-        let parsed: JsonValue = serde_json::from_slice(payload).map_err(|e| {
-            PaymentError::WebhookVerificationError {
-                message: format!("invalid webhook JSON payload: {}", e),
-            }
-        })?;
+        let parsed = match decode_webhook_object(ProviderName::Flutterwave, payload) {
+            Ok(parsed) => parsed,
+            Err(fallback) => return Ok(*fallback),
+        };
 
         let event_type = parsed
             .get("event")
@@ -478,7 +517,14 @@ impl PaymentProvider for FlutterwaveProvider {
                     "successful" | "success" | "completed" => PaymentState::Success,
                     "pending" | "new" | "processing" => PaymentState::Pending,
                     "failed" | "cancelled" => PaymentState::Failed,
-                    _ => PaymentState::Unknown,
+                    other => {
+                        warn!(
+                            event_type = %event_type,
+                            status = other,
+                            "unrecognised flutterwave webhook status"
+                        );
+                        PaymentState::Unknown
+                    }
                 });
 
         let provider_reference = data
@@ -1,16 +1,22 @@
 use crate::payments::error::{PaymentError, PaymentResult};
+use crate::payments::mode::ProviderMode;
 use crate::payments::provider::PaymentProvider;
 use crate::payments::types::{
     PaymentRequest, PaymentResponse, PaymentState, ProviderName, StatusRequest, StatusResponse,
     WebhookEvent, WebhookVerificationResult, WithdrawalRequest, WithdrawalResponse,
 };
+use crate::payments::utils::decode_webhook_object;
 use async_trait::async_trait;
 
+pub const MPESA_SANDBOX_BASE_URL: &str = "https://sandbox.safaricom.co.ke";
+pub const MPESA_LIVE_BASE_URL: &str = "https://api.safaricom.co.ke";
+
 #[derive(Debug, Clone)]
 pub struct MpesaConfig {
     pub consumer_key: String,
     pub consumer_secret: String,
     pub passkey: String,
+    pub base_url: String,
 }
 
 impl MpesaConfig {
@@ -25,12 +31,21 @@ impl MpesaConfig {
                 field: Some("mpesa".to_string()),
             });
         }
+        // Daraja keys carry no mode prefix, so only the host follows the mode
+        let mode = ProviderMode::from_env("MPESA_MODE")?;
         Ok(Self {
             consumer_key,
             consumer_secret,
             passkey,
+            base_url: std::env::var("MPESA_BASE_URL")
+                .unwrap_or_else(|_| Self::base_url_for(mode).to_string()),
         })
     }
+
+    /// Default base URL for `mode` when `MPESA_BASE_URL` isn't set.
+    pub fn base_url_for(mode: ProviderMode) -> &'static str {
+        mode.select(MPESA_SANDBOX_BASE_URL, MPESA_LIVE_BASE_URL)
+    }
 }
 
 pub struct MpesaProvider {
@@ -110,7 +125,10 @@ impl PaymentProvider for MpesaProvider {
     }
 
     fn parse_webhook_event(&self, payload: &[u8]) -> PaymentResult<WebhookEvent> {
-        let parsed = serde_json::from_slice(payload).unwrap_or_else(|_| serde_json::json!({}));
+        let parsed = match decode_webhook_object(ProviderName::Mpesa, payload) {
+            Ok(parsed) => parsed,
+            Err(fallback) => return Ok(*fallback),
+        };
         Ok(WebhookEvent {
             provider: ProviderName::Mpesa,
             event_type: "unknown".to_string(),
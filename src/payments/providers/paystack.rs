@@ -1,17 +1,26 @@
 use crate::payments::error::{PaymentError, PaymentResult};
+use crate::payments::mode::{self, KeyPrefixes, ProviderMode};
 use crate::payments::provider::PaymentProvider;
 use crate::payments::types::{
     Money, PaymentMethod, PaymentRequest, PaymentResponse, PaymentState, ProviderName,
     StatusRequest, StatusResponse, WebhookEvent, WebhookVerificationResult, WithdrawalMethod,
     WithdrawalRequest, WithdrawalResponse,
 };
-use crate::payments::utils::{verify_hmac_sha512_hex, PaymentHttpClient};
+use crate::payments::utils::{decode_webhook_object, verify_hmac_sha512_hex, PaymentHttpClient};
 use async_trait::async_trait;
 use dotenv::dotenv;
 use serde::Deserialize;
 use serde_json::Value as JsonValue;
 use std::time::Duration;
-use tracing::info;
+use tracing::{info, warn};
+
+/// Paystack serves sandbox and live from one host; the key picks the mode
+pub const PAYSTACK_SANDBOX_BASE_URL: &str = "https://api.paystack.co";
+pub const PAYSTACK_LIVE_BASE_URL: &str = "https://api.paystack.co";
+pub const PAYSTACK_KEY_PREFIXES: KeyPrefixes = KeyPrefixes {
+    sandbox: "sk_test_",
+    live: "sk_live_",
+};
 
 #[derive(Debug, Clone)]
 pub struct PaystackConfig {
@@ -29,7 +38,7 @@ impl Default for PaystackConfig {
             public_key: None,
             secret_key: String::new(),
             webhook_secret: None,
-            base_url: "https://api.paystack.co".to_string(),
+            base_url: PAYSTACK_LIVE_BASE_URL.to_string(),
             timeout_secs: 30,
             max_retries: 3,
         }
@@ -44,12 +53,21 @@ impl PaystackConfig {
                 message: "PAYSTACK_SECRET_KEY environment variable is required".to_string(),
                 field: Some("PAYSTACK_SECRET_KEY".to_string()),
             })?;
+        let mode = ProviderMode::from_env("PAYSTACK_MODE")?;
+        mode::validate_key_prefix(
+            ProviderName::Paystack,
+            "PAYSTACK_SECRET_KEY",
+            mode,
+            &secret_key,
+            PAYSTACK_KEY_PREFIXES,
+            mode::is_production(),
+        )?;
 
         Ok(Self {
             public_key: std::env::var("PAYSTACK_PUBLIC_KEY").ok(),
             webhook_secret: std::env::var("PAYSTACK_WEBHOOK_SECRET").ok(),
             base_url: std::env::var("PAYSTACK_BASE_URL")
-                .unwrap_or_else(|_| "https://api.paystack.co".to_string()),
+                .unwrap_or_else(|_| Self::base_url_for(mode).to_string()),
             timeout_secs: std::env::var("PAYSTACK_TIMEOUT_SECS")
                 .ok()
                 .and_then(|v| v.parse::<u64>().ok())
@@ -61,6 +79,11 @@ impl PaystackConfig {
             secret_key,
         })
     }
+
+    /// Default base URL for `mode` when `PAYSTACK_BASE_URL` isn't set.
+    pub fn base_url_for(mode: ProviderMode) -> &'static str {
+        mode.select(PAYSTACK_SANDBOX_BASE_URL, PAYSTACK_LIVE_BASE_URL)
+    }
 }
 
 pub struct PaystackProvider {
@@ -327,6 +350,28 @@ impl PaymentProvider for PaystackProvider {
         self.verify_payment(request).await
     }
 
+    async fn validate_credentials(&self) -> PaymentResult<()> {
+        let raw: PaystackEnvelope<JsonValue> = self
+            .http
+            .request_json(
+                reqwest::Method::GET,
+                &self.endpoint("/balance"),
+                Some(&self.config.secret_key),
+                None,
+                &[],
+            )
+            .await?;
+        if !raw.status {
+            return Err(PaymentError::ProviderError {
+                provider: "paystack".to_string(),
+                message: raw.message,
+                provider_code: None,
+                retryable: false,
+            });
+        }
+        Ok(())
+    }
+
     fn name(&self) -> ProviderName {
         ProviderName::Paystack
     }
@@ -361,11 +406,10 @@ impl PaymentProvider for PaystackProvider {
     }
 
     fn parse_webhook_event(&self, payload: &[u8]) -> PaymentResult<WebhookEvent> {
-        let parsed: JsonValue = serde_json::from_slice(payload).map_err(|e| {
-            PaymentError::WebhookVerificationError {
-                message: format!("invalid webhook JSON payload: {}", e),
-            }
-        })?;
+        let parsed = match decode_webhook_object(ProviderName::Paystack, payload) {
+            Ok(parsed) => parsed,
+            Err(fallback) => return Ok(*fallback),
+        };
 
         let event_type = parsed
             .get("event")
@@ -385,7 +429,14 @@ impl PaymentProvider for PaystackProvider {
                 "success" => PaymentState::Success,
                 "pending" => PaymentState::Pending,
                 "failed" => PaymentState::Failed,
-                _ => PaymentState::Unknown,
+                other => {
+                    warn!(
+                        event_type = %event_type,
+                        status = other,
+                        "unrecognised paystack webhook status"
+                    );
+                    PaymentState::Unknown
+                }
             });
 
         Ok(WebhookEvent {
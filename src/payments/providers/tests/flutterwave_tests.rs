@@ -5,8 +5,8 @@
 use crate::payments::provider::PaymentProvider;
 use crate::payments::providers::flutterwave::{FlutterwaveConfig, FlutterwaveProvider};
 use crate::payments::types::{
-    CustomerContact, Money, PaymentMethod, PaymentRequest, PaymentState, StatusRequest,
-    WithdrawalMethod, WithdrawalRecipient, WithdrawalRequest,
+    CustomerContact, Money, PaymentMethod, PaymentRequest, PaymentState, ProviderName,
+    StatusRequest, WithdrawalMethod, WithdrawalRecipient, WithdrawalRequest,
 };
 use wiremock::matchers::{header, method, path, query_param};
 use wiremock::{Mock, MockServer, ResponseTemplate};
@@ -566,13 +566,40 @@ fn parse_webhook_event_maps_failed_status() {
 }
 
 #[test]
-fn parse_webhook_event_handles_malformed_json() {
+fn parse_webhook_event_falls_back_on_malformed_json() {
     let provider = provider_with_base("http://localhost:9999");
-    let err = provider
+    let event = provider
         .parse_webhook_event(b"not valid json {{{{")
-        .expect_err("should fail on malformed JSON");
+        .expect("malformed JSON should yield a fallback event");
+
+    assert_eq!(event.event_type, "unknown");
+    assert!(matches!(event.status, Some(PaymentState::Unknown)));
+    assert_eq!(event.payload["raw"], "not valid json {{{{");
+}
 
-    assert!(err.to_string().contains("invalid webhook JSON"));
+#[test]
+fn parse_webhook_event_falls_back_on_truncated_empty_and_array_bodies() {
+    let provider = provider_with_base("http://localhost:9999");
+    let bodies: [&[u8]; 3] = [br#"{"event":"charge.completed","data":{"#, b"", b"[1, 2]"];
+    for body in bodies {
+        let event = provider
+            .parse_webhook_event(body)
+            .expect("unexpected bodies must not error");
+
+        assert_eq!(event.provider, ProviderName::Flutterwave);
+        assert!(matches!(event.status, Some(PaymentState::Unknown)));
+        assert!(event.transaction_reference.is_none());
+    }
+}
+
+#[test]
+fn parse_webhook_event_maps_unrecognised_status_to_unknown() {
+    let provider = provider_with_base("http://localhost:9999");
+    let payload = br#"{"event":"charge.completed","data":{"status":"on-hold","id":"x"}}"#;
+    let event = provider.parse_webhook_event(payload).expect("should parse");
+
+    assert!(matches!(event.status, Some(PaymentState::Unknown)));
+    assert!(event.provider_reference.is_none());
 }
 
 #[test]
@@ -4,7 +4,10 @@
 //! These tests verify stub behaviour, webhook parsing, and adapter metadata.
 
 use crate::payments::provider::PaymentProvider;
-use crate::payments::providers::mpesa::{MpesaConfig, MpesaProvider};
+use crate::payments::mode::ProviderMode;
+use crate::payments::providers::mpesa::{
+    MpesaConfig, MpesaProvider, MPESA_LIVE_BASE_URL, MPESA_SANDBOX_BASE_URL,
+};
 use crate::payments::types::{
     CustomerContact, Money, PaymentMethod, PaymentRequest, PaymentState, ProviderName,
     StatusRequest, WithdrawalMethod, WithdrawalRecipient, WithdrawalRequest,
@@ -17,6 +20,7 @@ fn provider() -> MpesaProvider {
         consumer_key: "test_consumer_key".to_string(),
         consumer_secret: "test_consumer_secret".to_string(),
         passkey: "test_passkey".to_string(),
+        base_url: MPESA_SANDBOX_BASE_URL.to_string(),
     })
     .expect("provider init should succeed")
 }
@@ -272,12 +276,13 @@ fn parse_webhook_event_parses_stk_callback_payload() {
 
 #[test]
 fn parse_webhook_event_handles_malformed_json_gracefully() {
-    // Stub uses unwrap_or_else(|_| json!({})) — must NOT panic
+    // Falls back to an Unknown event carrying the raw body — must NOT panic
     let event = provider()
         .parse_webhook_event(b"not valid json {{{{")
         .expect("stub must not panic on malformed JSON");
 
     assert_eq!(event.provider, ProviderName::Mpesa);
+    assert_eq!(event.payload["raw"], "not valid json {{{{");
 }
 
 #[test]
@@ -313,3 +318,18 @@ fn parse_webhook_event_sets_unknown_status_for_unrecognised_payload() {
         Some(PaymentState::Unknown) | None
     ));
 }
+
+// ── mode ──────────────────────────────────────────────────────────────────────
+
+#[test]
+fn mode_selects_base_url() {
+    assert_eq!(
+        MpesaConfig::base_url_for(ProviderMode::Sandbox),
+        "https://sandbox.safaricom.co.ke"
+    );
+    assert_eq!(
+        MpesaConfig::base_url_for(ProviderMode::Live),
+        "https://api.safaricom.co.ke"
+    );
+    assert_ne!(MPESA_SANDBOX_BASE_URL, MPESA_LIVE_BASE_URL);
+}
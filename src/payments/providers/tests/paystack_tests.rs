@@ -3,10 +3,14 @@
 //! All HTTP interactions are intercepted by wiremock — no real network calls.
 
 use crate::payments::provider::PaymentProvider;
-use crate::payments::providers::paystack::{PaystackConfig, PaystackProvider};
+use crate::payments::error::PaymentError;
+use crate::payments::mode::{self, ProviderMode};
+use crate::payments::providers::paystack::{
+    PaystackConfig, PaystackProvider, PAYSTACK_KEY_PREFIXES,
+};
 use crate::payments::types::{
-    CustomerContact, Money, PaymentMethod, PaymentRequest, PaymentState, StatusRequest,
-    WithdrawalMethod, WithdrawalRecipient, WithdrawalRequest,
+    CustomerContact, Money, PaymentMethod, PaymentRequest, PaymentState, ProviderName,
+    StatusRequest, WithdrawalMethod, WithdrawalRecipient, WithdrawalRequest,
 };
 use crate::payments::utils::verify_hmac_sha512_hex;
 use hmac::{Hmac, Mac};
@@ -644,13 +648,46 @@ fn parse_webhook_event_maps_failed_status() {
 }
 
 #[test]
-fn parse_webhook_event_handles_malformed_json() {
+fn parse_webhook_event_falls_back_on_malformed_json() {
     let provider = provider_with_base("http://localhost:9999");
-    let err = provider
+    let event = provider
         .parse_webhook_event(b"{{not valid json")
-        .expect_err("should fail on malformed JSON");
+        .expect("malformed JSON should yield a fallback event");
+
+    assert_eq!(event.event_type, "unknown");
+    assert!(matches!(event.status, Some(PaymentState::Unknown)));
+    assert_eq!(event.payload["raw"], "{{not valid json");
+}
 
-    assert!(err.to_string().contains("invalid webhook JSON"));
+#[test]
+fn parse_webhook_event_falls_back_on_truncated_empty_and_array_bodies() {
+    let provider = provider_with_base("http://localhost:9999");
+    let bodies: [&[u8]; 4] = [
+        br#"{"event":"charge.success","data":{"ref"#,
+        b"",
+        b"[]",
+        br#"[{"event":"charge.success"}]"#,
+    ];
+    for body in bodies {
+        let event = provider
+            .parse_webhook_event(body)
+            .expect("unexpected bodies must not error");
+
+        assert_eq!(event.provider, ProviderName::Paystack);
+        assert!(matches!(event.status, Some(PaymentState::Unknown)));
+        assert!(event.provider_reference.is_none());
+    }
+}
+
+#[test]
+fn parse_webhook_event_tolerates_non_object_data() {
+    let provider = provider_with_base("http://localhost:9999");
+    let event = provider
+        .parse_webhook_event(br#"{"event":"charge.success","data":"oops"}"#)
+        .expect("should not panic on scalar data");
+
+    assert_eq!(event.event_type, "charge.success");
+    assert!(event.status.is_none());
 }
 
 #[test]
@@ -664,3 +701,28 @@ fn parse_webhook_event_handles_missing_optional_fields_gracefully() {
     assert!(event.provider_reference.is_none());
     assert!(event.status.is_none());
 }
+
+// ── mode ──────────────────────────────────────────────────────────────────────
+
+#[test]
+fn live_mode_rejects_test_key_in_production() {
+    let result = mode::validate_key_prefix(
+        ProviderName::Paystack,
+        "PAYSTACK_SECRET_KEY",
+        ProviderMode::Live,
+        "sk_test_demo",
+        PAYSTACK_KEY_PREFIXES,
+        true,
+    );
+
+    assert!(matches!(result, Err(PaymentError::ValidationError { .. })));
+    assert!(mode::validate_key_prefix(
+        ProviderName::Paystack,
+        "PAYSTACK_SECRET_KEY",
+        ProviderMode::Live,
+        "sk_live_demo",
+        PAYSTACK_KEY_PREFIXES,
+        true,
+    )
+    .is_ok());
+}
@@ -260,6 +260,85 @@ async fn paystack_returns_network_error_when_server_unreachable() {
     );
 }
 
+// ── retry / backoff policy ────────────────────────────────────────────────────
+
+fn paystack_with_retries(base_url: &str, max_retries: u32) -> PaystackProvider {
+    PaystackProvider::new(PaystackConfig {
+        public_key: None,
+        secret_key: "sk_test".to_string(),
+        webhook_secret: None,
+        base_url: base_url.to_string(),
+        timeout_secs: 5,
+        max_retries,
+    })
+    .unwrap()
+}
+
+#[tokio::test]
+async fn paystack_get_payment_status_retries_transient_failures() {
+    let server = MockServer::start().await;
+    // Retry-After: 0 keeps the test fast while exercising the header path.
+    Mock::given(method("GET"))
+        .and(path("/transaction/verify/txn_shared_001"))
+        .respond_with(
+            ResponseTemplate::new(503)
+                .insert_header("Retry-After", "0")
+                .set_body_string("Service Unavailable"),
+        )
+        .up_to_n_times(2)
+        .expect(2)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/transaction/verify/txn_shared_001"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "status": true,
+            "message": "Verification successful",
+            "data": {
+                "status": "success",
+                "amount": 1000,
+                "currency": "NGN",
+                "channel": "card"
+            }
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let response = paystack_with_retries(&server.uri(), 3)
+        .get_payment_status(StatusRequest {
+            transaction_reference: None,
+            provider_reference: Some("txn_shared_001".to_string()),
+        })
+        .await
+        .expect("status lookup should succeed after retries");
+
+    assert_eq!(response.status, crate::payments::types::PaymentState::Success);
+}
+
+#[tokio::test]
+async fn paystack_initiate_without_idempotency_key_is_not_retried() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/transaction/initialize"))
+        .respond_with(
+            ResponseTemplate::new(503)
+                .insert_header("Retry-After", "0")
+                .set_body_string("Service Unavailable"),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let err = paystack_with_retries(&server.uri(), 3)
+        .initiate_payment(payment_request())
+        .await
+        .expect_err("should fail without retrying");
+
+    assert!(matches!(err, PaymentError::ProviderError { .. }));
+    server.verify().await;
+}
+
 // ── retry eligibility per error type ─────────────────────────────────────────
 
 #[test]
@@ -1,19 +1,58 @@
 use crate::payments::error::{PaymentError, PaymentResult};
+use crate::payments::types::{PaymentState, ProviderName, WebhookEvent};
 use reqwest::Client;
 use serde::de::DeserializeOwned;
 use serde_json::Value as JsonValue;
+use std::sync::Mutex;
 use std::time::Duration;
-use tracing::warn;
+
+/// Header providers use to make a mutating call safe to replay.
+const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+/// Upper bound applied to provider-supplied `Retry-After` values so a
+/// misbehaving provider cannot stall a request indefinitely.
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(30);
+
+pub use crate::retry::RetryPolicy;
+use crate::retry::{retry_async, RetryClass};
+
+/// Provider calls are only retried when replaying them is safe: `GET`/`HEAD`
+/// calls, and mutating calls that carry an `Idempotency-Key` header.
+fn is_idempotent(method: &reqwest::Method, headers: &[(&str, &str)]) -> bool {
+    matches!(*method, reqwest::Method::GET | reqwest::Method::HEAD)
+        || headers
+            .iter()
+            .any(|(k, v)| k.eq_ignore_ascii_case(IDEMPOTENCY_KEY_HEADER) && !v.is_empty())
+}
+
+fn parse_retry_after(headers: &reqwest::header::HeaderMap) -> Option<u64> {
+    headers
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<u64>().ok())
+}
 
 #[derive(Clone)]
 pub struct PaymentHttpClient {
     client: Client,
     timeout: Duration,
-    max_retries: u32,
+    retry_policy: RetryPolicy,
 }
 
 impl PaymentHttpClient {
+    /// Build a client using the env-configured retry policy, with the
+    /// provider-specific `max_retries` taking precedence.
     pub fn new(timeout: Duration, max_retries: u32) -> PaymentResult<Self> {
+        Self::with_retry_policy(
+            timeout,
+            RetryPolicy {
+                max_retries,
+                ..RetryPolicy::from_env()
+            },
+        )
+    }
+
+    pub fn with_retry_policy(timeout: Duration, retry_policy: RetryPolicy) -> PaymentResult<Self> {
         let client =
             Client::builder()
                 .timeout(timeout)
@@ -25,10 +64,14 @@ impl PaymentHttpClient {
         Ok(Self {
             client,
             timeout,
-            max_retries,
+            retry_policy,
         })
     }
 
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.retry_policy
+    }
+
     pub async fn request_json<T: DeserializeOwned>(
         &self,
         method: reqwest::Method,
@@ -37,84 +80,100 @@ impl PaymentHttpClient {
         body: Option<&JsonValue>,
         additional_headers: &[(&str, &str)],
     ) -> PaymentResult<T> {
-        let mut last_error = None;
-        for attempt in 0..=self.max_retries {
-            let mut request = self.client.request(method.clone(), url);
-            request = request.timeout(self.timeout);
-
-            if let Some(token) = bearer_token {
-                request = request.bearer_auth(token);
-            }
-            for (k, v) in additional_headers {
-                request = request.header(*k, *v);
-            }
-            if let Some(payload) = body {
-                request = request.json(payload);
+        let policy = if is_idempotent(&method, additional_headers) {
+            self.retry_policy.clone()
+        } else {
+            RetryPolicy {
+                max_retries: 0,
+                ..self.retry_policy.clone()
             }
+        };
+        // Retry-After from the latest response, honoured for 429s and 5xxs alike
+        let retry_after = Mutex::new(None::<u64>);
 
-            let response = request
-                .send()
-                .await
-                .map_err(|e| PaymentError::NetworkError {
-                    message: format!("provider request failed: {}", e),
-                });
-
-            match response {
-                Ok(resp) => {
-                    let status = resp.status();
-                    let text = resp.text().await.unwrap_or_default();
-                    if status.is_success() {
-                        return serde_json::from_str::<T>(&text).map_err(|e| {
-                            PaymentError::ProviderError {
-                                provider: "http".to_string(),
-                                message: format!("invalid provider JSON response: {}", e),
-                                provider_code: None,
-                                retryable: false,
-                            }
-                        });
+        retry_async(
+            &policy,
+            |e: &PaymentError| {
+                if !e.is_retryable() {
+                    return RetryClass::Fatal;
+                }
+                match *retry_after.lock().unwrap_or_else(|e| e.into_inner()) {
+                    Some(secs) => {
+                        RetryClass::RetryAfter(Duration::from_secs(secs).min(MAX_RETRY_AFTER))
                     }
+                    None => RetryClass::Retry,
+                }
+            },
+            |_| {
+                self.request_json_once(
+                    method.clone(),
+                    url,
+                    bearer_token,
+                    body,
+                    additional_headers,
+                    &retry_after,
+                )
+            },
+        )
+        .await
+    }
 
-                    if status.as_u16() == 429 {
-                        if attempt < self.max_retries {
-                            tokio::time::sleep(Duration::from_secs(1 << attempt)).await;
-                            continue;
-                        }
-                        return Err(PaymentError::RateLimitError {
-                            message: "provider rate limit exceeded".to_string(),
-                            retry_after_seconds: None,
-                        });
-                    }
+    async fn request_json_once<T: DeserializeOwned>(
+        &self,
+        method: reqwest::Method,
+        url: &str,
+        bearer_token: Option<&str>,
+        body: Option<&JsonValue>,
+        additional_headers: &[(&str, &str)],
+        retry_after_hint: &Mutex<Option<u64>>,
+    ) -> PaymentResult<T> {
+        *retry_after_hint.lock().unwrap_or_else(|e| e.into_inner()) = None;
+        let mut request = self.client.request(method, url);
+        request = request.timeout(self.timeout);
 
-                    if status.is_server_error() && attempt < self.max_retries {
-                        warn!(
-                            status = %status,
-                            attempt = attempt + 1,
-                            "provider server error, retrying"
-                        );
-                        tokio::time::sleep(Duration::from_secs(1 << attempt)).await;
-                        continue;
-                    }
+        if let Some(token) = bearer_token {
+            request = request.bearer_auth(token);
+        }
+        for (k, v) in additional_headers {
+            request = request.header(*k, *v);
+        }
+        if let Some(payload) = body {
+            request = request.json(payload);
+        }
 
-                    return Err(PaymentError::ProviderError {
-                        provider: "http".to_string(),
-                        message: format!("HTTP {}: {}", status, text),
-                        provider_code: Some(status.as_u16().to_string()),
-                        retryable: status.is_server_error(),
-                    });
-                }
-                Err(e) => {
-                    last_error = Some(e);
-                    if attempt < self.max_retries {
-                        tokio::time::sleep(Duration::from_secs(1 << attempt)).await;
-                        continue;
-                    }
-                }
-            }
+        let resp = request
+            .send()
+            .await
+            .map_err(|e| PaymentError::NetworkError {
+                message: format!("provider request failed: {}", e),
+            })?;
+
+        let status = resp.status();
+        let retry_after = parse_retry_after(resp.headers());
+        let text = resp.text().await.unwrap_or_default();
+        if status.is_success() {
+            return serde_json::from_str::<T>(&text).map_err(|e| PaymentError::ProviderError {
+                provider: "http".to_string(),
+                message: format!("invalid provider JSON response: {}", e),
+                provider_code: None,
+                retryable: false,
+            });
+        }
+
+        *retry_after_hint.lock().unwrap_or_else(|e| e.into_inner()) = retry_after;
+        if status.as_u16() == 429 {
+            return Err(PaymentError::RateLimitError {
+                message: "provider rate limit exceeded".to_string(),
+                retry_after_seconds: retry_after,
+            });
         }
 
-        Err(last_error.unwrap_or(PaymentError::NetworkError {
-            message: "provider request failed".to_string(),
-        }))
+        Err(PaymentError::ProviderError {
+            provider: "http".to_string(),
+            message: format!("HTTP {}: {}", status, text),
+            provider_code: Some(status.as_u16().to_string()),
+            retryable: status.is_server_error(),
+        })
     }
 }
 
@@ -142,10 +201,61 @@ pub fn secure_eq(a: &[u8], b: &[u8]) -> bool {
         == 0
 }
 
+/// Decode a webhook body that should be a JSON object.
+///
+/// Anything else (truncated JSON, an empty body, an array or scalar) becomes
+/// `Err` with a fallback event carrying `PaymentState::Unknown` and the raw
+/// body, so a provider sending garbage is logged and stored rather than
+/// failing the request.
+pub fn decode_webhook_object(
+    provider: ProviderName,
+    payload: &[u8],
+) -> Result<JsonValue, Box<WebhookEvent>> {
+    let (raw, reason) = match serde_json::from_slice::<JsonValue>(payload) {
+        Ok(value @ JsonValue::Object(_)) => return Ok(value),
+        Ok(other) => (other, "webhook body is not a JSON object".to_string()),
+        Err(e) => (
+            serde_json::json!({ "raw": String::from_utf8_lossy(payload) }),
+            format!("invalid webhook JSON payload: {}", e),
+        ),
+    };
+
+    tracing::warn!(
+        provider = %provider,
+        event_type = "unknown",
+        reason = %reason,
+        "unparseable webhook payload"
+    );
+    Err(Box::new(WebhookEvent {
+        provider,
+        event_type: "unknown".to_string(),
+        transaction_reference: None,
+        provider_reference: None,
+        status: Some(PaymentState::Unknown),
+        payload: raw,
+        received_at: chrono::Utc::now().to_rfc3339(),
+    }))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn webhook_objects_decode_and_garbage_falls_back() {
+        let object = decode_webhook_object(ProviderName::Paystack, br#"{"event":"x"}"#).unwrap();
+        assert_eq!(object["event"], "x");
+
+        for body in [&b""[..], b"{\"event\":\"charge.suc", b"[1,2,3]", b"null"] {
+            let fallback = decode_webhook_object(ProviderName::Paystack, body).unwrap_err();
+            assert_eq!(fallback.event_type, "unknown");
+            assert!(matches!(fallback.status, Some(PaymentState::Unknown)));
+        }
+
+        let truncated = decode_webhook_object(ProviderName::Paystack, b"{\"ev").unwrap_err();
+        assert_eq!(truncated.payload["raw"], "{\"ev");
+    }
+
     #[test]
     fn secure_eq_behaves_correctly() {
         assert!(secure_eq(b"abc", b"abc"));
@@ -153,6 +263,30 @@ mod tests {
         assert!(!secure_eq(b"abc", b"ab"));
     }
 
+    #[test]
+    fn only_idempotent_requests_are_retryable() {
+        assert!(is_idempotent(&reqwest::Method::GET, &[]));
+        assert!(!is_idempotent(&reqwest::Method::POST, &[]));
+        assert!(is_idempotent(
+            &reqwest::Method::POST,
+            &[("Idempotency-Key", "txn_1")]
+        ));
+    }
+
+    #[test]
+    fn backoff_delay_grows_exponentially_within_jitter() {
+        let policy = RetryPolicy {
+            max_retries: 3,
+            base_delay: Duration::from_millis(100),
+            jitter: Duration::from_millis(10),
+            max_elapsed: None,
+        };
+        let first = policy.backoff_delay(0);
+        let third = policy.backoff_delay(2);
+        assert!(first >= Duration::from_millis(100) && first <= Duration::from_millis(110));
+        assert!(third >= Duration::from_millis(400) && third <= Duration::from_millis(410));
+    }
+
     #[test]
     fn webhook_hmac_verification_detects_invalid_signature() {
         let payload = br#"{"event":"charge.success"}"#;
@@ -0,0 +1,232 @@
+//! Retry with exponential backoff for calls whose failures may be transient
+//!
+//! Horizon, Soroban RPC and the payment providers fail in different ways, so
+//! each subsystem supplies its own classifier saying which errors are worth
+//! another attempt. `retry_async` owns everything else: the backoff schedule,
+//! jitter, the attempt limit and the overall time budget.
+
+use std::fmt::Display;
+use std::future::Future;
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+/// How often and for how long `retry_async` keeps trying
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Number of retries after the first attempt.
+    pub max_retries: u32,
+    /// Delay before the first retry; doubled on each subsequent attempt.
+    pub base_delay: Duration,
+    /// Maximum random jitter added on top of the backoff delay.
+    pub jitter: Duration,
+    /// Give up rather than start a wait that would end past this budget,
+    /// measured from the first attempt. `None` leaves only `max_retries`.
+    pub max_elapsed: Option<Duration>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_millis(500),
+            jitter: Duration::from_millis(250),
+            max_elapsed: None,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Reads `PAYMENT_RETRY_BASE_DELAY_MS`, `PAYMENT_RETRY_JITTER_MS` and
+    /// `RETRY_MAX_ELAPSED_MS`. The attempt limit is per client (e.g.
+    /// `PAYSTACK_MAX_RETRIES`), so callers set `max_retries` themselves.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let env_ms = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .map(Duration::from_millis)
+        };
+        Self {
+            max_retries: defaults.max_retries,
+            base_delay: env_ms("PAYMENT_RETRY_BASE_DELAY_MS").unwrap_or(defaults.base_delay),
+            jitter: env_ms("PAYMENT_RETRY_JITTER_MS").unwrap_or(defaults.jitter),
+            max_elapsed: env_ms("RETRY_MAX_ELAPSED_MS").or(defaults.max_elapsed),
+        }
+    }
+
+    /// Backoff delay before retry number `attempt` (0-based).
+    pub fn backoff_delay(&self, attempt: u32) -> Duration {
+        let exp = self.base_delay.saturating_mul(1u32 << attempt.min(16));
+        let jitter_ms = self.jitter.as_millis() as u64;
+        if jitter_ms == 0 {
+            return exp;
+        }
+        exp + Duration::from_millis(rand::random::<u64>() % (jitter_ms + 1))
+    }
+}
+
+/// A classifier's verdict on one failed attempt
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryClass {
+    /// Transient; retry after the policy's backoff delay
+    Retry,
+    /// Transient; retry after this delay instead (e.g. a `Retry-After` hint)
+    RetryAfter(Duration),
+    /// Retrying cannot help; return the error now
+    Fatal,
+}
+
+/// Run `op` until it succeeds, `classify` calls its error fatal, or `policy`
+/// runs out of attempts or time. `op` receives the 0-based attempt number.
+/// The last error is returned when giving up.
+pub async fn retry_async<T, E, C, Op, Fut>(
+    policy: &RetryPolicy,
+    classify: C,
+    mut op: Op,
+) -> Result<T, E>
+where
+    E: Display,
+    C: Fn(&E) -> RetryClass,
+    Op: FnMut(u32) -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let started = Instant::now();
+    let mut attempt = 0;
+    loop {
+        let error = match op(attempt).await {
+            Ok(value) => return Ok(value),
+            Err(e) => e,
+        };
+
+        let delay = match classify(&error) {
+            RetryClass::Fatal => return Err(error),
+            _ if attempt >= policy.max_retries => return Err(error),
+            RetryClass::Retry => policy.backoff_delay(attempt),
+            RetryClass::RetryAfter(delay) => delay,
+        };
+        if policy
+            .max_elapsed
+            .is_some_and(|budget| started.elapsed() + delay > budget)
+        {
+            return Err(error);
+        }
+
+        warn!(
+            attempt = attempt + 1,
+            delay_ms = delay.as_millis() as u64,
+            error = %error,
+            "transient failure, retrying"
+        );
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn policy(max_retries: u32, base_delay_ms: u64) -> RetryPolicy {
+        RetryPolicy {
+            max_retries,
+            base_delay: Duration::from_millis(base_delay_ms),
+            jitter: Duration::ZERO,
+            max_elapsed: None,
+        }
+    }
+
+    fn by_message(e: &&str) -> RetryClass {
+        if e.starts_with("transient") {
+            RetryClass::Retry
+        } else {
+            RetryClass::Fatal
+        }
+    }
+
+    #[tokio::test]
+    async fn retries_retryable_errors_until_success() {
+        let calls = AtomicU32::new(0);
+        let result = retry_async(&policy(3, 1), by_message, |attempt| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            async move {
+                if attempt < 2 {
+                    Err("transient: 503")
+                } else {
+                    Ok(attempt)
+                }
+            }
+        })
+        .await;
+
+        assert_eq!(result, Ok(2));
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_fatal_errors() {
+        let calls = AtomicU32::new(0);
+        let result: Result<(), _> = retry_async(&policy(5, 1), by_message, |_| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            async { Err("fatal: bad request") }
+        })
+        .await;
+
+        assert_eq!(result, Err("fatal: bad request"));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_max_retries() {
+        let calls = AtomicU32::new(0);
+        let result: Result<(), _> = retry_async(&policy(2, 1), by_message, |_| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            async { Err("transient: timeout") }
+        })
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn stops_before_exceeding_time_budget() {
+        let calls = AtomicU32::new(0);
+        // Waits of 50ms then 100ms: the second would end past the 120ms budget
+        let budgeted = RetryPolicy {
+            max_elapsed: Some(Duration::from_millis(120)),
+            ..policy(10, 50)
+        };
+        let started = Instant::now();
+        let result: Result<(), _> = retry_async(&budgeted, by_message, |_| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            async { Err("transient: 503") }
+        })
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert!(started.elapsed() < Duration::from_millis(120));
+    }
+
+    #[tokio::test]
+    async fn retry_after_hint_replaces_backoff() {
+        let started = Instant::now();
+        let result = retry_async(
+            &policy(1, 10_000),
+            |_: &&str| RetryClass::RetryAfter(Duration::from_millis(5)),
+            |attempt| async move {
+                if attempt == 0 {
+                    Err("rate limited")
+                } else {
+                    Ok(())
+                }
+            },
+        )
+        .await;
+
+        assert!(result.is_ok());
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+}
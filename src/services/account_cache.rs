@@ -0,0 +1,77 @@
+//! Horizon account lookups behind a short-lived cache.
+//!
+//! When a hot account's entry expires, every request in flight would miss at
+//! once and each go to Horizon. Misses are coalesced per address instead: one
+//! request fetches and caches the account while the rest wait for its result.
+
+use crate::cache::{
+    cache::Cache, keys::wallet::AccountKey, single_flight::SingleFlight, RedisCache,
+};
+use crate::chains::stellar::{
+    client::StellarClient, errors::StellarError, types::StellarAccountInfo,
+};
+use crate::services::balance::DataFreshness;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+/// Account records change with every transaction; keep them only long
+/// enough to absorb bursts
+const ACCOUNT_CACHE_TTL: Duration = Duration::from_secs(10);
+
+type Lookup = Result<StellarAccountInfo, StellarError>;
+
+/// Cheap to clone; clones share the cache and the in-flight lookups
+#[derive(Clone)]
+pub struct AccountCache {
+    stellar_client: StellarClient,
+    cache: Option<RedisCache>,
+    in_flight: Arc<SingleFlight<Lookup>>,
+}
+
+impl AccountCache {
+    /// Without `cache`, concurrent lookups are still coalesced but nothing is
+    /// kept once they finish.
+    pub fn new(stellar_client: StellarClient, cache: Option<RedisCache>) -> Self {
+        Self {
+            stellar_client,
+            cache,
+            in_flight: SingleFlight::new(),
+        }
+    }
+
+    /// Same errors as `StellarClient::get_account`; concurrent callers for
+    /// one address all get the same answer, errors included. Failures are
+    /// not cached.
+    pub async fn get_account(&self, address: &str) -> Lookup {
+        self.lookup(address).await.map(|(account, _)| account)
+    }
+
+    /// `get_account`, also saying whether the record came from the cache
+    pub async fn lookup(
+        &self,
+        address: &str,
+    ) -> Result<(StellarAccountInfo, DataFreshness), StellarError> {
+        let key = AccountKey::new(address).to_string();
+        if let Some(cache) = &self.cache {
+            if let Ok(Some(account)) = cache.get(&key).await {
+                debug!("Account cache hit for {}", address);
+                return Ok((account, DataFreshness::Cached));
+            }
+        }
+
+        self.in_flight
+            .get_or_rebuild(&key, || async {
+                let lookup = self.stellar_client.get_account(address).await;
+                if let (Ok(account), Some(cache)) = (&lookup, &self.cache) {
+                    if let Err(e) = cache.set(&key, account, Some(ACCOUNT_CACHE_TTL)).await {
+                        warn!("Failed to cache account {}: {}", address, e);
+                    }
+                }
+                Ok(lookup)
+            })
+            .await
+            .unwrap_or_else(|e| Err(StellarError::unexpected_error(e)))
+            .map(|account| (account, DataFreshness::Live))
+    }
+}
@@ -0,0 +1,141 @@
+//! AFRI deposit orchestration
+//!
+//! A deposit needs the recipient to trust the asset before anything can be
+//! sent. The recipient has to sign their own changeTrust, so when the
+//! trustline is missing we stop and tell the client what to do; once it
+//! exists we build the unsigned payment. Each step yields a trustline
+//! operation entry for the audit log.
+
+use crate::chains::stellar::client::StellarClient;
+use crate::chains::stellar::errors::StellarResult;
+use crate::chains::stellar::payment::{CngnMemo, CngnPaymentBuilder, CngnPaymentDraft};
+use crate::chains::stellar::trustline::{CngnAssetConfig, CngnTrustlineManager};
+use crate::services::trustline_operation::TrustlineOperationInput;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+/// Endpoint the recipient's wallet calls to get an unsigned changeTrust
+pub const TRUSTLINE_BUILD_ENDPOINT: &str = "/api/cngn/trustlines/build";
+const AUDIT_FLOW: &str = "afri_deposit";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DepositRequest {
+    pub source: String,
+    pub destination: String,
+    pub amount: String,
+    /// Internal account the deposit credits; sent as the memo id
+    #[serde(default)]
+    pub deposit_account_id: Option<u64>,
+    #[serde(default)]
+    pub fee_stroops: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum DepositOutcome {
+    /// The destination must add a trustline, signed with its own key, first
+    TrustlineRequired {
+        destination: String,
+        asset_code: String,
+        issuer: String,
+        instructions: String,
+        next_step: String,
+    },
+    /// Unsigned payment ready for the source to sign and submit
+    PaymentDraft { draft: CngnPaymentDraft },
+}
+
+/// Outcome plus the audit entries recorded on the way to it, in order
+#[derive(Debug, Clone)]
+pub struct DepositPlan {
+    pub outcome: DepositOutcome,
+    pub audit: Vec<TrustlineOperationInput>,
+}
+
+pub struct AfriDepositService {
+    trustlines: CngnTrustlineManager,
+    payments: CngnPaymentBuilder,
+}
+
+impl AfriDepositService {
+    pub fn new(stellar_client: StellarClient) -> Self {
+        Self::with_config(stellar_client, CngnAssetConfig::from_env())
+    }
+
+    pub fn with_config(stellar_client: StellarClient, config: CngnAssetConfig) -> Self {
+        Self {
+            trustlines: CngnTrustlineManager::with_config(stellar_client.clone(), config),
+            payments: CngnPaymentBuilder::new(stellar_client),
+        }
+    }
+
+    pub async fn prepare(&self, request: &DepositRequest) -> StellarResult<DepositPlan> {
+        let memo = CngnMemo::resolve(None, request.deposit_account_id)?;
+        let asset = self.trustlines.asset()?;
+        let status = self
+            .trustlines
+            .check_asset_trustline(&request.destination, &asset)
+            .await?;
+
+        let entry = |operation_type: &str, status: &str, tx_hash: Option<String>, step: &str| {
+            TrustlineOperationInput {
+                wallet_address: request.destination.clone(),
+                asset_code: asset.code().to_string(),
+                issuer: asset.issuer().map(str::to_string),
+                operation_type: operation_type.to_string(),
+                status: status.to_string(),
+                transaction_hash: tx_hash,
+                error_message: None,
+                metadata: json!({
+                    "flow": AUDIT_FLOW,
+                    "step": step,
+                    "source": request.source,
+                    "amount": request.amount,
+                }),
+            }
+        };
+
+        let mut audit = vec![entry("verify", "completed", None, "trustline_check")];
+
+        if !status.has_trustline {
+            audit.push(entry("create", "pending", None, "trustline_required"));
+            return Ok(DepositPlan {
+                outcome: DepositOutcome::TrustlineRequired {
+                    destination: request.destination.clone(),
+                    instructions: format!(
+                        "{} has no {} trustline. The recipient must sign and submit a \
+                         changeTrust for {} issued by {} before the deposit can be sent.",
+                        request.destination, status.asset_code, status.asset_code, status.issuer
+                    ),
+                    asset_code: status.asset_code,
+                    issuer: status.issuer,
+                    next_step: TRUSTLINE_BUILD_ENDPOINT.to_string(),
+                },
+                audit,
+            });
+        }
+
+        let draft = self
+            .payments
+            .build_asset_payment(
+                &request.source,
+                &request.destination,
+                &asset,
+                &request.amount,
+                memo,
+                request.fee_stroops,
+            )
+            .await?;
+        audit.push(entry(
+            "verify",
+            "pending",
+            Some(draft.transaction_hash.clone()),
+            "payment_draft",
+        ));
+
+        Ok(DepositPlan {
+            outcome: DepositOutcome::PaymentDraft { draft },
+            audit,
+        })
+    }
+}
@@ -1,4 +1,8 @@
-use crate::cache::{cache::Cache, keys::wallet::BalanceKey, RedisCache};
+use crate::cache::{
+    cache::Cache,
+    keys::wallet::{BalanceKey, LastKnownBalanceKey},
+    RedisCache,
+};
 use crate::chains::stellar::{client::StellarClient, errors::StellarError, types::AssetBalance};
 use chrono::Utc;
 use rust_decimal::Decimal;
@@ -8,9 +12,37 @@ use std::time::Duration;
 use tracing::{debug, warn};
 
 const BALANCE_CACHE_TTL: Duration = Duration::from_secs(30);
+/// How long the last Horizon result stays available as a fallback
+const LAST_KNOWN_BALANCE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
 const BASE_RESERVE_XLM: &str = "1.0";
 const TRUSTLINE_RESERVE_XLM: &str = "0.5";
 
+/// Response header telling clients where the returned data came from
+pub const DATA_FRESHNESS_HEADER: &str = "x-data-freshness";
+
+/// Where a balance came from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DataFreshness {
+    /// Fetched from Horizon for this request
+    #[default]
+    Live,
+    /// Served from the short-lived balance cache
+    Cached,
+    /// Horizon was unavailable; this is the last balance it returned
+    Stale,
+}
+
+impl DataFreshness {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Live => "live",
+            Self::Cached => "cached",
+            Self::Stale => "stale",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WalletBalance {
     pub wallet_address: String,
@@ -20,6 +52,8 @@ pub struct WalletBalance {
     pub minimum_xlm_required: String,
     pub last_updated: String,
     pub cached: bool,
+    #[serde(default)]
+    pub freshness: DataFreshness,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -75,12 +109,35 @@ impl BalanceService {
         if !force_refresh {
             if let Ok(Some(cached)) = self.cache.get(&cache_key).await {
                 debug!("Balance cache hit for {}", address);
-                return Ok(cached);
+                return Ok(WalletBalance {
+                    cached: true,
+                    freshness: DataFreshness::Cached,
+                    ..cached
+                });
             }
         }
 
         debug!("Fetching balance from Stellar for {}", address);
-        let account = self.stellar_client.get_account(address).await?;
+        let last_known_key = LastKnownBalanceKey::new(address).to_string();
+        let account = match self.stellar_client.get_account(address).await {
+            Ok(account) => account,
+            Err(e) if e.is_retryable() => {
+                if let Ok(Some(last_known)) = self.cache.get(&last_known_key).await {
+                    warn!(
+                        address = %address,
+                        error = %e,
+                        "Horizon unavailable, serving last known balance"
+                    );
+                    return Ok(WalletBalance {
+                        cached: true,
+                        freshness: DataFreshness::Stale,
+                        ..last_known
+                    });
+                }
+                return Err(e);
+            }
+            Err(e) => return Err(e),
+        };
 
         let xlm_balance = self.extract_xlm_balance(&account.balances);
         let trustline_count = account
@@ -115,6 +172,7 @@ impl BalanceService {
             minimum_xlm_required: self.calculate_reserve(trustline_count),
             last_updated: Utc::now().to_rfc3339(),
             cached: false,
+            freshness: DataFreshness::Live,
         };
 
         if let Err(e) = self
@@ -124,6 +182,13 @@ impl BalanceService {
         {
             warn!("Failed to cache balance for {}: {}", address, e);
         }
+        if let Err(e) = self
+            .cache
+            .set(&last_known_key, &balance, Some(LAST_KNOWN_BALANCE_TTL))
+            .await
+        {
+            warn!("Failed to store last known balance for {}: {}", address, e);
+        }
 
         Ok(balance)
     }
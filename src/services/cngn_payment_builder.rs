@@ -2,6 +2,7 @@
 //! Builds payment transaction drafts, calculates fees, supports memo, and signs payloads.
 
 use crate::chains::stellar::client::StellarClient;
+use crate::chains::stellar::types::{check_text_memo, max_text_memo_bytes};
 use crate::error::{AppError, AppErrorKind, ExternalError, ValidationError};
 use ed25519_dalek::{Signer, SigningKey};
 use serde::{Deserialize, Serialize};
@@ -348,16 +349,17 @@ fn memo_to_xdr(memo: &PaymentMemo) -> Result<Memo, AppError> {
     match memo {
         PaymentMemo::None => Ok(Memo::None),
         PaymentMemo::Text(text) => {
-            if text.as_bytes().len() > 28 {
+            let max_bytes = max_text_memo_bytes();
+            if let Err(reason) = check_text_memo(text, max_bytes) {
                 return Err(AppError::new(AppErrorKind::Validation(
-                    ValidationError::OutOfRange {
+                    ValidationError::InvalidFormat {
                         field: "memo".to_string(),
-                        min: None,
-                        max: Some("28 bytes".to_string()),
+                        expected: format!("at most {} bytes of UTF-8 text", max_bytes),
+                        got: reason,
                     },
                 )));
             }
-            let value: StringM<28> = text.parse().map_err(|_| {
+            let value = StringM::<28>::try_from(text.as_bytes().to_vec()).map_err(|_| {
                 AppError::new(AppErrorKind::Validation(ValidationError::InvalidAmount {
                     amount: text.clone(),
                     reason: "memo contains invalid characters".to_string(),
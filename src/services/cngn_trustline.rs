@@ -9,7 +9,9 @@ use crate::chains::stellar::{
     types::{AssetBalance, StellarAccountInfo},
 };
 use crate::error::{AppError, AppErrorKind, DomainError, ExternalError};
+use bigdecimal::BigDecimal;
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
 use std::time::Duration;
 use tokio::time::{sleep, timeout};
 use tracing::{debug, error, info, warn};
@@ -19,12 +21,18 @@ const BASE_RESERVE_XLM: f64 = 0.5;
 const TRUSTLINE_RESERVE_XLM: f64 = 0.5;
 const MIN_BALANCE_BUFFER_XLM: f64 = 0.5; // Extra buffer for transaction fees
 
+/// Minimum trustline limit used when `CNGN_MIN_TRUSTLINE_LIMIT` is set but
+/// isn't a usable number
+pub const DEFAULT_MIN_TRUSTLINE_LIMIT: &str = "1000000";
+
 /// Configuration for cNGN asset
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CngnAssetConfig {
     pub asset_code: String,
     pub issuer_public_key: String,
     pub default_limit: Option<String>,
+    /// Smallest trustline limit considered large enough to receive deposits
+    pub min_trustline_limit: Option<String>,
 }
 
 impl Default for CngnAssetConfig {
@@ -34,6 +42,7 @@ impl Default for CngnAssetConfig {
             issuer_public_key: std::env::var("CNGN_ISSUER_PUBLIC_KEY")
                 .unwrap_or_else(|_| "GCNGN_ISSUER_PLACEHOLDER".to_string()),
             default_limit: None, // Unlimited by default
+            min_trustline_limit: None,
         }
     }
 }
@@ -45,6 +54,25 @@ impl CngnAssetConfig {
             issuer_public_key: std::env::var("CNGN_ISSUER_PUBLIC_KEY")
                 .unwrap_or_else(|_| "GCNGN_ISSUER_PLACEHOLDER".to_string()),
             default_limit: std::env::var("CNGN_DEFAULT_LIMIT").ok(),
+            min_trustline_limit: std::env::var("CNGN_MIN_TRUSTLINE_LIMIT")
+                .ok()
+                .map(|v| parse_min_trustline_limit(&v)),
+        }
+    }
+}
+
+/// A typo in the minimum must not switch the check off, so anything that
+/// isn't a non-negative number is logged and replaced by the default.
+pub fn parse_min_trustline_limit(value: &str) -> String {
+    match BigDecimal::from_str(value.trim()) {
+        Ok(limit) if limit >= BigDecimal::from(0) => value.trim().to_string(),
+        _ => {
+            error!(
+                value = %value,
+                default = DEFAULT_MIN_TRUSTLINE_LIMIT,
+                "CNGN_MIN_TRUSTLINE_LIMIT is not a valid amount; using the default"
+            );
+            DEFAULT_MIN_TRUSTLINE_LIMIT.to_string()
         }
     }
 }
@@ -58,6 +86,16 @@ pub struct TrustlineStatus {
     pub is_authorized: bool,
 }
 
+/// Result of checking an existing trustline limit against the configured minimum
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrustlineLimitCheck {
+    pub account_id: String,
+    pub has_trustline: bool,
+    pub limit: Option<String>,
+    pub min_limit: Option<String>,
+    pub sufficient: bool,
+}
+
 /// Trustline transaction details
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TrustlineTransaction {
@@ -217,6 +255,54 @@ impl CngnTrustlineService {
         Ok(())
     }
 
+    /// Check that an account's cNGN trustline limit meets the configured minimum
+    ///
+    /// # Arguments
+    /// * `account_id` - Stellar account public key
+    ///
+    /// # Returns
+    /// TrustlineLimitCheck describing the current limit and whether it is sufficient.
+    /// Accounts without a trustline are reported as insufficient rather than erroring.
+    pub async fn validate_trustline_limit(
+        &self,
+        account_id: &str,
+    ) -> Result<TrustlineLimitCheck, AppError> {
+        debug!(account_id = %account_id, "Validating trustline limit");
+
+        let status = self.check_trustline(account_id).await?;
+        let min_limit = self.cngn_config.min_trustline_limit.clone();
+
+        let sufficient = status.exists
+            && match (min_limit.as_deref(), status.limit.as_deref()) {
+                (None, _) => true,
+                (Some(min), Some(limit)) => {
+                    match (BigDecimal::from_str(min), BigDecimal::from_str(limit)) {
+                        (Ok(min), Ok(limit)) => limit >= min,
+                        // An unreadable minimum fails the check rather than passing everything
+                        _ => false,
+                    }
+                }
+                (Some(_), None) => false,
+            };
+
+        if status.exists && !sufficient {
+            warn!(
+                account_id = %account_id,
+                limit = ?status.limit,
+                min_limit = ?min_limit,
+                "Trustline limit below configured minimum"
+            );
+        }
+
+        Ok(TrustlineLimitCheck {
+            account_id: account_id.to_string(),
+            has_trustline: status.exists,
+            limit: status.limit,
+            min_limit,
+            sufficient,
+        })
+    }
+
     /// Create a trustline transaction for cNGN
     ///
     /// Note: This prepares the transaction details but does not submit it.
@@ -413,6 +499,14 @@ mod tests {
         assert!(config.issuer_public_key.len() > 0);
     }
 
+    #[test]
+    fn test_min_trustline_limit_typo_falls_back_to_default() {
+        assert_eq!(parse_min_trustline_limit(" 500000 "), "500000");
+        assert_eq!(parse_min_trustline_limit("1,000,000"), DEFAULT_MIN_TRUSTLINE_LIMIT);
+        assert_eq!(parse_min_trustline_limit("-5"), DEFAULT_MIN_TRUSTLINE_LIMIT);
+        assert_eq!(parse_min_trustline_limit(""), DEFAULT_MIN_TRUSTLINE_LIMIT);
+    }
+
     #[test]
     fn test_calculate_required_balance() {
         let stellar_client = StellarClient::new(Default::default()).unwrap();
@@ -1,7 +1,9 @@
 //! Conversion audit service
 //! Handles quote creation, execution updates, and failure/expiry tracking.
 
-use crate::database::conversion_audit_repository::{ConversionAudit, ConversionAuditRepository};
+use crate::database::conversion_audit_repository::{
+    ConversionAudit, ConversionAuditRepository, CreatedConversionAudit,
+};
 use crate::database::error::DatabaseError;
 use crate::database::repository::Repository;
 use sqlx::types::BigDecimal;
@@ -34,11 +36,12 @@ impl ConversionAuditService {
         Self { repo }
     }
 
-    /// Record a new conversion quote
+    /// Record a new conversion quote, or return the audit already recorded
+    /// for the same transaction and currency pair
     pub async fn create_quote(
         &self,
         input: ConversionQuoteInput,
-    ) -> Result<ConversionAudit, DatabaseError> {
+    ) -> Result<CreatedConversionAudit, DatabaseError> {
         self.repo
             .create(
                 input.user_id,
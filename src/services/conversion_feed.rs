@@ -0,0 +1,238 @@
+//! Live feed of conversions across all users
+//!
+//! Backs the operations dashboard's `GET /api/conversions/feed` stream.
+//! A subscriber first receives the most recent audits, then every audit created
+//! after them, found by polling with a `(created_at, id)` cursor. Polling keeps
+//! the feed working without Redis.
+//!
+//! `created_at` is stamped when an audit is inserted, not when its transaction
+//! commits, so a row can become visible behind a cursor that has already moved
+//! past it. The feed only reads audits older than a commit lag, which gives
+//! such rows time to land; a dashboard sees new conversions within the lag plus
+//! one poll interval. Each subscriber holds a database poller, so the number of
+//! concurrent subscribers is capped.
+
+use crate::database::conversion_audit_repository::{ConversionAudit, ConversionAuditRepository};
+use futures::Stream;
+use sqlx::PgPool;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use tokio::sync::{mpsc, watch, OwnedSemaphorePermit, Semaphore};
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// How old an audit must be before the feed reads it; longer than any
+/// transaction that writes a conversion audit should stay open
+pub const DEFAULT_COMMIT_LAG: Duration = Duration::from_secs(5);
+/// Concurrent subscribers when `CONVERSION_FEED_MAX_SUBSCRIBERS` isn't set
+pub const DEFAULT_MAX_SUBSCRIBERS: usize = 20;
+/// Audits sent on connect when the client doesn't ask for a number
+pub const DEFAULT_BACKFILL: i64 = 50;
+pub const MAX_BACKFILL: i64 = 500;
+/// Most audits fetched per poll; a longer backlog drains over several polls
+const POLL_PAGE_SIZE: i64 = 200;
+
+/// Position in the feed: the last audit a subscriber has seen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeedCursor {
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub id: Uuid,
+}
+
+impl From<&ConversionAudit> for FeedCursor {
+    fn from(audit: &ConversionAudit) -> Self {
+        Self {
+            created_at: audit.created_at,
+            id: audit.id,
+        }
+    }
+}
+
+/// Where a new subscription starts
+#[derive(Debug, Clone, Copy)]
+pub enum FeedStart {
+    /// Send the last `n` audits first, then new ones
+    Backfill(i64),
+    /// Resume after an audit the client already has
+    After(FeedCursor),
+}
+
+/// Bounds how many feeds poll the database at once
+#[derive(Debug, Clone)]
+pub struct SubscriberLimit {
+    slots: Arc<Semaphore>,
+}
+
+impl SubscriberLimit {
+    pub fn new(max_subscribers: usize) -> Self {
+        Self {
+            slots: Arc::new(Semaphore::new(max_subscribers)),
+        }
+    }
+
+    /// Process-wide limit, sized by `CONVERSION_FEED_MAX_SUBSCRIBERS`
+    pub fn global() -> &'static SubscriberLimit {
+        static LIMIT: OnceLock<SubscriberLimit> = OnceLock::new();
+        LIMIT.get_or_init(|| {
+            let max = std::env::var("CONVERSION_FEED_MAX_SUBSCRIBERS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(DEFAULT_MAX_SUBSCRIBERS);
+            SubscriberLimit::new(max)
+        })
+    }
+
+    /// A slot for one more subscriber, or `None` when all are taken. The slot
+    /// is given back when the feed holding it ends.
+    pub fn try_acquire(&self) -> Option<SubscriberSlot> {
+        self.slots
+            .clone()
+            .try_acquire_owned()
+            .ok()
+            .map(|permit| SubscriberSlot { _permit: permit })
+    }
+}
+
+/// One subscriber's share of a [`SubscriberLimit`]
+#[derive(Debug)]
+pub struct SubscriberSlot {
+    _permit: OwnedSemaphorePermit,
+}
+
+pub struct ConversionFeed {
+    pool: PgPool,
+    poll_interval: Duration,
+    commit_lag: Duration,
+    slot: Option<SubscriberSlot>,
+}
+
+impl ConversionFeed {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            pool,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            commit_lag: DEFAULT_COMMIT_LAG,
+            slot: None,
+        }
+    }
+
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    pub fn with_commit_lag(mut self, commit_lag: Duration) -> Self {
+        self.commit_lag = commit_lag;
+        self
+    }
+
+    /// Hold `slot` for as long as the stream runs
+    pub fn with_slot(mut self, slot: SubscriberSlot) -> Self {
+        self.slot = Some(slot);
+        self
+    }
+
+    /// Stream audits from `start` onwards until the client goes away or
+    /// `shutdown` flips.
+    pub fn stream(
+        self,
+        start: FeedStart,
+        mut shutdown: watch::Receiver<bool>,
+    ) -> impl Stream<Item = ConversionAudit> + Send + 'static {
+        let (tx, rx) = mpsc::channel(32);
+
+        tokio::spawn(async move {
+            let _slot = self.slot;
+            let repo = ConversionAuditRepository::new(self.pool);
+            let lag = self.commit_lag;
+
+            let mut cursor = match start {
+                FeedStart::After(cursor) => cursor,
+                FeedStart::Backfill(n) => {
+                    // With no backfill the newest audit still anchors the cursor
+                    let recent = match repo.find_recent(n.clamp(1, MAX_BACKFILL), lag).await {
+                        Ok(recent) => recent,
+                        Err(e) => {
+                            // Without an anchor the feed would replay all history
+                            warn!(error = %e, "conversion feed backfill failed; closing feed");
+                            return;
+                        }
+                    };
+                    let cursor = recent.last().map(FeedCursor::from).unwrap_or_else(|| {
+                        // No audits yet: anything past the lag horizon is new
+                        FeedCursor {
+                            created_at: chrono::Utc::now()
+                                - chrono::Duration::from_std(lag).unwrap_or_default(),
+                            id: Uuid::nil(),
+                        }
+                    });
+                    if n > 0 {
+                        for audit in recent {
+                            if tx.send(audit).await.is_err() {
+                                return;
+                            }
+                        }
+                    }
+                    cursor
+                }
+            };
+
+            loop {
+                if *shutdown.borrow() {
+                    debug!("closing conversion feed for shutdown");
+                    return;
+                }
+
+                match repo
+                    .find_created_after(cursor.created_at, cursor.id, lag, POLL_PAGE_SIZE)
+                    .await
+                {
+                    Ok(audits) => {
+                        for audit in audits {
+                            cursor = FeedCursor::from(&audit);
+                            if tx.send(audit).await.is_err() {
+                                return;
+                            }
+                        }
+                    }
+                    // Try again next tick; the cursor hasn't moved
+                    Err(e) => warn!(error = %e, "conversion feed poll failed"),
+                }
+
+                tokio::select! {
+                    _ = tokio::time::sleep(self.poll_interval) => {}
+                    changed = shutdown.changed() => {
+                        // A dropped sender means the server is going away too
+                        if changed.is_err() {
+                            return;
+                        }
+                    }
+                    _ = tx.closed() => {
+                        debug!("conversion feed client disconnected");
+                        return;
+                    }
+                }
+            }
+        });
+
+        futures::stream::unfold(rx, |mut rx| async move {
+            rx.recv().await.map(|audit| (audit, rx))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn subscriber_limit_frees_slot_when_dropped() {
+        let limit = SubscriberLimit::new(1);
+        let slot = limit.try_acquire().expect("first subscriber gets a slot");
+        assert!(limit.try_acquire().is_none());
+
+        drop(slot);
+        assert!(limit.try_acquire().is_some());
+    }
+}
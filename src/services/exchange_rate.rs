@@ -8,7 +8,9 @@ use crate::cache::cache::{Cache, RedisCache};
 use crate::cache::keys::exchange_rate::CurrencyPairKey;
 use crate::database::error::DatabaseError;
 use crate::database::exchange_rate_repository::ExchangeRateRepository;
-use crate::services::fee_structure::{FeeCalculationInput, FeeStructureService};
+use crate::services::fee_structure::{
+    FeeCalculationError, FeeCalculationInput, FeeMode, FeeStructureService, FeeType,
+};
 use async_trait::async_trait;
 use bigdecimal::BigDecimal;
 use chrono::{DateTime, Utc};
@@ -33,8 +35,9 @@ pub enum ExchangeRateError {
     #[error("Rate provider error: {0}")]
     ProviderError(String),
 
+    /// Passed through so callers can report it as `AppError::from` would
     #[error("Fee calculation error: {0}")]
-    FeeCalculationError(String),
+    Fee(#[from] FeeCalculationError),
 
     #[error("Invalid amount: {0}")]
     InvalidAmount(String),
@@ -390,37 +393,35 @@ impl ExchangeRateService {
 
         // Calculate provider fee (1.4%)
         let provider_fee_input = FeeCalculationInput {
-            fee_type: "provider_fee".to_string(),
+            fee_type: FeeType::ProviderFee,
             amount: gross_amount.clone(),
             currency: Some(request.to_currency.clone()),
             at_time: None,
+            fee_mode: FeeMode::Inclusive,
         };
 
-        let provider_fee = match fee_service.calculate_fee(provider_fee_input).await {
-            Ok(Some(result)) => result.fee,
-            Ok(None) => BigDecimal::from(0),
-            Err(e) => {
-                warn!("Failed to calculate provider fee: {}", e);
-                BigDecimal::from(0)
-            }
-        };
+        // A fee that can't be calculated fails the conversion rather than
+        // quoting it free
+        let provider_fee = fee_service
+            .calculate_fee(provider_fee_input)
+            .await?
+            .map(|result| result.fee)
+            .unwrap_or_else(|| BigDecimal::from(0));
 
         // Calculate platform fee (0.1%)
         let platform_fee_input = FeeCalculationInput {
-            fee_type: "platform_fee".to_string(),
+            fee_type: FeeType::PlatformFee,
             amount: gross_amount.clone(),
             currency: Some(request.to_currency.clone()),
             at_time: None,
+            fee_mode: FeeMode::Inclusive,
         };
 
-        let platform_fee = match fee_service.calculate_fee(platform_fee_input).await {
-            Ok(Some(result)) => result.fee,
-            Ok(None) => BigDecimal::from(0),
-            Err(e) => {
-                warn!("Failed to calculate platform fee: {}", e);
-                BigDecimal::from(0)
-            }
-        };
+        let platform_fee = fee_service
+            .calculate_fee(platform_fee_input)
+            .await?
+            .map(|result| result.fee)
+            .unwrap_or_else(|| BigDecimal::from(0));
 
         let total_fees = &provider_fee + &platform_fee;
 
@@ -1,24 +1,281 @@
 //! Fee structure service
-//! Provides active fee lookup and fee calculation helper.
+//! Provides active fee lookup, fee calculation helper and the per-type
+//! history used to reconstruct what a fee was at a past moment.
 
 use crate::database::error::DatabaseError;
-use crate::database::fee_structure_repository::{FeeStructure, FeeStructureRepository};
-use bigdecimal::BigDecimal;
+use crate::error::{AppError, AppErrorKind, InfrastructureError, ValidationError};
+use crate::database::fee_structure_repository::{
+    FeeStructure, FeeStructureRepository, NewFeeStructure,
+};
+use bigdecimal::{BigDecimal, RoundingMode};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
 use std::str::FromStr;
+use thiserror::Error;
+
+/// Fee types stored in `fee_structures.fee_type`; the provider/platform ones
+/// price each share of an exchange or onramp quote separately
+pub const FEE_TYPES: &[&str] = &[
+    "onramp",
+    "offramp",
+    "bill_payment",
+    "exchange",
+    "transfer",
+    "provider_fee",
+    "platform_fee",
+    "onramp_platform",
+    "onramp_provider",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeeType {
+    Onramp,
+    Offramp,
+    BillPayment,
+    Exchange,
+    Transfer,
+    ProviderFee,
+    PlatformFee,
+    OnrampPlatform,
+    OnrampProvider,
+}
+
+impl FeeType {
+    pub const ALL: [FeeType; 9] = [
+        FeeType::Onramp,
+        FeeType::Offramp,
+        FeeType::BillPayment,
+        FeeType::Exchange,
+        FeeType::Transfer,
+        FeeType::ProviderFee,
+        FeeType::PlatformFee,
+        FeeType::OnrampPlatform,
+        FeeType::OnrampProvider,
+    ];
+
+    /// Value stored in `fee_structures.fee_type`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FeeType::Onramp => "onramp",
+            FeeType::Offramp => "offramp",
+            FeeType::BillPayment => "bill_payment",
+            FeeType::Exchange => "exchange",
+            FeeType::Transfer => "transfer",
+            FeeType::ProviderFee => "provider_fee",
+            FeeType::PlatformFee => "platform_fee",
+            FeeType::OnrampPlatform => "onramp_platform",
+            FeeType::OnrampProvider => "onramp_provider",
+        }
+    }
+}
+
+impl std::fmt::Display for FeeType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for FeeType {
+    type Err = UnknownFeeType;
+
+    /// Exact match on the stored form; `"Onramp"` or `"bill-payment"` are
+    /// rejected rather than guessed at.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        FeeType::ALL
+            .into_iter()
+            .find(|t| t.as_str() == s)
+            .ok_or_else(|| UnknownFeeType {
+                fee_type: s.to_string(),
+                supported: FEE_TYPES.iter().map(|t| t.to_string()).collect(),
+            })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("unknown fee_type '{fee_type}', must be one of: {}", .supported.join(", "))]
+pub struct UnknownFeeType {
+    pub fee_type: String,
+    pub supported: Vec<String>,
+}
+
+/// Decimal places used for a fee whose currency has no rounding rule
+pub const DEFAULT_FEE_SCALE: i64 = 2;
+
+/// Decimal places each currency's fees are rounded to (half-up).
+/// Currency codes are matched case-insensitively.
+#[derive(Debug, Clone)]
+pub struct FeeRounding {
+    scales: HashMap<String, i64>,
+    default_scale: i64,
+}
+
+impl FeeRounding {
+    pub fn new(default_scale: i64) -> Self {
+        Self {
+            scales: HashMap::new(),
+            default_scale,
+        }
+    }
+
+    pub fn with_currency(mut self, currency: &str, scale: i64) -> Self {
+        self.scales.insert(currency.to_ascii_uppercase(), scale);
+        self
+    }
+
+    pub fn scale_for(&self, currency: Option<&str>) -> i64 {
+        currency
+            .and_then(|c| self.scales.get(&c.to_ascii_uppercase()))
+            .copied()
+            .unwrap_or(self.default_scale)
+    }
+
+    pub fn round(&self, fee: &BigDecimal, currency: Option<&str>) -> BigDecimal {
+        fee.with_scale_round(self.scale_for(currency), RoundingMode::HalfUp)
+    }
+}
+
+impl Default for FeeRounding {
+    /// Fiat to kobo/cents except KES (whole shillings); Stellar assets to
+    /// their 7-decimal stroop precision.
+    fn default() -> Self {
+        Self::new(DEFAULT_FEE_SCALE)
+            .with_currency("NGN", 2)
+            .with_currency("KES", 0)
+            .with_currency("CNGN", 7)
+            .with_currency("XLM", 7)
+            .with_currency("USDC", 7)
+    }
+}
+
+/// Currencies fees may be priced in when `FEE_ALLOWED_CURRENCIES` is unset
+pub const DEFAULT_FEE_CURRENCIES: &[&str] =
+    &["NGN", "KES", "GHS", "ZAR", "USD", "CNGN", "XLM", "USDC"];
+
+/// Recognised fee currency codes, matched case-insensitively. A structure in
+/// an unknown currency (say "NGM" for "NGN") would never match a calculation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeeCurrencies {
+    codes: BTreeSet<String>,
+}
+
+impl FeeCurrencies {
+    pub fn new<I, S>(codes: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Self {
+            codes: codes
+                .into_iter()
+                .map(|c| Self::normalize(c.as_ref()))
+                .filter(|c| !c.is_empty())
+                .collect(),
+        }
+    }
+
+    /// Comma-separated `FEE_ALLOWED_CURRENCIES` replaces the default list, so a
+    /// new market can be opened without a release.
+    pub fn from_env() -> Self {
+        std::env::var("FEE_ALLOWED_CURRENCIES")
+            .ok()
+            .map(|v| Self::new(v.split(',')))
+            .filter(|c| !c.codes.is_empty())
+            .unwrap_or_default()
+    }
+
+    /// The form currencies are checked, compared and stored in
+    pub fn normalize(currency: &str) -> String {
+        currency.trim().to_ascii_uppercase()
+    }
+
+    pub fn is_allowed(&self, currency: &str) -> bool {
+        self.codes.contains(&Self::normalize(currency))
+    }
+
+    /// Sorted, upper-cased
+    pub fn supported(&self) -> Vec<String> {
+        self.codes.iter().cloned().collect()
+    }
+
+    pub fn check(&self, currency: &str) -> Result<(), UnsupportedFeeCurrency> {
+        if self.is_allowed(currency) {
+            Ok(())
+        } else {
+            Err(UnsupportedFeeCurrency {
+                currency: currency.to_string(),
+                supported: self.supported(),
+            })
+        }
+    }
+}
+
+impl Default for FeeCurrencies {
+    fn default() -> Self {
+        Self::new(DEFAULT_FEE_CURRENCIES)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("unsupported currency '{currency}', must be one of: {}", .supported.join(", "))]
+pub struct UnsupportedFeeCurrency {
+    pub currency: String,
+    pub supported: Vec<String>,
+}
+
+/// Which side of the transfer the input amount describes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeeMode {
+    /// The amount is what the user sends; the fee comes out of it and the
+    /// recipient gets `amount - fee`.
+    Inclusive,
+    /// The amount is what the recipient gets; the user pays `amount + fee`.
+    #[default]
+    Exclusive,
+}
 
 /// Fee calculation input
 #[derive(Debug, Clone)]
 pub struct FeeCalculationInput {
-    pub fee_type: String,
+    pub fee_type: FeeType,
     pub amount: BigDecimal,
     pub currency: Option<String>,
     pub at_time: Option<chrono::DateTime<chrono::Utc>>,
+    pub fee_mode: FeeMode,
+}
+
+impl FeeCalculationInput {
+    /// An input without a currency matches structures in any currency
+    pub fn check_currency(
+        &self,
+        currencies: &FeeCurrencies,
+    ) -> Result<(), UnsupportedFeeCurrency> {
+        match self.currency.as_deref() {
+            Some(currency) => currencies.check(currency),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Fee together with the amounts on either side of it
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeeAmounts {
+    pub fee: BigDecimal,
+    /// What the recipient gets
+    pub net_amount: BigDecimal,
+    /// What the user pays
+    pub gross_amount: BigDecimal,
 }
 
 /// Fee calculation result
 #[derive(Debug, Clone)]
 pub struct FeeCalculationResult {
     pub fee: BigDecimal,
+    pub fee_mode: FeeMode,
+    pub net_amount: BigDecimal,
+    pub gross_amount: BigDecimal,
     pub rate_bps: i32,
     pub flat_fee: BigDecimal,
     pub min_fee: Option<BigDecimal>,
@@ -27,63 +284,386 @@ pub struct FeeCalculationResult {
     pub structure_id: uuid::Uuid,
 }
 
+/// One fee structure on a fee type's timeline with the window it actually
+/// applied in
+#[derive(Debug, Clone, Serialize)]
+pub struct FeeTimelineEntry {
+    #[serde(flatten)]
+    pub structure: FeeStructure,
+    /// When the structure was first replaced or expired: the earlier of the
+    /// start of the next structure and its own `effective_until`. `None`
+    /// while in force.
+    pub effective_to: Option<DateTime<Utc>>,
+    /// Replaced by a later structure rather than expiring on its own
+    pub superseded: bool,
+}
+
+/// A single problem with one item of a batch
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FeeStructureIssue {
+    pub index: usize,
+    pub field: String,
+    pub message: String,
+}
+
+/// A caller-supplied amount that isn't a decimal number
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("amount '{amount}' is not a valid decimal number")]
+pub struct InvalidFeeAmount {
+    pub amount: String,
+}
+
+/// Stored fee structure data that no fee can be calculated from. Only a bad
+/// data migration or a manual edit produces one, so it's a server fault and
+/// never the caller's.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error(
+    "fee structure {} is corrupt: {reason}",
+    .structure_id.map_or_else(|| "<unknown>".to_string(), |id| id.to_string())
+)]
+pub struct CorruptFeeStructure {
+    pub structure_id: Option<uuid::Uuid>,
+    pub reason: String,
+}
+
+#[derive(Debug, Error)]
+pub enum FeeCalculationError {
+    #[error(transparent)]
+    CorruptStructure(#[from] CorruptFeeStructure),
+    #[error(transparent)]
+    Database(#[from] DatabaseError),
+}
+
+impl From<InvalidFeeAmount> for AppError {
+    fn from(err: InvalidFeeAmount) -> Self {
+        AppError::new(AppErrorKind::Validation(ValidationError::InvalidAmount {
+            amount: err.amount,
+            reason: "must be a decimal number".to_string(),
+        }))
+    }
+}
+
+impl From<CorruptFeeStructure> for AppError {
+    fn from(err: CorruptFeeStructure) -> Self {
+        AppError::new(AppErrorKind::Infrastructure(
+            InfrastructureError::CorruptFeeStructure {
+                structure_id: err.structure_id.map(|id| id.to_string()),
+                message: err.reason,
+            },
+        ))
+    }
+}
+
+impl From<FeeCalculationError> for AppError {
+    fn from(err: FeeCalculationError) -> Self {
+        match err {
+            FeeCalculationError::CorruptStructure(e) => e.into(),
+            FeeCalculationError::Database(e) => e.into(),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum FeeStructureBatchError {
+    #[error("fee structure batch is invalid ({} issue(s))", .0.len())]
+    Invalid(Vec<FeeStructureIssue>),
+    #[error(transparent)]
+    Database(#[from] DatabaseError),
+}
+
 /// Service for fee structures
 pub struct FeeStructureService {
     repo: FeeStructureRepository,
+    rounding: FeeRounding,
+    currencies: FeeCurrencies,
 }
 
 impl FeeStructureService {
     pub fn new(repo: FeeStructureRepository) -> Self {
-        Self { repo }
+        Self {
+            repo,
+            rounding: FeeRounding::default(),
+            currencies: FeeCurrencies::default(),
+        }
+    }
+
+    /// Replace the per-currency rounding rules applied by `calculate_fee`
+    pub fn with_rounding(mut self, rounding: FeeRounding) -> Self {
+        self.rounding = rounding;
+        self
+    }
+
+    /// Replace the currencies structures may be created in
+    pub fn with_currencies(mut self, currencies: FeeCurrencies) -> Self {
+        self.currencies = currencies;
+        self
+    }
+
+    pub fn currencies(&self) -> &FeeCurrencies {
+        &self.currencies
     }
 
     /// Get active fee structures for a fee type
     pub async fn get_active(
         &self,
-        fee_type: &str,
+        fee_type: FeeType,
         at_time: Option<chrono::DateTime<chrono::Utc>>,
     ) -> Result<Vec<FeeStructure>, DatabaseError> {
-        self.repo.get_active_by_type(fee_type, at_time).await
+        self.repo
+            .get_active_by_type(fee_type.as_str(), at_time)
+            .await
+    }
+
+    /// Validate every structure up front, then insert them all or none.
+    /// Currencies are stored upper-cased.
+    pub async fn create_batch(
+        &self,
+        structures: &[NewFeeStructure],
+    ) -> Result<Vec<FeeStructure>, FeeStructureBatchError> {
+        let mut structures = structures.to_vec();
+        normalize_currencies(&mut structures);
+        validate_batch(&structures, &self.currencies)
+            .map_err(FeeStructureBatchError::Invalid)?;
+        Ok(self.repo.create_batch(&structures).await?)
+    }
+
+    /// Every active structure for `fee_type`, superseded ones included,
+    /// oldest first
+    pub async fn timeline(
+        &self,
+        fee_type: FeeType,
+    ) -> Result<Vec<FeeTimelineEntry>, DatabaseError> {
+        Ok(build_timeline(
+            self.repo.find_by_type(fee_type.as_str()).await?,
+        ))
+    }
+
+    /// The fee as it would have been charged at `at_time`, using whichever
+    /// structure [`Self::calculate_fee`] would have picked then, even if it
+    /// has since been superseded.
+    pub async fn calculate_fee_at(
+        &self,
+        input: FeeCalculationInput,
+        at_time: DateTime<Utc>,
+    ) -> Result<Option<FeeCalculationResult>, FeeCalculationError> {
+        let timeline = match self.timeline(input.fee_type).await {
+            Ok(timeline) => timeline,
+            Err(e) => return Err(self.classify_load_error(input.fee_type, e).await),
+        };
+        structure_at(&timeline, at_time)
+            .map(|structure| self.apply(structure, input))
+            .transpose()
     }
 
     /// Calculate fee based on the most recent active fee structure
     pub async fn calculate_fee(
         &self,
         input: FeeCalculationInput,
-    ) -> Result<Option<FeeCalculationResult>, DatabaseError> {
-        let structures = self.get_active(&input.fee_type, input.at_time).await?;
-        let structure = match structures.first() {
-            Some(s) => s.clone(),
-            None => return Ok(None),
+    ) -> Result<Option<FeeCalculationResult>, FeeCalculationError> {
+        let structures = match self.get_active(input.fee_type, input.at_time).await {
+            Ok(structures) => structures,
+            Err(e) => return Err(self.classify_load_error(input.fee_type, e).await),
         };
+        structures
+            .first()
+            .map(|structure| self.apply(structure, input))
+            .transpose()
+    }
 
-        let rate_fee = calculate_rate_fee(&input.amount, structure.fee_rate_bps);
-        let mut total_fee = rate_fee + structure.fee_flat.clone();
-
-        if let Some(min_fee) = structure.min_fee.clone() {
-            if total_fee < min_fee {
-                total_fee = min_fee;
+    /// A row holding a `NaN` amount fails the whole load with a decode error
+    /// that doesn't say which row. Look for one so ops get the structure id;
+    /// anything else stays a database error.
+    async fn classify_load_error(
+        &self,
+        fee_type: FeeType,
+        err: DatabaseError,
+    ) -> FeeCalculationError {
+        match self.repo.find_unreadable_ids(fee_type.as_str()).await {
+            Ok(ids) if !ids.is_empty() => {
+                let corrupt = CorruptFeeStructure {
+                    structure_id: ids.first().copied(),
+                    reason: "stored amount is NaN".to_string(),
+                };
+                tracing::error!(
+                    structure_id = ?corrupt.structure_id,
+                    unreadable_ids = ?ids,
+                    fee_type = %fee_type,
+                    error = %err,
+                    "Corrupt fee structure data; fees of this type can't be calculated"
+                );
+                corrupt.into()
             }
+            _ => err.into(),
         }
+    }
 
-        if let Some(max_fee) = structure.max_fee.clone() {
-            if total_fee > max_fee {
-                total_fee = max_fee;
-            }
+    fn apply(
+        &self,
+        structure: &FeeStructure,
+        input: FeeCalculationInput,
+    ) -> Result<FeeCalculationResult, CorruptFeeStructure> {
+        if let Err(corrupt) = check_stored_structure(structure) {
+            tracing::error!(
+                structure_id = %structure.id,
+                fee_type = %structure.fee_type,
+                reason = %corrupt.reason,
+                "Corrupt fee structure data; fees of this type can't be calculated"
+            );
+            return Err(corrupt);
         }
 
-        Ok(Some(FeeCalculationResult {
-            fee: total_fee,
+        let currency = input.currency.or(structure.currency.clone());
+        let amounts = compute_fee_amounts(
+            &input.amount,
+            structure,
+            input.fee_mode,
+            &self.rounding,
+            currency.as_deref(),
+        );
+
+        Ok(FeeCalculationResult {
+            fee: amounts.fee,
+            fee_mode: input.fee_mode,
+            net_amount: amounts.net_amount,
+            gross_amount: amounts.gross_amount,
             rate_bps: structure.fee_rate_bps,
-            flat_fee: structure.fee_flat,
-            min_fee: structure.min_fee,
-            max_fee: structure.max_fee,
-            currency: input.currency.or(structure.currency),
+            flat_fee: structure.fee_flat.clone(),
+            min_fee: structure.min_fee.clone(),
+            max_fee: structure.max_fee.clone(),
+            currency,
             structure_id: structure.id,
-        }))
+        })
     }
 }
 
+/// Check a structure loaded from the database against the rules
+/// `validate_batch` enforces on the way in. A failure means the row was
+/// changed behind the API's back.
+pub fn check_stored_structure(structure: &FeeStructure) -> Result<(), CorruptFeeStructure> {
+    let zero = BigDecimal::from(0);
+    let reason = if !(0..=10_000).contains(&structure.fee_rate_bps) {
+        Some(format!("fee_rate_bps {} is outside 0..=10000", structure.fee_rate_bps))
+    } else if structure.fee_flat < zero {
+        Some(format!("fee_flat {} is negative", structure.fee_flat))
+    } else if structure.min_fee.as_ref().is_some_and(|min| *min < zero) {
+        Some("min_fee is negative".to_string())
+    } else if structure.max_fee.as_ref().is_some_and(|max| *max < zero) {
+        Some("max_fee is negative".to_string())
+    } else if let (Some(min), Some(max)) = (&structure.min_fee, &structure.max_fee) {
+        (min > max).then(|| format!("min_fee {} exceeds max_fee {}", min, max))
+    } else {
+        None
+    };
+
+    match reason {
+        Some(reason) => Err(CorruptFeeStructure {
+            structure_id: Some(structure.id),
+            reason,
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Close each structure's window, given active structures ordered by
+/// `effective_from`. Like the live lookup, a later structure replaces an
+/// earlier one whatever its currency or network.
+pub fn build_timeline(structures: Vec<FeeStructure>) -> Vec<FeeTimelineEntry> {
+    let next_starts: Vec<Option<DateTime<Utc>>> = structures
+        .iter()
+        .enumerate()
+        .map(|(i, s)| {
+            structures[i + 1..]
+                .iter()
+                .map(|next| next.effective_from)
+                .find(|from| *from > s.effective_from)
+        })
+        .collect();
+
+    structures
+        .into_iter()
+        .zip(next_starts)
+        .map(|(structure, next_start)| {
+            let own_end = structure.effective_until;
+            let superseded = next_start.is_some_and(|next| own_end.is_none_or(|end| next <= end));
+            let effective_to = if superseded { next_start } else { own_end };
+            FeeTimelineEntry {
+                structure,
+                effective_to,
+                superseded,
+            }
+        })
+        .collect()
+}
+
+/// The structure [`FeeStructureRepository::get_active_by_type`] would have
+/// returned first at `at`: the latest to start among those whose window,
+/// `effective_until` included, covers `at`. A superseded structure applies
+/// again if everything newer has expired.
+pub fn structure_at(timeline: &[FeeTimelineEntry], at: DateTime<Utc>) -> Option<&FeeStructure> {
+    timeline
+        .iter()
+        .map(|entry| &entry.structure)
+        .filter(|s| s.effective_from <= at && s.effective_until.is_none_or(|until| at <= until))
+        .max_by_key(|s| s.effective_from)
+}
+
+/// Apply `structure` to `amount` and split it into fee, net and gross.
+///
+/// The fee is always computed on the input amount and rounded before the
+/// min/max clamp and the arithmetic, so rounding never takes it past
+/// `max_fee` and `net + fee == gross` holds exactly. In inclusive mode the
+/// fee cannot take more than was sent: a minimum fee above the amount is
+/// capped at the amount and the recipient gets zero.
+pub fn compute_fee_amounts(
+    amount: &BigDecimal,
+    structure: &FeeStructure,
+    mode: FeeMode,
+    rounding: &FeeRounding,
+    currency: Option<&str>,
+) -> FeeAmounts {
+    let fee = clamp_fee(rounding.round(&raw_fee(amount, structure), currency), structure);
+
+    match mode {
+        FeeMode::Inclusive => {
+            let fee = if fee > *amount { amount.clone() } else { fee };
+            FeeAmounts {
+                net_amount: amount - &fee,
+                gross_amount: amount.clone(),
+                fee,
+            }
+        }
+        FeeMode::Exclusive => FeeAmounts {
+            net_amount: amount.clone(),
+            gross_amount: amount + &fee,
+            fee,
+        },
+    }
+}
+
+/// Rate plus flat fee, before rounding and clamping
+fn raw_fee(amount: &BigDecimal, structure: &FeeStructure) -> BigDecimal {
+    calculate_rate_fee(amount, structure.fee_rate_bps) + structure.fee_flat.clone()
+}
+
+/// Hold `fee` within the structure's min/max
+fn clamp_fee(fee: BigDecimal, structure: &FeeStructure) -> BigDecimal {
+    let mut total_fee = fee;
+
+    if let Some(min_fee) = structure.min_fee.clone() {
+        if total_fee < min_fee {
+            total_fee = min_fee;
+        }
+    }
+
+    if let Some(max_fee) = structure.max_fee.clone() {
+        if total_fee > max_fee {
+            total_fee = max_fee;
+        }
+    }
+
+    total_fee
+}
+
 fn calculate_rate_fee(amount: &BigDecimal, fee_rate_bps: i32) -> BigDecimal {
     if fee_rate_bps == 0 {
         return BigDecimal::from(0);
@@ -93,11 +673,125 @@ fn calculate_rate_fee(amount: &BigDecimal, fee_rate_bps: i32) -> BigDecimal {
     amount * rate
 }
 
+/// Upper-case each structure's currency, so "ngn" is stored as "NGN" and
+/// overlaps are found whatever case the client sent.
+pub fn normalize_currencies(structures: &mut [NewFeeStructure]) {
+    for structure in structures {
+        if let Some(currency) = structure.currency.as_mut() {
+            *currency = FeeCurrencies::normalize(currency);
+        }
+    }
+}
+
+/// Check ranges and currencies on each item and that no two items cover
+/// overlapping windows for the same fee type, currency and network.
+pub fn validate_batch(
+    structures: &[NewFeeStructure],
+    currencies: &FeeCurrencies,
+) -> Result<(), Vec<FeeStructureIssue>> {
+    let mut issues = Vec::new();
+    let mut issue = |index: usize, field: &str, message: String| {
+        issues.push(FeeStructureIssue {
+            index,
+            field: field.to_string(),
+            message,
+        })
+    };
+    let zero = BigDecimal::from(0);
+
+    if structures.is_empty() {
+        issue(0, "structures", "batch must contain at least one fee structure".to_string());
+    }
+
+    for (index, s) in structures.iter().enumerate() {
+        if let Err(e) = FeeType::from_str(&s.fee_type) {
+            issue(
+                index,
+                "fee_type",
+                format!("must be one of: {}", e.supported.join(", ")),
+            );
+        }
+        if let Some(Err(e)) = s.currency.as_deref().map(|c| currencies.check(c)) {
+            issue(
+                index,
+                "currency",
+                format!("must be one of: {}", e.supported.join(", ")),
+            );
+        }
+        if !(0..=10_000).contains(&s.fee_rate_bps) {
+            issue(index, "fee_rate_bps", "must be between 0 and 10000".to_string());
+        }
+        if s.fee_flat < zero {
+            issue(index, "fee_flat", "must not be negative".to_string());
+        }
+        if s.min_fee.as_ref().is_some_and(|min| *min < zero) {
+            issue(index, "min_fee", "must not be negative".to_string());
+        }
+        if s.max_fee.as_ref().is_some_and(|max| *max < zero) {
+            issue(index, "max_fee", "must not be negative".to_string());
+        }
+        if let (Some(min), Some(max)) = (&s.min_fee, &s.max_fee) {
+            if min > max {
+                issue(index, "min_fee", "must not exceed max_fee".to_string());
+            }
+        }
+        if s
+            .network
+            .as_deref()
+            .is_some_and(|network| !matches!(network, "testnet" | "mainnet"))
+        {
+            issue(index, "network", "must be testnet or mainnet".to_string());
+        }
+        if s.effective_until.is_some_and(|until| until <= s.effective_from) {
+            issue(
+                index,
+                "effective_until",
+                "must be after effective_from".to_string(),
+            );
+        }
+
+        for (other_index, other) in structures[..index].iter().enumerate() {
+            if other.fee_type == s.fee_type
+                && other.currency == s.currency
+                && other.network == s.network
+                && windows_overlap(other, s)
+            {
+                issue(
+                    index,
+                    "effective_from",
+                    format!("overlaps item {} for the same fee type and currency", other_index),
+                );
+            }
+        }
+    }
+
+    if issues.is_empty() {
+        Ok(())
+    } else {
+        Err(issues)
+    }
+}
+
+/// Open-ended windows (`effective_until = None`) extend forever
+fn windows_overlap(a: &NewFeeStructure, b: &NewFeeStructure) -> bool {
+    let a_before_b_ends = b.effective_until.is_none_or(|until| a.effective_from < until);
+    let b_before_a_ends = a.effective_until.is_none_or(|until| b.effective_from < until);
+    a_before_b_ends && b_before_a_ends
+}
+
 /// Helper to parse string amounts into BigDecimal
 pub fn parse_amount(amount: &str) -> BigDecimal {
     BigDecimal::from_str(amount).unwrap_or_else(|_| BigDecimal::from(0))
 }
 
+/// Parse an amount sent by a client, rejecting anything that isn't a decimal
+/// number instead of reading it as zero.
+pub fn parse_input_amount(amount: &str) -> Result<BigDecimal, InvalidFeeAmount> {
+    BigDecimal::from_str(amount.trim()).map_err(|_| InvalidFeeAmount {
+        amount: amount.to_string(),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -123,8 +817,429 @@ mod tests {
         assert_eq!(fee, BigDecimal::from_str("1.000125").unwrap());
     }
 
+    #[test]
+    fn test_ngn_fee_rounds_to_two_decimals() {
+        let fee = FeeRounding::default()
+            .round(&BigDecimal::from_str("700.125").unwrap(), Some("NGN"));
+
+        assert_eq!(fee.to_string(), "700.13");
+    }
+
+    #[test]
+    fn test_kes_fee_rounds_to_whole_shillings() {
+        let rounding = FeeRounding::default();
+        let up = rounding.round(&BigDecimal::from_str("14.5").unwrap(), Some("KES"));
+        let down = rounding.round(&BigDecimal::from_str("14.49").unwrap(), Some("kes"));
+
+        assert_eq!(up.to_string(), "15");
+        assert_eq!(down.to_string(), "14");
+    }
+
+    #[test]
+    fn test_unconfigured_currency_uses_default_scale() {
+        let rounding = FeeRounding::new(3).with_currency("NGN", 2);
+        let amount = BigDecimal::from_str("1.00049").unwrap();
+
+        assert_eq!(rounding.scale_for(Some("GHS")), 3);
+        assert_eq!(rounding.round(&amount, Some("GHS")).to_string(), "1.000");
+        assert_eq!(rounding.round(&amount, None).to_string(), "1.000");
+    }
+
     #[test]
     fn test_parse_amount_returns_zero_for_invalid_input() {
         assert_eq!(parse_amount("not-a-number"), BigDecimal::from(0));
     }
+
+    fn active_structure(min_fee: Option<&str>, max_fee: Option<&str>) -> FeeStructure {
+        let now = chrono::Utc::now();
+        FeeStructure {
+            id: uuid::Uuid::new_v4(),
+            fee_type: "transfer".to_string(),
+            fee_rate_bps: 100,
+            fee_flat: BigDecimal::from(10),
+            min_fee: min_fee.map(|v| BigDecimal::from_str(v).unwrap()),
+            max_fee: max_fee.map(|v| BigDecimal::from_str(v).unwrap()),
+            currency: Some("NGN".to_string()),
+            is_active: true,
+            effective_from: now,
+            effective_until: None,
+            metadata: serde_json::json!({}),
+            network: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    fn amounts(
+        amount: &str,
+        structure: &FeeStructure,
+        mode: FeeMode,
+    ) -> (String, String, String) {
+        let a = compute_fee_amounts(
+            &BigDecimal::from_str(amount).unwrap(),
+            structure,
+            mode,
+            &FeeRounding::default(),
+            Some("NGN"),
+        );
+        (a.fee.to_string(), a.net_amount.to_string(), a.gross_amount.to_string())
+    }
+
+    #[test]
+    fn test_inclusive_mode_nets_fee_out_of_amount() {
+        // 1% of 1000 + 10 flat = 20
+        let structure = active_structure(None, None);
+        let (fee, net, gross) = amounts("1000", &structure, FeeMode::Inclusive);
+
+        assert_eq!(
+            (fee.as_str(), net.as_str(), gross.as_str()),
+            ("20.00", "980.00", "1000")
+        );
+    }
+
+    #[test]
+    fn test_exclusive_mode_adds_fee_on_top() {
+        let structure = active_structure(None, None);
+        let (fee, net, gross) = amounts("1000", &structure, FeeMode::Exclusive);
+
+        assert_eq!(
+            (fee.as_str(), net.as_str(), gross.as_str()),
+            ("20.00", "1000", "1020.00")
+        );
+    }
+
+    #[test]
+    fn test_min_fee_applies_before_netting() {
+        // 1% of 100 + 10 = 11, raised to the 50 minimum
+        let structure = active_structure(Some("50"), None);
+
+        let (fee, net, _) = amounts("100", &structure, FeeMode::Inclusive);
+        assert_eq!((fee.as_str(), net.as_str()), ("50.00", "50.00"));
+
+        let (fee, _, gross) = amounts("100", &structure, FeeMode::Exclusive);
+        assert_eq!((fee.as_str(), gross.as_str()), ("50.00", "150.00"));
+    }
+
+    #[test]
+    fn test_max_fee_caps_before_grossing_up() {
+        // 1% of 100000 + 10 = 1010, capped at 500
+        let structure = active_structure(None, Some("500"));
+
+        let (fee, net, gross) = amounts("100000", &structure, FeeMode::Exclusive);
+        assert_eq!(
+            (fee.as_str(), net.as_str(), gross.as_str()),
+            ("500.00", "100000", "100500.00")
+        );
+
+        let (fee, net, _) = amounts("100000", &structure, FeeMode::Inclusive);
+        assert_eq!((fee.as_str(), net.as_str()), ("500.00", "99500.00"));
+    }
+
+    #[test]
+    fn test_rounding_up_never_exceeds_max_fee() {
+        // 1% of 49999.5 + 0 = 499.995, which rounds half-up to 500.00; a
+        // 499.99 cap must still hold after rounding
+        let structure = FeeStructure {
+            fee_flat: BigDecimal::from(0),
+            ..active_structure(None, Some("499.99"))
+        };
+
+        let (fee, _, gross) = amounts("49999.5", &structure, FeeMode::Exclusive);
+        assert_eq!((fee.as_str(), gross.as_str()), ("499.99", "50499.49"));
+
+        // Exactly at the cap is left alone
+        let (fee, _, _) = amounts("49999", &structure, FeeMode::Exclusive);
+        assert_eq!(fee, "499.99");
+    }
+
+    #[test]
+    fn test_inclusive_min_fee_never_exceeds_amount_sent() {
+        let structure = active_structure(Some("50"), None);
+        let (fee, net, gross) = amounts("30", &structure, FeeMode::Inclusive);
+
+        assert_eq!(
+            (fee.as_str(), net.as_str(), gross.as_str()),
+            ("30", "0", "30")
+        );
+    }
+
+    fn history_entry(rate_bps: i32, from: &str, until: Option<&str>) -> FeeStructure {
+        FeeStructure {
+            fee_rate_bps: rate_bps,
+            fee_flat: BigDecimal::from(0),
+            effective_from: from.parse().unwrap(),
+            effective_until: until.map(|t| t.parse().unwrap()),
+            ..active_structure(None, None)
+        }
+    }
+
+    fn onramp_history() -> Vec<FeeTimelineEntry> {
+        build_timeline(vec![
+            // 1.0% until replaced by 1.5% on March 1st, which expires with June 1st
+            history_entry(100, "2024-01-01T00:00:00Z", None),
+            history_entry(150, "2024-03-01T00:00:00Z", Some("2024-06-01T00:00:00Z")),
+            history_entry(120, "2024-07-01T00:00:00Z", None),
+        ])
+    }
+
+    #[test]
+    fn test_timeline_closes_superseded_and_expired_windows() {
+        let timeline = onramp_history();
+        let to = |i: usize| timeline[i].effective_to.map(|t| t.to_rfc3339());
+
+        assert_eq!(to(0).as_deref(), Some("2024-03-01T00:00:00+00:00"));
+        assert!(timeline[0].superseded);
+        assert_eq!(to(1).as_deref(), Some("2024-06-01T00:00:00+00:00"));
+        assert!(!timeline[1].superseded);
+        assert_eq!(to(2), None);
+    }
+
+    #[test]
+    fn test_historical_calculation_uses_structure_in_force_then() {
+        let timeline = onramp_history();
+        let at = |t: &str| structure_at(&timeline, t.parse().unwrap());
+
+        let march = at("2024-03-15T12:00:00Z").expect("1.5% applied in March");
+        assert_eq!(march.fee_rate_bps, 150);
+        let fee = compute_fee_amounts(
+            &BigDecimal::from(10_000),
+            march,
+            FeeMode::Exclusive,
+            &FeeRounding::default(),
+            Some("NGN"),
+        )
+        .fee;
+        assert_eq!(fee.to_string(), "150.00");
+
+        assert_eq!(at("2024-02-01T00:00:00Z").unwrap().fee_rate_bps, 100);
+        assert_eq!(at("2024-08-01T00:00:00Z").unwrap().fee_rate_bps, 120);
+        assert!(at("2023-12-31T00:00:00Z").is_none());
+    }
+
+    #[test]
+    fn test_historical_calculation_matches_live_window_edges() {
+        let timeline = onramp_history();
+        let at = |t: &str| {
+            structure_at(&timeline, t.parse().unwrap())
+                .unwrap()
+                .fee_rate_bps
+        };
+
+        // The new structure takes over on its first moment
+        assert_eq!(at("2024-03-01T00:00:00Z"), 150);
+        // `effective_until` is the last moment a structure applies
+        assert_eq!(at("2024-06-01T00:00:00Z"), 150);
+        // Once it expires, the live query falls back to the 1.0% structure
+        assert_eq!(at("2024-06-15T00:00:00Z"), 100);
+    }
+
+    fn structure(fee_type: &str, from_day: u32, until_day: Option<u32>) -> NewFeeStructure {
+        use chrono::TimeZone;
+        let day = |d| chrono::Utc.with_ymd_and_hms(2024, 1, d, 0, 0, 0).unwrap();
+        NewFeeStructure {
+            fee_type: fee_type.to_string(),
+            fee_rate_bps: 50,
+            fee_flat: BigDecimal::from(100),
+            min_fee: None,
+            max_fee: None,
+            currency: Some("NGN".to_string()),
+            is_active: true,
+            effective_from: day(from_day),
+            effective_until: until_day.map(day),
+            metadata: serde_json::json!({}),
+            network: None,
+        }
+    }
+
+    #[test]
+    fn test_validate_batch_accepts_adjacent_windows() {
+        let batch = vec![
+            structure("onramp", 1, Some(10)),
+            structure("onramp", 10, None),
+            structure("offramp", 1, None),
+        ];
+
+        assert!(validate_batch(&batch, &FeeCurrencies::default()).is_ok());
+    }
+
+    #[test]
+    fn test_validate_batch_rejects_out_of_range_values() {
+        let mut bad = structure("onramp", 1, None);
+        bad.fee_rate_bps = 10_001;
+        bad.min_fee = Some(BigDecimal::from(500));
+        bad.max_fee = Some(BigDecimal::from(100));
+
+        let issues = validate_batch(
+            &[structure("offramp", 1, None), bad],
+            &FeeCurrencies::default(),
+        )
+        .unwrap_err();
+
+        let fields: Vec<_> = issues.iter().map(|i| (i.index, i.field.as_str())).collect();
+        assert_eq!(fields, vec![(1, "fee_rate_bps"), (1, "min_fee")]);
+    }
+
+    #[test]
+    fn test_validate_batch_rejects_overlapping_windows() {
+        let batch = vec![
+            structure("onramp", 1, Some(10)),
+            structure("onramp", 5, None),
+        ];
+
+        let issues = validate_batch(&batch, &FeeCurrencies::default()).unwrap_err();
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].index, 1);
+        assert!(issues[0].message.contains("item 0"));
+    }
+
+    #[test]
+    fn test_validate_batch_rejects_unknown_fee_type_and_empty_batch() {
+        assert!(validate_batch(&[], &FeeCurrencies::default()).is_err());
+
+        let issues = validate_batch(
+            &[structure("withdrawal", 1, None)],
+            &FeeCurrencies::default(),
+        )
+        .unwrap_err();
+        assert_eq!(issues[0].field, "fee_type");
+    }
+
+    #[test]
+    fn test_fee_type_parses_every_stored_value() {
+        let expected = [
+            ("onramp", FeeType::Onramp),
+            ("offramp", FeeType::Offramp),
+            ("bill_payment", FeeType::BillPayment),
+            ("exchange", FeeType::Exchange),
+            ("transfer", FeeType::Transfer),
+            ("provider_fee", FeeType::ProviderFee),
+            ("platform_fee", FeeType::PlatformFee),
+            ("onramp_platform", FeeType::OnrampPlatform),
+            ("onramp_provider", FeeType::OnrampProvider),
+        ];
+        for (raw, fee_type) in expected {
+            assert_eq!(FeeType::from_str(raw), Ok(fee_type));
+            assert_eq!(fee_type.as_str(), raw);
+        }
+        assert_eq!(
+            FeeType::ALL.iter().map(FeeType::as_str).collect::<Vec<_>>(),
+            FEE_TYPES
+        );
+    }
+
+    #[test]
+    fn test_fee_type_rejects_unknown_string_listing_supported() {
+        for raw in ["withdrawal", "Onramp", "bill-payment", ""] {
+            let err = FeeType::from_str(raw).unwrap_err();
+            assert_eq!(err.fee_type, raw);
+            assert_eq!(err.supported, FEE_TYPES);
+        }
+        assert_eq!(
+            FeeType::from_str("withdrawal").unwrap_err().to_string(),
+            "unknown fee_type 'withdrawal', must be one of: onramp, offramp, bill_payment, \
+             exchange, transfer, provider_fee, platform_fee, onramp_platform, onramp_provider"
+        );
+    }
+
+    #[test]
+    fn test_validate_batch_checks_currency_against_allowlist() {
+        let mut typo = structure("onramp", 1, None);
+        typo.currency = Some("NGM".to_string());
+        let mut lowercase = structure("offramp", 1, None);
+        lowercase.currency = Some("ngn".to_string());
+
+        let issues =
+            validate_batch(&[lowercase, typo], &FeeCurrencies::default()).unwrap_err();
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!((issues[0].index, issues[0].field.as_str()), (1, "currency"));
+        assert!(issues[0].message.contains("NGN"));
+    }
+
+    #[test]
+    fn test_currency_case_is_normalized_before_overlap_check() {
+        let upper = structure("onramp", 1, None);
+        let mut lower = structure("onramp", 5, None);
+        lower.currency = Some(" ngn".to_string());
+        let mut batch = vec![upper, lower];
+
+        normalize_currencies(&mut batch);
+
+        assert_eq!(batch[1].currency.as_deref(), Some("NGN"));
+        let issues = validate_batch(&batch, &FeeCurrencies::default()).unwrap_err();
+        assert_eq!((issues[0].index, issues[0].field.as_str()), (1, "effective_from"));
+    }
+
+    #[test]
+    fn test_fee_currencies_check_lists_supported_set() {
+        let currencies = FeeCurrencies::new(["ngn", " KES ", ""]);
+
+        assert!(currencies.check("NGN").is_ok());
+        let err = currencies.check("NGM").unwrap_err();
+        assert_eq!(err.currency, "NGM");
+        assert_eq!(err.supported, vec!["KES".to_string(), "NGN".to_string()]);
+    }
+
+    #[test]
+    fn test_calculation_input_currency_is_checked() {
+        let input = |currency: Option<&str>| FeeCalculationInput {
+            fee_type: FeeType::Onramp,
+            amount: BigDecimal::from(1000),
+            currency: currency.map(str::to_string),
+            at_time: None,
+            fee_mode: FeeMode::Exclusive,
+        };
+        let currencies = FeeCurrencies::default();
+
+        assert!(input(Some("NGN")).check_currency(&currencies).is_ok());
+        assert!(input(None).check_currency(&currencies).is_ok());
+        let err = input(Some("NGM")).check_currency(&currencies).unwrap_err();
+        assert!(err.supported.contains(&"NGN".to_string()));
+    }
+
+    #[test]
+    fn test_malformed_input_amount_is_a_client_error() {
+        assert_eq!(
+            parse_input_amount(" 1500.50 ").unwrap(),
+            BigDecimal::from_str("1500.50").unwrap()
+        );
+
+        let err = parse_input_amount("1,500.50").unwrap_err();
+        assert_eq!(err.amount, "1,500.50");
+        let app_err = AppError::from(err);
+        assert_eq!(app_err.status_code(), 400);
+        assert_eq!(app_err.error_code(), crate::error::ErrorCode::ValidationError);
+    }
+
+    #[test]
+    fn test_corrupt_stored_structure_is_a_server_error_with_code() {
+        // Min above max can't be created through the API, only by editing rows
+        let structure = active_structure(Some("500"), Some("100"));
+
+        let corrupt = check_stored_structure(&structure).unwrap_err();
+        assert_eq!(corrupt.structure_id, Some(structure.id));
+        assert!(corrupt.to_string().contains(&structure.id.to_string()));
+
+        let app_err = AppError::from(FeeCalculationError::from(corrupt));
+        assert_eq!(app_err.status_code(), 500);
+        assert_eq!(
+            app_err.error_code(),
+            crate::error::ErrorCode::CorruptFeeStructure
+        );
+        assert!(!app_err.is_retryable());
+    }
+
+    #[test]
+    fn test_check_stored_structure_flags_out_of_range_values() {
+        assert!(check_stored_structure(&active_structure(Some("10"), Some("500"))).is_ok());
+
+        let mut negative_rate = active_structure(None, None);
+        negative_rate.fee_rate_bps = -5;
+        assert!(check_stored_structure(&negative_rate).is_err());
+
+        let mut negative_flat = active_structure(None, None);
+        negative_flat.fee_flat = BigDecimal::from(-1);
+        assert!(check_stored_structure(&negative_flat).is_err());
+    }
 }
@@ -1,5 +1,9 @@
 //! Services module for business logic and integrations
 
+#[cfg(feature = "cache")]
+pub mod account_cache;
+#[cfg(feature = "database")]
+pub mod afri_deposit;
 pub mod balance;
 #[cfg(feature = "database")]
 pub mod bank_verification;
@@ -10,6 +14,8 @@ pub mod cngn_trustline;
 #[cfg(feature = "database")]
 pub mod conversion_audit;
 #[cfg(feature = "database")]
+pub mod conversion_feed;
+#[cfg(feature = "database")]
 pub mod exchange_rate;
 #[cfg(feature = "database")]
 pub mod fee_calculation;
@@ -36,6 +42,10 @@ pub mod rate_providers;
 pub mod transaction;
 #[cfg(feature = "database")]
 pub mod trustline_operation;
+#[cfg(feature = "cache")]
+pub mod volume_limit;
+#[cfg(feature = "cache")]
+pub mod wallet_overview;
 pub mod webhook_processor;
 
 // Re-export blockchain traits for convenience
@@ -59,4 +59,23 @@ impl NotificationService {
             }
         }
     }
+
+    /// Tell a wallet owner that a deposit to the shared deposit address was
+    /// attributed to them. There's no transaction row for these deposits, so
+    /// the payment itself identifies the notification.
+    pub async fn send_deposit_credited(
+        &self,
+        wallet_address: &str,
+        amount: &str,
+        asset_code: &str,
+        transaction_hash: &str,
+    ) {
+        info!(
+            wallet = %wallet_address,
+            amount = %amount,
+            asset = %asset_code,
+            stellar_tx_hash = %transaction_hash,
+            "🔔 NOTIFICATION: Deposit Credited - {} {} received", amount, asset_code
+        );
+    }
 }
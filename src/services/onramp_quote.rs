@@ -10,8 +10,12 @@ use crate::chains::stellar::client::StellarClient;
 use crate::chains::stellar::trustline::CngnTrustlineManager;
 use crate::chains::stellar::types::{extract_cngn_balance, is_valid_stellar_address};
 use crate::error::{AppError, AppErrorKind, DomainError, ValidationError};
-use crate::services::exchange_rate::{ConversionDirection, ConversionRequest, ExchangeRateService};
-use crate::services::fee_structure::{FeeCalculationInput, FeeStructureService};
+use crate::services::exchange_rate::{
+    ConversionDirection, ConversionRequest, ExchangeRateError, ExchangeRateService,
+};
+use crate::services::fee_structure::{
+    FeeCalculationInput, FeeMode, FeeStructureService, FeeType,
+};
 use bigdecimal::{BigDecimal, Zero};
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
@@ -284,13 +288,14 @@ impl OnrampQuoteService {
                 direction: ConversionDirection::Buy,
             })
             .await
-            .map_err(|e| {
-                AppError::new(AppErrorKind::External(
+            .map_err(|e| match e {
+                ExchangeRateError::Fee(e) => AppError::from(e),
+                e => AppError::new(AppErrorKind::External(
                     crate::error::ExternalError::Blockchain {
                         message: e.to_string(),
                         is_retryable: true,
                     },
-                ))
+                )),
             })?;
 
         // Parse fees from conversion result
@@ -404,6 +409,9 @@ impl OnrampQuoteService {
         })
     }
 
+    /// Fee lookup failures, including corrupt fee structures, are returned
+    /// as `AppError::from(FeeCalculationError)` would report them; a quote
+    /// is never priced with a fee that couldn't be calculated.
     async fn calculate_onramp_fees(
         &self,
         amount_ngn: &BigDecimal,
@@ -411,38 +419,24 @@ impl OnrampQuoteService {
         let platform_fee = self
             .fee_service
             .calculate_fee(FeeCalculationInput {
-                fee_type: "onramp_platform".to_string(),
+                fee_type: FeeType::OnrampPlatform,
                 amount: amount_ngn.clone(),
                 currency: Some("NGN".to_string()),
                 at_time: None,
+                fee_mode: FeeMode::Inclusive,
             })
-            .await
-            .map_err(|e| {
-                AppError::new(AppErrorKind::Infrastructure(
-                    crate::error::InfrastructureError::Database {
-                        message: e.to_string(),
-                        is_retryable: true,
-                    },
-                ))
-            })?;
+            .await?;
 
         let provider_fee = self
             .fee_service
             .calculate_fee(FeeCalculationInput {
-                fee_type: "onramp_provider".to_string(),
+                fee_type: FeeType::OnrampProvider,
                 amount: amount_ngn.clone(),
                 currency: Some("NGN".to_string()),
                 at_time: None,
+                fee_mode: FeeMode::Inclusive,
             })
-            .await
-            .map_err(|e| {
-                AppError::new(AppErrorKind::Infrastructure(
-                    crate::error::InfrastructureError::Database {
-                        message: e.to_string(),
-                        is_retryable: true,
-                    },
-                ))
-            })?;
+            .await?;
 
         let platform_fee_bd = platform_fee
             .map(|r| r.fee)
@@ -455,20 +449,13 @@ impl OnrampQuoteService {
             let total = self
                 .fee_service
                 .calculate_fee(FeeCalculationInput {
-                    fee_type: "onramp".to_string(),
+                    fee_type: FeeType::Onramp,
                     amount: amount_ngn.clone(),
                     currency: Some("NGN".to_string()),
                     at_time: None,
+                    fee_mode: FeeMode::Inclusive,
                 })
-                .await
-                .map_err(|e| {
-                    AppError::new(AppErrorKind::Infrastructure(
-                        crate::error::InfrastructureError::Database {
-                            message: e.to_string(),
-                            is_retryable: true,
-                        },
-                    ))
-                })?;
+                .await?;
 
             let total_fee = total.map(|r| r.fee).unwrap_or_else(|| BigDecimal::from(0));
             return Ok(split_fallback_onramp_fee(&total_fee));
@@ -30,6 +30,25 @@ impl TrustlineOperationService {
         Self { repo }
     }
 
+    /// Record an operation of whatever type `input.operation_type` names
+    pub async fn record(
+        &self,
+        input: TrustlineOperationInput,
+    ) -> Result<TrustlineOperation, DatabaseError> {
+        self.repo
+            .create_operation(
+                &input.wallet_address,
+                &input.asset_code,
+                input.issuer.as_deref(),
+                &input.operation_type,
+                &input.status,
+                input.transaction_hash.as_deref(),
+                input.error_message.as_deref(),
+                input.metadata,
+            )
+            .await
+    }
+
     /// Record a trustline create operation
     pub async fn record_create(
         &self,
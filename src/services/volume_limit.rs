@@ -0,0 +1,550 @@
+//! Platform-wide daily cNGN payment volume ceiling
+//!
+//! Per-wallet limits don't bound total exposure, so every submitted payment is
+//! also added to a Redis counter for the current UTC hour, and the limit
+//! applies to the sum of the last 24 hourly counters. Crossing the warning
+//! ratio logs and bumps a metric; crossing the ceiling rejects submissions
+//! until enough of the oldest hours have rolled out of the window.
+
+use crate::cache::cache::Cache;
+use crate::cache::keys::transaction::VolumeBucketKey;
+use crate::cache::RedisCache;
+use crate::chains::stellar::asset::StellarAsset;
+use crate::chains::stellar::payment::{envelope_payment_stroops, EnvelopeSummary};
+use crate::chains::stellar::submission::SubmissionLimit;
+use crate::error::{AppError, AppErrorKind, DomainError, InfrastructureError};
+use async_trait::async_trait;
+use bigdecimal::{BigDecimal, ToPrimitive};
+use chrono::{DateTime, Duration as ChronoDuration, DurationRound, Utc};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use thiserror::Error;
+use tracing::warn;
+
+const STROOPS_PER_UNIT: i64 = 10_000_000;
+const DEFAULT_WARNING_RATIO: f64 = 0.9;
+/// Hourly buckets summed into the rolling daily total
+const WINDOW_HOURS: i64 = 24;
+// A bucket is read for 24 hours after it opens; keep it an hour past that
+const BUCKET_TTL: Duration = Duration::from_secs(25 * 60 * 60);
+
+#[derive(Debug, Clone)]
+pub struct DailyVolumeLimitConfig {
+    pub asset_code: String,
+    pub ceiling_stroops: i64,
+    pub warning_ratio: f64,
+}
+
+impl DailyVolumeLimitConfig {
+    /// Returns `None` when `DAILY_VOLUME_CEILING` is unset, which disables the limit.
+    pub fn from_env() -> Option<Self> {
+        let ceiling = std::env::var("DAILY_VOLUME_CEILING").ok()?;
+        let ceiling_stroops = match parse_units_to_stroops(&ceiling) {
+            Some(value) if value > 0 => value,
+            _ => {
+                warn!(value = %ceiling, "Ignoring invalid DAILY_VOLUME_CEILING");
+                return None;
+            }
+        };
+
+        let warning_ratio = std::env::var("DAILY_VOLUME_WARNING_RATIO")
+            .ok()
+            .and_then(|v| v.parse::<f64>().ok())
+            .filter(|ratio| *ratio > 0.0 && *ratio <= 1.0)
+            .unwrap_or(DEFAULT_WARNING_RATIO);
+
+        Some(Self {
+            asset_code: std::env::var("CNGN_ASSET_CODE").unwrap_or_else(|_| "cNGN".to_string()),
+            ceiling_stroops,
+            warning_ratio,
+        })
+    }
+
+    fn warning_stroops(&self) -> i64 {
+        (self.ceiling_stroops as f64 * self.warning_ratio) as i64
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum VolumeLimitError {
+    #[error("daily {asset} volume limit of {ceiling} reached")]
+    Exceeded {
+        asset: String,
+        ceiling: String,
+        retry_after_secs: u64,
+    },
+    #[error("volume counter unavailable: {0}")]
+    Store(String),
+}
+
+impl From<VolumeLimitError> for AppError {
+    fn from(err: VolumeLimitError) -> Self {
+        match err {
+            VolumeLimitError::Exceeded {
+                asset,
+                ceiling,
+                retry_after_secs,
+            } => AppError::new(AppErrorKind::Domain(DomainError::VolumeLimitExceeded {
+                asset,
+                ceiling,
+                retry_after_secs,
+            })),
+            VolumeLimitError::Store(message) => {
+                AppError::new(AppErrorKind::Infrastructure(InfrastructureError::Cache {
+                    message,
+                }))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VolumeStatus {
+    Normal,
+    Warning,
+}
+
+#[derive(Debug, Clone)]
+pub struct VolumeCheck {
+    /// Start of the hourly bucket the amount was added to
+    pub bucket: DateTime<Utc>,
+    pub total_stroops: i64,
+    pub ceiling_stroops: i64,
+    pub status: VolumeStatus,
+}
+
+/// Atomic counters backing the hourly buckets
+#[async_trait]
+pub trait VolumeCounterStore: Send + Sync {
+    /// Add `amount` (may be negative) to `key` and return the new total.
+    async fn add(&self, key: &str, amount: i64, ttl: Duration) -> Result<i64, VolumeLimitError>;
+
+    /// Current values of `keys`, in order; 0 for keys that don't exist.
+    async fn get_many(&self, keys: &[String]) -> Result<Vec<i64>, VolumeLimitError>;
+}
+
+#[async_trait]
+impl VolumeCounterStore for RedisCache {
+    async fn add(&self, key: &str, amount: i64, ttl: Duration) -> Result<i64, VolumeLimitError> {
+        let total = <RedisCache as Cache<String>>::increment(self, key, amount)
+            .await
+            .map_err(|e| VolumeLimitError::Store(e.to_string()))?;
+        let _ = <RedisCache as Cache<String>>::expire(self, key, ttl).await;
+        Ok(total)
+    }
+
+    async fn get_many(&self, keys: &[String]) -> Result<Vec<i64>, VolumeLimitError> {
+        if keys.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut conn = self
+            .get_connection()
+            .await
+            .map_err(|e| VolumeLimitError::Store(e.to_string()))?;
+        let values: Vec<Option<i64>> = redis::cmd("MGET")
+            .arg(keys)
+            .query_async(&mut *conn)
+            .await
+            .map_err(|e| VolumeLimitError::Store(e.to_string()))?;
+        Ok(values.into_iter().map(|v| v.unwrap_or(0)).collect())
+    }
+}
+
+#[derive(Clone)]
+pub struct DailyVolumeLimiter {
+    store: Arc<dyn VolumeCounterStore>,
+    config: DailyVolumeLimitConfig,
+}
+
+impl DailyVolumeLimiter {
+    pub fn new(store: Arc<dyn VolumeCounterStore>, config: DailyVolumeLimitConfig) -> Self {
+        Self { store, config }
+    }
+
+    pub fn config(&self) -> &DailyVolumeLimitConfig {
+        &self.config
+    }
+
+    /// Record a submission against the last 24 hours' total, rejecting it if
+    /// the ceiling would be exceeded.
+    pub async fn record(&self, amount_stroops: i64) -> Result<VolumeCheck, VolumeLimitError> {
+        self.record_at(amount_stroops, Utc::now()).await
+    }
+
+    pub async fn record_at(
+        &self,
+        amount_stroops: i64,
+        now: DateTime<Utc>,
+    ) -> Result<VolumeCheck, VolumeLimitError> {
+        let bucket = bucket_start(now);
+        let key = self.bucket_key(bucket);
+        let current = self.store.add(&key, amount_stroops, BUCKET_TTL).await?;
+        let mut window = self.volumes(&earlier_bucket_starts(bucket)).await?;
+        let total = current + window.iter().map(|(_, volume)| volume).sum::<i64>();
+
+        if total > self.config.ceiling_stroops {
+            // Give the reservation back so rejected submissions don't count
+            self.store.add(&key, -amount_stroops, BUCKET_TTL).await?;
+            window.push((bucket, current - amount_stroops));
+            crate::metrics::cngn::daily_volume_limit_events_total()
+                .with_label_values(&["exceeded"])
+                .inc();
+            warn!(
+                asset = %self.config.asset_code,
+                attempted_total = total,
+                ceiling = self.config.ceiling_stroops,
+                "Daily payment volume limit exceeded, rejecting submission"
+            );
+            return Err(self.exceeded(now, &window, total));
+        }
+
+        let status = if total >= self.config.warning_stroops() {
+            crate::metrics::cngn::daily_volume_limit_events_total()
+                .with_label_values(&["warning"])
+                .inc();
+            warn!(
+                asset = %self.config.asset_code,
+                total,
+                ceiling = self.config.ceiling_stroops,
+                warning_ratio = self.config.warning_ratio,
+                "Daily payment volume approaching limit"
+            );
+            VolumeStatus::Warning
+        } else {
+            VolumeStatus::Normal
+        };
+
+        Ok(VolumeCheck {
+            bucket,
+            total_stroops: total,
+            ceiling_stroops: self.config.ceiling_stroops,
+            status,
+        })
+    }
+
+    /// Whether `amount_stroops` would fit under the ceiling right now, without
+    /// recording it or touching the metrics; for dry runs.
+    pub async fn check(&self, amount_stroops: i64) -> Result<VolumeCheck, VolumeLimitError> {
+        self.check_at(amount_stroops, Utc::now()).await
+    }
+
+    pub async fn check_at(
+        &self,
+        amount_stroops: i64,
+        now: DateTime<Utc>,
+    ) -> Result<VolumeCheck, VolumeLimitError> {
+        let bucket = bucket_start(now);
+        let mut starts = earlier_bucket_starts(bucket);
+        starts.push(bucket);
+        let window = self.volumes(&starts).await?;
+        let total = amount_stroops + window.iter().map(|(_, volume)| volume).sum::<i64>();
+
+        if total > self.config.ceiling_stroops {
+            return Err(self.exceeded(now, &window, total));
+        }
+
+        Ok(VolumeCheck {
+            bucket,
+            total_stroops: total,
+            ceiling_stroops: self.config.ceiling_stroops,
+            status: if total >= self.config.warning_stroops() {
+                VolumeStatus::Warning
+            } else {
+                VolumeStatus::Normal
+            },
+        })
+    }
+
+    /// Return a recorded amount, e.g. when the submission it covered failed.
+    pub async fn release(&self, check: &VolumeCheck, amount_stroops: i64) {
+        let key = self.bucket_key(check.bucket);
+        if let Err(e) = self.store.add(&key, -amount_stroops, BUCKET_TTL).await {
+            warn!(error = %e, "Failed to release daily volume reservation");
+        }
+    }
+
+    fn exceeded(
+        &self,
+        now: DateTime<Utc>,
+        window: &[(DateTime<Utc>, i64)],
+        attempted_total: i64,
+    ) -> VolumeLimitError {
+        VolumeLimitError::Exceeded {
+            asset: self.config.asset_code.clone(),
+            ceiling: stroops_to_units(self.config.ceiling_stroops),
+            retry_after_secs: seconds_until_headroom(
+                now,
+                window,
+                attempted_total - self.config.ceiling_stroops,
+            ),
+        }
+    }
+
+    fn bucket_key(&self, bucket: DateTime<Utc>) -> String {
+        VolumeBucketKey::new(&self.config.asset_code, bucket).to_string()
+    }
+
+    /// Volume in each bucket starting at `starts`, paired with its start
+    async fn volumes(
+        &self,
+        starts: &[DateTime<Utc>],
+    ) -> Result<Vec<(DateTime<Utc>, i64)>, VolumeLimitError> {
+        let keys: Vec<String> = starts.iter().map(|start| self.bucket_key(*start)).collect();
+        let values = self.store.get_many(&keys).await?;
+        Ok(starts.iter().copied().zip(values).collect())
+    }
+}
+
+/// Counts each batch item's payment volume against the daily ceiling before
+/// it is submitted, and returns it if the submission fails
+pub struct VolumeSubmissionLimit {
+    limiter: DailyVolumeLimiter,
+    /// Only payments in this asset count
+    asset: StellarAsset,
+    /// Reservations by inner transaction hash, until the item settles
+    reserved: Mutex<HashMap<String, (VolumeCheck, i64)>>,
+}
+
+impl VolumeSubmissionLimit {
+    pub fn new(limiter: DailyVolumeLimiter, asset: StellarAsset) -> Self {
+        Self {
+            limiter,
+            asset,
+            reserved: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+#[async_trait]
+impl SubmissionLimit for VolumeSubmissionLimit {
+    async fn reserve(&self, envelope_xdr: &str, summary: &EnvelopeSummary) -> Result<(), String> {
+        let amount = envelope_payment_stroops(envelope_xdr, &self.asset)
+            .map_err(|e| e.to_string())?;
+        let check = self
+            .limiter
+            .record(amount)
+            .await
+            .map_err(|e| e.to_string())?;
+        self.reserved
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(summary.transaction_hash.clone(), (check, amount));
+        Ok(())
+    }
+
+    async fn release(&self, _envelope_xdr: &str, summary: &EnvelopeSummary) {
+        let reservation = self
+            .reserved
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&summary.transaction_hash);
+        if let Some((check, amount)) = reservation {
+            self.limiter.release(&check, amount).await;
+        }
+    }
+}
+
+fn parse_units_to_stroops(value: &str) -> Option<i64> {
+    let units = BigDecimal::from_str(value.trim()).ok()?;
+    (units * BigDecimal::from(STROOPS_PER_UNIT))
+        .with_scale(0)
+        .to_i64()
+}
+
+fn stroops_to_units(stroops: i64) -> String {
+    format!(
+        "{}.{:07}",
+        stroops / STROOPS_PER_UNIT,
+        (stroops % STROOPS_PER_UNIT).abs()
+    )
+}
+
+fn bucket_start(now: DateTime<Utc>) -> DateTime<Utc> {
+    now.duration_trunc(ChronoDuration::hours(1))
+        .expect("an hour divides any UTC timestamp")
+}
+
+/// Starts of the window's buckets before `bucket`, oldest first
+fn earlier_bucket_starts(bucket: DateTime<Utc>) -> Vec<DateTime<Utc>> {
+    (1..WINDOW_HOURS)
+        .rev()
+        .map(|hours| bucket - ChronoDuration::hours(hours))
+        .collect()
+}
+
+/// Seconds until enough of `window` (oldest first) has rolled out to free
+/// `excess` stroops. When even the whole window isn't enough, that's when the
+/// newest bucket leaves it.
+fn seconds_until_headroom(
+    now: DateTime<Utc>,
+    window: &[(DateTime<Utc>, i64)],
+    excess: i64,
+) -> u64 {
+    let mut freed = 0;
+    let mut free_at = now;
+    for (start, volume) in window {
+        freed += volume;
+        free_at = *start + ChronoDuration::hours(WINDOW_HOURS);
+        if freed >= excess {
+            break;
+        }
+    }
+    (free_at - now).num_seconds().max(1) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use std::collections::HashMap;
+    use tokio::sync::Mutex;
+
+    #[derive(Default)]
+    struct InMemoryCounter {
+        values: Mutex<HashMap<String, i64>>,
+    }
+
+    #[async_trait]
+    impl VolumeCounterStore for InMemoryCounter {
+        async fn add(
+            &self,
+            key: &str,
+            amount: i64,
+            _ttl: Duration,
+        ) -> Result<i64, VolumeLimitError> {
+            let mut values = self.values.lock().await;
+            let total = values.entry(key.to_string()).or_insert(0);
+            *total += amount;
+            Ok(*total)
+        }
+
+        async fn get_many(&self, keys: &[String]) -> Result<Vec<i64>, VolumeLimitError> {
+            let values = self.values.lock().await;
+            Ok(keys
+                .iter()
+                .map(|key| values.get(key).copied().unwrap_or(0))
+                .collect())
+        }
+    }
+
+    fn limiter() -> DailyVolumeLimiter {
+        let _ = crate::metrics::registry();
+        DailyVolumeLimiter::new(
+            Arc::new(InMemoryCounter::default()),
+            DailyVolumeLimitConfig {
+                asset_code: "cNGN".to_string(),
+                ceiling_stroops: 1_000 * STROOPS_PER_UNIT,
+                warning_ratio: 0.9,
+            },
+        )
+    }
+
+    fn units(n: i64) -> i64 {
+        n * STROOPS_PER_UNIT
+    }
+
+    #[tokio::test]
+    async fn warns_after_crossing_threshold_and_rejects_past_ceiling() {
+        let limiter = limiter();
+        let now = Utc.with_ymd_and_hms(2024, 5, 1, 22, 0, 0).unwrap();
+
+        let check = limiter.record_at(units(800), now).await.unwrap();
+        assert_eq!(check.status, VolumeStatus::Normal);
+
+        let check = limiter.record_at(units(150), now).await.unwrap();
+        assert_eq!(check.status, VolumeStatus::Warning);
+        assert_eq!(check.total_stroops, units(950));
+
+        let err = limiter.record_at(units(100), now).await.unwrap_err();
+        match err {
+            VolumeLimitError::Exceeded {
+                ceiling,
+                retry_after_secs,
+                ..
+            } => {
+                assert_eq!(ceiling, "1000.0000000");
+                // Everything sits in the 22:00 bucket, which leaves the window a day later
+                assert_eq!(retry_after_secs, 24 * 60 * 60);
+            }
+            other => panic!("expected Exceeded, got {other:?}"),
+        }
+
+        // The rejected amount was not counted, so a smaller payment still fits
+        let check = limiter.record_at(units(50), now).await.unwrap();
+        assert_eq!(check.total_stroops, units(1_000));
+
+        limiter.release(&check, units(50)).await;
+        let check = limiter.record_at(units(10), now).await.unwrap();
+        assert_eq!(check.total_stroops, units(960));
+    }
+
+    #[tokio::test]
+    async fn window_rolls_hour_by_hour_not_at_midnight() {
+        let limiter = limiter();
+        let morning = Utc.with_ymd_and_hms(2024, 5, 1, 10, 30, 0).unwrap();
+
+        limiter.record_at(units(600), morning).await.unwrap();
+        limiter
+            .record_at(units(400), morning + ChronoDuration::hours(12))
+            .await
+            .unwrap();
+
+        // Past UTC midnight the morning's 600 still counts
+        let after_midnight = Utc.with_ymd_and_hms(2024, 5, 2, 0, 30, 0).unwrap();
+        match limiter.record_at(units(1), after_midnight).await.unwrap_err() {
+            VolumeLimitError::Exceeded {
+                retry_after_secs, ..
+            } => {
+                // The 10:00 bucket rolls out at 10:00 the next day
+                assert_eq!(retry_after_secs, 9 * 60 * 60 + 30 * 60);
+            }
+            other => panic!("expected Exceeded, got {other:?}"),
+        }
+
+        let next_morning = Utc.with_ymd_and_hms(2024, 5, 2, 10, 0, 0).unwrap();
+        let check = limiter.record_at(units(1), next_morning).await.unwrap();
+        assert_eq!(check.total_stroops, units(401));
+        assert_eq!(check.status, VolumeStatus::Normal);
+    }
+
+    #[tokio::test]
+    async fn check_reports_headroom_without_recording() {
+        let limiter = limiter();
+        let now = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap();
+        limiter.record_at(units(900), now).await.unwrap();
+
+        let check = limiter.check_at(units(50), now).await.unwrap();
+        assert_eq!(check.total_stroops, units(950));
+        assert_eq!(check.status, VolumeStatus::Warning);
+        assert!(matches!(
+            limiter.check_at(units(200), now).await,
+            Err(VolumeLimitError::Exceeded { .. })
+        ));
+
+        // Neither check counted, so all 100 remaining units are still free
+        let check = limiter.record_at(units(100), now).await.unwrap();
+        assert_eq!(check.total_stroops, units(1_000));
+    }
+
+    #[test]
+    fn exceeded_maps_to_429_volume_limit_error() {
+        let err: AppError = VolumeLimitError::Exceeded {
+            asset: "cNGN".to_string(),
+            ceiling: "1000.0000000".to_string(),
+            retry_after_secs: 60,
+        }
+        .into();
+        assert_eq!(err.status_code(), 429);
+        assert_eq!(err.error_code(), crate::error::ErrorCode::VolumeLimitExceeded);
+        assert!(err.is_retryable());
+        assert_eq!(err.retry_after(), Some(60));
+    }
+
+    #[test]
+    fn ceiling_parses_decimal_units() {
+        assert_eq!(parse_units_to_stroops("2.5"), Some(25_000_000));
+        assert_eq!(parse_units_to_stroops("abc"), None);
+    }
+}
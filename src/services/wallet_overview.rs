@@ -0,0 +1,134 @@
+//! One-call wallet summary for dashboards: AFRI and XLM balances plus recent
+//! payment activity, cached briefly so a dashboard refresh doesn't fan out to
+//! several Horizon requests.
+
+use crate::cache::{cache::Cache, keys::wallet::OverviewKey, RedisCache};
+use crate::chains::stellar::{
+    client::StellarClient, errors::StellarError, types::extract_afri_balance,
+};
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tracing::{debug, warn};
+
+const OVERVIEW_CACHE_TTL: Duration = Duration::from_secs(15);
+/// Payments newer than this count towards `recent_payment_count`
+pub const ACTIVITY_WINDOW_DAYS: i64 = 30;
+const PAYMENTS_PAGE_SIZE: usize = 200;
+/// Upper bound on Horizon pages walked per overview; very busy wallets get a
+/// lower-bound count with `activity_truncated` set
+const MAX_PAYMENT_PAGES: usize = 5;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WalletOverview {
+    pub wallet_address: String,
+    pub xlm_balance: String,
+    /// `None` when the wallet has no AFRI trustline
+    pub afri_balance: Option<String>,
+    pub afri_trustline: bool,
+    pub activity_window_days: i64,
+    pub recent_payment_count: u32,
+    /// Set when the window held more payments than were scanned
+    pub activity_truncated: bool,
+    /// Timestamp of the most recent payment, if any
+    pub last_activity_at: Option<String>,
+    pub cached: bool,
+}
+
+pub struct WalletOverviewService {
+    stellar_client: StellarClient,
+    cache: Option<RedisCache>,
+}
+
+impl WalletOverviewService {
+    pub fn new(stellar_client: StellarClient, cache: Option<RedisCache>) -> Self {
+        Self {
+            stellar_client,
+            cache,
+        }
+    }
+
+    /// A nonexistent account surfaces as `StellarError::AccountNotFound`.
+    pub async fn get_overview(&self, address: &str) -> Result<WalletOverview, StellarError> {
+        let cache_key = OverviewKey::new(address).to_string();
+        if let Some(cache) = &self.cache {
+            if let Ok(Some(cached)) = cache.get(&cache_key).await {
+                debug!("Wallet overview cache hit for {}", address);
+                return Ok(WalletOverview {
+                    cached: true,
+                    ..cached
+                });
+            }
+        }
+
+        let account = self.stellar_client.get_account(address).await?;
+        let xlm_balance = account
+            .balances
+            .iter()
+            .find(|b| b.asset_type == "native")
+            .map(|b| b.balance.clone())
+            .unwrap_or_else(|| "0.0000000".to_string());
+        let afri = extract_afri_balance(&account.balances);
+
+        let cutoff = Utc::now() - ChronoDuration::days(ACTIVITY_WINDOW_DAYS);
+        let mut recent_payment_count = 0u32;
+        let mut last_activity_at = None;
+        let mut activity_truncated = false;
+        let mut cursor: Option<String> = None;
+
+        'pages: for page_number in 0..MAX_PAYMENT_PAGES {
+            let page = self
+                .stellar_client
+                .list_account_payments(address, PAYMENTS_PAGE_SIZE, cursor.as_deref())
+                .await?;
+
+            for record in &page.records {
+                // Newest first: the first record is the latest activity
+                if last_activity_at.is_none() {
+                    last_activity_at = record.created_at.clone();
+                }
+                let created_at = record
+                    .created_at
+                    .as_deref()
+                    .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+                    .map(|t| t.with_timezone(&Utc));
+                match created_at {
+                    Some(t) if t < cutoff => break 'pages,
+                    _ => recent_payment_count += 1,
+                }
+            }
+
+            if page.records.len() < PAYMENTS_PAGE_SIZE {
+                break;
+            }
+            cursor = page.records.last().and_then(|r| r.paging_token.clone());
+            if cursor.is_none() {
+                break;
+            }
+            activity_truncated = page_number + 1 == MAX_PAYMENT_PAGES;
+        }
+
+        let overview = WalletOverview {
+            wallet_address: address.to_string(),
+            xlm_balance,
+            afri_balance: afri.balance,
+            afri_trustline: afri.has_trustline,
+            activity_window_days: ACTIVITY_WINDOW_DAYS,
+            recent_payment_count,
+            activity_truncated,
+            last_activity_at,
+            cached: false,
+        };
+
+        if let Some(cache) = &self.cache {
+            if let Err(e) = cache
+                .set(&cache_key, &overview, Some(OVERVIEW_CACHE_TTL))
+                .await
+            {
+                warn!("Failed to cache wallet overview for {}: {}", address, e);
+            }
+        }
+
+        Ok(overview)
+    }
+}
@@ -17,6 +17,10 @@ pub enum WebhookProcessorError {
     AlreadyProcessed,
     #[error("Unknown provider: {0}")]
     UnknownProvider(String),
+    #[error("Unsupported content type: {0}")]
+    UnsupportedContentType(String),
+    #[error("Invalid payload: {0}")]
+    InvalidPayload(String),
     #[error("Database error: {0}")]
     DatabaseError(String),
     #[error("Processing error: {0}")]
@@ -42,13 +46,19 @@ impl WebhookProcessor {
         }
     }
 
+    /// Verify and process a webhook.
+    ///
+    /// `raw_body` must be the exact bytes the provider sent: signatures are
+    /// computed over them, so the body is only decoded once verification passes.
     pub async fn process_webhook(
         &self,
         provider_name: &str,
         signature: Option<&str>,
-        payload: &JsonValue,
+        content_type: Option<&str>,
+        raw_body: &[u8],
     ) -> Result<(), WebhookProcessorError> {
         let provider = self.parse_provider(provider_name)?;
+        let media_type = webhook_media_type(content_type)?;
         let signature = signature.ok_or(WebhookProcessorError::InvalidSignature)?;
 
         let provider_impl = self
@@ -56,11 +66,9 @@ impl WebhookProcessor {
             .get_provider(provider.clone())
             .map_err(|e| WebhookProcessorError::ProcessingError(e.to_string()))?;
 
-        // Verify signature
-        let payload_bytes = serde_json::to_vec(payload)
-            .map_err(|e| WebhookProcessorError::ProcessingError(e.to_string()))?;
+        // Verify signature against the untouched body
         let verification = provider_impl
-            .verify_webhook(&payload_bytes, signature)
+            .verify_webhook(raw_body, signature)
             .map_err(|e| WebhookProcessorError::ProcessingError(e.to_string()))?;
 
         if !verification.valid {
@@ -68,10 +76,18 @@ impl WebhookProcessor {
             return Err(WebhookProcessorError::InvalidSignature);
         }
 
+        let payload = decode_webhook_body(media_type, raw_body)?;
+
         // Parse webhook event
-        let event = provider_impl
-            .parse_webhook_event(&payload_bytes)
-            .map_err(|e| WebhookProcessorError::ProcessingError(e.to_string()))?;
+        let event = match media_type {
+            WebhookMediaType::Json => provider_impl.parse_webhook_event(raw_body),
+            WebhookMediaType::Form => {
+                let normalized = serde_json::to_vec(&payload)
+                    .map_err(|e| WebhookProcessorError::ProcessingError(e.to_string()))?;
+                provider_impl.parse_webhook_event(&normalized)
+            }
+        }
+        .map_err(|e| WebhookProcessorError::ProcessingError(e.to_string()))?;
 
         // Extract event ID for idempotency
         let event_id = self.extract_event_id(&event.payload, provider_name);
@@ -253,3 +269,108 @@ impl WebhookProcessor {
         Ok(processed)
     }
 }
+
+/// Body encodings accepted on the webhook endpoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebhookMediaType {
+    Json,
+    Form,
+}
+
+/// Classify a `Content-Type` header, ignoring parameters such as `charset`.
+///
+/// A missing header is treated as JSON since that is what every provider
+/// defaults to.
+pub fn webhook_media_type(
+    content_type: Option<&str>,
+) -> Result<WebhookMediaType, WebhookProcessorError> {
+    let Some(raw) = content_type else {
+        return Ok(WebhookMediaType::Json);
+    };
+    let essence = raw
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+
+    match essence.as_str() {
+        "" | "application/json" | "text/json" | "text/plain" => Ok(WebhookMediaType::Json),
+        "application/x-www-form-urlencoded" => Ok(WebhookMediaType::Form),
+        other if other.ends_with("+json") => Ok(WebhookMediaType::Json),
+        _ => Err(WebhookProcessorError::UnsupportedContentType(
+            raw.to_string(),
+        )),
+    }
+}
+
+/// Decode a verified webhook body into JSON.
+///
+/// Form-encoded bodies either wrap the JSON event in a single `payload` field
+/// or carry flat key/value pairs, which become a JSON object of strings.
+pub fn decode_webhook_body(
+    media_type: WebhookMediaType,
+    body: &[u8],
+) -> Result<JsonValue, WebhookProcessorError> {
+    match media_type {
+        WebhookMediaType::Json => serde_json::from_slice(body)
+            .map_err(|e| WebhookProcessorError::InvalidPayload(e.to_string())),
+        WebhookMediaType::Form => {
+            let fields: Vec<(String, String)> = serde_urlencoded::from_bytes(body)
+                .map_err(|e| WebhookProcessorError::InvalidPayload(e.to_string()))?;
+
+            if let [(key, value)] = fields.as_slice() {
+                if key == "payload" {
+                    return serde_json::from_str(value)
+                        .map_err(|e| WebhookProcessorError::InvalidPayload(e.to_string()));
+                }
+            }
+
+            Ok(JsonValue::Object(
+                fields
+                    .into_iter()
+                    .map(|(k, v)| (k, JsonValue::String(v)))
+                    .collect(),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn media_type_ignores_parameters_and_case() {
+        assert_eq!(
+            webhook_media_type(Some("Application/JSON; charset=utf-8")).unwrap(),
+            WebhookMediaType::Json
+        );
+        assert_eq!(
+            webhook_media_type(Some("application/x-www-form-urlencoded")).unwrap(),
+            WebhookMediaType::Form
+        );
+        assert_eq!(webhook_media_type(None).unwrap(), WebhookMediaType::Json);
+        assert!(matches!(
+            webhook_media_type(Some("multipart/form-data")),
+            Err(WebhookProcessorError::UnsupportedContentType(_))
+        ));
+    }
+
+    #[test]
+    fn form_body_with_payload_field_is_decoded_as_json() {
+        let body = b"payload=%7B%22event%22%3A%22charge.success%22%2C%22id%22%3A7%7D";
+        let decoded = decode_webhook_body(WebhookMediaType::Form, body).unwrap();
+        assert_eq!(decoded["event"], "charge.success");
+        assert_eq!(decoded["id"], 7);
+    }
+
+    #[test]
+    fn flat_form_body_becomes_string_object() {
+        let decoded =
+            decode_webhook_body(WebhookMediaType::Form, b"event=charge.failed&reference=tx_9")
+                .unwrap();
+        assert_eq!(decoded["event"], "charge.failed");
+        assert_eq!(decoded["reference"], "tx_9");
+    }
+}
@@ -0,0 +1,172 @@
+//! Bounded graceful shutdown
+//!
+//! axum's graceful shutdown waits for every open connection to finish, so one
+//! hung handler can keep the process alive forever. `serve_with_shutdown_timeout`
+//! gives in-flight requests `SHUTDOWN_TIMEOUT_SECS` to drain after the signal
+//! fires, then stops the server anyway and logs the requests it cut off.
+
+use axum::{extract::Request, middleware::Next, response::Response, Router};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::net::TcpListener;
+use tokio::sync::oneshot;
+use tracing::{info, warn};
+
+pub const DEFAULT_SHUTDOWN_TIMEOUT_SECS: u64 = 30;
+
+/// Reads `SHUTDOWN_TIMEOUT_SECS`, defaulting to 30 seconds.
+pub fn shutdown_timeout_from_env() -> Duration {
+    let secs = std::env::var("SHUTDOWN_TIMEOUT_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(DEFAULT_SHUTDOWN_TIMEOUT_SECS);
+    Duration::from_secs(secs)
+}
+
+pub const DEFAULT_TELEMETRY_FLUSH_TIMEOUT_SECS: u64 = 5;
+
+/// Reads `TELEMETRY_FLUSH_TIMEOUT_SECS`, defaulting to 5 seconds.
+pub fn telemetry_flush_timeout_from_env() -> Duration {
+    let secs = std::env::var("TELEMETRY_FLUSH_TIMEOUT_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(DEFAULT_TELEMETRY_FLUSH_TIMEOUT_SECS);
+    Duration::from_secs(secs)
+}
+
+/// A request that was still running when the shutdown timeout expired
+#[derive(Debug, Clone)]
+pub struct AbortedRequest {
+    pub method: String,
+    pub path: String,
+    pub elapsed: Duration,
+}
+
+#[derive(Debug)]
+pub enum ShutdownOutcome {
+    /// Every in-flight request finished within the timeout
+    Graceful,
+    /// The timeout expired and these requests were dropped
+    TimedOut { aborted: Vec<AbortedRequest> },
+}
+
+/// Requests currently being handled, keyed by an internal sequence number
+#[derive(Clone, Default)]
+struct InFlightRequests {
+    next_id: Arc<AtomicU64>,
+    active: Arc<Mutex<HashMap<u64, (String, String, Instant)>>>,
+}
+
+impl InFlightRequests {
+    fn snapshot(&self) -> Vec<AbortedRequest> {
+        let active = self.active.lock().unwrap_or_else(|e| e.into_inner());
+        active
+            .values()
+            .map(|(method, path, started)| AbortedRequest {
+                method: method.clone(),
+                path: path.clone(),
+                elapsed: started.elapsed(),
+            })
+            .collect()
+    }
+}
+
+/// Removes the request from the in-flight set however the handler ends,
+/// including being dropped when the server is aborted.
+struct InFlightGuard {
+    id: u64,
+    requests: InFlightRequests,
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        if let Ok(mut active) = self.requests.active.lock() {
+            active.remove(&self.id);
+        }
+    }
+}
+
+async fn track_in_flight(requests: InFlightRequests, request: Request, next: Next) -> Response {
+    let id = requests.next_id.fetch_add(1, Ordering::Relaxed);
+    if let Ok(mut active) = requests.active.lock() {
+        active.insert(
+            id,
+            (
+                request.method().to_string(),
+                request.uri().path().to_string(),
+                Instant::now(),
+            ),
+        );
+    }
+    let _guard = InFlightGuard { id, requests };
+    next.run(request).await
+}
+
+/// Serve `app` until `signal` resolves, then allow up to `timeout` for
+/// in-flight requests to drain before forcibly stopping.
+pub async fn serve_with_shutdown_timeout<F>(
+    listener: TcpListener,
+    app: Router,
+    signal: F,
+    timeout: Duration,
+) -> std::io::Result<ShutdownOutcome>
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    let requests = InFlightRequests::default();
+    let tracked = requests.clone();
+    let app = app.layer(axum::middleware::from_fn(move |request, next| {
+        track_in_flight(tracked.clone(), request, next)
+    }));
+
+    let (signalled_tx, signalled_rx) = oneshot::channel();
+    let mut server = tokio::spawn(async move {
+        axum::serve(listener, app)
+            .with_graceful_shutdown(async move {
+                signal.await;
+                let _ = signalled_tx.send(());
+            })
+            .await
+    });
+
+    tokio::select! {
+        result = &mut server => return join_server(result).map(|_| ShutdownOutcome::Graceful),
+        _ = signalled_rx => {}
+    }
+
+    info!(
+        timeout_secs = timeout.as_secs(),
+        in_flight = requests.snapshot().len(),
+        "Draining in-flight requests"
+    );
+    match tokio::time::timeout(timeout, &mut server).await {
+        Ok(result) => join_server(result).map(|_| ShutdownOutcome::Graceful),
+        Err(_) => {
+            let aborted = requests.snapshot();
+            for request in &aborted {
+                warn!(
+                    method = %request.method,
+                    path = %request.path,
+                    elapsed_ms = request.elapsed.as_millis() as u64,
+                    "Aborting in-flight request at shutdown timeout"
+                );
+            }
+            server.abort();
+            warn!(
+                aborted = aborted.len(),
+                timeout_secs = timeout.as_secs(),
+                "Shutdown timeout reached, forcing server stop"
+            );
+            Ok(ShutdownOutcome::TimedOut { aborted })
+        }
+    }
+}
+
+fn join_server(
+    result: Result<std::io::Result<()>, tokio::task::JoinError>,
+) -> std::io::Result<()> {
+    result.map_err(std::io::Error::other)?
+}
@@ -1,16 +1,26 @@
+use futures::future::BoxFuture;
+use opentelemetry::trace::TracerProvider as _;
 use opentelemetry::{global, KeyValue};
 use opentelemetry_otlp::WithExportConfig;
 use opentelemetry_sdk::{
+    export::trace::{ExportResult, SpanData, SpanExporter},
     propagation::TraceContextPropagator,
     runtime,
     trace::{self, RandomIdGenerator, Sampler, TracerProvider},
     Resource,
 };
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
 use tracing_opentelemetry::OpenTelemetryLayer;
 use tracing_subscriber::{
     fmt, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer,
 };
 
+/// The installed provider and its export counter, kept so shutdown can flush
+/// it and report what went out
+static INSTALLED: OnceLock<(TracerProvider, SpanCounter)> = OnceLock::new();
+
 /// Configuration for the OpenTelemetry tracer loaded from environment variables.
 #[derive(Debug, Clone)]
 pub struct TracingConfig {
@@ -67,21 +77,25 @@ pub fn init_tracer(config: &TracingConfig) -> anyhow::Result<()> {
         KeyValue::new("deployment.environment", config.environment.clone()),
     ]);
 
-    // Build OTLP exporter (sends to Jaeger / Grafana Tempo / any OTLP backend).
-    let exporter = opentelemetry_otlp::new_exporter()
-        .tonic()
-        .with_endpoint(config.otlp_endpoint.clone());
+    // Build OTLP exporter (sends to Jaeger / Grafana Tempo / any OTLP backend),
+    // counting what it exports so the shutdown flush can report it.
+    let exporter = opentelemetry_otlp::SpanExporterBuilder::from(
+        opentelemetry_otlp::new_exporter()
+            .tonic()
+            .with_endpoint(config.otlp_endpoint.clone()),
+    )
+    .build_span_exporter()?;
+    let counter = SpanCounter::default();
 
-    let tracer_provider = opentelemetry_otlp::new_pipeline()
-        .tracing()
-        .with_exporter(exporter)
-        .with_trace_config(
+    let tracer_provider = TracerProvider::builder()
+        .with_batch_exporter(CountingExporter::new(exporter, counter.clone()), runtime::Tokio)
+        .with_config(
             trace::config()
                 .with_sampler(sampler)
                 .with_id_generator(RandomIdGenerator::default())
                 .with_resource(resource),
         )
-        .install_batch(runtime::Tokio)?;
+        .build();
 
     // Build the tracing subscriber with:
     //  1. JSON formatter that includes trace_id / span_id fields for log correlation.
@@ -107,6 +121,7 @@ pub fn init_tracer(config: &TracingConfig) -> anyhow::Result<()> {
         .with(otel_layer)
         .init();
 
+    let _ = INSTALLED.set((tracer_provider.clone(), counter));
     global::set_tracer_provider(tracer_provider);
 
     tracing::info!(
@@ -120,11 +135,99 @@ pub fn init_tracer(config: &TracingConfig) -> anyhow::Result<()> {
     Ok(())
 }
 
-/// Flush and shut down the global tracer provider gracefully.
-/// Call this on application shutdown to ensure all buffered spans are exported.
-pub fn shutdown_tracer() {
-    global::shutdown_tracer_provider();
-    tracing::info!("OpenTelemetry tracer shut down");
+/// Number of spans an exporter has handed on, shared with whoever reports it.
+#[derive(Debug, Clone, Default)]
+pub struct SpanCounter(Arc<AtomicU64>);
+
+impl SpanCounter {
+    pub fn get(&self) -> u64 {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// Wraps an exporter to count the spans passed to it.
+#[derive(Debug)]
+pub struct CountingExporter<E> {
+    inner: E,
+    counter: SpanCounter,
+}
+
+impl<E> CountingExporter<E> {
+    pub fn new(inner: E, counter: SpanCounter) -> Self {
+        Self { inner, counter }
+    }
+}
+
+impl<E: SpanExporter> SpanExporter for CountingExporter<E> {
+    fn export(&mut self, batch: Vec<SpanData>) -> BoxFuture<'static, ExportResult> {
+        self.counter.0.fetch_add(batch.len() as u64, Ordering::SeqCst);
+        self.inner.export(batch)
+    }
+
+    fn shutdown(&mut self) {
+        self.inner.shutdown();
+    }
+
+    fn force_flush(&mut self) -> BoxFuture<'static, ExportResult> {
+        self.inner.force_flush()
+    }
+}
+
+/// Export every span `provider` still has buffered, waiting at most
+/// `timeout`. Returns how many spans went out during the flush, or `None`
+/// if it didn't finish in time.
+pub async fn flush_tracer_provider(
+    provider: TracerProvider,
+    counter: &SpanCounter,
+    timeout: Duration,
+) -> Option<u64> {
+    let before = counter.get();
+    // force_flush blocks until the batch processor has exported
+    let flush = tokio::task::spawn_blocking(move || {
+        for result in provider.force_flush() {
+            if let Err(e) = result {
+                tracing::warn!(error = %e, "Span flush reported an error");
+            }
+        }
+    });
+    match tokio::time::timeout(timeout, flush).await {
+        Ok(_) => Some(counter.get() - before),
+        Err(_) => None,
+    }
+}
+
+/// Flush buffered spans, then shut down the global tracer provider, within
+/// `timeout` overall. Call this on application shutdown, after requests have
+/// drained, so spans recorded during the drain are exported too.
+///
+/// Returns the number of spans flushed, or `None` on timeout.
+pub async fn shutdown_tracer(timeout: Duration) -> Option<u64> {
+    let started = std::time::Instant::now();
+    let flushed = match INSTALLED.get() {
+        Some((provider, counter)) => {
+            flush_tracer_provider(provider.clone(), counter, timeout).await
+        }
+        None => Some(0),
+    };
+
+    let remaining = timeout.saturating_sub(started.elapsed());
+    let shutdown = tokio::task::spawn_blocking(global::shutdown_tracer_provider);
+    if tokio::time::timeout(remaining, shutdown).await.is_err() {
+        tracing::warn!(
+            timeout_secs = timeout.as_secs(),
+            "OpenTelemetry tracer shutdown timed out"
+        );
+        return flushed;
+    }
+
+    match flushed {
+        Some(spans) => tracing::info!(spans_flushed = spans, "OpenTelemetry tracer shut down"),
+        None => tracing::warn!(
+            timeout_secs = timeout.as_secs(),
+            "OpenTelemetry span flush timed out; buffered spans may be lost"
+        ),
+    }
+    flushed
 }
 
 // ---------------------------------------------------------------------------
@@ -142,4 +245,55 @@ fn build_sampler(rate: f64) -> Sampler {
         // Child spans inherit the parent decision so traces are never split.
         Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(rate)))
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentelemetry::trace::{Span, Tracer};
+    use std::sync::Mutex;
+
+    /// Keeps exported spans in memory for assertions
+    #[derive(Debug, Clone, Default)]
+    struct InMemoryExporter {
+        spans: Arc<Mutex<Vec<SpanData>>>,
+    }
+
+    impl SpanExporter for InMemoryExporter {
+        fn export(&mut self, batch: Vec<SpanData>) -> BoxFuture<'static, ExportResult> {
+            self.spans.lock().unwrap().extend(batch);
+            Box::pin(async { Ok(()) })
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn spans_ended_just_before_shutdown_are_exported_by_the_flush() {
+        let exporter = InMemoryExporter::default();
+        let counter = SpanCounter::default();
+        let provider = TracerProvider::builder()
+            .with_batch_exporter(
+                CountingExporter::new(exporter.clone(), counter.clone()),
+                runtime::Tokio,
+            )
+            .build();
+
+        let tracer = provider.tracer("shutdown-test");
+        for name in ["drain-request", "final-request"] {
+            tracer.start(name).end();
+        }
+        // Batched, not yet exported: the scheduled export is seconds away
+        assert!(exporter.spans.lock().unwrap().is_empty());
+
+        let flushed = flush_tracer_provider(provider, &counter, Duration::from_secs(5)).await;
+
+        assert_eq!(flushed, Some(2));
+        let names: Vec<String> = exporter
+            .spans
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|span| span.name.to_string())
+            .collect();
+        assert_eq!(names, vec!["drain-request", "final-request"]);
+    }
+}
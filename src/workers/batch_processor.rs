@@ -10,6 +10,7 @@
 //! Stellar envelopes are atomic — a full envelope failure marks all included items
 //! as failed without affecting other envelopes in the same batch.
 
+use crate::chains::stellar::payment;
 use bigdecimal::BigDecimal;
 use sqlx::PgPool;
 use std::time::Duration;
@@ -17,8 +18,6 @@ use tokio::sync::watch;
 use tracing::{error, info, warn};
 use uuid::Uuid;
 
-/// Maximum Stellar operations per transaction envelope (protocol limit is 100).
-const MAX_OPS_PER_ENVELOPE: usize = 100;
 /// How long a batch may stay in `processing` before it is considered stuck (minutes).
 const MAX_PROCESSING_MINUTES: i64 = 30;
 
@@ -105,8 +104,8 @@ async fn process_pending_cngn_batch(
         return Ok(());
     }
 
-    // Group items into envelopes of MAX_OPS_PER_ENVELOPE operations each
-    for chunk in items.chunks(MAX_OPS_PER_ENVELOPE) {
+    // Group items into envelopes of at most STELLAR_MAX_OPS_PER_TX operations each
+    for chunk in items.chunks(payment::max_operations_from_env()) {
         let item_ids: Vec<Uuid> = chunk.iter().map(|i| i.id).collect();
         let destinations: Vec<(String, BigDecimal, Option<String>)> = chunk
             .iter()
@@ -191,6 +190,14 @@ async fn build_and_submit_envelope(
     source_wallet: &str,
     payments: &[(String, BigDecimal, Option<String>)],
 ) -> anyhow::Result<String> {
+    // Refuse oversized envelopes up front rather than leave Horizon to reject them
+    payment::check_batch_limits(
+        payments.len(),
+        payment::DEFAULT_BASE_FEE_STROOPS,
+        payment::max_operations_from_env(),
+        payment::max_fee_per_op_from_env(),
+    )?;
+
     // Placeholder — production implementation must:
     // 1. Load source account sequence from Horizon
     // 2. Build a TransactionEnvelope with one Payment op per item in `payments`
@@ -78,9 +78,12 @@ impl BillProcessorWorker {
         loop {
             tokio::select! {
                 _ = interval.tick() => {
-                    if let Err(e) = self.run_cycle().await {
-                        error!(error = %e, "bill processor cycle failed");
-                    }
+                    crate::workers::run_iteration("bill_processor", async {
+                        if let Err(e) = self.run_cycle().await {
+                            error!(error = %e, "bill processor cycle failed");
+                        }
+                    })
+                    .await;
                 }
                 _ = shutdown_rx.changed() => {
                     if *shutdown_rx.borrow() {
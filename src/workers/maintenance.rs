@@ -176,13 +176,13 @@ impl MaintenanceWorker {
                     if *shutdown.borrow() {
                         info!("Maintenance worker received shutdown signal — finishing current cycle");
                         // Run one final cycle to completion before exiting.
-                        self.run_cycle().await;
+                        crate::workers::run_iteration("maintenance", self.run_cycle()).await;
                         info!("Maintenance worker shut down cleanly");
                         return;
                     }
                 }
                 _ = ticker.tick() => {
-                    self.run_cycle().await;
+                    crate::workers::run_iteration("maintenance", self.run_cycle()).await;
                 }
             }
         }
@@ -0,0 +1,535 @@
+//! Memo-based deposit reconciliation worker
+//!
+//! Exchange-style deposits all arrive at one shared address, each carrying a
+//! MEMO_ID that identifies the depositor. The worker follows that address's
+//! payments with a persisted Horizon cursor, looks each memo id up in the
+//! deposit memo mapping, and for a match records a conversion audit crediting
+//! the wallet and notifies its owner. Payments with no memo, a non-id memo, an
+//! unissued memo id or an amount that doesn't parse can't be credited and are
+//! quarantined in `memo_deposits` for manual review.
+//!
+//! With no saved cursor the worker starts from the address's newest payment
+//! rather than replaying its history.
+
+use crate::chains::stellar::client::{PaymentRecord, StellarClient};
+use crate::database::conversion_audit_repository::ConversionAuditRepository;
+use crate::database::deposit_memo_repository::DepositMemoRepository;
+use crate::database::error::DatabaseError;
+use crate::database::memo_deposit_repository::{MemoDepositRepository, NewMemoDeposit};
+use crate::services::notification::NotificationService;
+use bigdecimal::BigDecimal;
+use serde_json::json;
+use sqlx::{PgConnection, PgPool};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// Horizon operation types that move an asset to the destination
+const PAYMENT_TYPES: &[&str] = &[
+    "payment",
+    "path_payment_strict_receive",
+    "path_payment_strict_send",
+];
+
+// ---------------------------------------------------------------------------
+// Configuration
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone)]
+pub struct MemoDepositConfig {
+    /// Shared address deposits are sent to
+    pub deposit_address: String,
+    /// Asset credited; payments in anything else are ignored
+    pub asset_code: String,
+    pub asset_issuer: String,
+    pub poll_interval: Duration,
+    /// Payments fetched per Horizon page
+    pub batch_size: usize,
+}
+
+impl MemoDepositConfig {
+    /// `MEMO_DEPOSIT_ADDRESS` is required; the asset comes from the cNGN
+    /// asset configuration for `network`.
+    pub fn from_env(network: &crate::chains::stellar::config::StellarNetwork) -> Option<Self> {
+        let deposit_address = std::env::var("MEMO_DEPOSIT_ADDRESS")
+            .ok()
+            .filter(|v| !v.trim().is_empty())?;
+        let asset = crate::chains::stellar::trustline::CngnAssetConfig::from_env();
+        Some(Self {
+            deposit_address,
+            asset_issuer: asset.issuer_for_network(network).to_string(),
+            asset_code: asset.asset_code,
+            poll_interval: Duration::from_secs(
+                std::env::var("MEMO_DEPOSIT_POLL_INTERVAL_SECS")
+                    .ok()
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(10),
+            ),
+            batch_size: std::env::var("MEMO_DEPOSIT_BATCH_SIZE")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(100),
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Classification
+// ---------------------------------------------------------------------------
+
+/// Why an inbound deposit couldn't be attributed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuarantineReason {
+    MissingMemo,
+    /// A text, hash or return memo where a memo id was expected
+    NonIdMemo,
+    /// A memo id that was never issued to a wallet
+    UnknownMemo,
+    /// An amount that is missing, unparseable or not positive
+    InvalidAmount,
+}
+
+impl QuarantineReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QuarantineReason::MissingMemo => "missing_memo",
+            QuarantineReason::NonIdMemo => "non_id_memo",
+            QuarantineReason::UnknownMemo => "unknown_memo",
+            QuarantineReason::InvalidAmount => "invalid_amount",
+        }
+    }
+}
+
+/// What a payment on the deposit address's stream is
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InboundPayment {
+    /// Outgoing, another asset, or not a payment at all
+    NotADeposit,
+    /// A deposit carrying this memo id
+    MemoId(u64),
+    /// A deposit that can't be attributed from its memo
+    Unattributable(QuarantineReason),
+}
+
+/// Decide whether `record` is a deposit and which memo id it carries.
+pub fn classify_payment(record: &PaymentRecord, config: &MemoDepositConfig) -> InboundPayment {
+    let is_deposit = PAYMENT_TYPES.contains(&record.payment_type.as_str())
+        && record.to.as_deref() == Some(config.deposit_address.as_str())
+        && record.asset_code.as_deref() == Some(config.asset_code.as_str())
+        && record.asset_issuer.as_deref() == Some(config.asset_issuer.as_str());
+    if !is_deposit {
+        return InboundPayment::NotADeposit;
+    }
+
+    match (record.memo_type.as_deref(), record.memo.as_deref()) {
+        (None, _) | (_, None) => InboundPayment::Unattributable(QuarantineReason::MissingMemo),
+        (Some("id"), Some(memo)) => match memo.parse::<u64>() {
+            Ok(id) => InboundPayment::MemoId(id),
+            Err(_) => InboundPayment::Unattributable(QuarantineReason::NonIdMemo),
+        },
+        _ => InboundPayment::Unattributable(QuarantineReason::NonIdMemo),
+    }
+}
+
+/// The payment's amount, if it is a positive decimal
+pub fn parse_amount(record: &PaymentRecord) -> Option<BigDecimal> {
+    record
+        .amount
+        .as_deref()
+        .and_then(|a| BigDecimal::from_str(a).ok())
+        .filter(|a| *a > BigDecimal::from(0))
+}
+
+// ---------------------------------------------------------------------------
+// Reconciliation
+// ---------------------------------------------------------------------------
+
+/// Result of reconciling one payment
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReconcileOutcome {
+    Ignored,
+    /// Recorded on an earlier pass
+    AlreadyProcessed,
+    Credited {
+        wallet_address: String,
+        conversion_audit_id: Uuid,
+    },
+    Quarantined(QuarantineReason),
+}
+
+/// Attributes individual payments; the worker feeds it Horizon's stream
+pub struct MemoDepositReconciler {
+    pool: PgPool,
+    notifications: Arc<NotificationService>,
+    config: MemoDepositConfig,
+}
+
+impl MemoDepositReconciler {
+    pub fn new(
+        pool: PgPool,
+        notifications: Arc<NotificationService>,
+        config: MemoDepositConfig,
+    ) -> Self {
+        Self {
+            pool,
+            notifications,
+            config,
+        }
+    }
+
+    pub fn config(&self) -> &MemoDepositConfig {
+        &self.config
+    }
+
+    pub async fn reconcile(
+        &self,
+        record: &PaymentRecord,
+    ) -> Result<ReconcileOutcome, DatabaseError> {
+        let wallet = match classify_payment(record, &self.config) {
+            InboundPayment::NotADeposit => return Ok(ReconcileOutcome::Ignored),
+            InboundPayment::Unattributable(reason) => Err(reason),
+            InboundPayment::MemoId(memo_id) => {
+                match DepositMemoRepository::new(self.pool.clone())
+                    .resolve(memo_id)
+                    .await?
+                {
+                    Some(mapping) => Ok(mapping.wallet_address),
+                    None => Err(QuarantineReason::UnknownMemo),
+                }
+            }
+        };
+
+        let credit = wallet.and_then(|wallet_address| match parse_amount(record) {
+            Some(amount) => Ok((wallet_address, amount)),
+            None => Err(QuarantineReason::InvalidAmount),
+        });
+        let claim = NewMemoDeposit {
+            payment_id: record.id.clone(),
+            transaction_hash: record.transaction_hash.clone(),
+            deposit_address: self.config.deposit_address.clone(),
+            source_address: record.from.clone(),
+            asset_code: self.config.asset_code.clone(),
+            asset_issuer: Some(self.config.asset_issuer.clone()),
+            // Quarantined rows keep 0 when the amount itself is the problem
+            amount: parse_amount(record).unwrap_or_else(|| BigDecimal::from(0)),
+            memo_type: record.memo_type.clone(),
+            memo: record.memo.clone(),
+            wallet_address: credit.as_ref().ok().map(|(wallet, _)| wallet.clone()),
+            status: if credit.is_ok() { "credited" } else { "quarantined" }.to_string(),
+            quarantine_reason: credit.as_ref().err().map(|r| r.as_str().to_string()),
+        };
+
+        // The claim, the audit and the link commit together, so a failed
+        // credit leaves nothing behind for the next pass to trip over
+        let mut tx = self.pool.begin().await.map_err(DatabaseError::from_sqlx)?;
+        if MemoDepositRepository::claim_in(&mut tx, &claim).await?.is_none() {
+            return Ok(ReconcileOutcome::AlreadyProcessed);
+        }
+
+        let (wallet_address, amount) = match credit {
+            Ok(credit) => credit,
+            Err(reason) => {
+                tx.commit().await.map_err(DatabaseError::from_sqlx)?;
+                warn!(
+                    payment_id = %record.id,
+                    stellar_tx_hash = %record.transaction_hash,
+                    memo_type = ?record.memo_type,
+                    memo = ?record.memo,
+                    amount = ?record.amount,
+                    reason = reason.as_str(),
+                    "deposit quarantined for manual review"
+                );
+                return Ok(ReconcileOutcome::Quarantined(reason));
+            }
+        };
+
+        let audit = self.credit(&mut tx, record, &wallet_address, amount).await?;
+        MemoDepositRepository::link_conversion_audit_in(&mut tx, &record.id, audit).await?;
+        tx.commit().await.map_err(DatabaseError::from_sqlx)?;
+
+        self.notifications
+            .send_deposit_credited(
+                &wallet_address,
+                record.amount.as_deref().unwrap_or("0"),
+                &self.config.asset_code,
+                &record.transaction_hash,
+            )
+            .await;
+        info!(
+            payment_id = %record.id,
+            wallet = %wallet_address,
+            conversion_audit_id = %audit,
+            "memo deposit credited"
+        );
+
+        Ok(ReconcileOutcome::Credited {
+            wallet_address,
+            conversion_audit_id: audit,
+        })
+    }
+
+    /// Audit the deposit on `conn` as a 1:1 credit of the asset to `wallet_address`
+    async fn credit(
+        &self,
+        conn: &mut PgConnection,
+        record: &PaymentRecord,
+        wallet_address: &str,
+        amount: BigDecimal,
+    ) -> Result<Uuid, DatabaseError> {
+        // conversion_audits.wallet_address references wallets, but a memo can
+        // be issued before its wallet is connected
+        let wallet_known: bool =
+            sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM wallets WHERE wallet_address = $1)")
+                .bind(wallet_address)
+                .fetch_one(&mut *conn)
+                .await
+                .map_err(DatabaseError::from_sqlx)?;
+
+        let created = ConversionAuditRepository::create_in(
+            conn,
+            None,
+            wallet_known.then_some(wallet_address),
+            None,
+            &self.config.asset_code,
+            &self.config.asset_code,
+            amount.clone(),
+            amount,
+            BigDecimal::from(1),
+            BigDecimal::from(0),
+            None,
+            Some("stellar_memo_deposit"),
+            "executed",
+            None,
+            json!({
+                "flow": "memo_deposit",
+                "payment_id": record.id,
+                "stellar_tx_hash": record.transaction_hash,
+                "memo_id": record.memo,
+                "source": record.from,
+                "credited_wallet": wallet_address,
+            }),
+        )
+        .await?;
+        Ok(created.audit.id)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Worker
+// ---------------------------------------------------------------------------
+
+pub struct MemoDepositWorker {
+    stellar: StellarClient,
+    reconciler: MemoDepositReconciler,
+    deposits: MemoDepositRepository,
+}
+
+impl MemoDepositWorker {
+    pub fn new(stellar: StellarClient, reconciler: MemoDepositReconciler) -> Self {
+        let deposits = MemoDepositRepository::new(reconciler.pool.clone());
+        Self {
+            stellar,
+            reconciler,
+            deposits,
+        }
+    }
+
+    pub async fn run(self, mut shutdown_rx: watch::Receiver<bool>) {
+        let config = self.reconciler.config();
+        info!(
+            deposit_address = %config.deposit_address,
+            asset = %config.asset_code,
+            poll_interval_secs = config.poll_interval.as_secs(),
+            "memo deposit reconciler started"
+        );
+
+        loop {
+            tokio::select! {
+                _ = shutdown_rx.changed() => {
+                    if *shutdown_rx.borrow() {
+                        info!("memo deposit reconciler stopping");
+                        break;
+                    }
+                }
+                _ = tokio::time::sleep(self.reconciler.config().poll_interval) => {
+                    crate::workers::run_iteration("memo_deposit_reconciler", async {
+                        if let Err(e) = self.run_cycle().await {
+                            warn!(error = %e, "memo deposit reconciliation cycle failed");
+                        }
+                    })
+                    .await;
+                }
+            }
+        }
+
+        info!("memo deposit reconciler stopped");
+    }
+
+    /// Work through every payment since the saved cursor. The cursor only
+    /// advances past payments that were fully handled, so a failure resumes
+    /// at the payment that failed.
+    async fn run_cycle(&self) -> anyhow::Result<()> {
+        let config = self.reconciler.config();
+        if self.deposits.cursor(&config.deposit_address).await?.is_none() {
+            self.start_from_newest().await?;
+        }
+        loop {
+            let cursor = self.deposits.cursor(&config.deposit_address).await?;
+            let page = self
+                .stellar
+                .list_account_payments_since(
+                    &config.deposit_address,
+                    cursor.as_deref(),
+                    config.batch_size,
+                )
+                .await?;
+            if page.records.is_empty() {
+                return Ok(());
+            }
+
+            let page_len = page.records.len() + page.skipped;
+            for record in &page.records {
+                // Without its memo the payment can't be attributed; stop here
+                // so the next cycle retries it instead of quarantining it
+                if record.memo_unavailable {
+                    anyhow::bail!("memo for payment {} is unavailable", record.id);
+                }
+                self.reconciler.reconcile(record).await?;
+                let token = record.paging_token.as_deref().unwrap_or(&record.id);
+                self.deposits
+                    .save_cursor(&config.deposit_address, token)
+                    .await?;
+            }
+            if page_len < config.batch_size {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Save the address's newest payment as the cursor, so a first start
+    /// follows payments from now on instead of crediting its whole history.
+    /// An address with no payments yet keeps no cursor and is read from the
+    /// beginning.
+    async fn start_from_newest(&self) -> anyhow::Result<()> {
+        let config = self.reconciler.config();
+        let newest = self
+            .stellar
+            .list_account_payments(&config.deposit_address, 1, None)
+            .await?;
+        if let Some(record) = newest.records.first() {
+            let token = record.paging_token.as_deref().unwrap_or(&record.id);
+            self.deposits
+                .save_cursor(&config.deposit_address, token)
+                .await?;
+            info!(
+                deposit_address = %config.deposit_address,
+                paging_token = %token,
+                "memo deposit reconciler starting from the newest payment"
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEPOSIT_ADDRESS: &str = "GDEPOSITADDRESS";
+    const ISSUER: &str = "GCNGNISSUER";
+
+    fn config() -> MemoDepositConfig {
+        MemoDepositConfig {
+            deposit_address: DEPOSIT_ADDRESS.to_string(),
+            asset_code: "cNGN".to_string(),
+            asset_issuer: ISSUER.to_string(),
+            poll_interval: Duration::from_secs(10),
+            batch_size: 100,
+        }
+    }
+
+    fn deposit(memo_type: Option<&str>, memo: Option<&str>) -> PaymentRecord {
+        PaymentRecord {
+            id: "1".to_string(),
+            paging_token: Some("1".to_string()),
+            payment_type: "payment".to_string(),
+            created_at: None,
+            transaction_hash: "abc".to_string(),
+            from: Some("GSENDER".to_string()),
+            to: Some(DEPOSIT_ADDRESS.to_string()),
+            amount: Some("100.0000000".to_string()),
+            asset_type: Some("credit_alphanum4".to_string()),
+            asset_code: Some("cNGN".to_string()),
+            asset_issuer: Some(ISSUER.to_string()),
+            memo_type: memo_type.map(str::to_string),
+            memo: memo.map(str::to_string),
+            memo_unavailable: false,
+        }
+    }
+
+    #[test]
+    fn memo_id_deposit_is_attributed() {
+        assert_eq!(
+            classify_payment(&deposit(Some("id"), Some("42")), &config()),
+            InboundPayment::MemoId(42)
+        );
+        let mut path = deposit(Some("id"), Some("42"));
+        path.payment_type = "path_payment_strict_send".to_string();
+        assert_eq!(classify_payment(&path, &config()), InboundPayment::MemoId(42));
+    }
+
+    #[test]
+    fn other_assets_destinations_and_operations_are_not_deposits() {
+        let mut other_asset = deposit(Some("id"), Some("42"));
+        other_asset.asset_code = Some("USDC".to_string());
+        let mut other_issuer = deposit(Some("id"), Some("42"));
+        other_issuer.asset_issuer = Some("GIMPOSTOR".to_string());
+        let mut outgoing = deposit(Some("id"), Some("42"));
+        outgoing.to = Some("GSOMEONEELSE".to_string());
+        let mut create_account = deposit(Some("id"), Some("42"));
+        create_account.payment_type = "create_account".to_string();
+
+        for record in [other_asset, other_issuer, outgoing, create_account] {
+            assert_eq!(
+                classify_payment(&record, &config()),
+                InboundPayment::NotADeposit
+            );
+        }
+    }
+
+    #[test]
+    fn unattributable_memos_are_quarantined() {
+        let cases = [
+            (deposit(None, None), QuarantineReason::MissingMemo),
+            (deposit(Some("text"), Some("42")), QuarantineReason::NonIdMemo),
+            (deposit(Some("hash"), Some("abcd")), QuarantineReason::NonIdMemo),
+            (deposit(Some("id"), Some("not-a-number")), QuarantineReason::NonIdMemo),
+            (deposit(Some("id"), Some("-1")), QuarantineReason::NonIdMemo),
+        ];
+        for (record, reason) in cases {
+            assert_eq!(
+                classify_payment(&record, &config()),
+                InboundPayment::Unattributable(reason),
+                "memo {:?} {:?}",
+                record.memo_type,
+                record.memo
+            );
+        }
+    }
+
+    #[test]
+    fn only_positive_amounts_parse() {
+        let mut record = deposit(Some("id"), Some("42"));
+        assert_eq!(
+            parse_amount(&record),
+            Some(BigDecimal::from_str("100").unwrap())
+        );
+        for amount in [None, Some("abc"), Some("0"), Some("-5.5")] {
+            record.amount = amount.map(str::to_string);
+            assert_eq!(parse_amount(&record), None, "amount {:?}", amount);
+        }
+    }
+}
@@ -1,3 +1,9 @@
+//! Background workers
+//!
+//! Each worker loops on a timer. `run_iteration` wraps one pass of that loop
+//! in a `worker_iteration` span carrying the task name and a fresh `run_id`,
+//! so every log line from the same unit of work can be correlated.
+
 pub mod batch_processor;
 pub mod bill_processor;
 #[cfg(feature = "database")]
@@ -5,6 +11,7 @@ pub mod ip_detection_worker;
 #[cfg(feature = "database")]
 pub mod key_rotation_worker;
 pub mod maintenance;
+pub mod memo_deposit_reconciler;
 pub mod offramp_processor;
 pub mod onramp_processor;
 pub mod payment_poller;
@@ -12,3 +19,123 @@ pub mod recurring_payment_worker;
 pub mod stellar_confirmation_worker;
 pub mod transaction_monitor;
 pub mod webhook_retry;
+
+use futures::FutureExt;
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use tracing::Instrument;
+
+/// Run one iteration of a background task inside its own `worker_iteration`
+/// span. A panic is logged and swallowed so the caller's loop carries on with
+/// the next tick; `None` is returned in that case.
+pub async fn run_iteration<F>(task: &'static str, iteration: F) -> Option<F::Output>
+where
+    F: Future,
+{
+    let run_id = uuid::Uuid::new_v4();
+    let span = tracing::info_span!("worker_iteration", task, %run_id);
+
+    match AssertUnwindSafe(iteration)
+        .catch_unwind()
+        .instrument(span.clone())
+        .await
+    {
+        Ok(output) => Some(output),
+        Err(panic) => {
+            let message = panic
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| panic.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_string());
+            span.in_scope(|| {
+                tracing::error!(panic = %message, "background task iteration panicked")
+            });
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn iteration_fields(captured: &Captured) -> Vec<(String, String)> {
+        let bytes = captured.0.lock().unwrap().clone();
+        String::from_utf8(bytes)
+            .unwrap()
+            .lines()
+            .map(|line| {
+                let event: serde_json::Value = serde_json::from_str(line).unwrap();
+                let span = &event["span"];
+                assert_eq!(span["name"], "worker_iteration", "{line}");
+                (
+                    span["task"].as_str().unwrap().to_string(),
+                    span["run_id"].as_str().unwrap().to_string(),
+                )
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn iteration_logs_share_run_id_and_task() {
+        let captured = Captured::default();
+        let writer = captured.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .json()
+            .with_current_span(true)
+            .with_writer(move || writer.clone())
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        run_iteration("transaction_monitor", async {
+            tracing::info!("scanning pending transactions");
+            tokio::task::yield_now().await;
+            tracing::warn!("transaction monitor cycle failed");
+        })
+        .await;
+        run_iteration("transaction_monitor", async {
+            tracing::info!("next iteration");
+        })
+        .await;
+
+        let fields = iteration_fields(&captured);
+        assert_eq!(fields.len(), 3);
+        assert!(fields.iter().all(|(task, _)| task == "transaction_monitor"));
+        assert_eq!(fields[0].1, fields[1].1);
+        assert_ne!(fields[1].1, fields[2].1);
+    }
+
+    #[tokio::test]
+    async fn panicking_iteration_does_not_end_the_loop() {
+        let mut completed = 0;
+        for tick in 0..3 {
+            let result = run_iteration("test_worker", async move {
+                if tick == 1 {
+                    panic!("boom");
+                }
+                tick
+            })
+            .await;
+            if result.is_some() {
+                completed += 1;
+            }
+        }
+        assert_eq!(completed, 2);
+    }
+}
@@ -350,9 +350,12 @@ impl OfframpProcessorWorker {
         loop {
             tokio::select! {
                 _ = interval.tick() => {
-                    if let Err(e) = self.run_cycle().await {
-                        error!(error = %e, "offramp processor cycle failed");
-                    }
+                    crate::workers::run_iteration("offramp_processor", async {
+                        if let Err(e) = self.run_cycle().await {
+                            error!(error = %e, "offramp processor cycle failed");
+                        }
+                    })
+                    .await;
                 }
                 _ = shutdown_rx.changed() => {
                     if *shutdown_rx.borrow() {
@@ -168,6 +168,7 @@ impl From<StellarError> for ProcessorError {
             // Transient — network hiccups, rate limits, timeouts
             StellarError::NetworkError { .. }
             | StellarError::TimeoutError { .. }
+            | StellarError::ServiceUnavailable { .. }
             | StellarError::RateLimitError => ProcessorError::StellarTransientError(e.to_string()),
             // Permanent — bad sequence, signing failure, serialization
             _ => ProcessorError::StellarPermanentError(e.to_string()),
@@ -261,9 +262,12 @@ impl OnrampProcessor {
                     break;
                 }
                 _ = ticker.tick() => {
-                    if let Err(e) = self.process_cycle().await {
-                        error!(error = %e, "Error in onramp processor cycle");
-                    }
+                    crate::workers::run_iteration("onramp_processor", async {
+                        if let Err(e) = self.process_cycle().await {
+                            error!(error = %e, "Error in onramp processor cycle");
+                        }
+                    })
+                    .await;
                 }
             }
         }
@@ -172,13 +172,13 @@ impl PaymentPollerWorker {
                 _ = shutdown.changed() => {
                     if *shutdown.borrow() {
                         info!("Payment poller received shutdown — completing current cycle");
-                        self.run_cycle().await;
+                        crate::workers::run_iteration("payment_poller", self.run_cycle()).await;
                         info!("Payment poller shut down cleanly");
                         return;
                     }
                 }
                 _ = ticker.tick() => {
-                    self.run_cycle().await;
+                    crate::workers::run_iteration("payment_poller", self.run_cycle()).await;
                 }
             }
         }
@@ -83,7 +83,7 @@ impl RecurringPaymentWorker {
         loop {
             tokio::select! {
                 _ = interval.tick() => {
-                    self.run_cycle().await;
+                    crate::workers::run_iteration("recurring_payment", self.run_cycle()).await;
                 }
                 _ = shutdown.changed() => {
                     if *shutdown.borrow() {
@@ -187,16 +187,22 @@ impl StellarConfirmationWorker {
                     if *shutdown_rx.borrow() {
                         info!("stellar confirmation worker: shutdown signal received — completing current cycle");
                         // Run one final cycle so in-flight work is not abandoned.
-                        if let Err(e) = self.run_cycle().await {
-                            warn!(error = %e, "final cycle error during shutdown");
-                        }
+                        crate::workers::run_iteration("stellar_confirmation", async {
+                            if let Err(e) = self.run_cycle().await {
+                                warn!(error = %e, "final cycle error during shutdown");
+                            }
+                        })
+                        .await;
                         break;
                     }
                 }
                 _ = ticker.tick() => {
-                    if let Err(e) = self.run_cycle().await {
-                        warn!(error = %e, "stellar confirmation cycle error");
-                    }
+                    crate::workers::run_iteration("stellar_confirmation", async {
+                        if let Err(e) = self.run_cycle().await {
+                            warn!(error = %e, "stellar confirmation cycle error");
+                        }
+                    })
+                    .await;
                 }
             }
         }
@@ -165,9 +165,12 @@ impl TransactionMonitorWorker {
                     }
                 }
                 _ = tokio::time::sleep(self.config.poll_interval) => {
-                    if let Err(e) = self.run_cycle().await {
-                        warn!(error = %e, "transaction monitor cycle failed");
-                    }
+                    crate::workers::run_iteration("transaction_monitor", async {
+                        if let Err(e) = self.run_cycle().await {
+                            warn!(error = %e, "transaction monitor cycle failed");
+                        }
+                    })
+                    .await;
                 }
             }
         }
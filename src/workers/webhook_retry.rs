@@ -28,16 +28,19 @@ impl WebhookRetryWorker {
         loop {
             ticker.tick().await;
 
-            match self.processor.retry_pending().await {
-                Ok(count) => {
-                    if count > 0 {
-                        info!(processed = count, "Retried pending webhooks");
+            crate::workers::run_iteration("webhook_retry", async {
+                match self.processor.retry_pending().await {
+                    Ok(count) => {
+                        if count > 0 {
+                            info!(processed = count, "Retried pending webhooks");
+                        }
+                    }
+                    Err(e) => {
+                        error!(error = %e, "Failed to retry pending webhooks");
                     }
                 }
-                Err(e) => {
-                    error!(error = %e, "Failed to retry pending webhooks");
-                }
-            }
+            })
+            .await;
         }
     }
 }
@@ -0,0 +1,116 @@
+//! Tests for the admin guard applied to `/api/admin/*` routes
+
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+    routing::{get, post},
+    Router,
+};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tower::ServiceExt;
+
+use Bitmesh_backend::auth::jwt::{generate_access_token, generate_refresh_token, Scope};
+use Bitmesh_backend::middleware::admin::{require_admin, AdminGuardState};
+
+const JWT_SECRET: &str = "admin-guard-test-secret-at-least-32-chars";
+
+fn admin_router() -> Router {
+    admin_router_with(Arc::new(AtomicBool::new(false)))
+}
+
+/// `changed` is set when the POST handler runs
+fn admin_router_with(changed: Arc<AtomicBool>) -> Router {
+    Router::new()
+        .route("/api/admin/ping", get(|| async { "pong" }))
+        .route(
+            "/api/admin/change",
+            post(move || async move {
+                changed.store(true, Ordering::SeqCst);
+                "changed"
+            }),
+        )
+        .route_layer(axum::middleware::from_fn_with_state(
+            AdminGuardState {
+                jwt_secret: JWT_SECRET.to_string(),
+                db: None,
+                redis_cache: None,
+            },
+            require_admin,
+        ))
+}
+
+async fn call(authorization: Option<String>) -> (StatusCode, serde_json::Value) {
+    let mut request = Request::builder().uri("/api/admin/ping");
+    if let Some(value) = authorization {
+        request = request.header(header::AUTHORIZATION, value);
+    }
+    let response = admin_router()
+        .oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body = serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null);
+    (status, body)
+}
+
+fn bearer(scope: Scope) -> Option<String> {
+    let (token, _) = generate_access_token("GADMINTEST", scope, JWT_SECRET).unwrap();
+    Some(format!("Bearer {}", token))
+}
+
+#[tokio::test]
+async fn admin_token_passes() {
+    let (status, _) = call(bearer(Scope::Admin)).await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn non_admin_token_is_forbidden() {
+    let (status, body) = call(bearer(Scope::User)).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["error"], "FORBIDDEN");
+}
+
+#[tokio::test]
+async fn unauthenticated_request_is_unauthorized_not_forbidden() {
+    let (status, body) = call(None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(body["error"], "UNAUTHORIZED");
+}
+
+#[tokio::test]
+async fn unknown_credential_without_database_is_unauthorized() {
+    let (status, _) = call(Some("Bearer not-a-jwt-or-known-key".to_string())).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn admin_refresh_token_is_unauthorized() {
+    let (token, _) = generate_refresh_token("GADMINTEST", Scope::Admin, JWT_SECRET).unwrap();
+    let (status, body) = call(Some(format!("Bearer {}", token))).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(body["error"], "UNAUTHORIZED");
+}
+
+#[tokio::test]
+async fn mutating_request_without_audit_database_is_refused() {
+    let changed = Arc::new(AtomicBool::new(false));
+    let request = Request::builder()
+        .method("POST")
+        .uri("/api/admin/change")
+        .header(header::AUTHORIZATION, bearer(Scope::Admin).unwrap())
+        .body(Body::empty())
+        .unwrap();
+    let response = admin_router_with(changed.clone())
+        .oneshot(request)
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    assert!(!changed.load(Ordering::SeqCst), "handler ran without an audit trail");
+}
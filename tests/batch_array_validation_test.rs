@@ -0,0 +1,79 @@
+//! Array bounds validation on the fiat payout batch endpoint.
+//!
+//! Bounds are checked before any database work, so the state uses a lazy pool
+//! that is never connected.
+
+use axum::{body::Body, routing::post, Router};
+use http::{Request, StatusCode};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tower::util::ServiceExt;
+use Bitmesh_backend::api::batch::{create_fiat_payout_batch, BatchState};
+
+fn app(max_fiat_batch_size: usize) -> Router {
+    let pool = sqlx::PgPool::connect_lazy("postgres://aframp@127.0.0.1:1/unused").unwrap();
+    let state = BatchState {
+        db: Arc::new(pool),
+        max_cngn_batch_size: 100,
+        max_fiat_batch_size,
+    };
+    Router::new()
+        .route("/api/batch/fiat-payout", post(create_fiat_payout_batch))
+        .with_state(state)
+}
+
+fn payout() -> Value {
+    json!({
+        "bank_account_number": "0123456789",
+        "bank_code": "058",
+        "amount_ngn": "5000",
+    })
+}
+
+async fn post_payouts(app: Router, payouts: Vec<Value>) -> (StatusCode, Value) {
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/batch/fiat-payout")
+                .header("content-type", "application/json")
+                .body(Body::from(json!({ "payouts": payouts }).to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+#[tokio::test]
+async fn empty_array_is_a_validation_error() {
+    let (status, body) = post_payouts(app(3), vec![]).await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "VALIDATION_ERROR");
+    assert_eq!(body["details"]["field"], "payouts");
+}
+
+#[tokio::test]
+async fn over_limit_array_reports_field_and_limit() {
+    let (status, body) = post_payouts(app(3), vec![payout(); 4]).await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "VALIDATION_ERROR");
+    assert_eq!(body["details"]["field"], "payouts");
+    assert_eq!(body["details"]["max_items"], 3);
+    assert_eq!(body["details"]["actual_items"], 4);
+}
+
+#[tokio::test]
+async fn array_within_limit_passes_validation() {
+    // Gets past validation and fails only on the unreachable database
+    let (status, body) = post_payouts(app(3), vec![payout(); 3]).await;
+
+    assert_ne!(status, StatusCode::BAD_REQUEST);
+    assert_ne!(body["error"], "VALIDATION_ERROR");
+}
@@ -231,6 +231,105 @@ mod cache_tests {
         assert_eq!(trustline_key.to_string(), "v1:wallet:trustline:GA123456789");
     }
 
+    #[tokio::test]
+    async fn test_balance_freshness_header_reflects_source() {
+        use axum::{body::Body, http::Request, routing::get, Router};
+        use std::sync::Arc;
+        use tower::ServiceExt;
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+        use Bitmesh_backend::api::wallet::{get_balance, WalletState};
+        use Bitmesh_backend::chains::stellar::client::StellarClient;
+        use Bitmesh_backend::chains::stellar::config::{StellarConfig, StellarNetwork};
+        use Bitmesh_backend::services::balance::BalanceService;
+
+        const ADDRESS: &str = "GCEZWKCA5VLDNRLN3RPRJMRZOX3Z6G5CHCGZXG5CPCJDGBI7XTPBGGM";
+
+        let cache = setup_cache().await;
+        for key in [
+            wallet::BalanceKey::new(ADDRESS).to_string(),
+            wallet::LastKnownBalanceKey::new(ADDRESS).to_string(),
+        ] {
+            let _ = Cache::<serde_json::Value>::delete(&cache, &key).await;
+        }
+
+        let horizon = MockServer::start().await;
+        let account = serde_json::json!({
+            "id": ADDRESS,
+            "account_id": ADDRESS,
+            "sequence": "100",
+            "subentry_count": 0,
+            "thresholds": { "low_threshold": 0, "med_threshold": 0, "high_threshold": 0 },
+            "flags": {
+                "auth_required": false,
+                "auth_revocable": false,
+                "auth_immutable": false,
+                "auth_clawback_enabled": false
+            },
+            "balances": [{ "asset_type": "native", "balance": "25.0000000" }],
+            "signers": [],
+            "data": {},
+            "last_modified_ledger": 1
+        });
+        Mock::given(method("GET"))
+            .and(path(format!("/accounts/{}", ADDRESS)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(account))
+            .mount(&horizon)
+            .await;
+
+        let client = StellarClient::new(StellarConfig {
+            network: StellarNetwork::Testnet,
+            horizon_url_override: Some(horizon.uri()),
+            horizon_fallback_urls: Vec::new(),
+            request_timeout: Duration::from_secs(5),
+            max_retries: 1,
+            health_check_interval: Duration::from_secs(30),
+        })
+        .unwrap();
+        let app = Router::new()
+            .route("/api/wallet/balance", get(get_balance))
+            .with_state(WalletState {
+                balance_service: Arc::new(BalanceService::new(
+                    client,
+                    cache,
+                    "GCJRI5CIWK5IU67Q6DGA7QW52JDKRO7JEAHQKFNDUJUPEZGURDBX3LDX".to_string(),
+                )),
+            });
+
+        let freshness = |refresh: bool| {
+            let app = app.clone();
+            async move {
+                let uri = format!("/api/wallet/balance?address={}&refresh={}", ADDRESS, refresh);
+                let response = app
+                    .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                    .await
+                    .unwrap();
+                assert_eq!(response.status(), 200);
+                let header = response.headers()["x-data-freshness"]
+                    .to_str()
+                    .unwrap()
+                    .to_string();
+                let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+                let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+                assert_eq!(body["freshness"], header.as_str());
+                header
+            }
+        };
+
+        assert_eq!(freshness(false).await, "live");
+        assert_eq!(freshness(false).await, "cached");
+
+        // Horizon goes down: a forced refresh falls back to the last known value
+        horizon.reset().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&horizon)
+            .await;
+        assert_eq!(freshness(true).await, "stale");
+    }
+
     #[tokio::test]
     async fn test_graceful_degradation() {
         // Test that operations work even when Redis is unavailable
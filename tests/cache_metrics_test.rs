@@ -0,0 +1,93 @@
+//! Prometheus counters for cache hits, misses and errors
+//!
+//! Runs in its own test binary so the global metric registry only sees the
+//! lookups made here. Requires a running Redis instance.
+//! Run with: REDIS_URL=redis://localhost:6379 cargo test --features cache --test cache_metrics_test
+
+#![cfg(feature = "cache")]
+
+use std::time::Duration;
+use Bitmesh_backend::cache::{cache::Cache, keys::*, CacheConfig, RedisCache};
+
+async fn setup_cache() -> RedisCache {
+    let config = CacheConfig {
+        redis_url: std::env::var("REDIS_URL")
+            .unwrap_or_else(|_| "redis://127.0.0.1:6379".to_string()),
+        ..Default::default()
+    };
+
+    let pool = Bitmesh_backend::cache::init_cache_pool(config)
+        .await
+        .expect("Failed to init cache pool");
+    RedisCache::new(pool)
+}
+
+/// Value of `name{cache="<cache>"}` in a Prometheus text scrape
+fn scraped(body: &str, name: &str, cache: &str) -> f64 {
+    let series = format!("{}{{cache=\"{}\"}} ", name, cache);
+    body.lines()
+        .find_map(|line| line.strip_prefix(&series))
+        .map(|v| v.trim().parse().unwrap())
+        .unwrap_or(0.0)
+}
+
+async fn scrape() -> String {
+    use axum::{body::Body, http::Request, routing::get, Router};
+    use tower::ServiceExt;
+
+    let response = Router::new()
+        .route("/metrics", get(Bitmesh_backend::metrics::handler::metrics_handler))
+        .oneshot(Request::builder().uri("/metrics").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    String::from_utf8(bytes.to_vec()).unwrap()
+}
+
+#[tokio::test]
+async fn test_cache_hit_miss_and_error_counters_are_scraped() {
+    Bitmesh_backend::metrics::registry();
+    let cache = setup_cache().await;
+    let key = wallet::BalanceKey::new(format!("GMETRICS{}", uuid::Uuid::new_v4().simple()))
+        .to_string();
+
+    let before = scrape().await;
+
+    // One miss, then two hits on the account cache
+    let missing: Option<String> = cache.get(&key).await.unwrap();
+    assert!(missing.is_none());
+    cache
+        .set(&key, &"cached".to_string(), Some(Duration::from_secs(30)))
+        .await
+        .unwrap();
+    for _ in 0..2 {
+        let hit: Option<String> = cache.get(&key).await.unwrap();
+        assert_eq!(hit.as_deref(), Some("cached"));
+    }
+
+    // A rate lookup against an unreachable Redis is an error and a miss
+    let unreachable = RedisCache::new(
+        bb8::Pool::builder()
+            .connection_timeout(Duration::from_millis(100))
+            .build_unchecked(
+                bb8_redis::RedisConnectionManager::new("redis://127.0.0.1:1").unwrap(),
+            ),
+    );
+    let rate_key = exchange_rate::CurrencyPairKey::cngn_rate("USD").to_string();
+    let failed: Option<String> = unreachable.get(&rate_key).await.unwrap();
+    assert!(failed.is_none());
+
+    let after = scrape().await;
+    let delta = |name: &str, cache: &str| {
+        scraped(&after, name, cache) - scraped(&before, name, cache)
+    };
+    assert_eq!(delta("aframp_cache_hits_total", "account"), 2.0);
+    assert_eq!(delta("aframp_cache_misses_total", "account"), 1.0);
+    assert_eq!(delta("aframp_cache_errors_total", "account"), 0.0);
+    assert_eq!(delta("aframp_cache_errors_total", "rate"), 1.0);
+    assert_eq!(delta("aframp_cache_misses_total", "rate"), 1.0);
+
+    let _ = Cache::<String>::delete(&cache, &key).await;
+}
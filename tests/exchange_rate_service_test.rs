@@ -69,6 +69,7 @@ mod tests {
             effective_from: Utc::now() - chrono::Duration::days(1),
             effective_until: None,
             metadata: serde_json::json!({}),
+            network: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
@@ -85,6 +86,7 @@ mod tests {
             effective_from: Utc::now() - chrono::Duration::days(1),
             effective_until: None,
             metadata: serde_json::json!({}),
+            network: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
@@ -0,0 +1,86 @@
+//! Shutdown must not wait forever on a handler that never completes.
+
+use axum::{routing::get, Router};
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::sync::oneshot;
+use Bitmesh_backend::shutdown::{serve_with_shutdown_timeout, ShutdownOutcome};
+
+async fn never_completes() -> &'static str {
+    std::future::pending().await
+}
+
+async fn fast() -> &'static str {
+    "ok"
+}
+
+fn app() -> Router {
+    Router::new()
+        .route("/hang", get(never_completes))
+        .route("/fast", get(fast))
+}
+
+#[tokio::test]
+async fn shutdown_proceeds_after_timeout_with_hung_request() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+
+    let server = tokio::spawn(serve_with_shutdown_timeout(
+        listener,
+        app(),
+        async move {
+            let _ = shutdown_rx.await;
+        },
+        Duration::from_millis(200),
+    ));
+
+    // Leave a request hanging on the server, then ask it to stop
+    let client = reqwest::Client::new();
+    let hung = tokio::spawn(client.get(format!("http://{addr}/hang")).send());
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    shutdown_tx.send(()).unwrap();
+
+    let outcome = tokio::time::timeout(Duration::from_secs(5), server)
+        .await
+        .expect("shutdown hung past its timeout")
+        .unwrap()
+        .unwrap();
+
+    match outcome {
+        ShutdownOutcome::TimedOut { aborted } => {
+            assert_eq!(aborted.len(), 1);
+            assert_eq!(aborted[0].path, "/hang");
+        }
+        other => panic!("expected TimedOut, got {other:?}"),
+    }
+    assert!(hung.await.unwrap().is_err(), "hung request should be cut off");
+}
+
+#[tokio::test]
+async fn idle_server_shuts_down_gracefully() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+
+    let server = tokio::spawn(serve_with_shutdown_timeout(
+        listener,
+        app(),
+        async move {
+            let _ = shutdown_rx.await;
+        },
+        Duration::from_secs(5),
+    ));
+
+    let body = reqwest::get(format!("http://{addr}/fast"))
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+    assert_eq!(body, "ok");
+    shutdown_tx.send(()).unwrap();
+
+    let outcome = server.await.unwrap().unwrap();
+    assert!(matches!(outcome, ShutdownOutcome::Graceful));
+}
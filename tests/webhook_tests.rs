@@ -114,4 +114,123 @@ mod webhook_tests {
             Some("tx_456")
         );
     }
+
+    // ── Raw body handling through the HTTP route ─────────────────────────────
+
+    mod raw_body {
+        use axum::{body::Body, routing::post, Router};
+        use hmac::{Hmac, Mac};
+        use http::{Request, StatusCode};
+        use sha2::Sha512;
+        use std::collections::HashMap;
+        use std::sync::Arc;
+        use tower::util::ServiceExt;
+        use Bitmesh_backend::api::webhooks::{handle_webhook, WebhookState};
+        use Bitmesh_backend::database::transaction_repository::TransactionRepository;
+        use Bitmesh_backend::database::webhook_repository::WebhookRepository;
+        use Bitmesh_backend::payments::credentials::ProviderCredentialStore;
+        use Bitmesh_backend::payments::factory::{PaymentFactoryConfig, PaymentProviderFactory};
+        use Bitmesh_backend::payments::types::ProviderName;
+        use Bitmesh_backend::services::payment_orchestrator::{
+            OrchestratorConfig, PaymentOrchestrator,
+        };
+        use Bitmesh_backend::services::webhook_processor::WebhookProcessor;
+
+        const WEBHOOK_SECRET: &str = "whsec_raw_body_test";
+
+        fn sign(body: &[u8]) -> String {
+            let mut mac = Hmac::<Sha512>::new_from_slice(WEBHOOK_SECRET.as_bytes()).unwrap();
+            mac.update(body);
+            hex::encode(mac.finalize().into_bytes())
+        }
+
+        fn app() -> Router {
+            std::env::set_var("PAYSTACK_SECRET_KEY", "sk_test_raw_body");
+            std::env::set_var("PAYSTACK_WEBHOOK_SECRET", WEBHOOK_SECRET);
+
+            // Never connected to: verification happens before the first query,
+            // and a failed insert afterwards is still acknowledged with 200.
+            let pool =
+                sqlx::PgPool::connect_lazy("postgres://aframp@127.0.0.1:1/unused").unwrap();
+
+            let factory = Arc::new(PaymentProviderFactory::with_credential_store(
+                PaymentFactoryConfig {
+                    default_provider: ProviderName::Paystack,
+                    enabled_providers: vec![ProviderName::Paystack],
+                    provider_fee_bps: HashMap::new(),
+                },
+                Arc::new(ProviderCredentialStore::new()),
+            ));
+            let orchestrator = Arc::new(PaymentOrchestrator::new(
+                Vec::new(),
+                Arc::new(TransactionRepository::new(pool.clone())),
+                OrchestratorConfig::default(),
+            ));
+            let processor = Arc::new(WebhookProcessor::new(
+                Arc::new(WebhookRepository::new(pool)),
+                factory,
+                orchestrator,
+            ));
+
+            Router::new()
+                .route("/webhooks/{provider}", post(handle_webhook))
+                .with_state(Arc::new(WebhookState { processor }))
+        }
+
+        fn request(body: &'static [u8], content_type: &str, signature: &str) -> Request<Body> {
+            Request::builder()
+                .method("POST")
+                .uri("/webhooks/paystack")
+                .header("content-type", content_type)
+                .header("x-paystack-signature", signature)
+                .body(Body::from(body))
+                .unwrap()
+        }
+
+        #[tokio::test]
+        async fn raw_body_with_valid_signature_passes_verification() {
+            // Whitespace and key order that re-serialising through serde_json
+            // would not reproduce.
+            let body: &'static [u8] =
+                b"{ \"id\": 4242,  \"event\":\"charge.success\", \"data\": {\"reference\": \"tx_raw\", \"status\": \"success\"} }";
+
+            let response = app()
+                .oneshot(request(body, "application/json; charset=utf-8", &sign(body)))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+
+            let response = app()
+                .oneshot(request(body, "application/json", &sign(b"{}")))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        }
+
+        #[tokio::test]
+        async fn form_encoded_body_is_verified_before_decoding() {
+            let body: &'static [u8] =
+                b"payload=%7B%22id%22%3A77%2C%22event%22%3A%22charge.success%22%2C%22data%22%3A%7B%22reference%22%3A%22tx_form%22%7D%7D";
+
+            let response = app()
+                .oneshot(request(
+                    body,
+                    "application/x-www-form-urlencoded",
+                    &sign(body),
+                ))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+
+        #[tokio::test]
+        async fn unsupported_content_type_is_rejected() {
+            let body: &'static [u8] = b"<event/>";
+            let response = app()
+                .oneshot(request(body, "application/xml", &sign(body)))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        }
+    }
 }